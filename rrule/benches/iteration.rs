@@ -0,0 +1,80 @@
+//! Measures the expansion of single rules, split into the simple rules, which the fast
+//! paths compute with date arithmetic, and the complex rules with `BYSETPOS` and other
+//! BYxxx rule parts, which go through the generic iteration.
+//!
+//! Run with `cargo bench --bench iteration`.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rrule::RRuleSet;
+
+/// The number of recurrences taken from each rule.
+const RECURRENCES: usize = 1_000;
+
+const SIMPLE_RULES: [(&str, &str); 5] = [
+    ("daily", "DTSTART:20200101T090000Z\nRRULE:FREQ=DAILY"),
+    (
+        "weekly on weekdays",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR",
+    ),
+    (
+        "monthly on the 1st and 15th",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=1,15",
+    ),
+    (
+        "daily in a timezone",
+        "DTSTART;TZID=Europe/Berlin:20200101T090000\nRRULE:FREQ=DAILY",
+    ),
+    (
+        "every 15 minutes",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=MINUTELY;INTERVAL=15",
+    ),
+];
+
+const COMPLEX_RULES: [(&str, &str); 5] = [
+    (
+        "monthly on the last weekday",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+    ),
+    (
+        "monthly on the 1st and last weekday",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=1,-1",
+    ),
+    (
+        "yearly on the 2nd monday of march",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2MO",
+    ),
+    (
+        "hourly during office hours",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=HOURLY;BYHOUR=9,10,11,12,13,14,15,16,17;BYDAY=MO,TU,WE,TH,FR",
+    ),
+    (
+        "yearly by week number",
+        "DTSTART:20200101T090000Z\nRRULE:FREQ=YEARLY;BYWEEKNO=1,26,52;BYDAY=MO,FR",
+    ),
+];
+
+fn expand(c: &mut Criterion, group_name: &str, rules: &[(&str, &str)]) {
+    let mut group = c.benchmark_group(group_name);
+    group.throughput(Throughput::Elements(RECURRENCES as u64));
+    for (name, rule) in rules {
+        let rrule_set: RRuleSet = rule.parse().expect("the rule is valid");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &rrule_set,
+            |b, rrule_set| {
+                b.iter(|| black_box(rrule_set.iter().take(RECURRENCES).count()));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn simple_rules(c: &mut Criterion) {
+    expand(c, "simple rules", &SIMPLE_RULES);
+}
+
+fn complex_rules(c: &mut Criterion) {
+    expand(c, "complex rules", &COMPLEX_RULES);
+}
+
+criterion_group!(benches, simple_rules, complex_rules);
+criterion_main!(benches);
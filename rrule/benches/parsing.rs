@@ -0,0 +1,56 @@
+//! Measures the parsing of the sets of the corpus, one by one and at once with
+//! `build_rrulesets`.
+//!
+//! Run with `cargo bench --bench parsing`.
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rrule::{build_rrulesets, ParserConfig, RRule, RRuleSet, Unvalidated};
+
+/// The sets of the corpus, separated by blank lines.
+fn corpus() -> Vec<&'static str> {
+    include_str!("corpus.txt")
+        .split("\n\n")
+        .map(str::trim)
+        .collect()
+}
+
+fn parsing(c: &mut Criterion) {
+    let corpus = corpus();
+    let mut group = c.benchmark_group("parsing");
+    group.throughput(Throughput::Elements(corpus.len() as u64));
+
+    group.bench_function("sets one by one", |b| {
+        b.iter(|| {
+            for input in &corpus {
+                let rrule_set: RRuleSet = black_box(input).parse().expect("the set is valid");
+                black_box(rrule_set);
+            }
+        });
+    });
+    group.bench_function("sets at once", |b| {
+        b.iter(|| {
+            black_box(build_rrulesets(
+                black_box(&corpus),
+                &ParserConfig::default(),
+            ))
+        });
+    });
+
+    let rules: Vec<&str> = corpus
+        .iter()
+        .flat_map(|input| input.lines())
+        .filter_map(|line| line.strip_prefix("RRULE:"))
+        .collect();
+    group.throughput(Throughput::Elements(rules.len() as u64));
+    group.bench_function("rules", |b| {
+        b.iter(|| {
+            for rule in &rules {
+                let rrule: RRule<Unvalidated> = black_box(rule).parse().expect("the rule is valid");
+                black_box(rrule);
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parsing);
+criterion_main!(benches);
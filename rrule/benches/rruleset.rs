@@ -0,0 +1,80 @@
+//! Measures the merging of the rules, rdates and exdates of sets, and the expansion of
+//! many sets over a window.
+//!
+//! Run with `cargo bench --bench rruleset`.
+use chrono::TimeZone;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rrule::{RRuleSet, Tz};
+
+/// The number of recurrences taken from each set.
+const RECURRENCES: usize = 1_000;
+
+const SETS: [(&str, &str); 3] = [
+    (
+        "4 rules",
+        "DTSTART:20200101T090000Z\n\
+         RRULE:FREQ=DAILY;BYHOUR=9\n\
+         RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;BYHOUR=12\n\
+         RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;BYHOUR=17\n\
+         RRULE:FREQ=HOURLY;INTERVAL=5",
+    ),
+    (
+        "rules with exdates",
+        "DTSTART:20200101T090000Z\n\
+         RRULE:FREQ=DAILY\n\
+         RRULE:FREQ=WEEKLY;BYDAY=SA;BYHOUR=10\n\
+         EXDATE:20200102T090000Z,20200103T090000Z,20200110T090000Z,20200111T100000Z\n\
+         EXDATE:20200201T090000Z,20200301T090000Z,20200401T090000Z,20200501T090000Z",
+    ),
+    (
+        "rules with rdates in a timezone",
+        "DTSTART;TZID=Europe/Berlin:20200101T090000\n\
+         RRULE:FREQ=WEEKLY;BYDAY=TU,TH\n\
+         RRULE:FREQ=MONTHLY;BYMONTHDAY=1,15\n\
+         RDATE;TZID=Europe/Berlin:20200105T100000,20200112T100000,20200119T100000",
+    ),
+];
+
+fn merging(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merging");
+    group.throughput(Throughput::Elements(RECURRENCES as u64));
+    for (name, set) in SETS {
+        let rrule_set: RRuleSet = set.parse().expect("the set is valid");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &rrule_set,
+            |b, rrule_set| {
+                b.iter(|| black_box(rrule_set.iter().take(RECURRENCES).count()));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn expand_many(c: &mut Criterion) {
+    let sets: Vec<RRuleSet> = SETS
+        .iter()
+        .cycle()
+        .take(30)
+        .map(|(_, set)| set.parse().expect("the set is valid"))
+        .collect();
+    let start = Tz::UTC.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap();
+    let end = Tz::UTC.with_ymd_and_hms(2020, 7, 1, 0, 0, 0).unwrap();
+
+    let mut group = c.benchmark_group("expanding a month");
+    group.throughput(Throughput::Elements(sets.len() as u64));
+    group.bench_function("set by set", |b| {
+        b.iter(|| {
+            for rrule_set in &sets {
+                black_box(rrule_set.between(start, end, true));
+            }
+        });
+    });
+    group.bench_function("expand_many", |b| {
+        b.iter(|| black_box(RRuleSet::expand_many(&sets, start..end)));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, merging, expand_many);
+criterion_main!(benches);
@@ -0,0 +1,265 @@
+//! A builder of rules that checks the combinations of the rule parts at compile time, see
+//! [`RRuleBuilder`].
+use std::marker::PhantomData;
+
+use chrono::{Month, Weekday};
+
+use super::{DateTime, Validated};
+use crate::{
+    Frequency, NWeekday, RRule, RRuleError, RRuleSet, RScale, Skip, Unvalidated, ValidationError,
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+/// An empty struct to keep the stage of an [`RRuleBuilder`] without a frequency
+pub struct NoFreq;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+/// An empty struct to keep the stage of an [`RRuleBuilder`] with a frequency
+pub struct WithFreq;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+/// An empty struct to keep the stage of an [`RRuleBuilder`] without `COUNT` and `UNTIL`
+pub struct Unbounded;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+/// An empty struct to keep the stage of an [`RRuleBuilder`] with a `COUNT`
+pub struct WithCount;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+/// An empty struct to keep the stage of an [`RRuleBuilder`] with an `UNTIL`
+pub struct WithUntil;
+
+/// Builds an [`RRule`] like the builder methods of `RRule<Unvalidated>`, but keeps in its
+/// type which rule parts are set, so that some invalid rules don't compile:
+/// - The frequency must be set with [`RRuleBuilder::freq`] before the rule is built.
+/// - At most one of `COUNT` and `UNTIL` can be set, as RFC 5545 requires.
+///
+/// The other rule parts are checked when the rule is validated.
+///
+/// # Usage
+///
+/// ```
+/// use chrono::TimeZone;
+/// use rrule::{Frequency, NWeekday, RRule, Tz, Weekday};
+///
+/// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 4, 9, 0, 0).unwrap();
+/// let rrule = RRule::builder()
+///     .freq(Frequency::Weekly)
+///     .by_weekday(vec![NWeekday::Every(Weekday::Mon)])
+///     .count(3)
+///     .validate(dt_start)
+///     .unwrap();
+/// assert_eq!(rrule.to_string(), "DTSTART:20210104T090000Z\nRRULE:FREQ=WEEKLY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=MO");
+/// ```
+///
+/// A rule without a frequency doesn't compile:
+///
+/// ```compile_fail
+/// use rrule::RRule;
+///
+/// let rrule = RRule::builder().count(3).build();
+/// ```
+///
+/// Neither does a rule with both `COUNT` and `UNTIL`:
+///
+/// ```compile_fail
+/// use chrono::TimeZone;
+/// use rrule::{Frequency, RRule, Tz};
+///
+/// let until = Tz::UTC.with_ymd_and_hms(2021, 2, 1, 9, 0, 0).unwrap();
+/// let rrule = RRule::builder().freq(Frequency::Daily).count(3).until(until);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRuleBuilder<Freq = NoFreq, End = Unbounded> {
+    rrule: RRule<Unvalidated>,
+    stage: PhantomData<(Freq, End)>,
+}
+
+impl Default for RRuleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RRuleBuilder {
+    /// Creates a builder without any rule part.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rrule: RRule::default(),
+            stage: PhantomData,
+        }
+    }
+}
+
+impl<Freq, End> RRuleBuilder<Freq, End> {
+    fn with<NewFreq, NewEnd>(
+        self,
+        update: impl FnOnce(RRule<Unvalidated>) -> RRule<Unvalidated>,
+    ) -> RRuleBuilder<NewFreq, NewEnd> {
+        RRuleBuilder {
+            rrule: update(self.rrule),
+            stage: PhantomData,
+        }
+    }
+
+    /// The interval between each freq iteration, see [`RRule::interval`].
+    #[must_use]
+    pub fn interval(self, interval: u16) -> Self {
+        self.with(|rrule| rrule.interval(interval))
+    }
+
+    /// The week start day, see [`RRule::week_start`].
+    #[must_use]
+    pub fn week_start(self, week_start: Weekday) -> Self {
+        self.with(|rrule| rrule.week_start(week_start))
+    }
+
+    /// The occurrence numbers within the periods of the frequency, see
+    /// [`RRule::by_set_pos`].
+    #[must_use]
+    pub fn by_set_pos(self, by_set_pos: Vec<i32>) -> Self {
+        self.with(|rrule| rrule.by_set_pos(by_set_pos))
+    }
+
+    /// The months to apply the recurrence to, see [`RRule::by_month`].
+    #[must_use]
+    pub fn by_month(self, by_month: &[Month]) -> Self {
+        self.with(|rrule| rrule.by_month(by_month))
+    }
+
+    /// The leap months to apply the recurrence to, see [`RRule::by_leap_month`].
+    #[must_use]
+    pub fn by_leap_month(self, by_leap_month: &[Month]) -> Self {
+        self.with(|rrule| rrule.by_leap_month(by_leap_month))
+    }
+
+    /// The month days to apply the recurrence to, see [`RRule::by_month_day`].
+    #[must_use]
+    pub fn by_month_day(self, by_month_day: Vec<i8>) -> Self {
+        self.with(|rrule| rrule.by_month_day(by_month_day))
+    }
+
+    /// The year days to apply the recurrence to, see [`RRule::by_year_day`].
+    #[must_use]
+    pub fn by_year_day(self, by_year_day: Vec<i16>) -> Self {
+        self.with(|rrule| rrule.by_year_day(by_year_day))
+    }
+
+    /// The week numbers to apply the recurrence to, see [`RRule::by_week_no`].
+    #[must_use]
+    pub fn by_week_no(self, by_week_no: Vec<i8>) -> Self {
+        self.with(|rrule| rrule.by_week_no(by_week_no))
+    }
+
+    /// The weekdays to apply the recurrence to, see [`RRule::by_weekday`].
+    #[must_use]
+    pub fn by_weekday(self, by_weekday: Vec<NWeekday>) -> Self {
+        self.with(|rrule| rrule.by_weekday(by_weekday))
+    }
+
+    /// The hours to apply the recurrence to, see [`RRule::by_hour`].
+    #[must_use]
+    pub fn by_hour(self, by_hour: Vec<u8>) -> Self {
+        self.with(|rrule| rrule.by_hour(by_hour))
+    }
+
+    /// The minutes to apply the recurrence to, see [`RRule::by_minute`].
+    #[must_use]
+    pub fn by_minute(self, by_minute: Vec<u8>) -> Self {
+        self.with(|rrule| rrule.by_minute(by_minute))
+    }
+
+    /// The seconds to apply the recurrence to, see [`RRule::by_second`].
+    #[must_use]
+    pub fn by_second(self, by_second: Vec<u8>) -> Self {
+        self.with(|rrule| rrule.by_second(by_second))
+    }
+
+    /// The offset from the Easter Sunday, see [`RRule::by_easter`].
+    #[cfg(feature = "by-easter")]
+    #[must_use]
+    pub fn by_easter(self, by_easter: i16) -> Self {
+        self.with(|rrule| rrule.by_easter(by_easter))
+    }
+
+    /// The calendar system of the months and month days, see [`RRule::rscale`].
+    #[must_use]
+    pub fn rscale(self, rscale: RScale) -> Self {
+        self.with(|rrule| rrule.rscale(rscale))
+    }
+
+    /// What happens to the dates that don't exist, see [`RRule::skip`].
+    #[must_use]
+    pub fn skip(self, skip: Skip) -> Self {
+        self.with(|rrule| rrule.skip(skip))
+    }
+
+    /// What happens to the 29th of February in non-leap years, see [`RRule::leap_day`].
+    #[must_use]
+    pub fn leap_day(self, leap_day: Skip) -> Self {
+        self.with(|rrule| rrule.leap_day(leap_day))
+    }
+}
+
+impl<End> RRuleBuilder<NoFreq, End> {
+    /// The FREQ rule part identifies the type of recurrence rule. It must be set before
+    /// the rule is built.
+    #[must_use]
+    pub fn freq(self, freq: Frequency) -> RRuleBuilder<WithFreq, End> {
+        self.with(|rrule| rrule.freq(freq))
+    }
+}
+
+impl<Freq> RRuleBuilder<Freq, Unbounded> {
+    /// The number of occurrences, see [`RRule::count`]. It can't be combined with
+    /// [`RRuleBuilder::until`].
+    #[must_use]
+    pub fn count(self, count: u32) -> RRuleBuilder<Freq, WithCount> {
+        self.with(|rrule| rrule.count(count))
+    }
+
+    /// The upper-bound limit of the recurrence, see [`RRule::until`]. It can't be
+    /// combined with [`RRuleBuilder::count`].
+    #[must_use]
+    pub fn until(self, until: DateTime) -> RRuleBuilder<Freq, WithUntil> {
+        self.with(|rrule| rrule.until(until))
+    }
+}
+
+impl<End> RRuleBuilder<WithFreq, End> {
+    /// Returns the unvalidated rule.
+    #[must_use]
+    pub fn build(self) -> RRule<Unvalidated> {
+        self.rrule
+    }
+
+    /// Checks the BYxxx rule parts against the frequency, see [`RRule::validate_by_rules`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ValidationError`] with the rule parts that can't be used with the
+    /// frequency.
+    pub fn validate_by_rules(&self) -> Result<(), ValidationError> {
+        self.rrule.validate_by_rules()
+    }
+
+    /// Validates the rule with the given `dt_start`, see [`RRule::validate`].
+    ///
+    /// # Errors
+    ///
+    /// If the rule parts aren't valid, it will return [`RRuleError`].
+    pub fn validate(self, dt_start: DateTime) -> Result<RRule<Validated>, RRuleError> {
+        self.rrule.validate(dt_start)
+    }
+
+    /// Validates the rule with the given `dt_start` and creates an [`RRuleSet`] with it,
+    /// see [`RRule::build`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError::ValidationError`] in case the rule is invalid.
+    pub fn build_set(self, dt_start: DateTime) -> Result<RRuleSet, RRuleError> {
+        self.rrule.build(dt_start)
+    }
+}
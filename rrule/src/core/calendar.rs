@@ -0,0 +1,105 @@
+//! The recurring events of a whole iCalendar file.
+use super::RRuleSet;
+use crate::parser::{Grammar, ParserConfig};
+use crate::RRuleError;
+
+/// A recurring event of an iCalendar file, returned by [`parse_calendar`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurringEvent {
+    /// The `UID` of the event.
+    uid: Option<String>,
+    /// The `SUMMARY` of the event, unescaped.
+    summary: Option<String>,
+    /// The recurrences of the event, with its overrides.
+    rrule_set: RRuleSet,
+}
+
+impl RecurringEvent {
+    /// Returns the `UID` of the event, or `None` if it has none.
+    #[must_use]
+    pub fn get_uid(&self) -> Option<&str> {
+        self.uid.as_deref()
+    }
+
+    /// Returns the `SUMMARY` of the event, or `None` if it has none. The escaped
+    /// characters of the summary, like `\,`, are unescaped.
+    #[must_use]
+    pub fn get_summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// Returns the recurrences of the event.
+    #[must_use]
+    pub fn get_rruleset(&self) -> &RRuleSet {
+        &self.rrule_set
+    }
+
+    /// Returns the recurrences of the event, without its metadata.
+    #[must_use]
+    pub fn into_rruleset(self) -> RRuleSet {
+        self.rrule_set
+    }
+}
+
+/// Parses the recurring events of a whole iCalendar file, i.e. a `VCALENDAR` with
+/// `VTIMEZONE` and `VEVENT` components.
+///
+/// The `VEVENT` components with the same `UID` are one event: the component without a
+/// `RECURRENCE-ID` has the recurrences of the event, see [`crate::build_rruleset_from_vevent`],
+/// and the components with a `RECURRENCE-ID` are its overrides, see
+/// [`crate::RecurrenceOverride`]. The events are returned in the order of the file.
+///
+/// The events without an `RRULE` or `RDATE`, which only happen once, and the overrides of
+/// events that aren't in the file are left out.
+///
+/// # Errors
+///
+/// Returns [`RRuleError`], if a recurring event is invalid or if there is more than one
+/// `VEVENT` without a `RECURRENCE-ID` for a `UID`.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::parse_calendar;
+///
+/// let events = parse_calendar(
+///     "BEGIN:VCALENDAR\n\
+///      BEGIN:VEVENT\n\
+///      UID:standup@example.com\n\
+///      SUMMARY:Stand-up\\, daily\n\
+///      DTSTART:20210104T090000Z\n\
+///      RRULE:FREQ=DAILY;COUNT=3\n\
+///      END:VEVENT\n\
+///      BEGIN:VEVENT\n\
+///      UID:lunch@example.com\n\
+///      SUMMARY:Lunch\n\
+///      DTSTART:20210104T120000Z\n\
+///      END:VEVENT\n\
+///      BEGIN:VEVENT\n\
+///      UID:standup@example.com\n\
+///      RECURRENCE-ID:20210105T090000Z\n\
+///      DTSTART:20210105T100000Z\n\
+///      END:VEVENT\n\
+///      END:VCALENDAR",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].get_uid(), Some("standup@example.com"));
+/// assert_eq!(events[0].get_summary(), Some("Stand-up, daily"));
+/// let dates = events[0].get_rruleset().clone().all(10).dates;
+/// assert_eq!(dates[1].to_rfc3339(), "2021-01-05T10:00:00+00:00");
+/// ```
+pub fn parse_calendar(input: &str) -> Result<Vec<RecurringEvent>, RRuleError> {
+    let config = ParserConfig::default();
+    Grammar::parse_calendar(input, &config)?
+        .into_iter()
+        .map(|event| {
+            Ok(RecurringEvent {
+                uid: event.uid,
+                summary: event.summary,
+                rrule_set: RRuleSet::from_grammar(event.grammar, &config, None)?,
+            })
+        })
+        .collect()
+}
@@ -29,6 +29,16 @@ pub(crate) fn get_second(dt: &DateTime) -> u8 {
     u8::try_from(dt.second()).expect("second is between 0-59 which is covered by u8")
 }
 
+/// Formats the `TZID` parameter of a date, with the timezone name in double quotes
+/// if it contains a `:`, `;` or `,`, e.g. `;TZID="UTC+05:30"`.
+pub(crate) fn tzid_parameter(tzid: &str) -> String {
+    if tzid.contains([':', ';', ',']) {
+        format!(";TZID=\"{}\"", tzid)
+    } else {
+        format!(";TZID={}", tzid)
+    }
+}
+
 /// Generates an iCalendar date-time string format with the prefix symbols.
 /// Like: `:19970714T173000Z` or `;TZID=America/New_York:19970714T133000`
 /// ref: <https://tools.ietf.org/html/rfc5545#section-3.3.5>
@@ -38,16 +48,34 @@ pub(crate) fn datetime_to_ical_format(dt: &DateTime) -> String {
     let tz = dt.timezone();
     match tz {
         Tz::Local(_) => {}
+        #[cfg(not(feature = "chrono-tz"))]
+        Tz::Utc(_) => {
+            tz_postfix = "Z".to_string();
+        }
+        #[cfg(feature = "chrono-tz")]
         Tz::Tz(tz) => match tz {
             chrono_tz::UTC => {
                 tz_postfix = "Z".to_string();
             }
             tz => {
-                tz_prefix = format!(";TZID={}", tz.name());
+                tz_prefix = tzid_parameter(tz.name());
             }
         },
+        Tz::Custom(tz) => {
+            tz_prefix = tzid_parameter(tz.tzid());
+        }
     }
 
     let dt = dt.format("%Y%m%dT%H%M%S");
     format!("{}:{}{}", tz_prefix, dt, tz_postfix)
 }
+
+/// Generates an iCalendar date string format of the day of `dt` in its timezone, with the
+/// prefix symbols.
+/// Like: `;VALUE=DATE:19970714`
+///
+/// A date can't have a `TZID` parameter, see
+/// <https://tools.ietf.org/html/rfc5545#section-3.2.19>, so it is a floating date.
+pub(crate) fn date_to_ical_format(dt: &DateTime) -> String {
+    format!(";VALUE=DATE:{}", dt.format("%Y%m%d"))
+}
@@ -0,0 +1,75 @@
+//! Policies for local times around daylight saving time transitions.
+
+/// How an occurrence is handled when its local time doesn't exist, because it is in the
+/// gap of a daylight saving time transition, e.g. 02:30 when the clocks go from 02:00
+/// to 03:00.
+///
+/// # Usage
+///
+#[cfg_attr(feature = "chrono-tz", doc = "```")]
+#[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+/// use chrono::TimeZone;
+/// use rrule::{DstGapPolicy, RRuleSet, Tz};
+///
+/// let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Berlin:20210327T023000\nRRULE:FREQ=DAILY;COUNT=2"
+///     .parse()
+///     .unwrap();
+/// let berlin = Tz::Europe__Berlin;
+///
+/// let naive = rrule_set.clone().all(10).dates;
+/// assert_eq!(naive[1], berlin.with_ymd_and_hms(2021, 3, 28, 3, 30, 0).unwrap());
+///
+/// let shifted = rrule_set.clone().dst_gap(DstGapPolicy::ShiftForward).all(10).dates;
+/// assert_eq!(shifted[1], berlin.with_ymd_and_hms(2021, 3, 28, 3, 0, 0).unwrap());
+///
+/// let skipped = rrule_set.dst_gap(DstGapPolicy::Skip).all(10).dates;
+/// assert_eq!(skipped[1], berlin.with_ymd_and_hms(2021, 3, 29, 2, 30, 0).unwrap());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DstGapPolicy {
+    /// Interprets the local time with the offset before the transition, which moves it
+    /// forward by the length of the gap, e.g. 02:30 becomes 03:30.
+    /// This is what RFC 5545 specifies.
+    #[default]
+    Naive,
+    /// Moves the occurrence to the first valid local time, i.e. the time of the
+    /// transition, e.g. 02:30 becomes 03:00.
+    ShiftForward,
+    /// Skips the occurrence. It doesn't count towards the `COUNT` of the rule.
+    Skip,
+}
+
+/// How an occurrence is handled when its local time is ambiguous, because it is in the
+/// hour that is repeated at a daylight saving time transition, e.g. 02:30 when the clocks
+/// go back from 03:00 to 02:00.
+///
+/// # Usage
+///
+#[cfg_attr(feature = "chrono-tz", doc = "```")]
+#[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+/// use rrule::{DstFoldPolicy, RRuleSet};
+///
+/// let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Berlin:20211030T023000\nRRULE:FREQ=DAILY;COUNT=3"
+///     .parse()
+///     .unwrap();
+///
+/// let dates = rrule_set.clone().all(10).dates;
+/// assert_eq!(dates[1].to_rfc3339(), "2021-10-31T02:30:00+02:00");
+///
+/// let dates = rrule_set.clone().dst_fold(DstFoldPolicy::Later).all(10).dates;
+/// assert_eq!(dates[1].to_rfc3339(), "2021-10-31T02:30:00+01:00");
+///
+/// let dates = rrule_set.dst_fold(DstFoldPolicy::Both).all(10).dates;
+/// assert_eq!(dates[1].to_rfc3339(), "2021-10-31T02:30:00+02:00");
+/// assert_eq!(dates[2].to_rfc3339(), "2021-10-31T02:30:00+01:00");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DstFoldPolicy {
+    /// Uses the offset before the transition, i.e. the first instance of the local time.
+    #[default]
+    Earlier,
+    /// Uses the offset after the transition, i.e. the second instance of the local time.
+    Later,
+    /// Returns both instances of the local time. Both count towards the `COUNT` of the rule.
+    Both,
+}
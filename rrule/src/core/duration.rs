@@ -0,0 +1,218 @@
+//! The duration of the recurrences of an event, from its `DURATION` or `DTEND`.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::{Duration, TimeZone};
+
+use super::DateTime;
+use crate::iter::add_time_to_date;
+use crate::{DstFoldPolicy, DstGapPolicy, ParseError};
+
+/// The duration of the recurrences of an [`crate::RRuleSet`], like the `DURATION` of
+/// an event in iCalendar, e.g. `PT1H30M` or `P1D`.
+///
+/// As in RFC 5545, a duration has a number of nominal days, which are calendar days in
+/// the timezone of the recurrences and can be 23 or 25 hours long at a daylight saving
+/// time transition, and a number of exact seconds.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::EventDuration;
+///
+/// let duration: EventDuration = "P1DT2H30M".parse().unwrap();
+/// assert_eq!(duration.get_days(), 1);
+/// assert_eq!(duration.get_seconds(), 2 * 3600 + 30 * 60);
+/// assert_eq!(duration.to_string(), "P1DT2H30M");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventDuration {
+    /// The number of nominal days.
+    days: u32,
+    /// The number of exact seconds.
+    seconds: u64,
+}
+
+impl EventDuration {
+    /// Creates a duration of `days` nominal days and `seconds` exact seconds.
+    #[must_use]
+    pub fn new(days: u32, seconds: u64) -> Self {
+        Self { days, seconds }
+    }
+
+    /// Returns the number of nominal days of the duration.
+    #[must_use]
+    pub fn get_days(&self) -> u32 {
+        self.days
+    }
+
+    /// Returns the number of exact seconds of the duration, after its nominal days.
+    #[must_use]
+    pub fn get_seconds(&self) -> u64 {
+        self.seconds
+    }
+
+    /// Returns the end of a recurrence that starts at `start` and lasts this duration,
+    /// or `None` if it is out of the range of dates.
+    ///
+    /// As in RFC 5545, the nominal days are added in the timezone of `start` and keep
+    /// its local time, and the exact seconds are added after that. A local time that
+    /// doesn't exist at the end is interpreted with the offset before the gap, like
+    /// [`DstGapPolicy::Naive`], and an ambiguous one is the earlier of the two.
+    ///
+    /// # Usage
+    ///
+    #[cfg_attr(feature = "chrono-tz", doc = "```")]
+    #[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+    /// use chrono::TimeZone;
+    /// use rrule::{EventDuration, Tz};
+    ///
+    /// let tz = Tz::Europe__Paris;
+    /// let start = tz.with_ymd_and_hms(2021, 3, 27, 9, 0, 0).unwrap();
+    ///
+    /// // The clocks go forward on 2021-03-28, so that day is 23 hours long.
+    /// let end = EventDuration::new(1, 0).end_of(&start).unwrap();
+    /// assert_eq!(end.to_rfc3339(), "2021-03-28T09:00:00+02:00");
+    /// let end = EventDuration::new(0, 24 * 3600).end_of(&start).unwrap();
+    /// assert_eq!(end.to_rfc3339(), "2021-03-28T10:00:00+02:00");
+    /// ```
+    #[must_use]
+    pub fn end_of(&self, start: &DateTime) -> Option<DateTime> {
+        let mut end = *start;
+        if self.days > 0 {
+            let day = start
+                .date_naive()
+                .checked_add_signed(Duration::days(i64::from(self.days)))?;
+            #[allow(deprecated)]
+            let day = start.timezone().from_local_date(&day).earliest()?;
+            end = add_time_to_date(
+                day,
+                start.time(),
+                DstGapPolicy::Naive,
+                DstFoldPolicy::Earlier,
+            )
+            .next()?;
+        }
+        // `Duration::seconds` panics above `i64::MAX / 1000` seconds.
+        let seconds = i64::try_from(self.seconds)
+            .ok()
+            .filter(|seconds| *seconds <= i64::MAX / 1000)?;
+        end.checked_add_signed(Duration::seconds(seconds))
+    }
+}
+
+impl FromStr for EventDuration {
+    type Err = ParseError;
+
+    /// Parses a `DURATION` value of RFC 5545. Negative durations are not valid for
+    /// the recurrences of an event.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::InvalidDuration(s.into());
+        let value = s.trim().to_uppercase();
+        let value = value.strip_prefix('+').unwrap_or(&value);
+        let value = value.strip_prefix('P').ok_or_else(invalid)?;
+        let (date, time) = match value.split_once('T') {
+            Some((_, "")) => return Err(invalid()),
+            Some((date, time)) => (date, time),
+            None => (value, ""),
+        };
+        if date.is_empty() && time.is_empty() {
+            return Err(invalid());
+        }
+
+        // The units of each part, in the order they have to be in, and their length.
+        let date_units: [(char, u64); 2] = [('W', 7), ('D', 1)];
+        let time_units: [(char, u64); 3] = [('H', 3600), ('M', 60), ('S', 1)];
+        let days = parse_units(date, &date_units).ok_or_else(invalid)?;
+        let seconds = parse_units(time, &time_units).ok_or_else(invalid)?;
+
+        Ok(Self {
+            days: u32::try_from(days).map_err(|_| invalid())?,
+            seconds,
+        })
+    }
+}
+
+/// Parses a list of numbers followed by their unit, like `1H30M`, and returns the sum
+/// of the numbers times the length of their unit. The units have to be in the order
+/// of `units`, and each unit can be used once.
+fn parse_units(value: &str, units: &[(char, u64)]) -> Option<u64> {
+    let mut total: u64 = 0;
+    let mut units = units.iter();
+    let mut rest = value;
+    while !rest.is_empty() {
+        let unit_idx = rest.find(|c: char| !c.is_ascii_digit())?;
+        let number: u64 = rest[..unit_idx].parse().ok()?;
+        let unit = rest[unit_idx..].chars().next()?;
+        let (_, length) = units.find(|(name, _)| *name == unit)?;
+        total = total.checked_add(number.checked_mul(*length)?)?;
+        rest = &rest[unit_idx + 1..];
+    }
+    Some(total)
+}
+
+impl Display for EventDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "P")?;
+        if self.days > 0 && self.days % 7 == 0 && self.seconds == 0 {
+            return write!(f, "{}W", self.days / 7);
+        }
+        if self.days > 0 {
+            write!(f, "{}D", self.days)?;
+        }
+        if self.seconds == 0 && self.days > 0 {
+            return Ok(());
+        }
+        write!(f, "T")?;
+        let (hours, minutes, seconds) = (
+            self.seconds / 3600,
+            self.seconds / 60 % 60,
+            self.seconds % 60,
+        );
+        if hours > 0 {
+            write!(f, "{}H", hours)?;
+        }
+        if minutes > 0 {
+            write!(f, "{}M", minutes)?;
+        }
+        if seconds > 0 || self.seconds == 0 {
+            write!(f, "{}S", seconds)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_durations() {
+        let tests = [
+            ("PT1H", EventDuration::new(0, 3600), "PT1H"),
+            ("P1DT2H30M", EventDuration::new(1, 9000), "P1DT2H30M"),
+            ("+P2W", EventDuration::new(14, 0), "P2W"),
+            ("P7D", EventDuration::new(7, 0), "P1W"),
+            ("PT90M", EventDuration::new(0, 5400), "PT1H30M"),
+            ("pt15m20s", EventDuration::new(0, 920), "PT15M20S"),
+            ("PT0S", EventDuration::new(0, 0), "PT0S"),
+        ];
+        for (input, duration, output) in tests {
+            assert_eq!(input.parse(), Ok(duration));
+            assert_eq!(duration.to_string(), output);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        let tests = [
+            "", "P", "PT", "1H", "-PT1H", "PT1D", "P1H", "PT1M1H", "PT1H1H", "P1DT",
+        ];
+        for input in tests {
+            assert_eq!(
+                input.parse::<EventDuration>(),
+                Err(ParseError::InvalidDuration(input.into()))
+            );
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! The date of a recurrence, which is a calendar day for all-day events.
+use chrono::NaiveDate;
+
+use super::DateTime;
+
+/// The date of a recurrence of an [`crate::RRuleSet`], returned by
+/// [`crate::RRuleSet::iter_event_dates`].
+///
+/// The recurrences of an all-day event, which has a `DTSTART;VALUE=DATE`, are calendar
+/// days without a time or a timezone, and those of other events are datetimes.
+///
+/// # Usage
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use rrule::{EventDate, RRuleSet};
+///
+/// let rrule_set: RRuleSet = "DTSTART;VALUE=DATE:20240101\nRRULE:FREQ=YEARLY;COUNT=2"
+///     .parse()
+///     .unwrap();
+///
+/// let dates = rrule_set.all_event_dates(10);
+/// assert_eq!(dates[1], EventDate::Date(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventDate {
+    /// The day of a recurrence of an all-day event.
+    Date(NaiveDate),
+    /// The start of a recurrence of an event with a time.
+    DateTime(DateTime),
+}
+
+impl EventDate {
+    /// Returns `true` if the recurrence is a whole day.
+    #[must_use]
+    pub fn is_all_day(&self) -> bool {
+        matches!(self, Self::Date(_))
+    }
+
+    /// Returns the day of the recurrence, in the timezone of its datetime.
+    #[must_use]
+    pub fn date_naive(&self) -> NaiveDate {
+        match self {
+            Self::Date(date) => *date,
+            Self::DateTime(datetime) => datetime.date_naive(),
+        }
+    }
+}
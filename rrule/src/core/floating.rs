@@ -0,0 +1,105 @@
+//! Recurrences of sets with a floating start date, i.e. a start date without a timezone.
+use super::DateTime;
+use crate::{RRule, RRuleSet, Tz};
+use chrono::{NaiveDateTime, TimeZone};
+
+/// The recurrences of an [`RRuleSet`] with a floating start date, i.e. a `DTSTART`
+/// without a `TZID` or `Z`, as local wall-clock times.
+///
+/// A floating time like 09:00 means 09:00 wherever the user is. The recurrences are
+/// computed on the wall-clock times, so they are not affected by the daylight saving
+/// time transitions of the system timezone, and every local time exists exactly once.
+///
+/// Created with [`RRuleSet::floating`].
+///
+/// # Usage
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use rrule::RRuleSet;
+///
+/// let rrule_set: RRuleSet = "DTSTART:20210327T023000\nRRULE:FREQ=DAILY;COUNT=3"
+///     .parse()
+///     .unwrap();
+/// let floating = rrule_set.floating().unwrap();
+///
+/// let dates = floating.all(10);
+/// assert_eq!(dates.len(), 3);
+/// assert_eq!(
+///     dates[1],
+///     NaiveDate::from_ymd_opt(2021, 3, 28).unwrap().and_hms_opt(2, 30, 0).unwrap()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FloatingRRuleSet {
+    /// The set with the wall-clock times as UTC datetimes, which don't have
+    /// daylight saving time.
+    rrule_set: RRuleSet,
+}
+
+impl FloatingRRuleSet {
+    pub(crate) fn new(rrule_set: &RRuleSet) -> Self {
+        let dates = |dates: &[DateTime]| dates.iter().map(to_wall_clock).collect();
+        let rrules = |rrules: &[RRule]| rrules.iter().map(wall_clock_rrule).collect();
+        Self {
+            rrule_set: RRuleSet {
+                rrule: rrules(&rrule_set.rrule),
+                rdate: dates(&rrule_set.rdate),
+                exrule: rrules(&rrule_set.exrule),
+                exdate: dates(&rrule_set.exdate),
+                dt_start: to_wall_clock(&rrule_set.dt_start),
+                before: rrule_set.before.as_ref().map(to_wall_clock),
+                after: rrule_set.after.as_ref().map(to_wall_clock),
+                ..rrule_set.clone()
+            },
+        }
+    }
+
+    /// Returns an iterator over the recurrences of the set.
+    ///
+    /// Validation limits are only enforced if [`RRuleSet::limit`] was called on the set.
+    pub fn iter(&self) -> impl Iterator<Item = NaiveDateTime> + '_ {
+        self.rrule_set.iter().map(|date| date.naive_utc())
+    }
+
+    /// Returns all the recurrences of the set, up to `limit`, see [`RRuleSet::all`].
+    #[must_use]
+    pub fn all(&self, limit: u16) -> Vec<NaiveDateTime> {
+        to_naive(self.rrule_set.clone().all(limit).dates)
+    }
+
+    /// Returns all the recurrences of the set between `after` and `before`,
+    /// see [`RRuleSet::between`].
+    #[must_use]
+    pub fn between(
+        &self,
+        after: NaiveDateTime,
+        before: NaiveDateTime,
+        inclusive: bool,
+    ) -> Vec<NaiveDateTime> {
+        let result = self.rrule_set.between(
+            Tz::UTC.from_utc_datetime(&after),
+            Tz::UTC.from_utc_datetime(&before),
+            inclusive,
+        );
+        to_naive(result.dates)
+    }
+}
+
+/// Returns the wall-clock time of a date of the set as a UTC datetime. Dates with a
+/// timezone are converted to the local timezone, in which floating dates are parsed.
+fn to_wall_clock(date: &DateTime) -> DateTime {
+    Tz::UTC.from_utc_datetime(&date.with_timezone(&Tz::LOCAL).naive_local())
+}
+
+fn wall_clock_rrule(rrule: &RRule) -> RRule {
+    RRule {
+        until: rrule.until.as_ref().map(to_wall_clock),
+        dt_start: rrule.dt_start.as_ref().map(to_wall_clock),
+        ..rrule.clone()
+    }
+}
+
+fn to_naive(dates: Vec<DateTime>) -> Vec<NaiveDateTime> {
+    dates.iter().map(DateTime::naive_utc).collect()
+}
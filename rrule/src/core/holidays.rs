@@ -0,0 +1,196 @@
+//! Calendars of holidays that are excluded from the recurrences of a set, or that the
+//! recurrences are moved away from.
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Weekday};
+
+use super::DateTime;
+use crate::iter::add_time_to_date;
+use crate::{DstFoldPolicy, DstGapPolicy};
+
+/// A calendar of holidays, like the public holidays of a country or the closing days of
+/// a business.
+///
+/// The recurrences of an [`crate::RRuleSet`] on a holiday are left out with
+/// [`crate::RRuleSet::exclude_holidays`], instead of listing them as `EXDATE`s.
+/// The dates are compared with the local date of the recurrences.
+///
+/// # Usage
+///
+/// ```
+/// use chrono::{Datelike, NaiveDate};
+/// use rrule::{HolidayCalendar, RRuleSet};
+///
+/// #[derive(Debug)]
+/// struct NewYear;
+///
+/// impl HolidayCalendar for NewYear {
+///     fn is_holiday(&self, date: NaiveDate) -> bool {
+///         date.month() == 1 && date.day() == 1
+///     }
+/// }
+///
+/// let rrule_set: RRuleSet = "DTSTART:20201231T090000Z\nRRULE:FREQ=DAILY;COUNT=3"
+///     .parse()
+///     .unwrap();
+/// let dates = rrule_set.exclude_holidays(NewYear).all(10).dates;
+/// assert_eq!(dates.len(), 2);
+/// assert_eq!(dates[1].to_rfc3339(), "2021-01-02T09:00:00+00:00");
+/// ```
+pub trait HolidayCalendar: Debug + Send + Sync {
+    /// Returns `true` if `date` is a holiday.
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+}
+
+impl HolidayCalendar for BTreeSet<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for HashSet<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidayCalendar for Vec<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl<T: HolidayCalendar + ?Sized> HolidayCalendar for Arc<T> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        (**self).is_holiday(date)
+    }
+}
+
+/// Returns `true` if the local date of `date` is a holiday in one of the `calendars`.
+pub(crate) fn is_holiday(calendars: &[Arc<dyn HolidayCalendar>], date: &DateTime) -> bool {
+    let date = date.date_naive();
+    calendars.iter().any(|calendar| calendar.is_holiday(date))
+}
+
+/// Returns `true` if both lists have the same calendars, in the same order.
+pub(crate) fn has_same_calendars(
+    calendars: &[Arc<dyn HolidayCalendar>],
+    other_calendars: &[Arc<dyn HolidayCalendar>],
+) -> bool {
+    calendars.len() == other_calendars.len()
+        && calendars
+            .iter()
+            .zip(other_calendars)
+            .all(|(calendar, other)| is_same_calendar(calendar, other))
+}
+
+/// Returns `true` if both calendars are the same instance.
+fn is_same_calendar(calendar: &Arc<dyn HolidayCalendar>, other: &Arc<dyn HolidayCalendar>) -> bool {
+    Arc::as_ptr(calendar).cast::<()>() == Arc::as_ptr(other).cast::<()>()
+}
+
+/// How the recurrences on a weekend or a holiday are moved to a business day, i.e. a day
+/// from Monday to Friday that isn't a holiday, with [`crate::RRuleSet::shift_to_business_days`].
+///
+/// The recurrences keep their local time. They are moved by at most 31 days, and left out
+/// if there is no business day in that range.
+///
+/// # Usage
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use rrule::{BusinessDayPolicy, RRuleSet};
+///
+/// // The 15th of May 2021 is a Saturday, and the 15th of August 2021 is a Sunday.
+/// let rrule_set: RRuleSet = "DTSTART:20210515T090000Z\nRRULE:FREQ=MONTHLY;BYMONTH=5,8;COUNT=2"
+///     .parse()
+///     .unwrap();
+/// let no_holidays: Vec<NaiveDate> = vec![];
+///
+/// let dates = rrule_set
+///     .clone()
+///     .shift_to_business_days(BusinessDayPolicy::Next, no_holidays.clone())
+///     .all(10)
+///     .dates;
+/// assert_eq!(dates[0].to_rfc3339(), "2021-05-17T09:00:00+00:00");
+/// assert_eq!(dates[1].to_rfc3339(), "2021-08-16T09:00:00+00:00");
+///
+/// let dates = rrule_set
+///     .shift_to_business_days(BusinessDayPolicy::Nearest, no_holidays)
+///     .all(10)
+///     .dates;
+/// assert_eq!(dates[0].to_rfc3339(), "2021-05-14T09:00:00+00:00");
+/// assert_eq!(dates[1].to_rfc3339(), "2021-08-16T09:00:00+00:00");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusinessDayPolicy {
+    /// Moves the recurrences to the next business day.
+    Next,
+    /// Moves the recurrences to the previous business day.
+    Previous,
+    /// Moves the recurrences to the nearest business day, e.g. from a Saturday to the
+    /// Friday before and from a Sunday to the Monday after. On a tie, the next business
+    /// day is used.
+    Nearest,
+}
+
+/// The number of days that a recurrence is moved at most.
+pub(crate) const MAX_SHIFT_DAYS: i64 = 31;
+
+/// Moves recurrences to business days with a [`BusinessDayPolicy`].
+#[derive(Debug, Clone)]
+pub(crate) struct BusinessDays {
+    pub policy: BusinessDayPolicy,
+    pub calendar: Arc<dyn HolidayCalendar>,
+}
+
+impl BusinessDays {
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.calendar.is_holiday(date)
+    }
+
+    /// Returns the business day for `date`, or `None` if there is none within
+    /// [`MAX_SHIFT_DAYS`].
+    fn business_day(&self, date: NaiveDate) -> Option<NaiveDate> {
+        (0..=MAX_SHIFT_DAYS).find_map(|days| {
+            let next = date.checked_add_signed(Duration::days(days));
+            let previous = date.checked_sub_signed(Duration::days(days));
+            let candidates = match self.policy {
+                BusinessDayPolicy::Next => [next, None],
+                BusinessDayPolicy::Previous => [previous, None],
+                BusinessDayPolicy::Nearest => [next, previous],
+            };
+            candidates
+                .into_iter()
+                .flatten()
+                .find(|date| self.is_business_day(*date))
+        })
+    }
+
+    /// Moves `date` to the business day for its local date, at the same local time.
+    /// Returns `None` if there is no business day, or if the local time doesn't exist
+    /// on that day and `dst_gap` skips it.
+    pub(crate) fn shift(
+        &self,
+        date: &DateTime,
+        dst_gap: DstGapPolicy,
+        dst_fold: DstFoldPolicy,
+    ) -> Option<DateTime> {
+        let local = date.date_naive();
+        let day = self.business_day(local)?;
+        if day == local {
+            return Some(*date);
+        }
+        #[allow(deprecated)]
+        let day = date.timezone().from_local_date(&day).earliest()?;
+        add_time_to_date(day, date.time(), dst_gap, dst_fold).next()
+    }
+}
+
+impl PartialEq for BusinessDays {
+    fn eq(&self, other: &Self) -> bool {
+        self.policy == other.policy && is_same_calendar(&self.calendar, &other.calendar)
+    }
+}
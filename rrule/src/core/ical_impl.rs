@@ -0,0 +1,107 @@
+//! Conversions from the properties and events parsed by the [`ical`] crate to the rules and
+//! sets of this crate.
+use ical::parser::ical::component::IcalEvent;
+use ical::property::Property;
+
+use crate::{ParseError, RRule, RRuleError, RRuleSet, Unvalidated};
+
+/// The names of the properties of an event that are used by an [`RRuleSet`].
+const PROPERTIES: [&str; 7] = [
+    "DTSTART", "DTEND", "DURATION", "RRULE", "RDATE", "EXRULE", "EXDATE",
+];
+
+impl TryFrom<&Property> for RRule<Unvalidated> {
+    type Error = RRuleError;
+
+    /// Parses the rule parts of an `RRULE` or `EXRULE` property.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use ical::property::Property;
+    /// use rrule::{Frequency, RRule, Unvalidated};
+    ///
+    /// let property = Property {
+    ///     name: "RRULE".into(),
+    ///     params: None,
+    ///     value: Some("FREQ=WEEKLY;BYDAY=MO,FR".into()),
+    /// };
+    /// let rrule = RRule::<Unvalidated>::try_from(&property).unwrap();
+    /// assert_eq!(rrule.get_freq(), Frequency::Weekly);
+    /// ```
+    fn try_from(property: &Property) -> Result<Self, Self::Error> {
+        let name = &property.name;
+        if !name.eq_ignore_ascii_case("RRULE") && !name.eq_ignore_ascii_case("EXRULE") {
+            return Err(ParseError::UnrecognizedPropertyName(name.clone()).into());
+        }
+        property.value.as_deref().unwrap_or_default().parse()
+    }
+}
+
+impl TryFrom<&[Property]> for RRuleSet {
+    type Error = RRuleError;
+
+    /// Creates a set from the `DTSTART`, `DTEND`, `DURATION`, `RRULE`, `RDATE`, `EXRULE`
+    /// and `EXDATE` properties of an event. The other properties are ignored.
+    fn try_from(properties: &[Property]) -> Result<Self, Self::Error> {
+        let lines: Vec<_> = properties
+            .iter()
+            .filter(|property| PROPERTIES.contains(&property.name.to_uppercase().as_str()))
+            .map(to_content_line)
+            .collect();
+        lines.join("\n").parse()
+    }
+}
+
+impl TryFrom<&IcalEvent> for RRuleSet {
+    type Error = RRuleError;
+
+    /// Creates a set from the `DTSTART`, `DTEND`, `DURATION`, `RRULE`, `RDATE`, `EXRULE`
+    /// and `EXDATE` properties of an event. The other properties are ignored.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use ical::IcalParser;
+    /// use rrule::RRuleSet;
+    ///
+    /// let ics = "BEGIN:VCALENDAR\n\
+    ///     BEGIN:VEVENT\n\
+    ///     UID:stand-up\n\
+    ///     SUMMARY:Stand-up\n\
+    ///     DTSTART;TZID=Europe/Berlin:20210104T093000\n\
+    ///     RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4\n\
+    ///     EXDATE:20210106T083000Z\n\
+    ///     END:VEVENT\n\
+    ///     END:VCALENDAR\n";
+    ///
+    /// let calendar = IcalParser::new(ics.as_bytes()).next().unwrap().unwrap();
+    /// let rrule_set = RRuleSet::try_from(&calendar.events[0]).unwrap();
+    /// let dates = rrule_set.all(10).dates;
+    /// assert_eq!(dates.len(), 3);
+    /// assert_eq!(dates[1].to_rfc3339(), "2021-01-11T09:30:00+01:00");
+    /// ```
+    fn try_from(event: &IcalEvent) -> Result<Self, Self::Error> {
+        Self::try_from(event.properties.as_slice())
+    }
+}
+
+/// Converts a property to a content line, with the parameter values in double quotes if
+/// they contain a `:`, `;` or `,`.
+fn to_content_line(property: &Property) -> String {
+    let mut line = property.name.clone();
+    for (key, values) in property.params.iter().flatten() {
+        let values: Vec<_> = values
+            .iter()
+            .map(|value| {
+                if value.contains([':', ';', ',']) {
+                    format!("\"{}\"", value)
+                } else {
+                    value.clone()
+                }
+            })
+            .collect();
+        line.push_str(&format!(";{}={}", key, values.join(",")));
+    }
+    format!("{}:{}", line, property.value.as_deref().unwrap_or_default())
+}
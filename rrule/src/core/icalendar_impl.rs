@@ -0,0 +1,164 @@
+//! Conversions between the rules and sets of this crate and the events and properties of
+//! the [`icalendar`] crate.
+use icalendar::{Component, Event, Property};
+
+use crate::parser::{find_unquoted, split_unquoted};
+use crate::{ParseError, RRule, RRuleError, RRuleSet, Unvalidated};
+
+/// The names of the properties of an event that are used by an [`RRuleSet`].
+const PROPERTIES: [&str; 7] = [
+    "DTSTART", "DTEND", "DURATION", "RRULE", "RDATE", "EXRULE", "EXDATE",
+];
+
+/// Properties that can be given more than once in an event.
+const MULTI_PROPERTIES: [&str; 4] = ["RRULE", "RDATE", "EXRULE", "EXDATE"];
+
+impl From<&RRule> for Property {
+    /// Creates an `RRULE` property with the rule parts of the rule.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use icalendar::Property;
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3".parse().unwrap();
+    /// let property = Property::from(&rrule_set.get_rrule()[0]);
+    /// assert_eq!(property.key(), "RRULE");
+    /// assert_eq!(property.value(), "FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0");
+    /// ```
+    fn from(rrule: &RRule) -> Self {
+        Self::new("RRULE", rrule.value_to_string())
+    }
+}
+
+impl TryFrom<&Property> for RRule<Unvalidated> {
+    type Error = RRuleError;
+
+    /// Parses the rule parts of an `RRULE` or `EXRULE` property.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use icalendar::Property;
+    /// use rrule::{Frequency, RRule, Unvalidated};
+    ///
+    /// let property = Property::new("RRULE", "FREQ=WEEKLY;BYDAY=MO,FR");
+    /// let rrule = RRule::<Unvalidated>::try_from(&property).unwrap();
+    /// assert_eq!(rrule.get_freq(), Frequency::Weekly);
+    /// ```
+    fn try_from(property: &Property) -> Result<Self, Self::Error> {
+        let key = property.key();
+        if !key.eq_ignore_ascii_case("RRULE") && !key.eq_ignore_ascii_case("EXRULE") {
+            return Err(ParseError::UnrecognizedPropertyName(key.into()).into());
+        }
+        property.value().parse()
+    }
+}
+
+impl From<&RRuleSet> for Event {
+    /// Creates an event with the `DTSTART`, `DURATION`, `RRULE`, `RDATE`, `EXRULE` and
+    /// `EXDATE` properties of the set.
+    ///
+    /// The overrides of the set are not included, as they are events of their own with a
+    /// `RECURRENCE-ID`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use icalendar::{Component, Event};
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Berlin:20210101T090000\n\
+    ///     RRULE:FREQ=DAILY;COUNT=3"
+    ///     .parse()
+    ///     .unwrap();
+    /// let event = Event::from(&rrule_set);
+    /// assert_eq!(event.property_value("DTSTART"), Some("20210101T090000"));
+    /// assert_eq!(RRuleSet::try_from(&event).unwrap(), rrule_set);
+    /// ```
+    fn from(rrule_set: &RRuleSet) -> Self {
+        let mut event = Self::new();
+        let lines = rrule_set.to_string();
+        for line in lines.lines().take_while(|line| *line != "BEGIN:VEVENT") {
+            let Some(property) = to_property(line) else {
+                continue;
+            };
+            if MULTI_PROPERTIES.contains(&property.key()) {
+                event.append_multi_property(property);
+            } else {
+                event.append_property(property);
+            }
+        }
+        event.done()
+    }
+}
+
+impl TryFrom<&Event> for RRuleSet {
+    type Error = RRuleError;
+
+    /// Creates a set from the `DTSTART`, `DTEND`, `DURATION`, `RRULE`, `RDATE`, `EXRULE`
+    /// and `EXDATE` properties of an event. The other properties are ignored.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use icalendar::{Component, Event, EventLike, Property};
+    /// use rrule::RRuleSet;
+    ///
+    /// let event = Event::new()
+    ///     .summary("Stand-up")
+    ///     .append_property(
+    ///         Property::new("DTSTART", "20210104T093000")
+    ///             .add_parameter("TZID", "Europe/Berlin")
+    ///             .done(),
+    ///     )
+    ///     .add_property("DURATION", "PT15M")
+    ///     .add_multi_property("RRULE", "FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4")
+    ///     .add_multi_property("EXDATE", "20210106T083000Z")
+    ///     .done();
+    ///
+    /// let rrule_set = RRuleSet::try_from(&event).unwrap();
+    /// let dates = rrule_set.all(10).dates;
+    /// assert_eq!(dates.len(), 3);
+    /// assert_eq!(dates[1].to_rfc3339(), "2021-01-11T09:30:00+01:00");
+    /// ```
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        let properties = event
+            .properties()
+            .values()
+            .chain(event.multi_properties().values().flatten())
+            .filter(|property| PROPERTIES.contains(&property.key().to_uppercase().as_str()));
+        let lines: Vec<_> = properties.map(to_content_line).collect();
+        lines.join("\n").parse()
+    }
+}
+
+/// Converts a content line, like `DTSTART;TZID=Europe/Berlin:20210101T090000`, to a
+/// property, or returns `None` if it has no value.
+fn to_property(line: &str) -> Option<Property> {
+    let colon = find_unquoted(line, ':')?;
+    let mut parts = split_unquoted(&line[..colon], ';').into_iter();
+    let mut property = Property::new(parts.next()?, &line[colon + 1..]);
+    for parameter in parts {
+        if let Some((key, value)) = parameter.split_once('=') {
+            property.add_parameter(key, value.trim_matches('"'));
+        }
+    }
+    Some(property.done())
+}
+
+/// Converts a property to a content line, with the parameter values in double quotes if
+/// they contain a `:`, `;` or `,`.
+fn to_content_line(property: &Property) -> String {
+    let mut line = property.key().to_string();
+    for parameter in property.params().values() {
+        let value = parameter.value();
+        if value.contains([':', ';', ',']) {
+            line.push_str(&format!(";{}=\"{}\"", parameter.key(), value));
+        } else {
+            line.push_str(&format!(";{}={}", parameter.key(), value));
+        }
+    }
+    format!("{}:{}", line, property.value())
+}
@@ -0,0 +1,185 @@
+//! Support for the jCal representation of recurrence rules.
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc7265#section-3.6.10>.
+use crate::core::{ByRule, OccurrenceCache};
+use crate::parser::{datestring_to_date, str_to_weekday};
+use crate::{Frequency, NWeekday, ParseError, RRule, RRuleError, RScale, Skip, Unvalidated};
+use serde_with::serde::{Deserialize, Serialize};
+use serde_with::{serde_as, OneOrMany};
+use std::marker::PhantomData;
+
+/// A `RECUR` value in the jCal format (RFC 7265), e.g.
+/// `{"freq": "WEEKLY", "count": 5, "byday": ["MO", "FR"]}`.
+///
+/// Rule parts with multiple values are written as an array, or as a single value
+/// if there is only one. Both forms are accepted when deserializing.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{JCalRecur, RRule, Unvalidated};
+///
+/// let jcal: JCalRecur = serde_json::from_str(r#"{"freq": "WEEKLY", "count": 5, "byday": ["MO", "FR"]}"#).unwrap();
+/// let rrule = RRule::<Unvalidated>::try_from(jcal).unwrap();
+/// assert_eq!(rrule.to_string(), "FREQ=WEEKLY;COUNT=5;BYDAY=MO,FR");
+///
+/// let jcal = JCalRecur::from(&rrule);
+/// assert_eq!(
+///     serde_json::to_string(&jcal).unwrap(),
+///     r#"{"freq":"WEEKLY","count":5,"byday":["MO","FR"]}"#
+/// );
+/// ```
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "serde_with::serde")]
+pub struct JCalRecur {
+    freq: Frequency,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    until: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    count: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interval: Option<u16>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bysecond: Vec<u8>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    byminute: Vec<u8>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    byhour: Vec<u8>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    byday: Vec<NWeekday>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bymonthday: Vec<i8>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    byyearday: Vec<i16>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    byweekno: Vec<i8>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bymonth: Vec<JCalMonth>,
+    #[serde_as(as = "OneOrMany<_>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bysetpos: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wkst: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    byeaster: Option<i16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rscale: Option<RScale>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    skip: Option<Skip>,
+}
+
+/// A `bymonth` value, which is a string for a leap month of RFC 7529, e.g. `"5L"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "serde_with::serde", untagged)]
+enum JCalMonth {
+    Month(u8),
+    LeapMonth(String),
+}
+
+impl<S> From<&RRule<S>> for JCalRecur {
+    fn from(rrule: &RRule<S>) -> Self {
+        let mut bymonthday = rrule.by_month_day.to_vec();
+        bymonthday.extend(&rrule.by_n_month_day);
+
+        Self {
+            freq: rrule.freq,
+            // jCal uses the extended ISO 8601 format for date-times.
+            until: rrule.until.as_ref().map(|until| {
+                let maybe_zulu = if until.timezone().is_local() { "" } else { "Z" };
+                format!("{}{}", until.format("%Y-%m-%dT%H:%M:%S"), maybe_zulu)
+            }),
+            count: rrule.count,
+            interval: Some(rrule.interval).filter(|interval| *interval != 1),
+            bysecond: rrule.by_second.to_vec(),
+            byminute: rrule.by_minute.to_vec(),
+            byhour: rrule.by_hour.to_vec(),
+            byday: rrule.by_weekday.to_vec(),
+            bymonthday,
+            byyearday: rrule.by_year_day.to_vec(),
+            byweekno: rrule.by_week_no.to_vec(),
+            bymonth: rrule
+                .month_values()
+                .into_iter()
+                .map(|month| {
+                    month
+                        .parse()
+                        .map_or(JCalMonth::LeapMonth(month), JCalMonth::Month)
+                })
+                .collect(),
+            bysetpos: rrule.by_set_pos.to_vec(),
+            wkst: Some(rrule.week_start)
+                .filter(|week_start| *week_start != chrono::Weekday::Mon)
+                .map(|week_start| NWeekday::Every(week_start).to_string()),
+            byeaster: rrule.by_easter,
+            rscale: rrule.rscale,
+            skip: Some(rrule.skip).filter(|skip| *skip != Skip::Omit),
+        }
+    }
+}
+
+impl TryFrom<JCalRecur> for RRule<Unvalidated> {
+    type Error = RRuleError;
+
+    fn try_from(jcal: JCalRecur) -> Result<Self, Self::Error> {
+        let until = jcal
+            .until
+            .map(|until| {
+                let until = until.replace(['-', ':'], "");
+                datestring_to_date(&until, None, "UNTIL")
+            })
+            .transpose()?;
+        let week_start = jcal
+            .wkst
+            .map(|wkst| str_to_weekday(&wkst).map_err(|_| ParseError::InvalidWeekdayStart(wkst)))
+            .transpose()?;
+        let mut by_month = vec![];
+        let mut by_leap_month = vec![];
+        for month in jcal.bymonth {
+            match month {
+                JCalMonth::Month(month) => by_month.push(month),
+                JCalMonth::LeapMonth(value) => {
+                    let month = value
+                        .strip_suffix(['L', 'l'])
+                        .and_then(|month| month.parse().ok())
+                        .ok_or(ParseError::InvalidByMonth(value))?;
+                    by_leap_month.push(month);
+                }
+            }
+        }
+
+        Ok(Self {
+            freq: jcal.freq,
+            interval: jcal.interval.unwrap_or(1),
+            count: jcal.count,
+            until,
+            week_start: week_start.unwrap_or(chrono::Weekday::Mon),
+            by_set_pos: jcal.bysetpos.into(),
+            by_month: by_month.into(),
+            by_leap_month: by_leap_month.into(),
+            by_month_day: jcal.bymonthday.into(),
+            by_n_month_day: ByRule::new(),
+            by_year_day: jcal.byyearday.into(),
+            by_week_no: jcal.byweekno.into(),
+            by_weekday: jcal.byday.into(),
+            by_hour: jcal.byhour.into(),
+            by_minute: jcal.byminute.into(),
+            by_second: jcal.bysecond.into(),
+            by_easter: jcal.byeaster,
+            rscale: jcal.rscale,
+            skip: jcal.skip.unwrap_or_default(),
+            leap_day: Skip::Omit,
+            dt_start: None,
+            cache: OccurrenceCache::default(),
+            stage: PhantomData,
+        })
+    }
+}
@@ -1,21 +1,67 @@
+mod builder;
+mod calendar;
 mod datetime;
+mod dst;
+mod duration;
+mod event_date;
+mod floating;
+mod holidays;
+#[cfg(feature = "ical")]
+mod ical_impl;
+#[cfg(feature = "icalendar")]
+mod icalendar_impl;
+#[cfg(feature = "serde")]
+mod jcal;
+mod occurrence_cache;
+#[cfg(feature = "serde")]
+mod options;
+mod recurrence_override;
 mod rrule;
 mod rruleset;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod stats;
+mod text;
+mod text_locale;
 mod timezone;
 mod timezone_impl;
 pub(crate) mod utils;
+mod vtimezone;
+#[cfg(feature = "xcal")]
+mod xcal;
 
-pub use self::rrule::{Frequency, NWeekday, RRule};
-pub use self::rruleset::{RRuleResult, RRuleSet};
+pub use self::builder::{NoFreq, RRuleBuilder, Unbounded, WithCount, WithFreq, WithUntil};
+pub use self::calendar::{parse_calendar, RecurringEvent};
+#[cfg(feature = "serde")]
+pub use self::jcal::JCalRecur;
+pub(crate) use self::occurrence_cache::OccurrenceCache;
+#[cfg(feature = "serde")]
+pub use self::options::RRuleOptions;
+pub(crate) use self::rrule::ByRule;
+pub use self::rrule::{Frequency, NWeekday, RRule, RScale, Skip};
+pub use self::rruleset::{
+    build_rruleset_from_vevent, build_rruleset_with_warnings, build_rrulesets, DuplicatePolicy,
+    OccurrenceDiff, RRuleResult, RRuleSet,
+};
+pub use self::stats::OccurrenceStats;
+pub use self::text_locale::{Language, TextLocale};
 pub(crate) use datetime::{
     duration_from_midnight, get_day, get_hour, get_minute, get_month, get_second, DateTime,
 };
+pub use dst::{DstFoldPolicy, DstGapPolicy};
+pub use duration::EventDuration;
+pub use event_date::EventDate;
+pub use floating::FloatingRRuleSet;
+pub(crate) use holidays::{has_same_calendars, is_holiday, BusinessDays, MAX_SHIFT_DAYS};
+pub use holidays::{BusinessDayPolicy, HolidayCalendar};
+pub use recurrence_override::RecurrenceOverride;
 pub use timezone::Tz;
+pub use vtimezone::VTimezone;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 /// An empty struct to keep the validated stage
 pub struct Validated;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 /// An empty struct to keep the unvalidated (or not-yet-validated) stage
 pub struct Unvalidated;
@@ -0,0 +1,163 @@
+//! A cache of the recurrences of a rule within the windows queried with
+//! [`crate::RRule::between`], see [`crate::RRule::with_cache`].
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use super::{DateTime, RRuleResult};
+
+/// The number of windows after which the cache is cleared, to bound its memory.
+const MAX_CACHED_WINDOWS: usize = 64;
+
+/// The `after` and `before` bounds of a window, and if they are inclusive.
+type Window = (DateTime, DateTime, bool);
+
+/// The recurrences of the windows queried on a rule, which are shared by its clones.
+///
+/// It is disabled by default. It doesn't take part in the comparison and the hash of the
+/// rule, as it only holds what the rule parts produce.
+#[derive(Clone, Default)]
+pub(crate) struct OccurrenceCache(Option<Arc<RwLock<HashMap<Window, RRuleResult>>>>);
+
+impl OccurrenceCache {
+    /// Creates an empty cache, which stores the recurrences of the queried windows.
+    pub(crate) fn enabled() -> Self {
+        Self(Some(Arc::default()))
+    }
+
+    /// Returns `true` if the cache stores the recurrences of the queried windows.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Returns the recurrences of the window, computing them with `compute` unless they
+    /// are cached.
+    pub(crate) fn get_or_compute(
+        &self,
+        window: Window,
+        compute: impl FnOnce() -> RRuleResult,
+    ) -> RRuleResult {
+        let Some(windows) = &self.0 else {
+            return compute();
+        };
+        // The results in a poisoned cache are still complete, as they are inserted at once.
+        if let Some(result) = windows
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&window)
+        {
+            return result.clone();
+        }
+        let result = compute();
+        let mut windows = windows.write().unwrap_or_else(PoisonError::into_inner);
+        if windows.len() >= MAX_CACHED_WINDOWS {
+            windows.clear();
+        }
+        windows.insert(window, result.clone());
+        result
+    }
+
+    /// Removes the cached recurrences.
+    pub(crate) fn clear(&self) {
+        if let Some(windows) = &self.0 {
+            windows
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+        }
+    }
+}
+
+impl Debug for OccurrenceCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OccurrenceCache")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl PartialEq for OccurrenceCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for OccurrenceCache {}
+
+impl Hash for OccurrenceCache {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::core::Tz;
+
+    fn window(day: u32) -> Window {
+        let dt = |day| Tz::UTC.with_ymd_and_hms(2021, 1, day, 0, 0, 0).unwrap();
+        (dt(day), dt(day + 1), true)
+    }
+
+    fn result(day: u32) -> RRuleResult {
+        RRuleResult {
+            dates: vec![window(day).0],
+            limited: false,
+        }
+    }
+
+    #[test]
+    fn computes_each_window_once() {
+        let cache = OccurrenceCache::enabled();
+        let computed = Cell::new(0);
+        let compute = |day| {
+            computed.set(computed.get() + 1);
+            result(day)
+        };
+        for _ in 0..3 {
+            assert_eq!(cache.get_or_compute(window(1), || compute(1)), result(1));
+            assert_eq!(cache.get_or_compute(window(2), || compute(2)), result(2));
+        }
+        assert_eq!(computed.get(), 2);
+
+        // The clones share the cache.
+        let clone = cache.clone();
+        clone.clear();
+        assert_eq!(cache.get_or_compute(window(1), || compute(1)), result(1));
+        assert_eq!(computed.get(), 3);
+    }
+
+    #[test]
+    fn computes_every_time_when_disabled() {
+        let cache = OccurrenceCache::default();
+        let computed = Cell::new(0);
+        for _ in 0..3 {
+            cache.get_or_compute(window(1), || {
+                computed.set(computed.get() + 1);
+                result(1)
+            });
+        }
+        assert_eq!(computed.get(), 3);
+    }
+
+    #[test]
+    fn empties_a_full_cache() {
+        let cache = OccurrenceCache::enabled();
+        let (after, _, _) = window(1);
+        for hours in 1..=MAX_CACHED_WINDOWS {
+            let before = after + Duration::hours(i64::try_from(hours).unwrap());
+            cache.get_or_compute((after, before, true), || result(1));
+        }
+        let windows = cache.0.as_ref().unwrap().read().unwrap();
+        assert_eq!(windows.len(), MAX_CACHED_WINDOWS);
+        drop(windows);
+
+        cache.get_or_compute(window(2), || result(2));
+        let windows = cache.0.as_ref().unwrap().read().unwrap();
+        assert_eq!(windows.len(), 1);
+    }
+}
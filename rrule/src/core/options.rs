@@ -0,0 +1,293 @@
+//! A structured representation of a validated [`RRule`], with dates in the RFC 3339 format.
+use super::{ByRule, DateTime, OccurrenceCache};
+use crate::core::Tz;
+use crate::{
+    ChronoTzResolver, Frequency, NWeekday, ParseError, RRule, RRuleError, RScale, Skip, TzResolver,
+    Unvalidated,
+};
+use chrono::{FixedOffset, Offset, Weekday};
+use serde_with::serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// The options of a validated [`RRule`] as plain fields, to build rules from structured
+/// input like JSON instead of an iCalendar string.
+///
+/// With the `schemars` feature, this type implements `JsonSchema`, so forms and rule
+/// editors can be built against the generated JSON schema.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{RRule, RRuleOptions};
+///
+/// let options: RRuleOptions = serde_json::from_str(
+///     r#"{
+///         "dt_start": "2021-01-01T09:00:00+01:00",
+///         "tzid": "Europe/Paris",
+///         "freq": "WEEKLY",
+///         "count": 3,
+///         "by_weekday": ["MO", "FR"]
+///     }"#,
+/// )
+/// .unwrap();
+/// let rrule = RRule::try_from(options).unwrap();
+/// assert_eq!(
+///     rrule.to_string(),
+///     "DTSTART;TZID=Europe/Paris:20210101T090000\n\
+///      RRULE:FREQ=WEEKLY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=MO,FR"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(crate = "serde_with::serde")]
+pub struct RRuleOptions {
+    /// The start date of the rule, in the RFC 3339 format.
+    pub dt_start: chrono::DateTime<FixedOffset>,
+    /// The IANA timezone of the start date, e.g. `Europe/Paris`.
+    /// The start date is converted to UTC if it isn't set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tzid: Option<String>,
+    /// The frequency of the rule.
+    pub freq: Frequency,
+    /// The interval between each frequency iteration.
+    #[serde(default = "default_interval")]
+    pub interval: u16,
+    /// The number of recurrences.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// The last date of the recurrences, in the RFC 3339 format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<chrono::DateTime<FixedOffset>>,
+    /// The start of the week, e.g. `MO`.
+    #[serde(default = "default_week_start")]
+    pub week_start: NWeekday,
+    /// The occurrences within the set of recurrences of each interval.
+    #[serde(default)]
+    pub by_set_pos: Vec<i32>,
+    /// The months, from 1 to 12.
+    #[serde(default)]
+    pub by_month: Vec<u8>,
+    /// The leap months of RFC 7529, from 1 to 12, e.g. `5` for `BYMONTH=5L`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub by_leap_month: Vec<u8>,
+    /// The days of the month, from -31 to 31.
+    #[serde(default)]
+    pub by_month_day: Vec<i8>,
+    /// The days of the year, from -366 to 366.
+    #[serde(default)]
+    pub by_year_day: Vec<i16>,
+    /// The ISO 8601 week numbers, from -53 to 53.
+    #[serde(default)]
+    pub by_week_no: Vec<i8>,
+    /// The weekdays, e.g. `MO` or `-1FR`.
+    #[serde(default)]
+    pub by_weekday: Vec<NWeekday>,
+    /// The hours, from 0 to 23.
+    #[serde(default)]
+    pub by_hour: Vec<u8>,
+    /// The minutes, from 0 to 59.
+    #[serde(default)]
+    pub by_minute: Vec<u8>,
+    /// The seconds, from 0 to 59.
+    #[serde(default)]
+    pub by_second: Vec<u8>,
+    /// The offset in days from Easter Sunday, with the `by-easter` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_easter: Option<i16>,
+    /// The calendar system of the months and month days, e.g. `CHINESE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rscale: Option<RScale>,
+    /// What happens to the dates that don't exist, e.g. `BACKWARD`.
+    #[serde(default, skip_serializing_if = "is_omit")]
+    pub skip: Skip,
+    /// What happens to the 29th of February of a yearly rule in non-leap years, when
+    /// `skip` is `OMIT`.
+    #[serde(default, skip_serializing_if = "is_omit")]
+    pub leap_day: Skip,
+}
+
+fn default_interval() -> u16 {
+    1
+}
+
+fn is_omit(skip: &Skip) -> bool {
+    *skip == Skip::Omit
+}
+
+fn default_week_start() -> NWeekday {
+    NWeekday::Every(Weekday::Mon)
+}
+
+impl From<&RRule> for RRuleOptions {
+    fn from(rrule: &RRule) -> Self {
+        let dt_start = rrule.dt_start();
+        let tzid = match dt_start.timezone() {
+            #[cfg(feature = "chrono-tz")]
+            Tz::Tz(tz) if tz != chrono_tz::UTC => Some(tz.name().to_string()),
+            _ => None,
+        };
+        let mut by_month_day = rrule.by_month_day.to_vec();
+        by_month_day.extend(&rrule.by_n_month_day);
+
+        Self {
+            dt_start: dt_start.with_timezone(&dt_start.offset().fix()),
+            tzid,
+            freq: rrule.freq,
+            interval: rrule.interval,
+            count: rrule.count,
+            until: rrule
+                .until
+                .map(|until| until.with_timezone(&until.offset().fix())),
+            week_start: NWeekday::Every(rrule.week_start),
+            by_set_pos: rrule.by_set_pos.to_vec(),
+            by_month: rrule.by_month.to_vec(),
+            by_leap_month: rrule.by_leap_month.to_vec(),
+            by_month_day,
+            by_year_day: rrule.by_year_day.to_vec(),
+            by_week_no: rrule.by_week_no.to_vec(),
+            by_weekday: rrule.by_weekday.to_vec(),
+            by_hour: rrule.by_hour.to_vec(),
+            by_minute: rrule.by_minute.to_vec(),
+            by_second: rrule.by_second.to_vec(),
+            by_easter: rrule.by_easter,
+            rscale: rrule.rscale,
+            skip: rrule.skip,
+            leap_day: rrule.leap_day,
+        }
+    }
+}
+
+impl TryFrom<RRuleOptions> for RRule {
+    type Error = RRuleError;
+
+    /// Validates the options with their start date.
+    ///
+    /// `until` is converted to UTC, as the iCalendar format requires when the
+    /// start date has a timezone.
+    fn try_from(options: RRuleOptions) -> Result<Self, Self::Error> {
+        let tz = match &options.tzid {
+            Some(tzid) => ChronoTzResolver
+                .resolve(tzid)
+                .ok_or_else(|| ParseError::InvalidTimezone(tzid.clone()))?,
+            None => Tz::UTC,
+        };
+        let dt_start: DateTime = options.dt_start.with_timezone(&tz);
+        let week_start = match options.week_start {
+            NWeekday::Every(weekday) => weekday,
+            NWeekday::Nth(_, _) => {
+                return Err(ParseError::InvalidWeekdayStart(options.week_start.to_string()).into())
+            }
+        };
+
+        let rrule = RRule::<Unvalidated> {
+            freq: options.freq,
+            interval: options.interval,
+            count: options.count,
+            until: options.until.map(|until| until.with_timezone(&Tz::UTC)),
+            week_start,
+            by_set_pos: options.by_set_pos.into(),
+            by_month: options.by_month.into(),
+            by_leap_month: options.by_leap_month.into(),
+            by_month_day: options.by_month_day.into(),
+            by_n_month_day: ByRule::new(),
+            by_year_day: options.by_year_day.into(),
+            by_week_no: options.by_week_no.into(),
+            by_weekday: options.by_weekday.into(),
+            by_hour: options.by_hour.into(),
+            by_minute: options.by_minute.into(),
+            by_second: options.by_second.into(),
+            by_easter: options.by_easter,
+            rscale: options.rscale,
+            skip: options.skip,
+            leap_day: options.leap_day,
+            dt_start: None,
+            cache: OccurrenceCache::default(),
+            stage: PhantomData,
+        };
+        rrule.validate(dt_start)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Frequency {
+    fn schema_name() -> String {
+        "Frequency".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            enum_values: Some(
+                [
+                    "YEARLY", "MONTHLY", "WEEKLY", "DAILY", "HOURLY", "MINUTELY", "SECONDLY",
+                ]
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            ),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for RScale {
+    fn schema_name() -> String {
+        "RScale".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            enum_values: Some(
+                ["GREGORIAN", "CHINESE"]
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Skip {
+    fn schema_name() -> String {
+        "Skip".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            enum_values: Some(
+                ["OMIT", "BACKWARD", "FORWARD"]
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for NWeekday {
+    fn schema_name() -> String {
+        "NWeekday".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            string: Some(Box::new(schemars::schema::StringValidation {
+                pattern: Some("^[+-]?[0-9]{0,2}(MO|TU|WE|TH|FR|SA|SU)$".into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
@@ -0,0 +1,99 @@
+//! Recurrences of a set that are moved or cancelled, identified by their `RECURRENCE-ID`.
+use super::DateTime;
+
+/// A recurrence of an [`crate::RRuleSet`] that is moved or cancelled, like a `VEVENT`
+/// with a `RECURRENCE-ID` in an iCalendar file.
+///
+/// The recurrence at the `RECURRENCE-ID` is left out of the set, and a moved recurrence
+/// is returned at its new start instead. The new start is not affected by the exdates,
+/// exrules, holidays or business days of the set.
+///
+/// In the string of a set, an override is a `VEVENT` component with a `RECURRENCE-ID`,
+/// and a `DTSTART` for a moved recurrence or a `STATUS:CANCELLED` for a cancelled one.
+/// The other properties of the component are ignored.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::RRuleSet;
+///
+/// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+///     RRULE:FREQ=DAILY;COUNT=3\n\
+///     BEGIN:VEVENT\n\
+///     RECURRENCE-ID:20210102T090000Z\n\
+///     DTSTART:20210102T140000Z\n\
+///     END:VEVENT\n\
+///     BEGIN:VEVENT\n\
+///     RECURRENCE-ID:20210103T090000Z\n\
+///     STATUS:CANCELLED\n\
+///     END:VEVENT"
+///     .parse()
+///     .unwrap();
+///
+/// let dates = rrule_set.all(10).dates;
+/// assert_eq!(dates.len(), 2);
+/// assert_eq!(dates[1].to_rfc3339(), "2021-01-02T14:00:00+00:00");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RecurrenceOverride {
+    /// The original start of the overridden recurrence.
+    recurrence_id: DateTime,
+    /// The new start of the recurrence, or `None` if it is cancelled.
+    start: Option<DateTime>,
+}
+
+impl RecurrenceOverride {
+    /// Creates an override that moves the recurrence at `recurrence_id` to `start`.
+    #[must_use]
+    pub fn moved(recurrence_id: DateTime, start: DateTime) -> Self {
+        Self {
+            recurrence_id,
+            start: Some(start),
+        }
+    }
+
+    /// Creates an override that cancels the recurrence at `recurrence_id`.
+    #[must_use]
+    pub fn cancelled(recurrence_id: DateTime) -> Self {
+        Self {
+            recurrence_id,
+            start: None,
+        }
+    }
+
+    /// Returns the original start of the overridden recurrence, its `RECURRENCE-ID`.
+    #[must_use]
+    pub fn get_recurrence_id(&self) -> &DateTime {
+        &self.recurrence_id
+    }
+
+    /// Returns the new start of the recurrence, or `None` if it is cancelled.
+    #[must_use]
+    pub fn get_start(&self) -> Option<&DateTime> {
+        self.start.as_ref()
+    }
+
+    /// Returns `true` if the recurrence is cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.start.is_none()
+    }
+
+    /// Returns the lines of the `VEVENT` component of the override, with the dates
+    /// formatted by `to_ical_format`.
+    pub(crate) fn to_component_lines(
+        self,
+        to_ical_format: &dyn Fn(&DateTime) -> String,
+    ) -> [String; 4] {
+        let status = match &self.start {
+            Some(start) => format!("DTSTART{}", to_ical_format(start)),
+            None => "STATUS:CANCELLED".into(),
+        };
+        [
+            "BEGIN:VEVENT".into(),
+            format!("RECURRENCE-ID{}", to_ical_format(&self.recurrence_id)),
+            status,
+            "END:VEVENT".into(),
+        ]
+    }
+}
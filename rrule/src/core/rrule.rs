@@ -1,26 +1,38 @@
-use super::datetime::DateTime;
+use super::datetime::{datetime_to_ical_format, DateTime};
 use crate::core::get_day;
 use crate::core::get_hour;
 use crate::core::get_minute;
 use crate::core::get_month;
 use crate::core::get_second;
-use crate::iter::RRuleIter;
+use crate::core::stats::OccurrenceStats;
+use crate::core::text::next_text;
+use crate::core::utils::{collect_with_error, fold_lines, group_dates_by};
+use crate::core::{OccurrenceCache, RRuleBuilder};
+use crate::iter::chinese::ChineseDate;
+use crate::iter::{
+    explain, fast_path, Explanation, Occurrence, OccurrenceIter, RRuleIter, RRuleRevIter,
+};
 use crate::parser::str_to_weekday;
-use crate::parser::ContentLineCaptures;
 use crate::parser::ParseError;
+use crate::parser::{unfold_lines, ContentLine, ContentLineCaptures, Grammar};
 use crate::validator::validate_rrule;
 use crate::validator::ValidationError;
-use crate::{RRuleError, RRuleSet, Unvalidated, Validated};
-use chrono::{Datelike, Month, Weekday};
+use crate::validator::YEAR_RANGE;
+use crate::validator::{lint_rrule, redundant_by_rules, LintWarning};
+use crate::{RRuleError, RRuleResult, RRuleSet, Tz, Unvalidated, Validated};
+use chrono::{Datelike, Month, TimeZone, Weekday};
 #[cfg(feature = "serde")]
-use serde_with::{serde_as, DeserializeFromStr, SerializeDisplay};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use smallvec::{smallvec, SmallVec};
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::marker::PhantomData;
+use std::ops::{ControlFlow, Range};
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
 /// The frequency of a recurrence.
 pub enum Frequency {
@@ -73,12 +85,133 @@ impl FromStr for Frequency {
     }
 }
 
+/// What happens to the dates of a recurrence that don't exist, like the 30th of
+/// February or the 29th of February of a yearly rule in a non-leap year.
+///
+/// Corresponds with the `SKIP` rule part of [RFC 7529](https://datatracker.ietf.org/doc/html/rfc7529#section-4.1).
+///
+/// # Usage
+///
+/// ```
+/// use chrono::TimeZone;
+/// use rrule::{Frequency, RRule, Skip, Tz};
+///
+/// let dt_start = Tz::UTC.with_ymd_and_hms(2020, 1, 31, 9, 0, 0).unwrap();
+/// let rrule = RRule::new(Frequency::Monthly)
+///     .count(3)
+///     .skip(Skip::Backward)
+///     .validate(dt_start)
+///     .unwrap();
+/// let dates: Vec<_> = rrule.into_iter().map(|date| date.to_rfc3339()).collect();
+/// assert_eq!(
+///     dates,
+///     [
+///         "2020-01-31T09:00:00+00:00",
+///         "2020-02-29T09:00:00+00:00",
+///         "2020-03-31T09:00:00+00:00",
+///     ]
+/// );
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
+pub enum Skip {
+    /// The dates that don't exist are left out, as RFC 5545 specifies.
+    #[default]
+    Omit,
+    /// The dates that don't exist are moved to the last day of the month.
+    ///
+    /// The parser selects it for the rules without a `SKIP` rule part with
+    /// [`crate::ParserConfig::clamp_month_days`].
+    Backward,
+    /// The dates that don't exist are moved to the first day of the next month.
+    Forward,
+}
+
+impl Display for Skip {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Omit => "OMIT",
+            Self::Backward => "BACKWARD",
+            Self::Forward => "FORWARD",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Skip {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let skip = match &value.to_uppercase()[..] {
+            "OMIT" => Self::Omit,
+            "BACKWARD" => Self::Backward,
+            "FORWARD" => Self::Forward,
+            _ => return Err(ParseError::InvalidSkip(value.into())),
+        };
+        Ok(skip)
+    }
+}
+
+/// The calendar system of the months and the month days of a rule.
+///
+/// Corresponds with the `RSCALE` rule part of [RFC 7529](https://datatracker.ietf.org/doc/html/rfc7529#section-4.1).
+/// The leap months of `BYMONTH`, like `5L`, only exist in the Chinese calendar.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{RRule, RScale};
+///
+/// // The Chinese New Year.
+/// let rrule: RRule = "DTSTART:20240210T000000Z\n\
+///     RRULE:FREQ=YEARLY;COUNT=3;RSCALE=CHINESE"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(rrule.get_rscale(), Some(RScale::Chinese));
+/// let dates: Vec<_> = rrule.into_iter().map(|date| date.date_naive().to_string()).collect();
+/// assert_eq!(dates, ["2024-02-10", "2025-01-29", "2026-02-17"]);
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
+pub enum RScale {
+    /// The Gregorian calendar of RFC 5545, which is used for the rules without `RSCALE`.
+    #[default]
+    Gregorian,
+    /// The Chinese lunisolar calendar, whose months start on the day of a new moon in
+    /// China. Only `YEARLY` and `MONTHLY` rules with `BYMONTH`, `BYMONTHDAY` and the
+    /// times are supported.
+    Chinese,
+}
+
+impl Display for RScale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Gregorian => "GREGORIAN",
+            Self::Chinese => "CHINESE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for RScale {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let rscale = match &value.to_uppercase()[..] {
+            "GREGORIAN" => Self::Gregorian,
+            "CHINESE" => Self::Chinese,
+            _ => return Err(ParseError::UnsupportedRScale(value.into())),
+        };
+        Ok(rscale)
+    }
+}
+
 /// This indicates the nth occurrence of a specific day within a MONTHLY or YEARLY RRULE.
 ///
 /// For example, `NWeekday::Nth(1, MO)` represents the first Monday within the month or year,
 /// whereas `NWeekday::Nth(-1, MO)` represents the last Monday of the month or year.
 /// And `NWeekday::Every(MO)`, means all Mondays of the month or year.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
 pub enum NWeekday {
     /// When it is every weekday of the month or year.
@@ -144,6 +277,88 @@ impl NWeekday {
             None => Self::Every(weekday),
         }
     }
+
+    /// Creates the last occurrence of the week day within the month or year, the same as
+    /// `NWeekday::Nth(-1, weekday)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{Frequency, NWeekday, RRule, Tz, Weekday};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule_set = RRule::builder()
+    ///     .freq(Frequency::Monthly)
+    ///     .by_weekday(vec![NWeekday::Nth(2, Weekday::Mon), NWeekday::last(Weekday::Fri)])
+    ///     .count(4)
+    ///     .build_set(dt_start)
+    ///     .unwrap();
+    /// let days: Vec<_> = rrule_set.all(4).dates.iter().map(|date| date.to_string()).collect();
+    /// assert_eq!(
+    ///     days,
+    ///     [
+    ///         "2021-01-11 09:00:00 UTC",
+    ///         "2021-01-29 09:00:00 UTC",
+    ///         "2021-02-08 09:00:00 UTC",
+    ///         "2021-02-26 09:00:00 UTC",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn last(weekday: Weekday) -> Self {
+        Self::Nth(-1, weekday)
+    }
+
+    /// Creates the nth occurrence of the week day counted from the end of the month or
+    /// year, so `NWeekday::nth_last(1, weekday)` is [`NWeekday::last`].
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The nth occurrence from the end, from 1 to 53.
+    /// * `weekday` - The week day
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not between 1 and 53, like `0` or a negative number. Use
+    /// [`NWeekday::try_nth_last`] for values that aren't known to be within range, like
+    /// user input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::Weekday;
+    /// use rrule::NWeekday;
+    ///
+    /// assert_eq!(NWeekday::nth_last(2, Weekday::Sun), NWeekday::Nth(-2, Weekday::Sun));
+    /// assert_eq!(NWeekday::nth_last(2, Weekday::Sun).to_string(), "-2SU");
+    /// ```
+    #[must_use]
+    pub const fn nth_last(n: i16, weekday: Weekday) -> Self {
+        assert!(matches!(n, 1..=53), "`n` must be between 1 and 53");
+        Self::Nth(-n, weekday)
+    }
+
+    /// Creates the nth occurrence of the week day counted from the end of the month or
+    /// year like [`NWeekday::nth_last`], or returns `None` if `n` is not between 1 and 53.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::Weekday;
+    /// use rrule::NWeekday;
+    ///
+    /// assert_eq!(NWeekday::try_nth_last(2, Weekday::Sun), Some(NWeekday::Nth(-2, Weekday::Sun)));
+    /// assert_eq!(NWeekday::try_nth_last(0, Weekday::Sun), None);
+    /// assert_eq!(NWeekday::try_nth_last(-2, Weekday::Sun), None);
+    /// ```
+    #[must_use]
+    pub const fn try_nth_last(n: i16, weekday: Weekday) -> Option<Self> {
+        match n {
+            1..=53 => Some(Self::Nth(-n, weekday)),
+            _ => None,
+        }
+    }
 }
 
 impl FromStr for NWeekday {
@@ -178,19 +393,13 @@ impl Display for NWeekday {
     /// use rrule::NWeekday;
     ///
     /// assert_eq!(format!("{}", NWeekday::Every(Weekday::Mon)), "MO");
-    /// assert_eq!(format!("{}", NWeekday::Nth(1, Weekday::Mon)), "MO");
+    /// assert_eq!(format!("{}", NWeekday::Nth(1, Weekday::Mon)), "1MO");
     /// assert_eq!(format!("{}", NWeekday::Nth(2, Weekday::Mon)), "2MO");
     /// ```
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let weekday = match self {
             Self::Every(wd) => weekday_to_str(*wd),
-            Self::Nth(number, wd) => {
-                let mut wd_str = weekday_to_str(*wd);
-                if *number != 1 {
-                    wd_str = format!("{}{}", number, wd_str);
-                };
-                wd_str
-            }
+            Self::Nth(number, wd) => format!("{}{}", number, weekday_to_str(*wd)),
         };
 
         write!(f, "{}", weekday)
@@ -209,13 +418,18 @@ fn weekday_to_str(d: Weekday) -> String {
     }
 }
 
+/// The values of a BYxxx rule part. Most rules have a few values per rule part, which
+/// are stored inline instead of in a separate allocation.
+pub(crate) type ByRule<T> = SmallVec<[T; 4]>;
+
 /// Represents a complete RRULE property based on the [iCalendar specification](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3)
 /// It has two stages, based on the attached type, `Validated` or `Unvalidated`.
 /// - `Unvalidated`, which is the raw string representation of the RRULE
 /// - `Validated`, which is when the `RRule` has been parsed and validated, based on the start date
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", serde_as)]
-#[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
+///
+/// Validation sorts and removes duplicates from the BYxxx rule parts, so validated rules
+/// that only differ in the order of those values are equal and have the same hash.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RRule<Stage = Validated> {
     /// The frequency of the rrule.
     /// For example, yearly, weekly, hourly
@@ -229,7 +443,6 @@ pub struct RRule<Stage = Validated> {
     pub(crate) count: Option<u32>,
     /// The end date after which new events will no longer be generated.
     /// If the `DateTime` is equal to an instance of the event, it will be the last event.
-    #[cfg_attr(feature = "serde", serde_as(as = "DisplayFromStr"))]
     pub(crate) until: Option<DateTime>,
     /// The start day of the week.
     /// This will affect recurrences based on weekly periods.
@@ -238,43 +451,61 @@ pub struct RRule<Stage = Validated> {
     /// For example:
     /// - A monthly frequency with an `by_set_pos` of `-1` meaning the last day of the month.
     /// - An hourly frequency with an `by_set_pos` of `2` meaning the 2nd hour. (TODO Check)
-    pub(crate) by_set_pos: Vec<i32>,
+    pub(crate) by_set_pos: ByRule<i32>,
     /// The months to apply the recurrence to.
     /// Can be a value from 1 to 12.
-    pub(crate) by_month: Vec<u8>,
+    pub(crate) by_month: ByRule<u8>,
+    /// The leap months to apply the recurrence to, like `5L` of RFC 7529.
+    /// Can be a value from 1 to 12.
+    /// The Gregorian calendar has no leap months, so they never match a date, but they
+    /// are kept to write the rule back.
+    pub(crate) by_leap_month: ByRule<u8>,
     /// The month days to apply the recurrence to.
     /// Can be a value from -31 to -1 and 1 to 31.
-    pub(crate) by_month_day: Vec<i8>,
-    pub(crate) by_n_month_day: Vec<i8>,
+    pub(crate) by_month_day: ByRule<i8>,
+    pub(crate) by_n_month_day: ByRule<i8>,
     /// The year days to apply the recurrence to.
     /// Can be a value from -366 to -1 and 1 to 366.
-    pub(crate) by_year_day: Vec<i16>,
+    pub(crate) by_year_day: ByRule<i16>,
     /// The week numbers to apply the recurrence to.
     /// Week numbers have the meaning described in ISO8601, that is,
     /// the first week of the year is that it contains at least four days of the new year.
     /// Week day starts counting on from `week_start` value.
     /// Can be a value from -53 to -1 and 1 to 53.
-    pub(crate) by_week_no: Vec<i8>,
+    pub(crate) by_week_no: ByRule<i8>,
     /// The days of the week the rules should be recurring.
     /// Should be a value of `Weekday` and optionally with a prefix of -366 to 366 depending on frequency.
     /// Corresponds with `BYDAY` field.
-    pub(crate) by_weekday: Vec<NWeekday>,
+    pub(crate) by_weekday: ByRule<NWeekday>,
     /// The hours to apply the recurrence to.
     /// Can be a value from 0 to 23.
-    pub(crate) by_hour: Vec<u8>,
+    pub(crate) by_hour: ByRule<u8>,
     /// The minutes to apply the recurrence to.
     /// Can be a value from 0 to 59.
-    pub(crate) by_minute: Vec<u8>,
+    pub(crate) by_minute: ByRule<u8>,
     /// The seconds to apply the recurrence to.
     /// Can be a value from 0 to 59.
-    pub(crate) by_second: Vec<u8>,
+    pub(crate) by_second: ByRule<u8>,
     /// Extension, not part of RFC spec.
     /// Amount of days/months from Easter Sunday itself.
     /// Can be a value from -366 to 366.
     /// Note: Only used when `by-easter` feature flag is set. Otherwise, it is ignored.
     pub(crate) by_easter: Option<i16>,
+    /// The calendar system of the months and month days, if the rule has an `RSCALE`.
+    pub(crate) rscale: Option<RScale>,
+    /// What happens to the dates that don't exist, like the 30th of February.
+    pub(crate) skip: Skip,
+    /// Extension, not part of RFC spec.
+    /// What happens to the 29th of February of a yearly rule in non-leap years, when
+    /// `skip` is [`Skip::Omit`]. It is not written to the `RRULE`.
+    pub(crate) leap_day: Skip,
+    /// The start datetime the rule was validated against.
+    /// Only set once the rule is [`Validated`].
+    pub(crate) dt_start: Option<DateTime>,
+    /// The recurrences of the windows queried with [`RRule::between`], once enabled with
+    /// [`RRule::with_cache`].
+    pub(crate) cache: OccurrenceCache,
     /// A phantom data to have the stage (unvalidated or validated).
-    #[cfg_attr(feature = "serde", serde_as(as = "ignore"))]
     pub(crate) stage: PhantomData<Stage>,
 }
 
@@ -287,17 +518,23 @@ impl Default for RRule<Unvalidated> {
             count: None,
             until: None,
             week_start: Weekday::Mon,
-            by_set_pos: Vec::new(),
-            by_month: Vec::new(),
-            by_month_day: Vec::new(),
-            by_n_month_day: Vec::new(),
-            by_year_day: Vec::new(),
-            by_week_no: Vec::new(),
-            by_weekday: Vec::new(),
-            by_hour: Vec::new(),
-            by_minute: Vec::new(),
-            by_second: Vec::new(),
+            by_set_pos: ByRule::new(),
+            by_month: ByRule::new(),
+            by_leap_month: ByRule::new(),
+            by_month_day: ByRule::new(),
+            by_n_month_day: ByRule::new(),
+            by_year_day: ByRule::new(),
+            by_week_no: ByRule::new(),
+            by_weekday: ByRule::new(),
+            by_hour: ByRule::new(),
+            by_minute: ByRule::new(),
+            by_second: ByRule::new(),
             by_easter: None,
+            rscale: None,
+            skip: Skip::Omit,
+            leap_day: Skip::Omit,
+            dt_start: None,
+            cache: OccurrenceCache::default(),
             stage: PhantomData,
         }
     }
@@ -313,6 +550,13 @@ impl RRule<Unvalidated> {
         }
     }
 
+    /// Creates an [`RRuleBuilder`], which checks at compile time that the frequency is
+    /// set and that `COUNT` and `UNTIL` aren't both set.
+    #[must_use]
+    pub fn builder() -> RRuleBuilder {
+        RRuleBuilder::new()
+    }
+
     /// The FREQ rule part identifies the type of recurrence rule.
     #[must_use]
     pub fn freq(mut self, freq: Frequency) -> Self {
@@ -357,12 +601,13 @@ impl RRule<Unvalidated> {
     /// work day of every month.
     #[must_use]
     pub fn by_set_pos(mut self, by_set_pos: Vec<i32>) -> Self {
-        self.by_set_pos = by_set_pos;
+        self.by_set_pos = by_set_pos.into();
         self
     }
 
     /// When given, these variables will define the months to apply the recurrence to.
     #[must_use]
+    #[allow(clippy::missing_panics_doc)]
     pub fn by_month(mut self, by_month: &[Month]) -> Self {
         self.by_month = by_month
             .iter()
@@ -373,11 +618,28 @@ impl RRule<Unvalidated> {
         self
     }
 
+    /// The leap months to apply the recurrence to, like `BYMONTH=5L` of RFC 7529.
+    ///
+    /// They are the leap months of the Chinese calendar of [`RScale::Chinese`]. The
+    /// Gregorian calendar has no leap months, so a Gregorian rule with only leap months
+    /// has no recurrences.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn by_leap_month(mut self, by_leap_month: &[Month]) -> Self {
+        self.by_leap_month = by_leap_month
+            .iter()
+            .map(|month| {
+                u8::try_from(month.number_from_month()).expect("1-12 is within range of u8")
+            })
+            .collect();
+        self
+    }
+
     /// If given, it must be either an integer, or a sequence of integers, meaning
     /// the month days to apply the recurrence to.
     #[must_use]
     pub fn by_month_day(mut self, by_month_day: Vec<i8>) -> Self {
-        self.by_month_day = by_month_day;
+        self.by_month_day = by_month_day.into();
         self
     }
 
@@ -385,7 +647,7 @@ impl RRule<Unvalidated> {
     /// the year days to apply the recurrence to.
     #[must_use]
     pub fn by_year_day(mut self, by_year_day: Vec<i16>) -> Self {
-        self.by_year_day = by_year_day;
+        self.by_year_day = by_year_day.into();
         self
     }
 
@@ -395,7 +657,7 @@ impl RRule<Unvalidated> {
     /// at least four days of the new year.
     #[must_use]
     pub fn by_week_no(mut self, by_week_no: Vec<i8>) -> Self {
-        self.by_week_no = by_week_no;
+        self.by_week_no = by_week_no.into();
         self
     }
 
@@ -403,7 +665,7 @@ impl RRule<Unvalidated> {
     /// will be applied.
     #[must_use]
     pub fn by_weekday(mut self, by_weekday: Vec<NWeekday>) -> Self {
-        self.by_weekday = by_weekday;
+        self.by_weekday = by_weekday.into();
         self
     }
 
@@ -411,7 +673,7 @@ impl RRule<Unvalidated> {
     /// meaning the hours to apply the recurrence to.
     #[must_use]
     pub fn by_hour(mut self, by_hour: Vec<u8>) -> Self {
-        self.by_hour = by_hour;
+        self.by_hour = by_hour.into();
         self
     }
 
@@ -419,7 +681,7 @@ impl RRule<Unvalidated> {
     /// meaning the minutes to apply the recurrence to.
     #[must_use]
     pub fn by_minute(mut self, by_minute: Vec<u8>) -> Self {
-        self.by_minute = by_minute;
+        self.by_minute = by_minute.into();
         self
     }
 
@@ -427,7 +689,7 @@ impl RRule<Unvalidated> {
     /// meaning the seconds to apply the recurrence to.
     #[must_use]
     pub fn by_second(mut self, by_second: Vec<u8>) -> Self {
-        self.by_second = by_second;
+        self.by_second = by_second.into();
         self
     }
 
@@ -441,10 +703,54 @@ impl RRule<Unvalidated> {
         self
     }
 
+    /// The RSCALE rule part, which defines the calendar system of the months and the
+    /// month days of the rule, like the Chinese calendar. See [`RScale`].
+    #[must_use]
+    pub fn rscale(mut self, rscale: RScale) -> Self {
+        self.rscale = Some(rscale);
+        self
+    }
+
+    /// The SKIP rule part, which defines what happens to the dates that don't exist,
+    /// like the 31st of a month with 30 days. See [`Skip`].
+    #[must_use]
+    pub fn skip(mut self, skip: Skip) -> Self {
+        self.skip = skip;
+        self
+    }
+
+    /// What happens to the 29th of February of a yearly rule, like a birthday, in
+    /// non-leap years: it is left out with [`Skip::Omit`], moved to the 28th of February
+    /// with [`Skip::Backward`] or to the 1st of March with [`Skip::Forward`].
+    ///
+    /// Unlike [`RRule::skip`], the other dates that don't exist are still left out, and
+    /// it is an extension that is not written to the `RRULE`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{Frequency, RRule, Skip, Tz};
+    ///
+    /// let birthday = Tz::UTC.with_ymd_and_hms(2020, 2, 29, 0, 0, 0).unwrap();
+    /// let rrule = RRule::new(Frequency::Yearly)
+    ///     .count(2)
+    ///     .leap_day(Skip::Backward)
+    ///     .validate(birthday)
+    ///     .unwrap();
+    /// let dates: Vec<_> = rrule.into_iter().map(|date| date.date_naive().to_string()).collect();
+    /// assert_eq!(dates, ["2020-02-29", "2021-02-28"]);
+    /// ```
+    #[must_use]
+    pub fn leap_day(mut self, leap_day: Skip) -> Self {
+        self.leap_day = leap_day;
+        self
+    }
+
     /// Fills in some additional fields in order to make iter work correctly.
     pub(crate) fn finalize_parsed_rrule(mut self, dt_start: &DateTime) -> Self {
         // TEMP: move negative months to another list
-        let mut by_month_day = vec![];
+        let mut by_month_day = ByRule::new();
         let mut by_n_month_day = self.by_n_month_day;
         for by_month_day_item in self.by_month_day {
             match by_month_day_item.cmp(&0) {
@@ -471,21 +777,25 @@ impl RRule<Unvalidated> {
             || !self.by_weekday.is_empty()
             || by_easter_is_some)
         {
+            let (month, leap, day) = self.start_month_and_day(dt_start);
             match self.freq {
                 Frequency::Yearly => {
-                    if self.by_month.is_empty() {
-                        let month = get_month(dt_start);
-                        self.by_month = vec![month];
+                    if self.by_month.is_empty() && self.by_leap_month.is_empty() {
+                        if leap {
+                            self.by_leap_month = smallvec![month];
+                        } else {
+                            self.by_month = smallvec![month];
+                        }
                     }
-                    let day = get_day(dt_start);
-                    self.by_month_day = vec![day];
+                    self.by_month_day = smallvec![day];
                 }
                 Frequency::Monthly => {
-                    let day = get_day(dt_start);
-                    self.by_month_day = vec![day];
+                    self.by_month_day = smallvec![day];
                 }
-                Frequency::Weekly => {
-                    self.by_weekday = vec![NWeekday::Every(dt_start.weekday())];
+                // The weeks don't depend on the calendar system, but they are only supported
+                // in the Gregorian calendar.
+                Frequency::Weekly if self.rscale != Some(RScale::Chinese) => {
+                    self.by_weekday = smallvec![NWeekday::Every(dt_start.weekday())];
                 }
                 _ => (),
             };
@@ -494,19 +804,19 @@ impl RRule<Unvalidated> {
         // by_hour
         if self.by_hour.is_empty() && self.freq < Frequency::Hourly {
             let hour = get_hour(dt_start);
-            self.by_hour = vec![hour];
+            self.by_hour = smallvec![hour];
         }
 
         // by_minute
         if self.by_minute.is_empty() && self.freq < Frequency::Minutely {
             let minute = get_minute(dt_start);
-            self.by_minute = vec![minute];
+            self.by_minute = smallvec![minute];
         }
 
         // by_second
         if self.by_second.is_empty() && self.freq < Frequency::Secondly {
             let second = get_second(dt_start);
-            self.by_second = vec![second];
+            self.by_second = smallvec![second];
         }
 
         // make sure all BYXXX are unique and sorted
@@ -522,6 +832,9 @@ impl RRule<Unvalidated> {
         self.by_month.sort_unstable();
         self.by_month.dedup();
 
+        self.by_leap_month.sort_unstable();
+        self.by_leap_month.dedup();
+
         self.by_month_day.sort_unstable();
         self.by_month_day.dedup();
 
@@ -547,34 +860,18 @@ impl RRule<Unvalidated> {
     ///
     /// # Errors
     ///
-    /// If the properties aren't valid, it will return [`RRuleError`].
+    /// If the properties aren't valid, it will return [`RRuleError`]. When more than one
+    /// property is invalid, the error is a [`ValidationError::Multiple`] with all the
+    /// problems, see [`ValidationError::errors`].
     pub fn validate(self, dt_start: DateTime) -> Result<RRule<Validated>, RRuleError> {
         let rrule = self.finalize_parsed_rrule(&dt_start);
 
         // Validate required checks (defined by RFC 5545)
-        validate_rrule::validate_rrule_forced(&rrule, &dt_start)?;
-
+        let mut errors = validate_rrule::collect_validation_errors(&rrule, &dt_start);
         // Check if it is possible to generate a timeset
-        match rrule.freq {
-            Frequency::Hourly => {
-                if rrule.by_minute.is_empty() && rrule.by_second.is_empty() {
-                    return Err(ValidationError::UnableToGenerateTimeset.into());
-                }
-            }
-            Frequency::Minutely => {
-                if rrule.by_second.is_empty() {
-                    return Err(ValidationError::UnableToGenerateTimeset.into());
-                }
-            }
-            Frequency::Secondly => {}
-            _ => {
-                if rrule.by_hour.is_empty()
-                    && rrule.by_minute.is_empty()
-                    && rrule.by_second.is_empty()
-                {
-                    return Err(ValidationError::UnableToGenerateTimeset.into());
-                }
-            }
+        errors.extend(validate_rrule::validate_timeset(&rrule).err());
+        if let Some(err) = ValidationError::from_errors(errors) {
+            return Err(err.into());
         }
 
         Ok(RRule {
@@ -585,6 +882,7 @@ impl RRule<Unvalidated> {
             week_start: rrule.week_start,
             by_set_pos: rrule.by_set_pos,
             by_month: rrule.by_month,
+            by_leap_month: rrule.by_leap_month,
             by_month_day: rrule.by_month_day,
             by_n_month_day: rrule.by_n_month_day,
             by_year_day: rrule.by_year_day,
@@ -594,6 +892,11 @@ impl RRule<Unvalidated> {
             by_minute: rrule.by_minute,
             by_second: rrule.by_second,
             by_easter: rrule.by_easter,
+            rscale: rrule.rscale,
+            skip: rrule.skip,
+            leap_day: rrule.leap_day,
+            dt_start: Some(dt_start),
+            cache: OccurrenceCache::default(),
             stage: PhantomData,
         })
     }
@@ -611,100 +914,1123 @@ impl RRule<Unvalidated> {
 }
 
 impl RRule {
-    pub(crate) fn iter_with_ctx(&self, dt_start: DateTime, limited: bool) -> RRuleIter {
-        RRuleIter::new(self, &dt_start, limited)
+    pub(crate) fn iter_with_ctx(&self, limited: bool) -> RRuleIter<'_> {
+        RRuleIter::new(self, &self.dt_start(), limited)
     }
-}
-
-impl FromStr for RRule<Unvalidated> {
-    type Err = RRuleError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = ContentLineCaptures::new(s)?;
-        Self::try_from(parts).map_err(From::from)
+    /// Returns an iterator over the recurrences of the rrule, starting from the
+    /// start datetime it was validated against.
+    ///
+    /// The recurrences are generated lazily, so this can be used with rules without
+    /// a `COUNT` or `UNTIL`. Note that the iterator does not enforce any validation
+    /// limits, so make sure to stop the iteration yourself, e.g. with [`Iterator::take`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let dates = rrule.iter().take(3).collect::<Vec<_>>();
+    /// assert_eq!(dates.len(), 3);
+    /// assert_eq!(dates[2], Tz::UTC.with_ymd_and_hms(2021, 1, 3, 9, 0, 0).unwrap());
+    /// ```
+    #[must_use]
+    pub fn iter(&self) -> RRuleIter<'_> {
+        self.iter_with_ctx(false)
     }
-}
 
-impl<S> Display for RRule<S> {
-    /// Generates a string based on the [iCalendar RRULE spec](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3).
-    /// It doesn't prepend "RRULE:" to the string.
-    /// When you call this function on [`RRule<Unvalidated>`], it can generate an invalid string, like 'FREQ=YEARLY;INTERVAL=-1'
-    /// But it is supposed to always generate a valid string on [`RRule<Validated>`].
-    /// So if you want a valid string, it's smarter to always use `rrule.validate(ds_start)?.to_string()`.
-    #[allow(clippy::too_many_lines)]
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut res = Vec::with_capacity(15);
-        res.push(format!("FREQ={}", &self.freq));
+    /// Returns the rule with its start datetime in the timezone `tz`, at the same local
+    /// wall-clock time, e.g. to move a schedule along when a user moves to another timezone.
+    ///
+    /// `UNTIL` is moved the same way, so the rule ends at the same local time.
+    /// A local time that is ambiguous in `tz` uses the earlier offset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError`], if the local start or until datetime doesn't exist in `tz`
+    /// because of daylight saving time, or if the rule isn't valid in `tz`.
+    ///
+    /// # Usage
+    ///
+    #[cfg_attr(feature = "chrono-tz", doc = "```")]
+    #[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+    /// use rrule::{RRule, Tz};
+    ///
+    /// let rrule: RRule = "DTSTART;TZID=Europe/Paris:20210104T090000\n\
+    ///     RRULE:FREQ=WEEKLY;UNTIL=20210125T080000Z;BYDAY=MO"
+    ///     .parse()
+    ///     .unwrap();
+    /// let rrule = rrule.with_timezone(Tz::America__New_York).unwrap();
+    /// assert_eq!(
+    ///     rrule.to_string(),
+    ///     "DTSTART;TZID=America/New_York:20210104T090000\n\
+    ///      RRULE:FREQ=WEEKLY;UNTIL=20210125T140000Z;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=MO"
+    /// );
+    /// ```
+    pub fn with_timezone(&self, tz: Tz) -> Result<Self, RRuleError> {
+        let dt_start = self.dt_start();
+        let to_local = |dt: &DateTime, property: &str| {
+            let local = dt.with_timezone(&dt_start.timezone()).naive_local();
+            tz.from_local_datetime(&local).earliest().ok_or_else(|| {
+                ParseError::InvalidDateTimeInLocalTimezone {
+                    value: local.format("%Y%m%dT%H%M%S").to_string(),
+                    property: property.into(),
+                }
+            })
+        };
+        let new_dt_start = to_local(&dt_start, "DTSTART")?;
+        let until = match &self.until {
+            // `UNTIL` has to be in UTC, unless the start datetime is in local time.
+            Some(until) if tz.is_local() => Some(to_local(until, "UNTIL")?),
+            Some(until) => Some(to_local(until, "UNTIL")?.with_timezone(&Tz::UTC)),
+            None => None,
+        };
 
-        if let Some(until) = &self.until {
-            let maybe_zulu = if until.timezone().is_local() { "" } else { "Z" };
-            res.push(format!(
-                "UNTIL={}{}",
-                until.format("%Y%m%dT%H%M%S"),
-                maybe_zulu
-            ));
+        RRule::<Unvalidated> {
+            freq: self.freq,
+            interval: self.interval,
+            count: self.count,
+            until,
+            week_start: self.week_start,
+            by_set_pos: self.by_set_pos.clone(),
+            by_month: self.by_month.clone(),
+            by_leap_month: self.by_leap_month.clone(),
+            by_month_day: self.by_month_day.clone(),
+            by_n_month_day: self.by_n_month_day.clone(),
+            by_year_day: self.by_year_day.clone(),
+            by_week_no: self.by_week_no.clone(),
+            by_weekday: self.by_weekday.clone(),
+            by_hour: self.by_hour.clone(),
+            by_minute: self.by_minute.clone(),
+            by_second: self.by_second.clone(),
+            by_easter: self.by_easter,
+            rscale: self.rscale,
+            skip: self.skip,
+            leap_day: self.leap_day,
+            dt_start: None,
+            cache: OccurrenceCache::default(),
+            stage: PhantomData,
         }
+        .validate(new_dt_start)
+    }
 
-        if let Some(count) = &self.count {
-            res.push(format!("COUNT={}", count));
-        }
+    /// Returns the same properties as the [`Display`] implementation, with the lines
+    /// separated by `\r\n` and folded at 75 octets, as in iCalendar files.
+    #[must_use]
+    pub fn to_folded_string(&self) -> String {
+        fold_lines(&self.to_string())
+    }
 
-        // One interval is the default, no need to expose it.
-        if self.interval != 1 {
-            res.push(format!("INTERVAL={}", &self.interval));
-        }
+    /// Returns the rule in a canonical form, so that rules that are written differently,
+    /// but produce the same recurrences from the same start date, are equal and have the
+    /// same hash.
+    ///
+    /// The BYxxx rule parts are sorted and without duplicates, and the parts that are
+    /// the same as the defaults taken from the start date are left out, like `BYHOUR=9`
+    /// with a start date at 9:00 or `BYMONTHDAY=15` in a `MONTHLY` rule that starts on
+    /// the 15th. `WKST` is reset to Monday when it doesn't change the recurrences.
+    ///
+    /// Validating the normalized rule with the start date gives a rule with the same
+    /// recurrences.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 15, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYMONTHDAY=15;BYHOUR=9;BYMINUTE=0;WKST=SU"
+    ///     .parse()
+    ///     .unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// assert_eq!(rrule.normalized().to_string(), "FREQ=MONTHLY");
+    ///
+    /// let other: RRule<Unvalidated> = "FREQ=MONTHLY".parse().unwrap();
+    /// let other = other.validate(dt_start).unwrap();
+    /// assert_ne!(other, rrule);
+    /// assert_eq!(other.normalized(), rrule.normalized());
+    /// ```
+    #[must_use]
+    pub fn normalized(&self) -> RRule<Unvalidated> {
+        let dt_start = self.dt_start();
+        let mut by_month_day: ByRule<_> = self
+            .by_month_day
+            .iter()
+            .chain(&self.by_n_month_day)
+            .copied()
+            .collect();
+        by_month_day.sort_unstable();
+        let mut rrule = RRule::<Unvalidated> {
+            freq: self.freq,
+            interval: self.interval,
+            count: self.count,
+            until: self.until,
+            week_start: self.week_start,
+            by_set_pos: self.by_set_pos.clone(),
+            by_month: self.by_month.clone(),
+            by_leap_month: self.by_leap_month.clone(),
+            by_month_day,
+            by_n_month_day: ByRule::new(),
+            by_year_day: self.by_year_day.clone(),
+            by_week_no: self.by_week_no.clone(),
+            by_weekday: self.by_weekday.clone(),
+            by_hour: self.by_hour.clone(),
+            by_minute: self.by_minute.clone(),
+            by_second: self.by_second.clone(),
+            by_easter: self.by_easter,
+            rscale: self.rscale,
+            skip: self.skip,
+            leap_day: self.leap_day,
+            dt_start: None,
+            cache: OccurrenceCache::default(),
+            stage: PhantomData,
+        };
 
-        // Monday is the default, no need to expose it.
-        if self.week_start != Weekday::Mon {
-            res.push(format!("WKST={}", &self.week_start));
+        // The time of day is taken from the start date when it is left out.
+        if rrule.freq < Frequency::Hourly && rrule.by_hour.as_slice() == [get_hour(&dt_start)] {
+            rrule.by_hour.clear();
+        }
+        if rrule.freq < Frequency::Minutely && rrule.by_minute.as_slice() == [get_minute(&dt_start)]
+        {
+            rrule.by_minute.clear();
+        }
+        if rrule.freq < Frequency::Secondly && rrule.by_second.as_slice() == [get_second(&dt_start)]
+        {
+            rrule.by_second.clear();
         }
 
-        if !self.by_set_pos.is_empty() {
-            res.push(format!(
-                "BYSETPOS={}",
-                self.by_set_pos
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ));
+        // So is the day, when there are no other BYxxx rule parts for the days.
+        let has_other_days = !rrule.by_week_no.is_empty()
+            || !rrule.by_year_day.is_empty()
+            || rrule.by_easter.is_some();
+        let (month, leap, day) = rrule.start_month_and_day(&dt_start);
+        let is_start_day = rrule.by_weekday.is_empty() && rrule.by_month_day.as_slice() == [day];
+        match rrule.freq {
+            // Without the months, the month day applies to every month of the year.
+            Frequency::Yearly
+                if !has_other_days
+                    && is_start_day
+                    && !(rrule.by_month.is_empty() && rrule.by_leap_month.is_empty()) =>
+            {
+                rrule.by_month_day.clear();
+                let start_months = if leap {
+                    (&rrule.by_leap_month, &rrule.by_month)
+                } else {
+                    (&rrule.by_month, &rrule.by_leap_month)
+                };
+                if start_months.0.as_slice() == [month] && start_months.1.is_empty() {
+                    rrule.by_month.clear();
+                    rrule.by_leap_month.clear();
+                }
+            }
+            Frequency::Monthly if !has_other_days && is_start_day => rrule.by_month_day.clear(),
+            Frequency::Weekly
+                if !has_other_days
+                    && rrule.by_month_day.is_empty()
+                    && rrule.by_weekday.as_slice() == [NWeekday::Every(dt_start.weekday())] =>
+            {
+                rrule.by_weekday.clear();
+            }
+            _ => {}
         }
 
-        if !self.by_month.is_empty() {
-            res.push(format!(
-                "BYMONTH={}",
-                self.by_month
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ));
+        // The start of the week only changes the week numbers, and the weeks of `WEEKLY`
+        // rules with an interval and more than one weekday.
+        let uses_week_start = !rrule.by_week_no.is_empty()
+            || (rrule.freq == Frequency::Weekly
+                && rrule.interval > 1
+                && rrule.by_weekday.len() > 1);
+        if !uses_week_start {
+            rrule.week_start = Weekday::Mon;
         }
+        rrule
+    }
 
-        if !self.by_month_day.is_empty() {
-            res.push(format!(
-                "BYMONTHDAY={}",
-                self.by_month_day
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ));
+    /// Returns a simpler rule with the same recurrences from the start date, to write the
+    /// rule for consumers that are picky about the rule parts.
+    ///
+    /// On top of [`RRule::normalized`], the BYxxx rule parts that only limit the
+    /// recurrences of the frequency and have all of their possible values are left out,
+    /// like `BYDAY` with all the weekdays in a `DAILY` rule. `DAILY` rules with an
+    /// interval of whole weeks become `WEEKLY`, and `MONTHLY` rules with an interval of
+    /// whole years become `YEARLY`, unless they have BYxxx rule parts for the days other
+    /// than the weekday of the start date.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR,SA,SU;BYHOUR=9".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    /// assert_eq!(rrule.simplified().to_string(), "FREQ=DAILY");
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY;INTERVAL=14;COUNT=3".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    /// assert_eq!(rrule.simplified().to_string(), "FREQ=WEEKLY;COUNT=3;INTERVAL=2");
+    /// ```
+    #[must_use]
+    pub fn simplified(&self) -> RRule<Unvalidated> {
+        let mut rrule = self.normalized();
+        for by_rule in redundant_by_rules(&rrule) {
+            match by_rule {
+                "BYMONTH" => rrule.by_month.clear(),
+                "BYMONTHDAY" => rrule.by_month_day.clear(),
+                "BYDAY" => rrule.by_weekday.clear(),
+                "BYHOUR" => rrule.by_hour.clear(),
+                "BYMINUTE" => rrule.by_minute.clear(),
+                "BYSECOND" => rrule.by_second.clear(),
+                _ => {}
+            }
+        }
+        // Every day of a `DAILY` rule with an interval of whole weeks is on the weekday
+        // of the start date.
+        let start_weekday = NWeekday::Every(self.dt_start().weekday());
+        if rrule.freq == Frequency::Daily
+            && rrule.interval % 7 == 0
+            && rrule.by_set_pos.is_empty()
+            && rrule.by_weekday.contains(&start_weekday)
+        {
+            rrule.by_weekday.clear();
         }
 
-        if !self.by_week_no.is_empty() {
-            res.push(format!(
-                "BYWEEKNO={}",
-                self.by_week_no
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ));
+        let has_days = !rrule.by_month_day.is_empty()
+            || !rrule.by_weekday.is_empty()
+            || !rrule.by_year_day.is_empty()
+            || !rrule.by_week_no.is_empty()
+            || !rrule.by_set_pos.is_empty()
+            || rrule.by_easter.is_some();
+        if !has_days {
+            // The day of the week or the year is taken from the start date.
+            match rrule.freq {
+                Frequency::Daily if rrule.interval % 7 == 0 => {
+                    rrule.freq = Frequency::Weekly;
+                    rrule.interval /= 7;
+                }
+                // The years of the Chinese calendar can have 13 months.
+                Frequency::Monthly
+                    if rrule.interval % 12 == 0
+                        && rrule.rscale != Some(RScale::Chinese)
+                        && rrule.by_month.is_empty()
+                        && rrule.by_leap_month.is_empty() =>
+                {
+                    rrule.freq = Frequency::Yearly;
+                    rrule.interval /= 12;
+                }
+                _ => {}
+            }
         }
+        rrule
+    }
 
-        if !self.by_hour.is_empty() {
-            res.push(format!(
+    /// Returns `true` if the rrule produces the same recurrences as `other`, although
+    /// they can be written differently.
+    ///
+    /// When both rrules are finite, all their recurrences are compared. Otherwise only the
+    /// recurrences up to `horizon` are, so the rrules can still differ after it. Rules with
+    /// the same start date and [`RRule::normalized`] form are equivalent without comparing
+    /// their recurrences.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 4, 9, 0, 0).unwrap();
+    /// let horizon = Tz::UTC.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+    /// let weekdays: RRule<Unvalidated> = "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR".parse().unwrap();
+    /// let weekdays = weekdays.validate(dt_start).unwrap();
+    /// let weekly: RRule<Unvalidated> = "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR".parse().unwrap();
+    /// let weekly = weekly.validate(dt_start).unwrap();
+    /// assert!(weekdays.equivalent_to(&weekly, horizon));
+    ///
+    /// let daily: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let daily = daily.validate(dt_start).unwrap();
+    /// assert!(!weekdays.equivalent_to(&daily, horizon));
+    /// ```
+    #[must_use]
+    pub fn equivalent_to(&self, other: &Self, horizon: DateTime) -> bool {
+        if self.dt_start == other.dt_start && self.normalized() == other.normalized() {
+            return true;
+        }
+        let is_bounded = self.is_finite() && other.is_finite();
+        let before_horizon = |date: &DateTime| is_bounded || *date <= horizon;
+        self.iter_with_ctx(true)
+            .take_while(before_horizon)
+            .eq(other.iter_with_ctx(true).take_while(before_horizon))
+    }
+
+    /// Get the start datetime the rule was validated against.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn get_dt_start(&self) -> &DateTime {
+        self.dt_start
+            .as_ref()
+            .expect("the start datetime is always set when the rule is validated")
+    }
+
+    /// Returns the start datetime the rule was validated against.
+    pub(crate) fn dt_start(&self) -> DateTime {
+        self.dt_start
+            .expect("the start datetime is always set when the rule is validated")
+    }
+
+    /// Returns all the recurrences of the rrule between `after` and `before`.
+    ///
+    /// When `inclusive` is `true`, recurrences equal to `after` or `before` are included.
+    /// The iteration stops as soon as a recurrence is past `before`, so this
+    /// can safely be used on rules without a `COUNT` or `UNTIL`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let after = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 9, 0, 0).unwrap();
+    /// let before = Tz::UTC.with_ymd_and_hms(2021, 1, 8, 9, 0, 0).unwrap();
+    /// assert_eq!(rrule.between(after, before, true).dates.len(), 4);
+    /// assert_eq!(rrule.between(after, before, false).dates.len(), 2);
+    /// ```
+    ///
+    /// With [`RRule::with_cache`], the recurrences of each window are computed once.
+    #[must_use]
+    pub fn between(&self, after: DateTime, before: DateTime, inclusive: bool) -> RRuleResult {
+        self.cache.get_or_compute((after, before, inclusive), || {
+            collect_with_error(
+                self.iter_with_ctx(true),
+                &Some(after),
+                &Some(before),
+                inclusive,
+                None,
+            )
+        })
+    }
+
+    /// Enables a cache of the recurrences of the windows queried with [`RRule::between`],
+    /// so that querying the same window again, e.g. on a rule shared with an `Arc`
+    /// between threads, doesn't compute them again.
+    ///
+    /// The clones of the rule share its cache. The cache holds the recurrences of up to
+    /// 64 windows, and is emptied when it is full or with [`RRule::clear_cache`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let rrule = Arc::new(rrule.validate(dt_start).unwrap().with_cache());
+    ///
+    /// let after = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 9, 0, 0).unwrap();
+    /// let before = Tz::UTC.with_ymd_and_hms(2021, 1, 8, 9, 0, 0).unwrap();
+    /// let shared = Arc::clone(&rrule);
+    /// let dates = std::thread::spawn(move || shared.between(after, before, true).dates)
+    ///     .join()
+    ///     .unwrap();
+    /// // Computed by the other thread.
+    /// assert_eq!(rrule.between(after, before, true).dates, dates);
+    /// ```
+    #[must_use]
+    pub fn with_cache(mut self) -> Self {
+        self.cache = OccurrenceCache::enabled();
+        self
+    }
+
+    /// Removes the recurrences cached since [`RRule::with_cache`]. The cache stays
+    /// enabled.
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// Returns the first recurrence after `dt`, or `None` if there is none.
+    ///
+    /// When `inclusive` is `true`, a recurrence equal to `dt` is returned as well.
+    ///
+    /// For `DAILY` and `HOURLY` rules, and `WEEKLY` rules without any BYxxx rule parts,
+    /// the recurrence is computed directly, however long after the start date `dt` is.
+    /// Other rules iterate up to the recurrence.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let dt = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 9, 0, 0).unwrap();
+    /// assert_eq!(rrule.after(dt, true), Some(dt));
+    /// assert_eq!(
+    ///     rrule.after(dt, false),
+    ///     Some(Tz::UTC.with_ymd_and_hms(2021, 1, 6, 9, 0, 0).unwrap())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn after(&self, dt: DateTime, inclusive: bool) -> Option<DateTime> {
+        match fast_path::fixed_step(self) {
+            Some(step) => {
+                let (n, date) = fast_path::first_after(&self.dt_start(), step, &dt, inclusive)?;
+                let is_within_count = self.count.map_or(true, |count| {
+                    usize::try_from(count).is_ok_and(|count| n < count)
+                });
+                let is_within_until = !matches!(self.until, Some(until) if date > until);
+                (is_within_count && is_within_until && YEAR_RANGE.contains(&date.year()))
+                    .then_some(date)
+            }
+            None => self
+                .iter_with_ctx(true)
+                .find(|date| if inclusive { *date >= dt } else { *date > dt }),
+        }
+    }
+
+    /// Describes the first recurrence after `now` relative to it, like `next: in 3 days
+    /// (Thu, Mar 14, 9:00 AM)`, or returns `None` if there is none.
+    ///
+    /// The amount of time is rounded down to minutes, hours, days, weeks, months or years,
+    /// and the date is in the timezone of the rrule.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2024, 3, 7, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;COUNT=2".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let now = Tz::UTC.with_ymd_and_hms(2024, 3, 11, 17, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     rrule.next_to_text(now).as_deref(),
+    ///     Some("next: in 3 days (Thu, Mar 14, 9:00 AM)")
+    /// );
+    /// assert_eq!(rrule.next_to_text(dt_start + chrono::Duration::days(7)), None);
+    /// ```
+    #[must_use]
+    pub fn next_to_text(&self, now: DateTime) -> Option<String> {
+        let next = self.after(now, false)?;
+        Some(next_text(&now, &next))
+    }
+
+    /// Returns the last recurrence before `dt`, or `None` if there is none.
+    ///
+    /// When `inclusive` is `true`, a recurrence equal to `dt` is returned as well.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let dt = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 9, 0, 0).unwrap();
+    /// assert_eq!(rrule.before(dt, true), Some(dt));
+    /// assert_eq!(
+    ///     rrule.before(dt, false),
+    ///     Some(Tz::UTC.with_ymd_and_hms(2021, 1, 4, 9, 0, 0).unwrap())
+    /// );
+    /// assert_eq!(rrule.before(dt_start, false), None);
+    /// ```
+    #[must_use]
+    pub fn before(&self, dt: DateTime, inclusive: bool) -> Option<DateTime> {
+        self.iter_with_ctx(true)
+            .take_while(|date| if inclusive { *date <= dt } else { *date < dt })
+            .last()
+    }
+
+    /// Returns `true` if `dt` is a recurrence of the rrule.
+    ///
+    /// The iteration stops at the first recurrence that is not before `dt`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// assert!(rrule.contains(Tz::UTC.with_ymd_and_hms(2021, 1, 8, 9, 0, 0).unwrap()));
+    /// assert!(!rrule.contains(Tz::UTC.with_ymd_and_hms(2021, 1, 9, 9, 0, 0).unwrap()));
+    /// ```
+    #[must_use]
+    pub fn contains(&self, dt: DateTime) -> bool {
+        self.after(dt, true) == Some(dt)
+    }
+
+    /// Returns which rule part accepted or rejected each candidate date within `range`,
+    /// to find out why the rrule does or doesn't produce a date.
+    ///
+    /// The candidates are the days of the periods of the frequency, like the months of a
+    /// `MONTHLY` rule, at the times of day of the rrule. The days are checked against
+    /// `BYMONTH`, `BYWEEKNO`, `BYDAY`, `BYEASTER`, `BYMONTHDAY` and `BYYEARDAY` in that
+    /// order, then `SKIP`, `BYSETPOS`, the start date, `UNTIL` and `COUNT`. The periods
+    /// that are skipped by the `INTERVAL` have no candidates, and the trace ends at the
+    /// candidate that is rejected by `UNTIL` or `COUNT`. For a rule of
+    /// [`RScale::Chinese`], only the recurrences are returned, as accepted candidates.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, RulePart, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"
+    ///     .parse()
+    ///     .unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let range = dt_start..Tz::UTC.with_ymd_and_hms(2021, 2, 1, 0, 0, 0).unwrap();
+    /// let explanations = rrule.explain(range);
+    /// assert_eq!(explanations.len(), 31);
+    /// assert_eq!(explanations[1].to_string(), "2021-01-02 09:00:00 UTC rejected by BYDAY");
+    /// assert_eq!(explanations[3].get_rule_part(), Some(RulePart::BySetPos));
+    /// assert!(!explanations[3].is_accepted());
+    /// assert_eq!(
+    ///     explanations[28].to_string(),
+    ///     "2021-01-29 09:00:00 UTC accepted by BYSETPOS"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn explain(&self, range: Range<DateTime>) -> Vec<Explanation> {
+        explain(self, &self.dt_start(), &range)
+    }
+
+    /// Returns the `n`th recurrence of the rrule, counting from zero,
+    /// or `None` if the rrule has less than `n + 1` recurrences.
+    ///
+    /// For `DAILY` rules, and `WEEKLY` rules without any BYxxx rule parts, the recurrence
+    /// is computed directly. Other rules iterate up to the `n`th recurrence.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY;COUNT=10".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// assert_eq!(rrule.get(0), Some(dt_start));
+    /// assert_eq!(rrule.get(9), Some(Tz::UTC.with_ymd_and_hms(2021, 1, 10, 9, 0, 0).unwrap()));
+    /// assert_eq!(rrule.get(10), None);
+    /// ```
+    #[must_use]
+    pub fn get(&self, n: usize) -> Option<DateTime> {
+        if matches!(self.count, Some(count) if usize::try_from(count).is_ok_and(|count| n >= count))
+        {
+            return None;
+        }
+
+        match fast_path::fixed_day_step(self) {
+            Some(step) => fast_path::nth_date(&self.dt_start(), step, n)
+                .filter(|date| !matches!(self.until, Some(until) if *date > until)),
+            None => self.iter_with_ctx(true).nth(n),
+        }
+    }
+
+    /// Returns the last recurrence of the rrule, or `None` if the rrule is
+    /// not bounded by `COUNT` or `UNTIL` or has no recurrences.
+    ///
+    /// For `DAILY` rules, and `WEEKLY` rules without any BYxxx rule parts, the last
+    /// recurrence is computed directly. Other rules iterate over all the recurrences.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY;UNTIL=20211231T000000Z".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// assert_eq!(
+    ///     rrule.last_occurrence(),
+    ///     Some(Tz::UTC.with_ymd_and_hms(2021, 12, 30, 9, 0, 0).unwrap())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn last_occurrence(&self) -> Option<DateTime> {
+        if self.count.is_none() && self.until.is_none() {
+            return None;
+        }
+
+        match fast_path::fixed_day_step(self) {
+            Some(step) => fast_path::nth_date(&self.dt_start(), step, self.last_index(step)?),
+            None => self.iter_with_ctx(true).last(),
+        }
+    }
+
+    /// Returns the number of recurrences strictly after `after`, or `None` if the
+    /// rrule is not bounded by `COUNT` or `UNTIL`.
+    ///
+    /// For `DAILY` rules, and `WEEKLY` rules without any BYxxx rule parts, the number
+    /// is computed directly. Other rules iterate over the remaining recurrences.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;COUNT=10".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let after = Tz::UTC.with_ymd_and_hms(2021, 1, 20, 0, 0, 0).unwrap();
+    /// assert_eq!(rrule.count_remaining(after), Some(7));
+    /// ```
+    #[must_use]
+    pub fn count_remaining(&self, after: DateTime) -> Option<u64> {
+        if self.is_infinite() {
+            return None;
+        }
+
+        match fast_path::fixed_day_step(self) {
+            Some(step) => {
+                let Some(last) = self.last_index(step) else {
+                    return Some(0);
+                };
+                let first = fast_path::last_index_until(&self.dt_start(), step, &after)
+                    .map_or(0, |n| n + 1);
+                u64::try_from((last + 1).saturating_sub(first)).ok()
+            }
+            None => Some(
+                self.iter_with_ctx(true)
+                    .skip_while(|date| *date <= after)
+                    .fold(0, |count, _| count + 1),
+            ),
+        }
+    }
+
+    /// Returns an iterator over the recurrences of the rrule from the last to the first,
+    /// or `None` if the rrule is not bounded by `COUNT` or `UNTIL`.
+    ///
+    /// For `DAILY` rules, and `WEEKLY` rules without any BYxxx rule parts, the
+    /// recurrences are computed one by one. Other rules collect all the recurrences first.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY;COUNT=10".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let last_two: Vec<_> = rrule.iter_rev().unwrap().take(2).collect();
+    /// assert_eq!(
+    ///     last_two,
+    ///     vec![
+    ///         Tz::UTC.with_ymd_and_hms(2021, 1, 10, 9, 0, 0).unwrap(),
+    ///         Tz::UTC.with_ymd_and_hms(2021, 1, 9, 9, 0, 0).unwrap(),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn iter_rev(&self) -> Option<RRuleRevIter> {
+        if self.is_infinite() {
+            return None;
+        }
+
+        Some(match fast_path::fixed_day_step(self) {
+            Some(step) => RRuleRevIter::fixed_step(
+                self.dt_start(),
+                step,
+                self.last_index(step).map_or(0, |n| n + 1),
+            ),
+            None => RRuleRevIter::collected(self.iter_with_ctx(true).collect()),
+        })
+    }
+
+    /// Calls `f` with each recurrence of the rrule, until it returns [`ControlFlow::Break`].
+    ///
+    /// Returns the value `f` breaks with, or `None` if all the recurrences were visited.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    /// use std::ops::ControlFlow;
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let mut seen = 0;
+    /// let found = rrule.for_each_until(|date| {
+    ///     seen += 1;
+    ///     if seen == 3 {
+    ///         ControlFlow::Break(date)
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    /// assert_eq!(found, Some(Tz::UTC.with_ymd_and_hms(2021, 1, 3, 9, 0, 0).unwrap()));
+    /// ```
+    pub fn for_each_until<B, F>(&self, f: F) -> Option<B>
+    where
+        F: FnMut(DateTime) -> ControlFlow<B>,
+    {
+        match self.iter_with_ctx(true).try_for_each(f) {
+            ControlFlow::Break(value) => Some(value),
+            ControlFlow::Continue(()) => None,
+        }
+    }
+
+    /// Returns an iterator over the recurrences of the rrule together with their index.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let occurrence = rrule.occurrences().nth(13).unwrap();
+    /// assert_eq!(occurrence.index, 13);
+    /// assert_eq!(occurrence.date, Tz::UTC.with_ymd_and_hms(2021, 1, 14, 9, 0, 0).unwrap());
+    /// ```
+    #[must_use]
+    pub fn occurrences(&self) -> OccurrenceIter<RRuleIter<'_>> {
+        OccurrenceIter::new(self.iter())
+    }
+
+    /// Returns all the recurrences of the rrule between `after` and `before`,
+    /// together with their index within the whole rrule.
+    ///
+    /// When `inclusive` is `true`, recurrences equal to `after` or `before` are included as well.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let after = Tz::UTC.with_ymd_and_hms(2021, 4, 1, 0, 0, 0).unwrap();
+    /// let before = Tz::UTC.with_ymd_and_hms(2021, 4, 10, 0, 0, 0).unwrap();
+    /// let occurrences = rrule.occurrences_between(after, before, true);
+    /// assert_eq!(occurrences.len(), 2);
+    /// assert_eq!(occurrences[0].index, 13);
+    /// ```
+    #[must_use]
+    pub fn occurrences_between(
+        &self,
+        after: DateTime,
+        before: DateTime,
+        inclusive: bool,
+    ) -> Vec<Occurrence> {
+        OccurrenceIter::new(self.iter_with_ctx(true))
+            .skip_while(|occurrence| {
+                occurrence.date < after || (!inclusive && occurrence.date == after)
+            })
+            .take_while(|occurrence| {
+                occurrence.date < before || (inclusive && occurrence.date == before)
+            })
+            .collect()
+    }
+
+    /// Returns the recurrences of the rrule within `range`, grouped by `(year, month)`
+    /// in the timezone of the start date.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let end = Tz::UTC.with_ymd_and_hms(2021, 3, 1, 0, 0, 0).unwrap();
+    /// let months = rrule.all_grouped_by_month(dt_start..end);
+    /// assert_eq!(months.len(), 2);
+    /// assert_eq!(months[&(2021, 1)].len(), 5);
+    /// assert_eq!(months[&(2021, 2)].len(), 4);
+    /// ```
+    #[must_use]
+    pub fn all_grouped_by_month(
+        &self,
+        range: Range<DateTime>,
+    ) -> BTreeMap<(i32, u32), Vec<DateTime>> {
+        group_dates_by(self.iter_with_ctx(true), &range, |date| {
+            (date.year(), date.month())
+        })
+    }
+
+    /// Returns the recurrences of the rrule within `range`, grouped by year
+    /// in the timezone of the start date.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=MONTHLY".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let end = Tz::UTC.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    /// let years = rrule.all_grouped_by_year(dt_start..end);
+    /// assert_eq!(years[&2021].len(), 12);
+    /// assert_eq!(years[&2022].len(), 12);
+    /// ```
+    #[must_use]
+    pub fn all_grouped_by_year(&self, range: Range<DateTime>) -> BTreeMap<i32, Vec<DateTime>> {
+        group_dates_by(self.iter_with_ctx(true), &range, Datelike::year)
+    }
+
+    /// Returns statistics about the recurrences of the rrule within `window`, like their
+    /// number, the shortest and longest time between them, and how many there are on each
+    /// weekday and in each month.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::{Duration, TimeZone};
+    /// use rrule::{RRule, Tz, Unvalidated};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;BYDAY=MO,FR".parse().unwrap();
+    /// let rrule = rrule.validate(dt_start).unwrap();
+    ///
+    /// let end = Tz::UTC.with_ymd_and_hms(2021, 2, 1, 0, 0, 0).unwrap();
+    /// let stats = rrule.stats(dt_start..end);
+    /// assert_eq!(stats.get_count(), 9);
+    /// assert_eq!(stats.get_min_gap(), Some(Duration::days(3)));
+    /// assert_eq!(stats.get_max_gap(), Some(Duration::days(4)));
+    /// assert_eq!(stats.get_per_weekday(), &[4, 0, 0, 0, 5, 0, 0]);
+    /// assert_eq!(stats.get_per_month()[0], 9);
+    /// ```
+    #[must_use]
+    pub fn stats(&self, window: Range<DateTime>) -> OccurrenceStats {
+        OccurrenceStats::from_dates(self.iter_with_ctx(true), &window)
+    }
+
+    /// Returns the index of the last recurrence of a rrule with a fixed day step,
+    /// or `None` if the rrule is unbounded or has no recurrences.
+    fn last_index(&self, step: i64) -> Option<usize> {
+        let last_by_count = self
+            .count
+            .map(|count| usize::try_from(count).ok()?.checked_sub(1));
+        let last_by_until = self
+            .until
+            .map(|until| fast_path::last_index_until(&self.dt_start(), step, &until));
+        match (last_by_count, last_by_until) {
+            (Some(by_count), Some(by_until)) => by_count.min(by_until),
+            (Some(n), None) | (None, Some(n)) => n,
+            (None, None) => None,
+        }
+    }
+}
+
+impl FromStr for RRule<Unvalidated> {
+    type Err = RRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = unfold_lines(s);
+        let line = input.trim();
+        let offset = input.len() - input.trim_start().len();
+        ContentLineCaptures::new(line)
+            .and_then(Self::try_from)
+            .map_err(|err| err.locate(s, offset, line).into())
+    }
+}
+
+impl FromStr for RRule<Validated> {
+    type Err = RRuleError;
+
+    /// Creates a validated [`RRule`] from a `DTSTART` and a single `RRULE` property,
+    /// like the output of its [`Display`] implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError`], if the input is invalid or contains other properties.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Grammar {
+            start,
+            content_lines,
+        } = Grammar::from_str(s)?;
+
+        match <[ContentLine; 1]>::try_from(content_lines) {
+            Ok([ContentLine::RRule(rrule)]) => rrule.validate(start.datetime),
+            _ => Err(ParseError::ExpectedSingleRRule.into()),
+        }
+    }
+}
+
+impl Display for RRule<Unvalidated> {
+    /// Generates a string based on the [iCalendar RRULE spec](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3).
+    /// It doesn't prepend "RRULE:" to the string.
+    /// It can generate an invalid string, like 'FREQ=YEARLY;INTERVAL=-1'.
+    /// If you want a valid string, use `rrule.validate(ds_start)?.to_string()`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value_to_string())
+    }
+}
+
+impl Display for RRule<Validated> {
+    /// Generates the `DTSTART` and `RRULE` properties of the rule, separated by a newline,
+    /// based on the [iCalendar RRULE spec](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3).
+    ///
+    /// The output is normalized: the rule parts that are filled in during validation are
+    /// included, so parsing the output reproduces the same rule.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DTSTART{}\nRRULE:{}",
+            datetime_to_ical_format(&self.dt_start()),
+            self.value_to_string()
+        )
+    }
+}
+
+impl<S> RRule<S> {
+    /// Returns the month, if it is a leap month, and the month day of `dt_start` in the
+    /// calendar system of the rule, which are the defaults of the `BYMONTH` and
+    /// `BYMONTHDAY` rule parts.
+    pub(crate) fn start_month_and_day(&self, dt_start: &DateTime) -> (u8, bool, i8) {
+        if self.rscale == Some(RScale::Chinese) {
+            let date = ChineseDate::from_date(dt_start.date_naive());
+            let day = i8::try_from(date.day).expect("range 1-30 is covered by i8");
+            (date.month, date.leap, day)
+        } else {
+            (get_month(dt_start), false, get_day(dt_start))
+        }
+    }
+
+    /// Returns the values of the `BYMONTH` rule part in order, with a leap month
+    /// after the month it follows, e.g. `["5", "5L", "6"]`.
+    pub(crate) fn month_values(&self) -> Vec<String> {
+        let mut months: Vec<(u8, bool)> = self
+            .by_month
+            .iter()
+            .map(|month| (*month, false))
+            .chain(self.by_leap_month.iter().map(|month| (*month, true)))
+            .collect();
+        months.sort_unstable();
+        months
+            .into_iter()
+            .map(|(month, leap)| {
+                if leap {
+                    format!("{}L", month)
+                } else {
+                    month.to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Generates the value of the `RRULE` property, without the "RRULE:" prefix.
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn value_to_string(&self) -> String {
+        let mut res = Vec::with_capacity(15);
+        res.push(format!("FREQ={}", &self.freq));
+
+        if let Some(until) = &self.until {
+            let maybe_zulu = if until.timezone().is_local() { "" } else { "Z" };
+            res.push(format!(
+                "UNTIL={}{}",
+                until.format("%Y%m%dT%H%M%S"),
+                maybe_zulu
+            ));
+        }
+
+        if let Some(count) = &self.count {
+            res.push(format!("COUNT={}", count));
+        }
+
+        // One interval is the default, no need to expose it.
+        if self.interval != 1 {
+            res.push(format!("INTERVAL={}", &self.interval));
+        }
+
+        // Monday is the default, no need to expose it.
+        if self.week_start != Weekday::Mon {
+            res.push(format!("WKST={}", weekday_to_str(self.week_start)));
+        }
+
+        if !self.by_set_pos.is_empty() {
+            res.push(format!(
+                "BYSETPOS={}",
+                self.by_set_pos
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+
+        if !self.by_month.is_empty() || !self.by_leap_month.is_empty() {
+            res.push(format!("BYMONTH={}", self.month_values().join(",")));
+        }
+
+        // Negative month days are moved to `by_n_month_day` during validation.
+        if !self.by_month_day.is_empty() || !self.by_n_month_day.is_empty() {
+            res.push(format!(
+                "BYMONTHDAY={}",
+                self.by_month_day
+                    .iter()
+                    .chain(&self.by_n_month_day)
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+
+        if !self.by_week_no.is_empty() {
+            res.push(format!(
+                "BYWEEKNO={}",
+                self.by_week_no
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+
+        if !self.by_hour.is_empty() {
+            res.push(format!(
                 "BYHOUR={}",
                 self.by_hour
                     .iter()
@@ -763,7 +2089,16 @@ impl<S> Display for RRule<S> {
             res.push(format!("BYEASTER={}", by_easter));
         }
 
-        write!(f, "{}", res.join(";"))
+        if let Some(rscale) = &self.rscale {
+            res.push(format!("RSCALE={}", rscale));
+        }
+
+        // Omitting the dates that don't exist is the default, no need to expose it.
+        if self.skip != Skip::Omit {
+            res.push(format!("SKIP={}", self.skip));
+        }
+
+        res.join(";")
     }
 }
 
@@ -792,13 +2127,13 @@ impl<S> RRule<S> {
         self.until.as_ref()
     }
 
-    /// Get the `by_set_pos` of the recurrence.
+    /// Get the `week_start` of the recurrence.
     #[must_use]
     pub fn get_week_start(&self) -> Weekday {
         self.week_start
     }
 
-    /// Get the `by_month` of the recurrence.
+    /// Get the `by_set_pos` of the recurrence.
     #[must_use]
     pub fn get_by_set_pos(&self) -> &[i32] {
         &self.by_set_pos
@@ -810,25 +2145,41 @@ impl<S> RRule<S> {
         &self.by_month
     }
 
+    /// Get the leap months of the recurrence, like `5` for `BYMONTH=5L`.
+    #[must_use]
+    pub fn get_by_leap_month(&self) -> &[u8] {
+        &self.by_leap_month
+    }
+
     /// Get the `by_month_day` of the recurrence.
+    ///
+    /// Once the rule is validated, these are only the positive days of the month.
+    /// The negative ones are returned by [`RRule::get_by_n_month_day`].
     #[must_use]
     pub fn get_by_month_day(&self) -> &[i8] {
         &self.by_month_day
     }
 
+    /// Get the negative `by_month_day` of the validated recurrence, like `-1` for the last
+    /// day of the month.
+    #[must_use]
+    pub fn get_by_n_month_day(&self) -> &[i8] {
+        &self.by_n_month_day
+    }
+
     /// Get the `by_year_day` of the recurrence.
     #[must_use]
     pub fn get_by_year_day(&self) -> &[i16] {
         &self.by_year_day
     }
 
-    /// Get the `by_hour` of the recurrence.
+    /// Get the `by_week_no` of the recurrence.
     #[must_use]
     pub fn get_by_week_no(&self) -> &[i8] {
         &self.by_week_no
     }
 
-    /// Get the `by_hour` of the recurrence.
+    /// Get the `by_weekday` of the recurrence.
     #[must_use]
     pub fn get_by_weekday(&self) -> &[NWeekday] {
         &self.by_weekday
@@ -852,10 +2203,113 @@ impl<S> RRule<S> {
         &self.by_second
     }
 
+    /// Get the calendar system of the recurrence, if the rule has an `RSCALE`.
+    #[must_use]
+    pub fn get_rscale(&self) -> Option<RScale> {
+        self.rscale
+    }
+
+    /// Get what happens to the dates of the recurrence that don't exist.
+    #[must_use]
+    pub fn get_skip(&self) -> Skip {
+        self.skip
+    }
+
+    /// Get what happens to the 29th of February of a yearly rule in non-leap years.
+    #[must_use]
+    pub fn get_leap_day(&self) -> Skip {
+        self.leap_day
+    }
+
     /// Get the `by_easter` of the recurrence.
     #[cfg(feature = "by-easter")]
     #[must_use]
     pub fn get_by_easter(&self) -> Option<&i16> {
         self.by_easter.as_ref()
     }
+
+    /// Returns `true` if the recurrence is bounded by `COUNT` or `UNTIL`.
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        self.count.is_some() || self.until.is_some()
+    }
+
+    /// Returns `true` if the recurrence is neither bounded by `COUNT` nor by `UNTIL`.
+    #[must_use]
+    pub fn is_infinite(&self) -> bool {
+        !self.is_finite()
+    }
+
+    /// Returns warnings about parts of the rule that are valid, but probably don't do what
+    /// was meant, like `BYMONTHDAY=31` in months without a 31st, or `COUNT` together
+    /// with `UNTIL`. The rule has no such issues if the result is empty.
+    ///
+    /// A validated rule can have parts that are taken from the start date, like the
+    /// month day of a `MONTHLY` rule, which are checked as well.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{LintWarning, RRule, Unvalidated};
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=YEARLY;BYMONTH=4,5;BYMONTHDAY=31;COUNT=3;UNTIL=20301231T000000Z"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     rrule.lint(),
+    ///     vec![
+    ///         LintWarning::MonthDayMissingInMonths { day: 31, months: vec![4] },
+    ///         LintWarning::CountAndUntil,
+    ///     ]
+    /// );
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;BYDAY=MO,FR".parse().unwrap();
+    /// assert!(rrule.lint().is_empty());
+    /// ```
+    #[must_use]
+    pub fn lint(&self) -> Vec<LintWarning> {
+        lint_rrule(self)
+    }
+
+    /// Checks the BYxxx rule parts against the frequency, following the table of
+    /// RFC 5545: `BYMONTHDAY` can't be used with `WEEKLY`, `BYYEARDAY` can't be used with
+    /// `DAILY`, `WEEKLY` and `MONTHLY`, and `BYWEEKNO` can only be used with `YEARLY`.
+    ///
+    /// Unlike [`RRule::validate`], it doesn't need a start date and doesn't consume the
+    /// rule, so it can be used while the rule is edited. [`RRule::validate`] does the
+    /// same checks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::InvalidByRuleAndFrequency`] for the rule part that can't
+    /// be used with the frequency, or [`ValidationError::Multiple`] if there are several.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{Frequency, RRule, Unvalidated, ValidationError};
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYYEARDAY=100;BYWEEKNO=20".parse().unwrap();
+    /// let err = rrule.validate_by_rules().unwrap_err();
+    /// assert_eq!(
+    ///     err.errors(),
+    ///     [
+    ///         ValidationError::InvalidByRuleAndFrequency {
+    ///             by_rule: "BYYEARDAY".into(),
+    ///             freq: Frequency::Monthly,
+    ///         },
+    ///         ValidationError::InvalidByRuleAndFrequency {
+    ///             by_rule: "BYWEEKNO".into(),
+    ///             freq: Frequency::Monthly,
+    ///         },
+    ///     ]
+    /// );
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=YEARLY;BYYEARDAY=100;BYWEEKNO=20".parse().unwrap();
+    /// assert!(rrule.validate_by_rules().is_ok());
+    /// ```
+    pub fn validate_by_rules(&self) -> Result<(), ValidationError> {
+        ValidationError::from_errors(validate_rrule::by_rules_with_freq_errors(self))
+            .map_or(Ok(()), Err)
+    }
 }
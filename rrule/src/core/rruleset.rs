@@ -1,17 +1,37 @@
-use crate::core::datetime::datetime_to_ical_format;
-use crate::core::utils::collect_with_error;
-use crate::core::DateTime;
-use crate::parser::{ContentLine, Grammar};
-use crate::{RRule, RRuleError};
-#[cfg(feature = "serde")]
-use serde_with::{serde_as, DeserializeFromStr, SerializeDisplay};
+use crate::core::datetime::{date_to_ical_format, datetime_to_ical_format};
+use crate::core::stats::OccurrenceStats;
+use crate::core::text::next_text;
+#[cfg(feature = "rayon")]
+use crate::core::utils::is_in_range;
+use crate::core::utils::{collect_with_error, fold_lines, group_dates_by};
+use crate::core::{has_same_calendars, is_holiday, BusinessDays, DateTime};
+use crate::iter::{
+    add_time_to_date, to_event_date, EventDateIter, IntervalIter, IterCursor, IterState,
+    OccurrenceIter, RRuleSetIter, SetOperation, SetOperationIter, SourceIter,
+};
+use crate::parser::{
+    CachedTzResolver, Compat, ContentLine, Grammar, ParseWarning, ParserConfig,
+    StartDateContentLine,
+};
+use crate::{
+    BusinessDayPolicy, DstFoldPolicy, DstGapPolicy, EventDate, EventDuration, FloatingRRuleSet,
+    HolidayCalendar, ParseError, RRule, RRuleError, RecurrenceOverride, Tz, Unvalidated,
+};
+use chrono::{Datelike, NaiveDate, TimeZone};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::ops::{ControlFlow, Range};
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// A validated Recurrence Rule that can be used to create an iterator.
-#[cfg_attr(feature = "serde", serde_as)]
-#[derive(Clone, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
+///
+/// Two sets are equal, and have the same hash, if they have the same start date, rules and dates,
+/// regardless of the order in which the rules and dates were added, and the same holiday
+/// calendars, i.e. the same instances in the same order, and business day policy.
+#[derive(Clone, Debug)]
 pub struct RRuleSet {
     /// List of rrules.
     pub(crate) rrule: Vec<RRule>,
@@ -29,6 +49,68 @@ pub struct RRuleSet {
     pub(crate) after: Option<DateTime>,
     /// If validation limits are enabled
     pub(crate) limited: bool,
+    /// How occurrences in the gap of a daylight saving time transition are handled.
+    pub(crate) dst_gap: DstGapPolicy,
+    /// How occurrences with an ambiguous local time at a daylight saving time transition
+    /// are handled.
+    pub(crate) dst_fold: DstFoldPolicy,
+    /// The calendars of the holidays that are excluded.
+    pub(crate) holidays: Vec<Arc<dyn HolidayCalendar>>,
+    /// If set, the recurrences on weekends and holidays are moved to business days.
+    pub(crate) business_days: Option<BusinessDays>,
+    /// How recurrences at the same instant are handled.
+    pub(crate) duplicates: DuplicatePolicy,
+    /// The moved and cancelled recurrences, with unique recurrence ids.
+    pub(crate) overrides: Vec<RecurrenceOverride>,
+    /// The duration of the recurrences, from the `DURATION` or `DTEND` of the event.
+    pub(crate) duration: Option<EventDuration>,
+    /// The start and the duration of the rdates that are periods, from an
+    /// `RDATE;VALUE=PERIOD`. The starts are rdates of the set as well.
+    pub(crate) periods: Vec<(DateTime, EventDuration)>,
+    /// The days on which every recurrence is excluded, from an `EXDATE;VALUE=DATE`.
+    pub(crate) exdate_days: Vec<NaiveDate>,
+    /// If the recurrences are whole days, from a `DTSTART;VALUE=DATE`.
+    pub(crate) all_day: bool,
+}
+
+/// How recurrences of an [`RRuleSet`] at the same instant are handled, e.g. an rdate
+/// that is also a date of an rrule.
+///
+/// The recurrences are returned in chronological order. Recurrences at the same instant
+/// are returned in the order of their source: the rdates first, then the rrules in the
+/// order of [`RRuleSet::get_rrule`].
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{DuplicatePolicy, RRuleSet};
+///
+/// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2\nRDATE:20210102T090000Z"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(rrule_set.clone().all(10).dates.len(), 2);
+/// assert_eq!(rrule_set.duplicates(DuplicatePolicy::Keep).all(10).dates.len(), 3);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuplicatePolicy {
+    /// Returns the first recurrence at an instant only, as RFC 5545 specifies.
+    #[default]
+    Merge,
+    /// Returns every recurrence at an instant, e.g. for systems that treat an rdate as
+    /// a distinct, pinned instance.
+    Keep,
+}
+
+/// The return result of `RRuleSet::diff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OccurrenceDiff {
+    /// The recurrences of the new set that are not in the old set.
+    pub added: Vec<DateTime>,
+    /// The recurrences of the old set that are not in the new set.
+    pub removed: Vec<DateTime>,
+    /// It is true if the recurrences of one of the sets are limited, so that the diff
+    /// can be incomplete.
+    pub limited: bool,
 }
 
 /// The return result of `RRuleSet::all`.
@@ -54,6 +136,16 @@ impl RRuleSet {
             before: None,
             after: None,
             limited: false,
+            dst_gap: DstGapPolicy::default(),
+            dst_fold: DstFoldPolicy::default(),
+            holidays: vec![],
+            business_days: None,
+            duplicates: DuplicatePolicy::default(),
+            overrides: vec![],
+            duration: None,
+            periods: vec![],
+            exdate_days: vec![],
+            all_day: false,
         }
     }
 
@@ -66,11 +158,63 @@ impl RRuleSet {
         self
     }
 
+    /// Sets how occurrences are handled when their local time doesn't exist because of
+    /// a daylight saving time transition, see [`DstGapPolicy`].
+    #[must_use]
+    pub fn dst_gap(mut self, policy: DstGapPolicy) -> Self {
+        self.dst_gap = policy;
+        self
+    }
+
+    /// Sets how recurrences at the same instant are handled, see [`DuplicatePolicy`].
+    #[must_use]
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
+    }
+
+    /// Sets how occurrences are handled when their local time is ambiguous because of
+    /// a daylight saving time transition, see [`DstFoldPolicy`].
+    #[must_use]
+    pub fn dst_fold(mut self, policy: DstFoldPolicy) -> Self {
+        self.dst_fold = policy;
+        self
+    }
+
+    /// Leaves out the recurrences on the holidays of `calendar`, like exdates.
+    ///
+    /// The calendar isn't part of the iCalendar output of the set. A calendar can be
+    /// shared between sets by passing it in an [`Arc`]. See [`HolidayCalendar`].
+    #[must_use]
+    pub fn exclude_holidays(mut self, calendar: impl HolidayCalendar + 'static) -> Self {
+        self.holidays.push(Arc::new(calendar));
+        self
+    }
+
+    /// Moves the recurrences on a weekend or on a holiday of `calendar` to a business day
+    /// with the `policy`, see [`BusinessDayPolicy`].
+    ///
+    /// The recurrences are moved after the exclusions are applied, so an exdate matches
+    /// the date of the recurrence before it is moved. Like the holidays of
+    /// [`RRuleSet::exclude_holidays`], this isn't part of the iCalendar output of the set.
+    #[must_use]
+    pub fn shift_to_business_days(
+        mut self,
+        policy: BusinessDayPolicy,
+        calendar: impl HolidayCalendar + 'static,
+    ) -> Self {
+        self.business_days = Some(BusinessDays {
+            policy,
+            calendar: Arc::new(calendar),
+        });
+        self
+    }
+
     /// Only return recurrences that comes before this `DateTime`.
     ///
     /// This value will not be used if you use the `Iterator` API directly.
     #[must_use]
-    pub fn before(mut self, dt: DateTime) -> Self {
+    pub fn only_before(mut self, dt: DateTime) -> Self {
         self.before = Some(dt);
         self
     }
@@ -79,12 +223,17 @@ impl RRuleSet {
     ///
     /// This value will not be used if you use the `Iterator` API directly.
     #[must_use]
-    pub fn after(mut self, dt: DateTime) -> Self {
+    pub fn only_after(mut self, dt: DateTime) -> Self {
         self.after = Some(dt);
         self
     }
 
     /// Adds a new rrule to the set.
+    ///
+    /// The rrule recurs from the start datetime it was validated against, which doesn't
+    /// have to be the start datetime of the set, e.g. to merge the series of several
+    /// events. The iCalendar output of the set writes such a start in the `X-DTSTART` and
+    /// `X-DTSTART-TZID` parameters of the `RRULE`.
     #[must_use]
     pub fn rrule(mut self, rrule: RRule) -> Self {
         self.rrule.push(rrule);
@@ -106,6 +255,33 @@ impl RRuleSet {
         self
     }
 
+    /// Adds a new rdate to the set that is the start of a period of `duration`, like an
+    /// `RDATE;VALUE=PERIOD`. The recurrence at `start` lasts `duration` in
+    /// [`RRuleSet::iter_intervals`] instead of the duration of the set.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{EventDuration, RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nDURATION:PT1H\nRRULE:FREQ=DAILY;COUNT=1"
+    ///     .parse()
+    ///     .unwrap();
+    /// let start = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 18, 0, 0).unwrap();
+    /// let rrule_set = rrule_set.rdate_period(start, EventDuration::new(0, 5 * 3600));
+    ///
+    /// let (_, end) = rrule_set.iter_intervals().nth(1).unwrap();
+    /// assert_eq!(end.to_rfc3339(), "2021-01-05T23:00:00+00:00");
+    /// assert!(rrule_set.to_string().contains("RDATE;VALUE=PERIOD:20210105T180000Z/PT5H"));
+    /// ```
+    #[must_use]
+    pub fn rdate_period(mut self, start: DateTime, duration: EventDuration) -> Self {
+        self.rdate.push(start);
+        self.periods.push((start, duration));
+        self
+    }
+
     /// Adds a new exdate to the set.
     #[must_use]
     pub fn exdate(mut self, exdate: DateTime) -> Self {
@@ -113,6 +289,36 @@ impl RRuleSet {
         self
     }
 
+    /// Moves or cancels a recurrence of the set, see [`RecurrenceOverride`]. An earlier
+    /// override of the same recurrence is replaced.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, RecurrenceOverride, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3".parse().unwrap();
+    /// let second = Tz::UTC.with_ymd_and_hms(2021, 1, 2, 9, 0, 0).unwrap();
+    /// let moved = Tz::UTC.with_ymd_and_hms(2021, 1, 4, 9, 0, 0).unwrap();
+    ///
+    /// let rrule_set = rrule_set.override_recurrence(RecurrenceOverride::moved(second, moved));
+    /// assert_eq!(rrule_set.all(10).dates[2], moved);
+    /// ```
+    #[must_use]
+    pub fn override_recurrence(mut self, recurrence_override: RecurrenceOverride) -> Self {
+        let recurrence_id = recurrence_override.get_recurrence_id();
+        match self
+            .overrides
+            .iter_mut()
+            .find(|existing| existing.get_recurrence_id() == recurrence_id)
+        {
+            Some(existing) => *existing = recurrence_override,
+            None => self.overrides.push(recurrence_override),
+        }
+        self
+    }
+
     /// Sets the rrules of the set.
     #[must_use]
     pub fn set_rrules(mut self, rrules: Vec<RRule>) -> Self {
@@ -128,10 +334,37 @@ impl RRuleSet {
         self
     }
 
+    /// Adds a new day to the set on which every recurrence is excluded, like an
+    /// `EXDATE;VALUE=DATE`. A recurrence is on the day if its local date is the day, in
+    /// its own timezone.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20211224T090000Z\nRRULE:FREQ=HOURLY;INTERVAL=12;COUNT=6"
+    ///     .parse()
+    ///     .unwrap();
+    /// let christmas = NaiveDate::from_ymd_opt(2021, 12, 25).unwrap();
+    ///
+    /// let dates = rrule_set.exdate_day(christmas).all(10).dates;
+    /// assert_eq!(dates.len(), 4);
+    /// assert_eq!(dates[2].to_rfc3339(), "2021-12-26T09:00:00+00:00");
+    /// ```
+    #[must_use]
+    pub fn exdate_day(mut self, day: NaiveDate) -> Self {
+        self.exdate_days.push(day);
+        self
+    }
+
     /// Sets the rdates of the set.
     #[must_use]
     pub fn set_rdates(mut self, rdates: Vec<DateTime>) -> Self {
         self.rdate = rdates;
+        let rdate = &self.rdate;
+        self.periods.retain(|(start, _)| rdate.contains(start));
         self
     }
 
@@ -142,36 +375,498 @@ impl RRuleSet {
         self
     }
 
+    /// Removes and returns the rrule at `index`, or returns `None` if there is none.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let mut rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nRRULE:FREQ=WEEKLY"
+    ///     .parse()
+    ///     .unwrap();
+    /// let daily = rrule_set.remove_rrule(0).unwrap();
+    /// assert_eq!(daily.get_freq(), rrule::Frequency::Daily);
+    /// assert_eq!(rrule_set.get_rrule().len(), 1);
+    /// assert!(rrule_set.remove_rrule(1).is_none());
+    /// ```
+    pub fn remove_rrule(&mut self, index: usize) -> Option<RRule> {
+        (index < self.rrule.len()).then(|| self.rrule.remove(index))
+    }
+
+    /// Replaces the rrule at `index` and returns the previous one, or returns `None` and
+    /// leaves the set unchanged if there is none.
+    pub fn replace_rrule(&mut self, index: usize, rrule: RRule) -> Option<RRule> {
+        self.rrule
+            .get_mut(index)
+            .map(|previous| std::mem::replace(previous, rrule))
+    }
+
+    /// Removes and returns the exrule at `index`, or returns `None` if there is none.
+    pub fn remove_exrule(&mut self, index: usize) -> Option<RRule> {
+        (index < self.exrule.len()).then(|| self.exrule.remove(index))
+    }
+
+    /// Removes all the rdates that are equal to `rdate`. Returns `true` if there was one.
+    pub fn remove_rdate(&mut self, rdate: &DateTime) -> bool {
+        let len = self.rdate.len();
+        self.rdate.retain(|date| date != rdate);
+        self.periods.retain(|(start, _)| start != rdate);
+        self.rdate.len() != len
+    }
+
+    /// Removes all the exdates that are equal to `exdate`. Returns `true` if there was one.
+    pub fn remove_exdate(&mut self, exdate: &DateTime) -> bool {
+        let len = self.exdate.len();
+        self.exdate.retain(|date| date != exdate);
+        self.exdate.len() != len
+    }
+
+    /// Removes and returns the override of the recurrence at `recurrence_id`, or returns
+    /// `None` if there is none.
+    pub fn remove_override(&mut self, recurrence_id: &DateTime) -> Option<RecurrenceOverride> {
+        let index = self
+            .overrides
+            .iter()
+            .position(|existing| existing.get_recurrence_id() == recurrence_id)?;
+        Some(self.overrides.remove(index))
+    }
+
+    /// Removes all the rrules of the set.
+    pub fn clear_rrules(&mut self) {
+        self.rrule.clear();
+    }
+
+    /// Removes all the exrules of the set.
+    pub fn clear_exrules(&mut self) {
+        self.exrule.clear();
+    }
+
+    /// Removes all the rdates of the set.
+    pub fn clear_rdates(&mut self) {
+        self.rdate.clear();
+        self.periods.clear();
+    }
+
+    /// Removes all the exdates of the set, including the excluded days.
+    pub fn clear_exdates(&mut self) {
+        self.exdate.clear();
+        self.exdate_days.clear();
+    }
+
     /// Returns the rrules of the set.
     #[must_use]
-    pub fn get_rrule(&self) -> &Vec<RRule> {
+    pub fn get_rrule(&self) -> &[RRule] {
         &self.rrule
     }
 
     /// Returns the exrules of the set.
     #[must_use]
-    pub fn get_exrule(&self) -> &Vec<RRule> {
+    pub fn get_exrule(&self) -> &[RRule] {
         &self.exrule
     }
 
     /// Returns the rdates of the set.
     #[must_use]
-    pub fn get_rdate(&self) -> &Vec<DateTime> {
+    pub fn get_rdate(&self) -> &[DateTime] {
         &self.rdate
     }
 
+    /// Returns the start and the duration of the rdates that are periods, see
+    /// [`RRuleSet::rdate_period`].
+    #[must_use]
+    pub fn get_rdate_periods(&self) -> &[(DateTime, EventDuration)] {
+        &self.periods
+    }
+
     /// Returns the exdates of the set.
     #[must_use]
-    pub fn get_exdate(&self) -> &Vec<DateTime> {
+    pub fn get_exdate(&self) -> &[DateTime] {
         &self.exdate
     }
 
+    /// Returns the days on which every recurrence is excluded, see
+    /// [`RRuleSet::exdate_day`].
+    #[must_use]
+    pub fn get_exdate_days(&self) -> &[NaiveDate] {
+        &self.exdate_days
+    }
+
+    /// Returns the moved and cancelled recurrences of the set.
+    #[must_use]
+    pub fn get_overrides(&self) -> &[RecurrenceOverride] {
+        &self.overrides
+    }
+
+    /// Returns the duration of the recurrences, from the `DURATION` or `DTEND` of the event,
+    /// if it has one.
+    #[must_use]
+    pub fn get_duration(&self) -> Option<EventDuration> {
+        self.duration
+    }
+
+    /// Sets the duration of the recurrences, like the `DURATION` of an event, which gives
+    /// the end of the intervals of [`RRuleSet::iter_intervals`].
+    #[must_use]
+    pub fn duration(mut self, duration: EventDuration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Returns `true` if the recurrences are whole days, like those of an event with a
+    /// `DTSTART;VALUE=DATE`.
+    #[must_use]
+    pub fn is_all_day(&self) -> bool {
+        self.all_day
+    }
+
+    /// Sets if the recurrences are whole days, like those of an event with a
+    /// `DTSTART;VALUE=DATE`.
+    ///
+    /// The recurrences of an all-day set are still computed at the time of the start of
+    /// the set, which is midnight for a parsed `DTSTART;VALUE=DATE`, but they are returned
+    /// as dates by [`RRuleSet::iter_event_dates`], are one day long in
+    /// [`RRuleSet::iter_intervals`] if the set has no duration, and are written with
+    /// `VALUE=DATE` by the [`Display`] implementation. Dates can't have a `TZID`, so they
+    /// are written as floating dates, which are in the local timezone when they are parsed
+    /// again. The exdates, and the rdates at another time of day, are written with their
+    /// time.
+    #[must_use]
+    pub fn all_day(mut self, all_day: bool) -> Self {
+        self.all_day = all_day;
+        self
+    }
+
     /// Returns the start datetime of the recurring event.
     #[must_use]
     pub fn get_dt_start(&self) -> &DateTime {
         &self.dt_start
     }
 
+    /// Returns the date before which the recurrences are returned, see [`RRuleSet::only_before`].
+    #[must_use]
+    pub fn get_before(&self) -> Option<&DateTime> {
+        self.before.as_ref()
+    }
+
+    /// Returns the date after which the recurrences are returned, see [`RRuleSet::only_after`].
+    #[must_use]
+    pub fn get_after(&self) -> Option<&DateTime> {
+        self.after.as_ref()
+    }
+
+    /// Returns how occurrences in the gap of a daylight saving time transition are handled.
+    #[must_use]
+    pub fn get_dst_gap(&self) -> DstGapPolicy {
+        self.dst_gap
+    }
+
+    /// Returns how occurrences with an ambiguous local time at a daylight saving time
+    /// transition are handled.
+    #[must_use]
+    pub fn get_dst_fold(&self) -> DstFoldPolicy {
+        self.dst_fold
+    }
+
+    /// Returns how recurrences at the same instant are handled.
+    #[must_use]
+    pub fn get_duplicates(&self) -> DuplicatePolicy {
+        self.duplicates
+    }
+
+    /// Creates an [`RRuleSet`] from a string with the given [`ParserConfig`].
+    ///
+    /// Parsing with [`FromStr`] is the same as parsing with the default, strict, config.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError`], if iCalendar string contains invalid parts.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{ParserConfig, RRuleSet};
+    ///
+    /// let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3;FREQ=WEEKLY;WKST=";
+    /// assert!(input.parse::<RRuleSet>().is_err());
+    ///
+    /// let rrule_set = RRuleSet::parse_with_config(input, &ParserConfig::lenient()).unwrap();
+    /// assert_eq!(rrule_set.all(10).dates.len(), 3);
+    /// ```
+    pub fn parse_with_config(s: &str, config: &ParserConfig) -> Result<Self, RRuleError> {
+        Self::parse_with_warnings(s, config, None)
+    }
+
+    /// Creates an [`RRuleSet`] from a string. If `warnings` are given, recoverable issues
+    /// are fixed where possible and added to them, instead of failing.
+    fn parse_with_warnings(
+        s: &str,
+        config: &ParserConfig,
+        warnings: Option<&mut Vec<ParseWarning>>,
+    ) -> Result<Self, RRuleError> {
+        Self::from_grammar(Grammar::parse(s, config)?, config, warnings)
+    }
+
+    /// Adds the rdates of a parsed `RDATE`, with a warning for the rdates that were
+    /// already added.
+    fn parsed_rdates(
+        mut self,
+        rdates: impl IntoIterator<Item = DateTime>,
+        mut warnings: Option<&mut Vec<ParseWarning>>,
+    ) -> Self {
+        for rdate in rdates {
+            if let Some(warnings) = warnings.as_deref_mut() {
+                if self.rdate.contains(&rdate) {
+                    warnings.push(ParseWarning::DuplicateRDate(rdate.to_rfc3339()));
+                }
+            }
+            self = self.rdate(rdate);
+        }
+        self
+    }
+
+    /// Creates an [`RRuleSet`] from the parsed properties.
+    pub(crate) fn from_grammar(
+        grammar: Grammar,
+        config: &ParserConfig,
+        mut warnings: Option<&mut Vec<ParseWarning>>,
+    ) -> Result<Self, RRuleError> {
+        let Grammar {
+            start,
+            content_lines,
+        } = grammar;
+        // An `UNTIL` in local time is reported as a warning, or accepted in lenient mode.
+        let fix_local_until = warnings.is_some() || config.compat == Compat::Lenient;
+
+        let mut rrule_set = Self::new(start.datetime).all_day(start.value == "DATE");
+        for content_line in content_lines {
+            rrule_set = match content_line {
+                ContentLine::RRule(mut rrule) => {
+                    let rule_start = rrule.dt_start.unwrap_or(start.datetime);
+                    if fix_local_until {
+                        fix_until(&mut rrule, &rule_start, warnings.as_deref_mut());
+                    }
+                    rrule_set.rrule(rrule.validate(rule_start)?)
+                }
+                ContentLine::ExRule(mut exrule) => {
+                    let rule_start = exrule.dt_start.unwrap_or(start.datetime);
+                    if let Some(warnings) = warnings.as_deref_mut() {
+                        warnings.push(ParseWarning::ExRuleDeprecated);
+                    }
+                    if fix_local_until {
+                        fix_until(&mut exrule, &rule_start, warnings.as_deref_mut());
+                    }
+                    #[cfg(feature = "exrule")]
+                    {
+                        rrule_set.exrule(exrule.validate(rule_start)?)
+                    }
+                    #[cfg(not(feature = "exrule"))]
+                    {
+                        log::warn!("Found EXRULE in input, but it will be ignored since the `exrule` feature is not enabled.");
+                        rrule_set
+                    }
+                }
+                ContentLine::ExDate(exdates) => exdates.into_iter().fold(rrule_set, Self::exdate),
+                ContentLine::ExDateDays(days) => days.into_iter().fold(rrule_set, Self::exdate_day),
+                ContentLine::RDate(rdates) => {
+                    rrule_set.parsed_rdates(rdates, warnings.as_deref_mut())
+                }
+                // The days of an `RDATE;VALUE=DATE` are recurrences at the time of `DTSTART`.
+                ContentLine::RDateDays(days) => {
+                    let rdates = days
+                        .into_iter()
+                        .filter_map(|day| day_at_time_of(day, &start.datetime));
+                    rrule_set.parsed_rdates(rdates, warnings.as_deref_mut())
+                }
+                ContentLine::RDatePeriods(periods) => periods
+                    .into_iter()
+                    .fold(rrule_set, |rrule_set, (start, duration)| {
+                        rrule_set.rdate_period(start, duration)
+                    }),
+                ContentLine::Override(recurrence_override) => {
+                    rrule_set.override_recurrence(recurrence_override)
+                }
+                ContentLine::DtEnd(_) | ContentLine::Duration(_)
+                    if rrule_set.duration.is_some() =>
+                {
+                    return Err(ParseError::ConflictingDuration.into());
+                }
+                ContentLine::Duration(duration) => Self {
+                    duration: Some(duration),
+                    ..rrule_set
+                },
+                ContentLine::DtEnd(dt_end) => Self {
+                    duration: Some(duration_until(&start, &dt_end)?),
+                    ..rrule_set
+                },
+            };
+        }
+        Ok(rrule_set)
+    }
+
+    /// Creates an [`RRuleSet`] from a start date and a list of `RRULE`, `RDATE`, `EXRULE`
+    /// and `EXDATE` lines, like the `recurrence` field of an event in the Google Calendar API.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError`], if one of the lines is invalid.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule_set = RRuleSet::from_recurrence_lines(
+    ///     dt_start,
+    ///     &["RRULE:FREQ=DAILY;COUNT=3", "EXDATE:20210102T090000Z"],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(rrule_set.all(10).dates.len(), 2);
+    /// ```
+    pub fn from_recurrence_lines<S: AsRef<str>>(
+        dt_start: DateTime,
+        lines: &[S],
+    ) -> Result<Self, RRuleError> {
+        let mut input = format!("DTSTART{}", datetime_to_ical_format(&dt_start));
+        for line in lines {
+            input.push('\n');
+            input.push_str(line.as_ref());
+        }
+        input.parse()
+    }
+
+    /// Returns the `RRULE`, `RDATE`, `EXRULE` and `EXDATE` lines of the set, without
+    /// the `DTSTART`, like the `recurrence` field of an event in the Google Calendar API.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3\nEXDATE:20210102T090000Z"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     rrule_set.to_recurrence_lines(),
+    ///     vec![
+    ///         "RRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0",
+    ///         "EXDATE:20210102T090000Z",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_recurrence_lines(&self) -> Vec<String> {
+        let rrules = self
+            .rrule
+            .iter()
+            .map(|rrule| format!("RRULE{}", self.rule_to_ical_format(rrule)));
+        let exrules = self
+            .exrule
+            .iter()
+            .map(|exrule| format!("EXRULE{}", self.rule_to_ical_format(exrule)));
+        let rdates = self
+            .rdate
+            .iter()
+            .filter(|rdate| !self.periods.iter().any(|(start, _)| start == *rdate))
+            .copied()
+            .collect::<Vec<_>>();
+        let periods = self.periods.iter().map(|(start, duration)| {
+            let ical = datetime_to_ical_format(start);
+            let (params, value) = ical.rsplit_once(':').unwrap_or_default();
+            format!("RDATE{};VALUE=PERIOD:{}/{}", params, value, duration)
+        });
+        rrules
+            .chain(dates_to_properties(
+                "RDATE",
+                &rdates,
+                &self.to_ical_format(),
+            ))
+            .chain(periods)
+            .chain(exrules)
+            .chain(dates_to_properties(
+                "EXDATE",
+                &self.exdate,
+                &datetime_to_ical_format,
+            ))
+            .chain(days_to_property("EXDATE", &self.exdate_days))
+            .collect()
+    }
+
+    /// Formats the parameters and the value of an rrule or exrule of the set in iCalendar.
+    ///
+    /// A rule validated against another start datetime than the set's has it in the
+    /// `X-DTSTART` and `X-DTSTART-TZID` parameters, which are read back by the parser.
+    fn rule_to_ical_format(&self, rrule: &RRule) -> String {
+        let ical = datetime_to_ical_format(&rrule.dt_start());
+        if ical == datetime_to_ical_format(&self.dt_start) {
+            return format!(":{}", rrule.value_to_string());
+        }
+        let (params, value) = ical.rsplit_once(':').unwrap_or_default();
+        let params = params.replacen(";TZID=", ";X-DTSTART-TZID=", 1);
+        format!("{};X-DTSTART={}:{}", params, value, rrule.value_to_string())
+    }
+
+    /// Returns the function that formats the dates of the set in iCalendar.
+    ///
+    /// The dates of an all-day set at the time of day of its start are formatted as
+    /// floating dates, which are at that time again when they are parsed. The other dates
+    /// keep their time.
+    fn to_ical_format(&self) -> impl Fn(&DateTime) -> String + '_ {
+        move |dt| {
+            let local = dt.with_timezone(&self.dt_start.timezone());
+            if self.all_day && local.time() == self.dt_start.time() {
+                date_to_ical_format(&local)
+            } else {
+                datetime_to_ical_format(dt)
+            }
+        }
+    }
+
+    /// Returns the same properties as the [`Display`] implementation, with the lines
+    /// separated by `\r\n` and folded at 75 octets, as in iCalendar files.
+    ///
+    /// Folded input is unfolded again when it is parsed.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+    ///     RDATE:20210201T090000Z,20210202T090000Z,20210203T090000Z,20210204T090000Z,20210205T090000Z"
+    ///     .parse()
+    ///     .unwrap();
+    /// let folded = rrule_set.to_folded_string();
+    /// assert_eq!(
+    ///     folded,
+    ///     "DTSTART:20210101T090000Z\r\n\
+    ///      RDATE:20210201T090000Z,20210202T090000Z,20210203T090000Z,20210204T090000Z,2\r\n \
+    ///      0210205T090000Z"
+    /// );
+    /// assert_eq!(folded.parse::<RRuleSet>().unwrap(), rrule_set);
+    /// ```
+    #[must_use]
+    pub fn to_folded_string(&self) -> String {
+        fold_lines(&self.to_string())
+    }
+
+    /// Returns `true` if the set has a finite number of recurrences,
+    /// meaning all of its rrules are bounded by `COUNT` or `UNTIL`.
+    /// A set with only rdates is always finite.
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        self.rrule.iter().all(RRule::is_finite)
+    }
+
+    /// Returns `true` if the set has an infinite number of recurrences,
+    /// meaning at least one of its rrules is neither bounded by `COUNT` nor by `UNTIL`.
+    #[must_use]
+    pub fn is_infinite(&self) -> bool {
+        !self.is_finite()
+    }
+
     /// Returns all the recurrences of the rrule.
     ///
     /// Limit must be set in order to prevent infinite loops.
@@ -197,79 +892,1173 @@ impl RRuleSet {
             &self.after,
             &self.before,
             true,
-            Some(limit),
+            Some(usize::from(limit)),
         )
     }
 
-    /// Returns all the recurrences of the rrule.
+    /// Returns all the recurrences of the set as `(start, end)` intervals, with the end
+    /// computed from the duration of the set as in [`RRuleSet::iter_intervals`].
     ///
-    /// # Note
+    /// Like [`RRuleSet::all`], at most `limit` recurrences are returned.
     ///
-    /// This method does not enforce any validation limits and might lead to
-    /// very long iteration times. Please read the `SECURITY.md` for more information.
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{EventDuration, RRuleSet};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    /// let intervals = rrule_set.duration(EventDuration::new(0, 3600)).all_intervals(2);
+    ///
+    /// assert_eq!(intervals.len(), 2);
+    /// assert_eq!(intervals[1].1.to_rfc3339(), "2021-01-02T10:00:00+00:00");
+    /// ```
     #[must_use]
-    pub fn all_unchecked(self) -> Vec<DateTime> {
-        collect_with_error(self.into_iter(), &self.after, &self.before, true, None).dates
+    pub fn all_intervals(mut self, limit: u16) -> Vec<(DateTime, DateTime)> {
+        self.limited = true;
+        let dates = collect_with_error(
+            self.into_iter(),
+            &self.after,
+            &self.before,
+            true,
+            Some(usize::from(limit)),
+        )
+        .dates;
+        dates
+            .into_iter()
+            .filter_map(|start| self.interval_of(start))
+            .collect()
     }
-}
 
-impl FromStr for RRuleSet {
-    type Err = RRuleError;
-
-    /// Creates an [`RRuleSet`] from a string if input is valid.
+    /// Returns all the recurrences of the rrule, or an error if there are more than `limit`.
+    ///
+    /// Unlike [`RRuleSet::all`], which silently truncates the result, this returns
+    /// [`RRuleError::IterationLimitExceeded`] once the limit is hit, or when the
+    /// validation limits stop the iteration. This makes it safe to expand untrusted rules.
     ///
     /// # Errors
     ///
-    /// Returns [`RRuleError`], if iCalendar string contains invalid parts.
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let Grammar {
-            start,
-            content_lines,
-        } = Grammar::from_str(s)?;
-
-        content_lines.into_iter().try_fold(
-            Self::new(start.datetime),
-            |rrule_set, content_line| match content_line {
-                ContentLine::RRule(rrule) => rrule
-                    .validate(start.datetime)
-                    .map(|rrule| rrule_set.rrule(rrule)),
-                #[allow(unused_variables)]
-                ContentLine::ExRule(exrule) => {
-                    #[cfg(feature = "exrule")]
-                    {
-                        exrule
-                            .validate(start.datetime)
-                            .map(|exrule| rrule_set.rrule(exrule))
-                    }
-                    #[cfg(not(feature = "exrule"))]
-                    {
-                        log::warn!("Found EXRULE in input, but it will be ignored since the `exrule` feature is not enabled.");
-                        Ok(rrule_set)
-                    }
-                }
-                ContentLine::ExDate(exdates) => {
-                    Ok(exdates.into_iter().fold(rrule_set, Self::exdate))
-                }
-                ContentLine::RDate(rdates) => {
-                    Ok(rdates.into_iter().fold(rrule_set, Self::rdate))
-                }
-            },
+    /// Returns [`RRuleError::IterationLimitExceeded`] if the set has more than `limit`
+    /// recurrences, or if the iteration was stopped by the validation limits.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{RRuleError, RRuleSet};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3".parse().unwrap();
+    /// assert_eq!(rrule_set.clone().all_with_error(3).unwrap().len(), 3);
+    /// assert_eq!(
+    ///     rrule_set.all_with_error(2),
+    ///     Err(RRuleError::IterationLimitExceeded(2))
+    /// );
+    /// ```
+    pub fn all_with_error(mut self, limit: u16) -> Result<Vec<DateTime>, RRuleError> {
+        self.limited = true;
+        // Collect one more recurrence than allowed to tell an exact fit from an overflow.
+        let result = collect_with_error(
+            self.into_iter(),
+            &self.after,
+            &self.before,
+            true,
+            Some(usize::from(limit) + 1),
+        );
+        if result.limited {
+            return Err(RRuleError::IterationLimitExceeded(limit));
+        }
+        Ok(result.dates)
+    }
+
+    /// Returns the recurrences of the set as local wall-clock times, if the start date is
+    /// floating, i.e. it has no `TZID` or `Z`. Returns `None` otherwise.
+    ///
+    /// Floating start dates are parsed in the local timezone, see [`FloatingRRuleSet`]
+    /// for recurrences that aren't affected by its daylight saving time transitions.
+    #[must_use]
+    pub fn floating(&self) -> Option<FloatingRRuleSet> {
+        matches!(self.dt_start.timezone(), Tz::Local(_)).then(|| FloatingRRuleSet::new(self))
+    }
+
+    /// Returns an iterator over the recurrences of the set.
+    ///
+    /// The recurrences of all the rrules and rdates are merged lazily and exrules and
+    /// exdates are applied on the fly, so this can be used with infinite rules.
+    /// Validation limits are only enforced if [`RRuleSet::limit`] was called.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEXDATE:20210102T090000Z"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let dates = rrule_set.iter().take(2).collect::<Vec<_>>();
+    /// assert_eq!(dates[1], Tz::UTC.with_ymd_and_hms(2021, 1, 3, 9, 0, 0).unwrap());
+    /// ```
+    #[must_use]
+    pub fn iter(&self) -> RRuleSetIter<'_> {
+        RRuleSetIter::new(self, self.limited)
+    }
+
+    /// Returns a [`futures_core::Stream`] over the recurrences of the set.
+    ///
+    /// See [`RRuleSetIter::into_stream`].
+    #[cfg(feature = "tokio")]
+    #[must_use]
+    pub fn stream(&self) -> crate::RRuleSetStream<'_> {
+        self.iter().into_stream()
+    }
+
+    /// Returns a [`futures_core::Stream`] that yields each future recurrence of the set
+    /// once its wall-clock time has arrived.
+    ///
+    /// See [`RRuleSetIter::into_timer_stream`].
+    #[cfg(feature = "tokio")]
+    #[must_use]
+    pub fn timer_stream(&self) -> crate::RRuleSetTimerStream<'_> {
+        self.iter().into_timer_stream()
+    }
+
+    /// Returns an iterator over the recurrences of the set together with their index.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEXDATE:20210102T090000Z"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let occurrence = rrule_set.occurrences().nth(1).unwrap();
+    /// assert_eq!(occurrence.index, 1);
+    /// assert_eq!(occurrence.date.to_rfc3339(), "2021-01-03T09:00:00+00:00");
+    /// ```
+    #[must_use]
+    pub fn occurrences(&self) -> OccurrenceIter<RRuleSetIter<'_>> {
+        OccurrenceIter::new(self.iter())
+    }
+
+    /// Returns an iterator over the recurrences of the set together with the rrule or rdate
+    /// they come from, e.g. to debug a set. See [`SourceIter`].
+    #[must_use]
+    pub fn iter_with_sources(&self) -> SourceIter<'_> {
+        SourceIter::new(self.iter())
+    }
+
+    /// Returns an iterator over the recurrences of the set as `(start, end)` intervals,
+    /// e.g. for free/busy lookups or to detect conflicts. See [`IntervalIter`].
+    #[must_use]
+    pub fn iter_intervals(&self) -> IntervalIter<'_> {
+        IntervalIter::new(self.iter(), self)
+    }
+
+    /// Returns the `(start, end)` interval of the recurrence at `start`, or `None` if its
+    /// end is out of the range of dates.
+    ///
+    /// The recurrence lasts the duration of its period, if it is one, or the duration of
+    /// the set, which is a day for an all-day set without a duration.
+    pub(crate) fn interval_of(&self, start: DateTime) -> Option<(DateTime, DateTime)> {
+        let duration = self
+            .periods
+            .iter()
+            .find(|(period_start, _)| *period_start == start)
+            .map(|(_, duration)| *duration)
+            .or(self.duration)
+            .or_else(|| self.all_day.then(|| EventDuration::new(1, 0)));
+        match duration {
+            Some(duration) => Some((start, duration.end_of(&start)?)),
+            None => Some((start, start)),
+        }
+    }
+
+    /// Returns an iterator over the recurrences of the set as [`EventDate`]s, which are
+    /// dates for an all-day set. See [`EventDateIter`].
+    #[must_use]
+    pub fn iter_event_dates(&self) -> EventDateIter<'_> {
+        EventDateIter::new(self.iter(), self.all_day)
+    }
+
+    /// Returns all the recurrences of the set as [`EventDate`]s, which are dates for an
+    /// all-day set.
+    ///
+    /// Like [`RRuleSet::all`], at most `limit` recurrences are returned.
+    #[must_use]
+    pub fn all_event_dates(self, limit: u16) -> Vec<EventDate> {
+        let all_day = self.all_day;
+        self.all(limit)
+            .dates
+            .into_iter()
+            .map(|date| to_event_date(date, all_day))
+            .collect()
+    }
+
+    /// Returns an iterator over the recurrences that are in this set, in `other` or in both.
+    ///
+    /// See [`SetOperationIter`] for how the recurrences are compared.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let mondays: RRuleSet = "DTSTART:20210104T090000Z\nRRULE:FREQ=WEEKLY;COUNT=2".parse().unwrap();
+    /// let daily: RRuleSet = "DTSTART:20210110T090000Z\nRRULE:FREQ=DAILY;COUNT=2".parse().unwrap();
+    ///
+    /// let dates: Vec<_> = mondays.union(&daily).map(|date| date.to_rfc3339()).collect();
+    /// assert_eq!(
+    ///     dates,
+    ///     [
+    ///         "2021-01-04T09:00:00+00:00",
+    ///         "2021-01-10T09:00:00+00:00",
+    ///         "2021-01-11T09:00:00+00:00",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn union<'a>(&'a self, other: &'a Self) -> SetOperationIter<'a> {
+        SetOperationIter::new(SetOperation::Union, self.iter(), other.iter())
+    }
+
+    /// Returns an iterator over the recurrences that are both in this set and in `other`.
+    ///
+    /// Sets without common recurrences are iterated until one of them ends, so make sure
+    /// to bound infinite sets, e.g. with [`RRuleSet::limit`]. See [`SetOperationIter`] for
+    /// how the recurrences are compared.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let mondays: RRuleSet = "DTSTART:20210104T090000Z\nRRULE:FREQ=WEEKLY;COUNT=2".parse().unwrap();
+    /// let daily: RRuleSet = "DTSTART:20210110T090000Z\nRRULE:FREQ=DAILY;COUNT=2".parse().unwrap();
+    ///
+    /// let dates: Vec<_> = mondays.intersection(&daily).map(|date| date.to_rfc3339()).collect();
+    /// assert_eq!(dates, ["2021-01-11T09:00:00+00:00"]);
+    /// ```
+    #[must_use]
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> SetOperationIter<'a> {
+        SetOperationIter::new(SetOperation::Intersection, self.iter(), other.iter())
+    }
+
+    /// Returns an iterator over the recurrences that are in this set, but not in `other`,
+    /// e.g. the available slots of a schedule without the booked ones.
+    ///
+    /// See [`SetOperationIter`] for how the recurrences are compared.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let mondays: RRuleSet = "DTSTART:20210104T090000Z\nRRULE:FREQ=WEEKLY;COUNT=2".parse().unwrap();
+    /// let daily: RRuleSet = "DTSTART:20210110T090000Z\nRRULE:FREQ=DAILY;COUNT=2".parse().unwrap();
+    ///
+    /// let dates: Vec<_> = mondays.difference(&daily).map(|date| date.to_rfc3339()).collect();
+    /// assert_eq!(dates, ["2021-01-04T09:00:00+00:00"]);
+    /// ```
+    #[must_use]
+    pub fn difference<'a>(&'a self, other: &'a Self) -> SetOperationIter<'a> {
+        SetOperationIter::new(SetOperation::Difference, self.iter(), other.iter())
+    }
+
+    /// Returns an iterator over the recurrences of the set after the position of `cursor`.
+    ///
+    /// The rules are moved to the periods of the cursor without computing the recurrences
+    /// before it, so this is cheaper than iterating from the start again. Rules with a
+    /// `COUNT` still go through the recurrences before the cursor to count them.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{IterCursor, RRuleSet};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    ///
+    /// let mut iter = rrule_set.iter();
+    /// let first_page: Vec<_> = iter.by_ref().take(10).collect();
+    /// let token = iter.cursor().unwrap().to_string();
+    ///
+    /// let cursor: IterCursor = token.parse().unwrap();
+    /// let second_page: Vec<_> = rrule_set.iter_from_cursor(&cursor).take(10).collect();
+    /// assert_eq!(second_page, rrule_set.iter().skip(10).take(10).collect::<Vec<_>>());
+    /// ```
+    #[must_use]
+    pub fn iter_from_cursor(&self, cursor: &IterCursor) -> RRuleSetIter<'_> {
+        RRuleSetIter::new(self, self.limited).resume_from(cursor, 0)
+    }
+
+    /// Returns an iterator over the recurrences of the set after the last recurrence of `state`.
+    ///
+    /// Like [`RRuleSet::iter_from_cursor`], but the number of emitted recurrences
+    /// of the `state` is carried over to the returned iterator.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    ///
+    /// let mut iter = rrule_set.iter();
+    /// iter.by_ref().take(10).for_each(drop);
+    /// let state = iter.state().unwrap();
+    /// assert_eq!(state.emitted, 10);
+    ///
+    /// let mut iter = rrule_set.iter_from_state(&state);
+    /// assert_eq!(iter.next(), rrule_set.iter().nth(10));
+    /// assert_eq!(iter.state().unwrap().emitted, 11);
+    /// ```
+    #[must_use]
+    pub fn iter_from_state(&self, state: &IterState) -> RRuleSetIter<'_> {
+        RRuleSetIter::new(self, self.limited).resume_from(&IterCursor::from(*state), state.emitted)
+    }
+
+    /// Returns all the recurrences of the set between `after` and `before`.
+    ///
+    /// When `inclusive` is `true`, recurrences equal to `after` or `before` are included.
+    /// The iteration stops as soon as a recurrence is past `before`, so this
+    /// can safely be used on sets with infinite rules.
+    ///
+    /// The `before` and `after` values configured on the set are not used.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    ///
+    /// let after = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 9, 0, 0).unwrap();
+    /// let before = Tz::UTC.with_ymd_and_hms(2021, 1, 8, 9, 0, 0).unwrap();
+    /// let result = rrule_set.between(after, before, true);
+    /// assert_eq!(result.dates.len(), 4);
+    /// assert_eq!(result.limited, false);
+    /// ```
+    #[must_use]
+    pub fn between(&self, after: DateTime, before: DateTime, inclusive: bool) -> RRuleResult {
+        collect_with_error(
+            RRuleSetIter::new(self, true),
+            &Some(after),
+            &Some(before),
+            inclusive,
+            None,
+        )
+    }
+
+    /// Returns the recurrences between `after` and `before` that were added and removed
+    /// when this set was changed to `new`, e.g. when a user edited a recurring event.
+    ///
+    /// The recurrences are compared by their instant, so a recurrence that only moved to
+    /// another timezone at the same instant is neither added nor removed. See
+    /// [`RRuleSet::between`] for the range.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let old: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    /// let new: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=2\nRDATE:20210102T100000Z"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let after = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+    /// let before = Tz::UTC.with_ymd_and_hms(2021, 1, 4, 0, 0, 0).unwrap();
+    /// let diff = old.diff(&new, after, before, true);
+    /// assert_eq!(diff.added, [Tz::UTC.with_ymd_and_hms(2021, 1, 2, 10, 0, 0).unwrap()]);
+    /// assert_eq!(diff.removed, [Tz::UTC.with_ymd_and_hms(2021, 1, 2, 9, 0, 0).unwrap()]);
+    /// ```
+    #[must_use]
+    pub fn diff(
+        &self,
+        new: &Self,
+        after: DateTime,
+        before: DateTime,
+        inclusive: bool,
+    ) -> OccurrenceDiff {
+        let old = self.between(after, before, inclusive);
+        let new = new.between(after, before, inclusive);
+        let timestamps = |dates: &[DateTime]| {
+            dates
+                .iter()
+                .map(DateTime::timestamp)
+                .collect::<HashSet<_>>()
+        };
+        let (old_timestamps, new_timestamps) = (timestamps(&old.dates), timestamps(&new.dates));
+        let only_in = |dates: Vec<DateTime>, other: &HashSet<i64>| {
+            dates
+                .into_iter()
+                .filter(|date| !other.contains(&date.timestamp()))
+                .collect()
+        };
+
+        OccurrenceDiff {
+            limited: old.limited || new.limited,
+            added: only_in(new.dates, &old_timestamps),
+            removed: only_in(old.dates, &new_timestamps),
+        }
+    }
+
+    /// Returns all the recurrences of the set between `after` and `before`, expanding
+    /// each rrule and exrule in parallel before merging them.
+    ///
+    /// Returns the same result as [`RRuleSet::between`], but is faster for sets with
+    /// many rules or windows with many recurrences. Sets that move their recurrences to
+    /// business days are not expanded in parallel.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nRRULE:FREQ=HOURLY;INTERVAL=6"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let after = Tz::UTC.with_ymd_and_hms(2021, 2, 1, 0, 0, 0).unwrap();
+    /// let before = Tz::UTC.with_ymd_and_hms(2021, 3, 1, 0, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     rrule_set.par_between(after, before, true),
+    ///     rrule_set.between(after, before, true)
+    /// );
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn par_between(&self, after: DateTime, before: DateTime, inclusive: bool) -> RRuleResult {
+        use rayon::prelude::*;
+
+        if self.business_days.is_some() {
+            return self.between(after, before, inclusive);
+        }
+
+        let expand = |rrule: &RRule| {
+            collect_with_error(
+                rrule
+                    .iter_with_ctx(true)
+                    .with_dst_policies(self.dst_gap, self.dst_fold),
+                &Some(after),
+                &Some(before),
+                inclusive,
+                None,
+            )
+        };
+        let (rrule_results, exrule_results): (Vec<_>, Vec<_>) = rayon::join(
+            || self.rrule.par_iter().map(expand).collect(),
+            || self.exrule.par_iter().map(expand).collect(),
+        );
+
+        self.merge_expanded(
+            rrule_results.iter(),
+            exrule_results.iter(),
+            |date: &DateTime| is_in_range(date, &Some(after), &Some(before), inclusive),
         )
     }
+
+    /// Returns the recurrences of each of the `sets` within `window`, in the order of the
+    /// sets, e.g. to expand the events of many calendars for the same week.
+    ///
+    /// The window includes its start and excludes its end. The rrules and exrules are
+    /// expanded over the window only once for all the sets they appear in, with the same
+    /// start date, timezone and DST policies, so batches in which many sets share the same
+    /// rules are much faster than calling [`RRuleSet::between`] on each set. The `before`
+    /// and `after` values configured on the sets are not used.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let daily: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    /// let with_exdates: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEXDATE:20210202T090000Z,20210204T090000Z"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let start = Tz::UTC.with_ymd_and_hms(2021, 2, 1, 0, 0, 0).unwrap();
+    /// let end = Tz::UTC.with_ymd_and_hms(2021, 2, 8, 0, 0, 0).unwrap();
+    /// // The daily rule is expanded once for both sets.
+    /// let results = RRuleSet::expand_many(&[daily, with_exdates], start..end);
+    /// assert_eq!(results[0].dates.len(), 7);
+    /// assert_eq!(results[1].dates.len(), 5);
+    /// ```
+    #[must_use]
+    pub fn expand_many<'a>(sets: &'a [Self], window: Range<DateTime>) -> Vec<RRuleResult> {
+        let mut expanded: Vec<RRuleResult> = vec![];
+        let mut expanded_rules: HashMap<(&RRule, String, DstGapPolicy, DstFoldPolicy), usize> =
+            HashMap::new();
+        let mut expand = |rrule: &'a RRule, set: &Self| {
+            // `DateTime`s compare by instant, so the timezone is part of the key.
+            let key = (
+                rrule,
+                rrule.dt_start().timezone().name().to_owned(),
+                set.dst_gap,
+                set.dst_fold,
+            );
+            *expanded_rules.entry(key).or_insert_with(|| {
+                expanded.push(collect_with_error(
+                    rrule
+                        .iter_with_ctx(true)
+                        .with_dst_policies(set.dst_gap, set.dst_fold),
+                    &Some(window.start),
+                    &Some(window.end),
+                    true,
+                    None,
+                ));
+                expanded.len() - 1
+            })
+        };
+        let rules: Vec<Option<(Vec<usize>, Vec<usize>)>> = sets
+            .iter()
+            .map(|set| {
+                // Dates moved to business days can come from outside the window.
+                set.business_days.is_none().then(|| {
+                    (
+                        set.rrule.iter().map(|rrule| expand(rrule, set)).collect(),
+                        set.exrule.iter().map(|rrule| expand(rrule, set)).collect(),
+                    )
+                })
+            })
+            .collect();
+
+        sets.iter()
+            .zip(rules)
+            .map(|(set, rules)| match rules {
+                Some((rrules, exrules)) => set.merge_expanded(
+                    rrules.iter().map(|&index| &expanded[index]),
+                    exrules.iter().map(|&index| &expanded[index]),
+                    |date: &DateTime| window.contains(date),
+                ),
+                None => {
+                    let mut result = set.between(window.start, window.end, true);
+                    result.dates.retain(|date| *date < window.end);
+                    result
+                }
+            })
+            .collect()
+    }
+
+    /// Merges the recurrences of the rrules and exrules of the set, expanded over a range,
+    /// with its rdates and exdates, like [`RRuleSetIter`] does, keeping the dates in the
+    /// range.
+    fn merge_expanded<'a>(
+        &self,
+        rrule_results: impl Iterator<Item = &'a RRuleResult>,
+        exrule_results: impl Iterator<Item = &'a RRuleResult>,
+        in_range: impl Fn(&DateTime) -> bool,
+    ) -> RRuleResult {
+        let overridden = self
+            .overrides
+            .iter()
+            .map(RecurrenceOverride::get_recurrence_id);
+        let excluded: HashSet<i64> = exrule_results
+            .flat_map(|result| &result.dates)
+            .chain(&self.exdate)
+            .chain(overridden)
+            .map(DateTime::timestamp)
+            .collect();
+        let mut limited = false;
+        let mut dates: Vec<_> = rrule_results
+            .flat_map(|result| {
+                limited |= result.limited;
+                &result.dates
+            })
+            .chain(&self.rdate)
+            .copied()
+            .filter(|date| {
+                in_range(date)
+                    && !excluded.contains(&date.timestamp())
+                    && !self.exdate_days.contains(&date.date_naive())
+                    && !is_holiday(&self.holidays, date)
+            })
+            .chain(
+                self.overrides
+                    .iter()
+                    .filter_map(RecurrenceOverride::get_start)
+                    .copied()
+                    .filter(|start| in_range(start)),
+            )
+            .collect();
+        dates.sort();
+        if self.duplicates == DuplicatePolicy::Merge {
+            dates.dedup();
+        }
+
+        RRuleResult { dates, limited }
+    }
+
+    /// Returns the first recurrence of the set after `dt`, or `None` if there is none.
+    ///
+    /// When `inclusive` is `true`, a recurrence equal to `dt` is returned as well.
+    /// This is the single-occurrence counterpart of [`RRuleSet::only_after`], which only
+    /// configures the boundary used by [`RRuleSet::all`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    ///
+    /// let dt = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 10, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     rrule_set.after(dt, false),
+    ///     Some(Tz::UTC.with_ymd_and_hms(2021, 1, 6, 9, 0, 0).unwrap())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn after(&self, dt: DateTime, inclusive: bool) -> Option<DateTime> {
+        RRuleSetIter::new(self, true).find(|date| if inclusive { *date >= dt } else { *date > dt })
+    }
+
+    /// Describes the first recurrence of the set after `now` relative to it, like
+    /// `next: in 3 days (Thu, Mar 14, 9:00 AM)`, or returns `None` if there is none.
+    ///
+    /// See [`RRule::next_to_text`] for the format.
+    ///
+    /// # Usage
+    ///
+    #[cfg_attr(feature = "chrono-tz", doc = "```")]
+    #[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Berlin:20240301T090000\n\
+    ///     RRULE:FREQ=MONTHLY;BYDAY=1FR"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let now = Tz::UTC.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     rrule_set.next_to_text(now).as_deref(),
+    ///     Some("next: in 2 weeks (Fri, Apr 5, 9:00 AM)")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn next_to_text(&self, now: DateTime) -> Option<String> {
+        let next = self.after(now, false)?;
+        Some(next_text(&now, &next))
+    }
+
+    /// Returns the last recurrence of the set before `dt`, or `None` if there is none.
+    ///
+    /// When `inclusive` is `true`, a recurrence equal to `dt` is returned as well.
+    /// This is the single-occurrence counterpart of [`RRuleSet::only_before`], which only
+    /// configures the boundary used by [`RRuleSet::all`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    ///
+    /// let dt = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 10, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     rrule_set.before(dt, false),
+    ///     Some(Tz::UTC.with_ymd_and_hms(2021, 1, 5, 9, 0, 0).unwrap())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn before(&self, dt: DateTime, inclusive: bool) -> Option<DateTime> {
+        RRuleSetIter::new(self, true)
+            .take_while(|date| if inclusive { *date <= dt } else { *date < dt })
+            .last()
+    }
+
+    /// Returns `true` if `dt` is a recurrence of the set.
+    ///
+    /// Exrules and exdates are taken into account. The iteration stops at the
+    /// first recurrence that is not before `dt`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEXDATE:20210102T090000Z"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// assert!(rrule_set.contains(Tz::UTC.with_ymd_and_hms(2021, 1, 3, 9, 0, 0).unwrap()));
+    /// assert!(!rrule_set.contains(Tz::UTC.with_ymd_and_hms(2021, 1, 2, 9, 0, 0).unwrap()));
+    /// ```
+    #[must_use]
+    pub fn contains(&self, dt: DateTime) -> bool {
+        self.after(dt, true) == Some(dt)
+    }
+
+    /// Calls `f` with each recurrence of the set, until it returns [`ControlFlow::Break`].
+    ///
+    /// Returns the value `f` breaks with, or `None` if all the recurrences were visited.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=5".parse().unwrap();
+    ///
+    /// let mut dates = vec![];
+    /// let result: Option<()> = rrule_set.for_each_until(|date| {
+    ///     dates.push(date);
+    ///     ControlFlow::Continue(())
+    /// });
+    /// assert_eq!(result, None);
+    /// assert_eq!(dates.len(), 5);
+    /// ```
+    pub fn for_each_until<B, F>(&self, f: F) -> Option<B>
+    where
+        F: FnMut(DateTime) -> ControlFlow<B>,
+    {
+        match self.iter().try_for_each(f) {
+            ControlFlow::Break(value) => Some(value),
+            ControlFlow::Continue(()) => None,
+        }
+    }
+
+    /// Returns the recurrences of the set within `range`, grouped by `(year, month)`
+    /// in the timezone of the start date.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=WEEKLY".parse().unwrap();
+    ///
+    /// let start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+    /// let end = Tz::UTC.with_ymd_and_hms(2021, 3, 1, 0, 0, 0).unwrap();
+    /// let months = rrule_set.all_grouped_by_month(start..end);
+    /// assert_eq!(months[&(2021, 1)].len(), 5);
+    /// assert_eq!(months[&(2021, 2)].len(), 4);
+    /// ```
+    #[must_use]
+    pub fn all_grouped_by_month(
+        &self,
+        range: Range<DateTime>,
+    ) -> BTreeMap<(i32, u32), Vec<DateTime>> {
+        group_dates_by(RRuleSetIter::new(self, true), &range, |date| {
+            (date.year(), date.month())
+        })
+    }
+
+    /// Returns the recurrences of the set within `range`, grouped by year
+    /// in the timezone of the start date.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=MONTHLY".parse().unwrap();
+    ///
+    /// let start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+    /// let end = Tz::UTC.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    /// let years = rrule_set.all_grouped_by_year(start..end);
+    /// assert_eq!(years[&2021].len(), 12);
+    /// assert_eq!(years[&2022].len(), 12);
+    /// ```
+    #[must_use]
+    pub fn all_grouped_by_year(&self, range: Range<DateTime>) -> BTreeMap<i32, Vec<DateTime>> {
+        group_dates_by(RRuleSetIter::new(self, true), &range, Datelike::year)
+    }
+
+    /// Returns statistics about the recurrences of the set within `window`, see
+    /// [`RRule::stats`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::{Duration, TimeZone};
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+    ///     RRULE:FREQ=DAILY;COUNT=10\n\
+    ///     EXDATE:20210105T090000Z"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let end = Tz::UTC.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+    /// let stats = rrule_set.stats(*rrule_set.get_dt_start()..end);
+    /// assert_eq!(stats.get_count(), 9);
+    /// assert_eq!(stats.get_min_gap(), Some(Duration::days(1)));
+    /// assert_eq!(stats.get_max_gap(), Some(Duration::days(2)));
+    /// ```
+    #[must_use]
+    pub fn stats(&self, window: Range<DateTime>) -> OccurrenceStats {
+        OccurrenceStats::from_dates(RRuleSetIter::new(self, true), &window)
+    }
+
+    /// Returns all the recurrences of the rrule.
+    ///
+    /// # Note
+    ///
+    /// This method does not enforce any validation limits and might lead to
+    /// very long iteration times. Please read the `SECURITY.md` for more information.
+    #[must_use]
+    pub fn all_unchecked(self) -> Vec<DateTime> {
+        collect_with_error(self.into_iter(), &self.after, &self.before, true, None).dates
+    }
+}
+
+impl PartialEq for RRuleSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.dt_start == other.dt_start
+            && self.before == other.before
+            && self.after == other.after
+            && self.limited == other.limited
+            && self.dst_gap == other.dst_gap
+            && self.dst_fold == other.dst_fold
+            && self.duplicates == other.duplicates
+            && has_same_rules(&self.rrule, &other.rrule)
+            && has_same_rules(&self.exrule, &other.exrule)
+            && sorted_dates(&self.rdate) == sorted_dates(&other.rdate)
+            && sorted_dates(&self.exdate) == sorted_dates(&other.exdate)
+            && has_same_calendars(&self.holidays, &other.holidays)
+            && self.business_days == other.business_days
+            && sorted_overrides(&self.overrides) == sorted_overrides(&other.overrides)
+            && self.duration == other.duration
+            && sorted_periods(&self.periods) == sorted_periods(&other.periods)
+            && sorted_days(&self.exdate_days) == sorted_days(&other.exdate_days)
+            && self.all_day == other.all_day
+    }
+}
+
+impl Eq for RRuleSet {}
+
+impl Hash for RRuleSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dt_start.hash(state);
+        self.before.hash(state);
+        self.after.hash(state);
+        self.limited.hash(state);
+        self.dst_gap.hash(state);
+        self.dst_fold.hash(state);
+        self.duplicates.hash(state);
+        unordered_hash(&self.rrule).hash(state);
+        unordered_hash(&self.exrule).hash(state);
+        sorted_dates(&self.rdate).hash(state);
+        sorted_dates(&self.exdate).hash(state);
+        self.holidays.len().hash(state);
+        self.business_days
+            .as_ref()
+            .map(|business_days| business_days.policy)
+            .hash(state);
+        sorted_overrides(&self.overrides).hash(state);
+        self.duration.hash(state);
+        sorted_periods(&self.periods).hash(state);
+        sorted_days(&self.exdate_days).hash(state);
+        self.all_day.hash(state);
+    }
+}
+
+/// Returns `true` if both lists have the same rules, in any order.
+fn has_same_rules(rules: &[RRule], other_rules: &[RRule]) -> bool {
+    let count = |rules: &[RRule], rule: &RRule| rules.iter().filter(|r| *r == rule).count();
+    rules.len() == other_rules.len()
+        && rules
+            .iter()
+            .all(|rule| count(rules, rule) == count(other_rules, rule))
+}
+
+/// Returns a hash of the rules that doesn't depend on their order.
+fn unordered_hash(rules: &[RRule]) -> u64 {
+    rules
+        .iter()
+        .map(|rule| {
+            let mut hasher = DefaultHasher::new();
+            rule.hash(&mut hasher);
+            hasher.finish()
+        })
+        .fold(0, u64::wrapping_add)
+}
+
+fn sorted_dates(dates: &[DateTime]) -> Vec<DateTime> {
+    let mut dates = dates.to_vec();
+    dates.sort();
+    dates
+}
+
+fn sorted_overrides(overrides: &[RecurrenceOverride]) -> Vec<RecurrenceOverride> {
+    let mut overrides = overrides.to_vec();
+    overrides.sort();
+    overrides
+}
+
+fn sorted_days(days: &[NaiveDate]) -> Vec<NaiveDate> {
+    let mut days = days.to_vec();
+    days.sort();
+    days
+}
+
+fn sorted_periods(periods: &[(DateTime, EventDuration)]) -> Vec<(DateTime, EventDuration)> {
+    let mut periods = periods.to_vec();
+    periods.sort();
+    periods
+}
+
+/// Interprets an `UNTIL` in local time in the timezone of `dt_start`, if `dt_start`
+/// is in UTC or has a timezone, as its `UNTIL` has to be in UTC.
+fn fix_until(
+    rrule: &mut RRule<Unvalidated>,
+    dt_start: &DateTime,
+    warnings: Option<&mut Vec<ParseWarning>>,
+) {
+    let tz = dt_start.timezone();
+    let Some(until) = rrule.until else {
+        return;
+    };
+    if tz.is_local() || !until.timezone().is_local() {
+        return;
+    }
+    if let Some(fixed) = tz.from_local_datetime(&until.naive_local()).earliest() {
+        let fixed = fixed.with_timezone(&Tz::UTC);
+        if let Some(warnings) = warnings {
+            warnings.push(ParseWarning::UntilNotInUtc {
+                until: fixed.to_rfc3339(),
+                tz: tz.name().into(),
+            });
+        }
+        rrule.until = Some(fixed);
+    }
+}
+
+/// Returns the recurrence on `day` at the local time of `dt_start`, in its timezone.
+fn day_at_time_of(day: NaiveDate, dt_start: &DateTime) -> Option<DateTime> {
+    #[allow(deprecated)]
+    let day = dt_start.timezone().from_local_date(&day).earliest()?;
+    add_time_to_date(
+        day,
+        dt_start.time(),
+        DstGapPolicy::default(),
+        DstFoldPolicy::default(),
+    )
+    .next()
+}
+
+/// Returns the duration from the `DTSTART` to the `DTEND` of an event, in nominal days for
+/// all-day events and in exact seconds otherwise.
+fn duration_until(
+    start: &StartDateContentLine,
+    dt_end: &DateTime,
+) -> Result<EventDuration, ParseError> {
+    let dt_start = &start.datetime;
+    if dt_end < dt_start {
+        return Err(ParseError::DtEndBeforeDtStart);
+    }
+    if start.value == "DATE" {
+        let days = (dt_end.date_naive() - dt_start.date_naive()).num_days();
+        let days =
+            u32::try_from(days).map_err(|_| ParseError::InvalidDuration(days.to_string()))?;
+        return Ok(EventDuration::new(days, 0));
+    }
+    let seconds = (*dt_end - *dt_start).num_seconds().unsigned_abs();
+    Ok(EventDuration::new(0, seconds))
+}
+
+/// Creates an [`RRuleSet`] from a `VEVENT` component of an iCalendar file, e.g.
+/// `BEGIN:VEVENT ... END:VEVENT`, so the properties of the event don't have to be removed
+/// first.
+///
+/// The `DTSTART`, `RRULE`, `RDATE`, `EXRULE` and `EXDATE` properties of the event are
+/// used, and its `DURATION` or `DTEND` is returned by [`RRuleSet::get_duration`]. The other
+/// properties, like `SUMMARY`, and the components in the event, like `VALARM`, are ignored.
+/// The input can be a whole `VCALENDAR` with `VTIMEZONE` components and the `VEVENT`
+/// components of overridden recurrences, see [`RecurrenceOverride`], but it can only have
+/// one `VEVENT` without a `RECURRENCE-ID`.
+///
+/// # Errors
+///
+/// Returns [`RRuleError`], if the event is invalid or if there isn't exactly one `VEVENT`
+/// without a `RECURRENCE-ID`.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{build_rruleset_from_vevent, EventDuration};
+///
+/// let rrule_set = build_rruleset_from_vevent(
+///     "BEGIN:VEVENT\n\
+///      UID:19970901T130000Z-123401@example.com\n\
+///      SUMMARY:Weekly meeting\n\
+///      DTSTART:20210104T090000Z\n\
+///      DTEND:20210104T100000Z\n\
+///      RRULE:FREQ=WEEKLY;COUNT=3\n\
+///      EXDATE:20210111T090000Z\n\
+///      END:VEVENT",
+/// )
+/// .unwrap();
+/// assert_eq!(rrule_set.get_duration(), Some(EventDuration::new(0, 3600)));
+/// assert_eq!(rrule_set.all(10).dates.len(), 2);
+/// ```
+pub fn build_rruleset_from_vevent(input: &str) -> Result<RRuleSet, RRuleError> {
+    let config = ParserConfig::default();
+    RRuleSet::from_grammar(Grammar::parse_vevent(input, &config)?, &config, None)
+}
+
+/// Creates an [`RRuleSet`] from a string like [`RRuleSet::parse_with_config`], and returns
+/// it together with the recoverable issues in the input, so they can be shown to users
+/// without failing the import:
+/// - An `UNTIL` in local time while `DTSTART` is in UTC or has a timezone. Instead of
+///   failing the validation, `UNTIL` is interpreted in the timezone of `DTSTART`.
+/// - `EXRULE`s, which are deprecated in RFC 5545.
+/// - Duplicate `RDATE`s.
+///
+/// # Errors
+///
+/// Returns [`RRuleError`], if iCalendar string contains invalid parts.
+///
+/// # Usage
+///
+#[cfg_attr(feature = "chrono-tz", doc = "```")]
+#[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+/// use rrule::{build_rruleset_with_warnings, ParseWarning, ParserConfig};
+///
+/// let (rrule_set, warnings) = build_rruleset_with_warnings(
+///     "DTSTART;TZID=Europe/Paris:20210101T090000\n\
+///      RRULE:FREQ=DAILY;UNTIL=20210103T090000",
+///     &ParserConfig::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(rrule_set.all(10).dates.len(), 3);
+/// assert_eq!(
+///     warnings,
+///     [ParseWarning::UntilNotInUtc {
+///         until: "2021-01-03T08:00:00+00:00".into(),
+///         tz: "Europe/Paris".into(),
+///     }]
+/// );
+/// ```
+pub fn build_rruleset_with_warnings(
+    input: &str,
+    config: &ParserConfig,
+) -> Result<(RRuleSet, Vec<ParseWarning>), RRuleError> {
+    let mut warnings = vec![];
+    let rrule_set = RRuleSet::parse_with_warnings(input, config, Some(&mut warnings))?;
+    Ok((rrule_set, warnings))
+}
+
+/// Creates an [`RRuleSet`] from each of the strings like [`RRuleSet::parse_with_config`],
+/// for importers that parse many stored rules at once.
+///
+/// The timezones of the `TZID`s are resolved once for all the inputs, instead of once
+/// per input. An invalid input only fails its own result.
+///
+/// # Usage
+///
+#[cfg_attr(feature = "chrono-tz", doc = "```")]
+#[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+/// use rrule::{build_rrulesets, ParserConfig};
+///
+/// let mut rrule_sets = build_rrulesets(
+///     &[
+///         "DTSTART;TZID=Europe/Paris:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3",
+///         "DTSTART;TZID=Europe/Paris:20210101T090000\nRRULE:FREQ=SOMETIMES",
+///         "DTSTART;TZID=Europe/Paris:20210104T090000\nRRULE:FREQ=WEEKLY;COUNT=2",
+///     ],
+///     &ParserConfig::default(),
+/// )
+/// .into_iter();
+/// assert_eq!(rrule_sets.next().unwrap().unwrap().all(10).dates.len(), 3);
+/// assert!(rrule_sets.next().unwrap().is_err());
+/// assert_eq!(rrule_sets.next().unwrap().unwrap().all(10).dates.len(), 2);
+/// ```
+#[must_use]
+pub fn build_rrulesets(
+    inputs: &[&str],
+    config: &ParserConfig,
+) -> Vec<Result<RRuleSet, RRuleError>> {
+    let config = ParserConfig {
+        resolver: Arc::new(CachedTzResolver::new(Arc::clone(&config.resolver))),
+        ..config.clone()
+    };
+    inputs
+        .iter()
+        .map(|input| RRuleSet::parse_with_config(input, &config))
+        .collect()
+}
+
+impl FromStr for RRuleSet {
+    type Err = RRuleError;
+
+    /// Creates an [`RRuleSet`] from a string if input is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError`], if iCalendar string contains invalid parts.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with_config(s, &ParserConfig::default())
+    }
 }
 
 impl Display for RRuleSet {
     /// Prints a valid set of iCalendar properties which can be used to create a new [`RRuleSet`] later.
     /// You may use the generated string to create a new iCalendar component, like VEVENT.
+    ///
+    /// The properties are printed in the order `DTSTART`, `DURATION`, `RRULE`, `RDATE`, `EXRULE`
+    /// and `EXDATE`, followed by a `VEVENT` component for each [`RecurrenceOverride`].
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let properties = self
-            .rrule
-            .iter()
-            .map(ToString::to_string)
-            .collect::<Vec<_>>()
-            .join("\n");
-        let datetime = datetime_to_ical_format(&self.dt_start);
+        let to_ical_format = self.to_ical_format();
+        write!(f, "DTSTART{}", to_ical_format(&self.dt_start))?;
+        if let Some(duration) = self.duration {
+            write!(f, "\nDURATION:{}", duration)?;
+        }
+        let overrides = self.overrides.iter().flat_map(|recurrence_override| {
+            recurrence_override.to_component_lines(&to_ical_format)
+        });
+        for line in self.to_recurrence_lines().into_iter().chain(overrides) {
+            write!(f, "\n{}", line)?;
+        }
+        Ok(())
+    }
+}
 
-        write!(f, "DTSTART{}\n{}", datetime, properties)
+/// Formats the days as an iCalendar property with the given name and `VALUE=DATE`, or
+/// returns `None` if there are no days.
+fn days_to_property(name: &str, days: &[NaiveDate]) -> Option<String> {
+    if days.is_empty() {
+        return None;
+    }
+    let days = days
+        .iter()
+        .map(|day| day.format("%Y%m%d").to_string())
+        .collect::<Vec<_>>();
+    Some(format!("{};VALUE=DATE:{}", name, days.join(",")))
+}
+
+/// Formats the dates as iCalendar properties with the given name, with `to_ical_format`.
+///
+/// Consecutive dates in the same timezone are joined into a single property,
+/// so the order of the dates is kept.
+fn dates_to_properties(
+    name: &str,
+    dates: &[DateTime],
+    to_ical_format: &dyn Fn(&DateTime) -> String,
+) -> Vec<String> {
+    let mut properties: Vec<(String, Vec<String>)> = vec![];
+    for date in dates {
+        let ical = to_ical_format(date);
+        let (params, value) = ical.split_once(':').unwrap_or_default();
+        match properties.last_mut() {
+            Some((last_params, values)) if last_params == params => values.push(value.into()),
+            _ => properties.push((params.into(), vec![value.into()])),
+        }
     }
+    properties
+        .into_iter()
+        .map(|(params, values)| format!("{}{}:{}", name, params, values.join(",")))
+        .collect()
 }
@@ -0,0 +1,471 @@
+//! Serde support for [`RRule`] and [`RRuleSet`].
+//!
+//! Human-readable formats, like JSON, use the iCalendar string of the rule or set.
+//! Other formats use a compact structured representation, in which dates are written as
+//! a Unix timestamp and the name of their timezone. The structured representation starts
+//! with a format version, so stored data is never misread after it changes.
+use super::{DateTime, OccurrenceCache, Tz};
+use crate::{
+    ChronoTzResolver, EventDuration, Frequency, NWeekday, ParseError, RRule, RRuleError, RRuleSet,
+    RScale, RecurrenceOverride, Skip, TzResolver, Unvalidated, Validated,
+};
+use chrono::{Datelike, NaiveDate, TimeZone, Weekday};
+use serde_with::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+/// The version of the structured representation.
+const FORMAT_VERSION: u8 = 4;
+
+const FREQUENCIES: [Frequency; 7] = [
+    Frequency::Yearly,
+    Frequency::Monthly,
+    Frequency::Weekly,
+    Frequency::Daily,
+    Frequency::Hourly,
+    Frequency::Minutely,
+    Frequency::Secondly,
+];
+
+const SKIPS: [Skip; 3] = [Skip::Omit, Skip::Backward, Skip::Forward];
+
+const RSCALES: [RScale; 2] = [RScale::Gregorian, RScale::Chinese];
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// A structured representation preceded by its [`FORMAT_VERSION`].
+struct Versioned<T>(T);
+
+impl<T: Serialize> Serialize for Versioned<T> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        (FORMAT_VERSION, &self.0).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Versioned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VersionedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> de::Visitor<'de> for VersionedVisitor<T> {
+            type Value = Versioned<T>;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a format version followed by the fields")
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let version: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                if version != FORMAT_VERSION {
+                    return Err(de::Error::custom(format!(
+                        "unsupported format version `{}`, expected `{}`",
+                        version, FORMAT_VERSION
+                    )));
+                }
+                let fields = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                Ok(Versioned(fields))
+            }
+        }
+
+        deserializer.deserialize_tuple(2, VersionedVisitor(PhantomData))
+    }
+}
+
+/// Structured representation of a date.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "serde_with::serde")]
+struct DateFields {
+    timestamp: i64,
+    /// The name of the timezone, or `None` for the local timezone.
+    tz: Option<String>,
+}
+
+impl From<&DateTime> for DateFields {
+    fn from(date: &DateTime) -> Self {
+        let tz = date.timezone();
+        Self {
+            timestamp: date.timestamp(),
+            tz: (!tz.is_local()).then(|| tz.name().to_string()),
+        }
+    }
+}
+
+impl TryFrom<DateFields> for DateTime {
+    type Error = RRuleError;
+
+    fn try_from(fields: DateFields) -> Result<Self, Self::Error> {
+        let tz = match fields.tz {
+            Some(name) => ChronoTzResolver
+                .resolve(&name)
+                .ok_or_else(|| ParseError::InvalidTimezone(name.clone()))?,
+            None => Tz::LOCAL,
+        };
+        tz.timestamp_opt(fields.timestamp, 0)
+            .single()
+            .ok_or_else(|| ParseError::InvalidDateTimeFormat(fields.timestamp.to_string()).into())
+    }
+}
+
+/// Structured representation of an [`RRule`].
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "serde_with::serde")]
+struct RRuleFields {
+    freq: u8,
+    interval: u16,
+    count: Option<u32>,
+    until: Option<DateFields>,
+    week_start: u8,
+    by_set_pos: Vec<i32>,
+    by_month: Vec<u8>,
+    by_leap_month: Vec<u8>,
+    by_month_day: Vec<i8>,
+    by_n_month_day: Vec<i8>,
+    by_year_day: Vec<i16>,
+    by_week_no: Vec<i8>,
+    /// The weekdays as the `n` of the nth weekday, `0` for every weekday, and the weekday.
+    by_weekday: Vec<(i16, u8)>,
+    by_hour: Vec<u8>,
+    by_minute: Vec<u8>,
+    by_second: Vec<u8>,
+    by_easter: Option<i16>,
+    rscale: Option<u8>,
+    skip: u8,
+    leap_day: u8,
+}
+
+/// Structured representation of a validated [`RRule`].
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "serde_with::serde")]
+struct ValidatedRRuleFields {
+    dt_start: DateFields,
+    rrule: RRuleFields,
+}
+
+/// Structured representation of an [`RRuleSet`].
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "serde_with::serde")]
+struct RRuleSetFields {
+    dt_start: DateFields,
+    /// The rrules, with the start datetime they were validated against if it isn't the
+    /// start datetime of the set.
+    rrule: Vec<(Option<DateFields>, RRuleFields)>,
+    rdate: Vec<DateFields>,
+    exrule: Vec<(Option<DateFields>, RRuleFields)>,
+    exdate: Vec<DateFields>,
+    /// The recurrence id and the new start, if any, of the overridden recurrences.
+    overrides: Vec<(DateFields, Option<DateFields>)>,
+    /// The nominal days and exact seconds of the duration.
+    duration: Option<(u32, u64)>,
+    /// The start, nominal days and exact seconds of the periods of the rdates.
+    periods: Vec<(DateFields, u32, u64)>,
+    /// The excluded days, as days since the first day of the common era.
+    exdate_days: Vec<i32>,
+    all_day: bool,
+}
+
+fn weekday_from_index(index: u8) -> Result<Weekday, RRuleError> {
+    WEEKDAYS
+        .get(usize::from(index))
+        .copied()
+        .ok_or_else(|| ParseError::InvalidWeekday(index.to_string()).into())
+}
+
+impl<S> From<&RRule<S>> for RRuleFields {
+    fn from(rrule: &RRule<S>) -> Self {
+        Self {
+            freq: rrule.freq as u8,
+            interval: rrule.interval,
+            count: rrule.count,
+            until: rrule.until.as_ref().map(DateFields::from),
+            week_start: rrule.week_start as u8,
+            by_set_pos: rrule.by_set_pos.to_vec(),
+            by_month: rrule.by_month.to_vec(),
+            by_leap_month: rrule.by_leap_month.to_vec(),
+            by_month_day: rrule.by_month_day.to_vec(),
+            by_n_month_day: rrule.by_n_month_day.to_vec(),
+            by_year_day: rrule.by_year_day.to_vec(),
+            by_week_no: rrule.by_week_no.to_vec(),
+            by_weekday: rrule
+                .by_weekday
+                .iter()
+                .map(|weekday| match weekday {
+                    NWeekday::Every(weekday) => (0, *weekday as u8),
+                    NWeekday::Nth(n, weekday) => (*n, *weekday as u8),
+                })
+                .collect(),
+            by_hour: rrule.by_hour.to_vec(),
+            by_minute: rrule.by_minute.to_vec(),
+            by_second: rrule.by_second.to_vec(),
+            by_easter: rrule.by_easter,
+            rscale: rrule.rscale.map(|rscale| rscale as u8),
+            skip: rrule.skip as u8,
+            leap_day: rrule.leap_day as u8,
+        }
+    }
+}
+
+impl TryFrom<RRuleFields> for RRule<Unvalidated> {
+    type Error = RRuleError;
+
+    fn try_from(fields: RRuleFields) -> Result<Self, Self::Error> {
+        let freq = FREQUENCIES
+            .get(usize::from(fields.freq))
+            .copied()
+            .ok_or_else(|| ParseError::InvalidFrequency(fields.freq.to_string()))?;
+        let by_weekday = fields
+            .by_weekday
+            .into_iter()
+            .map(|(n, weekday)| {
+                let weekday = weekday_from_index(weekday)?;
+                Ok(if n == 0 {
+                    NWeekday::Every(weekday)
+                } else {
+                    NWeekday::Nth(n, weekday)
+                })
+            })
+            .collect::<Result<_, RRuleError>>()?;
+        let rscale = fields
+            .rscale
+            .map(|rscale| {
+                RSCALES
+                    .get(usize::from(rscale))
+                    .copied()
+                    .ok_or_else(|| ParseError::UnsupportedRScale(rscale.to_string()))
+            })
+            .transpose()?;
+        let skip = SKIPS
+            .get(usize::from(fields.skip))
+            .copied()
+            .ok_or_else(|| ParseError::InvalidSkip(fields.skip.to_string()))?;
+        let leap_day = SKIPS
+            .get(usize::from(fields.leap_day))
+            .copied()
+            .ok_or_else(|| ParseError::InvalidSkip(fields.leap_day.to_string()))?;
+
+        Ok(Self {
+            freq,
+            interval: fields.interval,
+            count: fields.count,
+            until: fields.until.map(DateTime::try_from).transpose()?,
+            week_start: weekday_from_index(fields.week_start)?,
+            by_set_pos: fields.by_set_pos.into(),
+            by_month: fields.by_month.into(),
+            by_leap_month: fields.by_leap_month.into(),
+            by_month_day: fields.by_month_day.into(),
+            by_n_month_day: fields.by_n_month_day.into(),
+            by_year_day: fields.by_year_day.into(),
+            by_week_no: fields.by_week_no.into(),
+            by_weekday,
+            by_hour: fields.by_hour.into(),
+            by_minute: fields.by_minute.into(),
+            by_second: fields.by_second.into(),
+            by_easter: fields.by_easter,
+            rscale,
+            skip,
+            leap_day,
+            dt_start: None,
+            cache: OccurrenceCache::default(),
+            stage: PhantomData,
+        })
+    }
+}
+
+impl TryFrom<ValidatedRRuleFields> for RRule<Validated> {
+    type Error = RRuleError;
+
+    fn try_from(fields: ValidatedRRuleFields) -> Result<Self, Self::Error> {
+        let dt_start = DateTime::try_from(fields.dt_start)?;
+        RRule::try_from(fields.rrule)?.validate(dt_start)
+    }
+}
+
+/// Returns the structured representation of a rule of a set starting at `dt_start`.
+fn rule_to_fields(
+    rrule: &RRule<Validated>,
+    dt_start: &DateTime,
+) -> (Option<DateFields>, RRuleFields) {
+    let rule_start = rrule.dt_start();
+    let rule_start = (rule_start != *dt_start
+        || rule_start.timezone().name() != dt_start.timezone().name())
+    .then(|| DateFields::from(&rule_start));
+    (rule_start, RRuleFields::from(rrule))
+}
+
+/// Validates a rule of a set starting at `dt_start` from its structured representation.
+fn rule_from_fields(
+    (rule_start, rrule): (Option<DateFields>, RRuleFields),
+    dt_start: DateTime,
+) -> Result<RRule<Validated>, RRuleError> {
+    let rule_start = rule_start.map(DateTime::try_from).transpose()?;
+    RRule::try_from(rrule)?.validate(rule_start.unwrap_or(dt_start))
+}
+
+impl TryFrom<RRuleSetFields> for RRuleSet {
+    type Error = RRuleError;
+
+    fn try_from(fields: RRuleSetFields) -> Result<Self, Self::Error> {
+        let dt_start = DateTime::try_from(fields.dt_start)?;
+        let mut rrule_set = Self::new(dt_start);
+        for rrule in fields.rrule {
+            rrule_set = rrule_set.rrule(rule_from_fields(rrule, dt_start)?);
+        }
+        for exrule in fields.exrule {
+            rrule_set.exrule.push(rule_from_fields(exrule, dt_start)?);
+        }
+        for rdate in fields.rdate {
+            rrule_set.rdate.push(DateTime::try_from(rdate)?);
+        }
+        for exdate in fields.exdate {
+            rrule_set.exdate.push(DateTime::try_from(exdate)?);
+        }
+        for (recurrence_id, start) in fields.overrides {
+            let recurrence_id = DateTime::try_from(recurrence_id)?;
+            rrule_set = rrule_set.override_recurrence(match start {
+                Some(start) => RecurrenceOverride::moved(recurrence_id, start.try_into()?),
+                None => RecurrenceOverride::cancelled(recurrence_id),
+            });
+        }
+        rrule_set.duration = fields
+            .duration
+            .map(|(days, seconds)| EventDuration::new(days, seconds));
+        for (start, days, seconds) in fields.periods {
+            rrule_set.periods.push((
+                DateTime::try_from(start)?,
+                EventDuration::new(days, seconds),
+            ));
+        }
+        for day in fields.exdate_days {
+            let day = NaiveDate::from_num_days_from_ce_opt(day)
+                .ok_or_else(|| ParseError::InvalidDateTimeFormat(day.to_string()))?;
+            rrule_set.exdate_days.push(day);
+        }
+        rrule_set.all_day = fields.all_day;
+        Ok(rrule_set)
+    }
+}
+
+impl Serialize for RRule<Unvalidated> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            Versioned(RRuleFields::from(self)).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RRule<Unvalidated> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(de::Error::custom)
+        } else {
+            let Versioned(fields) = Versioned::<RRuleFields>::deserialize(deserializer)?;
+            Self::try_from(fields).map_err(de::Error::custom)
+        }
+    }
+}
+
+impl Serialize for RRule<Validated> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            Versioned(ValidatedRRuleFields {
+                dt_start: DateFields::from(&self.dt_start()),
+                rrule: RRuleFields::from(self),
+            })
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RRule<Validated> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(de::Error::custom)
+        } else {
+            let Versioned(fields) = Versioned::<ValidatedRRuleFields>::deserialize(deserializer)?;
+            Self::try_from(fields).map_err(de::Error::custom)
+        }
+    }
+}
+
+impl Serialize for RRuleSet {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            Versioned(RRuleSetFields {
+                dt_start: DateFields::from(&self.dt_start),
+                rrule: self
+                    .rrule
+                    .iter()
+                    .map(|rrule| rule_to_fields(rrule, &self.dt_start))
+                    .collect(),
+                rdate: self.rdate.iter().map(DateFields::from).collect(),
+                exrule: self
+                    .exrule
+                    .iter()
+                    .map(|exrule| rule_to_fields(exrule, &self.dt_start))
+                    .collect(),
+                exdate: self.exdate.iter().map(DateFields::from).collect(),
+                overrides: self
+                    .overrides
+                    .iter()
+                    .map(|recurrence_override| {
+                        (
+                            DateFields::from(recurrence_override.get_recurrence_id()),
+                            recurrence_override.get_start().map(DateFields::from),
+                        )
+                    })
+                    .collect(),
+                duration: self
+                    .duration
+                    .map(|duration| (duration.get_days(), duration.get_seconds())),
+                periods: self
+                    .periods
+                    .iter()
+                    .map(|(start, duration)| {
+                        (
+                            DateFields::from(start),
+                            duration.get_days(),
+                            duration.get_seconds(),
+                        )
+                    })
+                    .collect(),
+                exdate_days: self
+                    .exdate_days
+                    .iter()
+                    .map(Datelike::num_days_from_ce)
+                    .collect(),
+                all_day: self.all_day,
+            })
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RRuleSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(de::Error::custom)
+        } else {
+            let Versioned(fields) = Versioned::<RRuleSetFields>::deserialize(deserializer)?;
+            Self::try_from(fields).map_err(de::Error::custom)
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Statistics about the recurrences within a window, see [`crate::RRule::stats`].
+use std::ops::Range;
+
+use chrono::{Datelike, Duration};
+
+use super::DateTime;
+
+/// Statistics about the recurrences of a rule or set within a window, see
+/// [`crate::RRule::stats`] and [`crate::RRuleSet::stats`].
+///
+/// The weekdays and months are those of the recurrences in the timezone of the start
+/// date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OccurrenceStats {
+    /// The number of recurrences.
+    count: usize,
+    /// The shortest time between two consecutive recurrences.
+    min_gap: Option<Duration>,
+    /// The longest time between two consecutive recurrences.
+    max_gap: Option<Duration>,
+    /// The number of recurrences on each weekday, from Monday.
+    per_weekday: [usize; 7],
+    /// The number of recurrences in each month, from January.
+    per_month: [usize; 12],
+}
+
+impl OccurrenceStats {
+    /// Computes the statistics of the dates within `range`, stopping at the end of it.
+    ///
+    /// The dates must be sorted.
+    pub(crate) fn from_dates(
+        dates: impl Iterator<Item = DateTime>,
+        range: &Range<DateTime>,
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut previous: Option<DateTime> = None;
+        for date in dates
+            .skip_while(|date| *date < range.start)
+            .take_while(|date| *date < range.end)
+        {
+            stats.count += 1;
+            stats.per_weekday[date.weekday().num_days_from_monday() as usize] += 1;
+            stats.per_month[date.month0() as usize] += 1;
+            if let Some(previous) = previous {
+                let gap = date - previous;
+                stats.min_gap = Some(stats.min_gap.map_or(gap, |min_gap| min_gap.min(gap)));
+                stats.max_gap = Some(stats.max_gap.map_or(gap, |max_gap| max_gap.max(gap)));
+            }
+            previous = Some(date);
+        }
+        stats
+    }
+
+    /// Returns the number of recurrences.
+    #[must_use]
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the shortest time between two consecutive recurrences, or `None` if there
+    /// are less than two recurrences.
+    #[must_use]
+    pub fn get_min_gap(&self) -> Option<Duration> {
+        self.min_gap
+    }
+
+    /// Returns the longest time between two consecutive recurrences, or `None` if there
+    /// are less than two recurrences.
+    #[must_use]
+    pub fn get_max_gap(&self) -> Option<Duration> {
+        self.max_gap
+    }
+
+    /// Returns the number of recurrences on each weekday, from Monday.
+    #[must_use]
+    pub fn get_per_weekday(&self) -> &[usize; 7] {
+        &self.per_weekday
+    }
+
+    /// Returns the number of recurrences in each month, from January.
+    #[must_use]
+    pub fn get_per_month(&self) -> &[usize; 12] {
+        &self.per_month
+    }
+}
@@ -0,0 +1,1286 @@
+//! Descriptions of rules, like `every 2 weeks on Monday and Friday`, and the parsing of
+//! such descriptions in English.
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, NaiveDate, TimeZone, Timelike, Weekday};
+use smallvec::smallvec;
+
+use super::{DateTime, Frequency, Language, NWeekday, RRule, RScale, TextLocale};
+use crate::{ParseError, Tz, Unvalidated};
+
+/// The weekdays, from Monday.
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// The names of the numbers that can be written out, from one.
+const NUMBER_NAMES: [&str; 12] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+    "twelve",
+];
+
+/// The names of the ordinal numbers that can be written out, from first.
+const ORDINAL_NAMES: [&str; 10] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+];
+
+/// The working days, which are described as `weekday`.
+const WORKING_DAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+/// The maximum number of times of day that are listed, like `9:00 AM and 5:00 PM`. With
+/// more combinations of hours and minutes, they are described separately.
+const MAX_LISTED_TIMES: usize = 12;
+
+impl<S> RRule<S> {
+    /// Returns an English description of the rule, like `every 2 weeks on Monday and
+    /// Friday until Jan 30, 2013`. See [`RRule::to_text_in`] for other languages.
+    ///
+    /// The description is meant to be shown to users. The rule parts are described in
+    /// the order frequency, months, days, times of day and end. A validated rule has the
+    /// parts that are taken from its start date, like the time of day, so they are
+    /// described as well. The descriptions can be parsed back with [`RRule::from_text`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{Frequency, NWeekday, RRule, Unvalidated, Weekday};
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;UNTIL=20130130T230000Z"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(rrule.to_text(), "every 2 weeks on Monday and Friday until Jan 30, 2013");
+    ///
+    /// let rrule = RRule::new(Frequency::Monthly).by_weekday(vec![NWeekday::Nth(-1, Weekday::Fri)]);
+    /// assert_eq!(rrule.to_text(), "every month on the last Friday");
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9;BYMINUTE=0;COUNT=10"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(rrule.to_text(), "every weekday at 9:00 AM for 10 times");
+    /// ```
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        self.to_text_in(&Language::English)
+    }
+
+    /// Returns a description of the rule in the language of `locale`, like [`RRule::to_text`]
+    /// in English. The built-in languages are in [`Language`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{Language, RRule, Unvalidated};
+    ///
+    /// let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;BYDAY=MO,FR;BYHOUR=17;BYMINUTE=30".parse().unwrap();
+    /// assert_eq!(rrule.to_text_in(&Language::German), "jede Woche am Montag und Freitag um 17:30");
+    /// assert_eq!(rrule.to_text_in(&Language::French), "chaque semaine le lundi et vendredi à 17:30");
+    /// ```
+    #[must_use]
+    pub fn to_text_in(&self, locale: &dyn TextLocale) -> String {
+        let mut parts = vec![if self.is_every_weekday() {
+            locale.every_weekday()
+        } else {
+            locale.every(self.freq, self.interval)
+        }];
+        if self.rscale == Some(RScale::Chinese) {
+            let mut months: Vec<_> = self.by_month.iter().map(|month| (*month, false)).collect();
+            months.extend(self.by_leap_month.iter().map(|month| (*month, true)));
+            months.sort_unstable();
+            if !months.is_empty() {
+                let months: Vec<_> = months
+                    .into_iter()
+                    .map(|(month, leap)| locale.lunar_month(month, leap))
+                    .collect();
+                parts.push(locale.in_months(&locale.and_list(&months)));
+            }
+        } else if !self.by_month.is_empty() {
+            let months: Vec<_> = self
+                .by_month
+                .iter()
+                .map(|month| locale.month(*month))
+                .collect();
+            parts.push(locale.in_months(&locale.and_list(&months)));
+        }
+        if !self.by_week_no.is_empty() {
+            let weeks = ordinals(locale, &self.by_week_no);
+            parts.push(locale.in_weeks(&locale.and_list(&weeks)));
+        }
+        if !self.by_year_day.is_empty() {
+            let days = ordinals(locale, &self.by_year_day);
+            parts.push(locale.on_year_days(&locale.and_list(&days)));
+        }
+        parts.extend(self.days_text(locale));
+        if let Some(days) = self.by_easter {
+            parts.push(locale.easter(days));
+        }
+        parts.extend(self.times_text(locale));
+        if let Some(count) = self.count {
+            parts.push(locale.count(count));
+        }
+        if let Some(until) = &self.until {
+            parts.push(locale.until(until.date_naive()));
+        }
+        parts.join(" ")
+    }
+
+    /// Returns `true` if the rule is described as `every weekday`, without an interval
+    /// or other days.
+    fn is_every_weekday(&self) -> bool {
+        self.interval == 1
+            && matches!(self.freq, Frequency::Weekly | Frequency::Daily)
+            && self.by_set_pos.is_empty()
+            && self.month_days().is_empty()
+            && is_working_days(&self.by_weekday)
+    }
+
+    /// Returns the month days of the rule, the positive ones first. Validation moves the
+    /// negative ones to `by_n_month_day`.
+    fn month_days(&self) -> Vec<i8> {
+        let days: Vec<_> = self
+            .by_month_day
+            .iter()
+            .chain(&self.by_n_month_day)
+            .copied()
+            .collect();
+        positives_first(&days).collect()
+    }
+
+    /// Describes the weekdays and the month days, like `on Friday the 13th`, or `None` if
+    /// the rule has neither.
+    fn days_text(&self, locale: &dyn TextLocale) -> Option<String> {
+        let month_days = self.month_days();
+        let from_end = month_days.iter().any(|day| *day < 0);
+        let month_days = locale.and_list(&ordinals(locale, &month_days));
+
+        let every_weekdays: Vec<_> = self
+            .by_weekday
+            .iter()
+            .filter_map(|weekday| match weekday {
+                NWeekday::Every(weekday) => Some(*weekday),
+                NWeekday::Nth(..) => None,
+            })
+            .collect();
+        if !self.by_set_pos.is_empty() && every_weekdays.len() == self.by_weekday.len() {
+            let positions = locale.and_list(&ordinals(locale, &self.by_set_pos));
+            let days = if every_weekdays.is_empty() {
+                return Some(locale.on_positions(&positions, None));
+            } else if is_working_days(&self.by_weekday) {
+                locale.working_day()
+            } else if every_weekdays.len() == WEEKDAYS.len() {
+                locale.any_day()
+            } else {
+                let days: Vec<_> = every_weekdays
+                    .iter()
+                    .map(|day| locale.weekday(*day))
+                    .collect();
+                locale.or_list(&days)
+            };
+            return Some(locale.on_positions(&positions, Some(&days)));
+        }
+
+        if self.by_weekday.is_empty() {
+            return (!month_days.is_empty()).then(|| locale.on_month_days(&month_days, from_end));
+        }
+        if self.is_every_weekday() {
+            return None;
+        }
+        if month_days.is_empty() && is_working_days(&self.by_weekday) {
+            return Some(locale.on_working_days());
+        }
+        let weekdays: Vec<_> = self
+            .by_weekday
+            .iter()
+            .map(|weekday| match weekday {
+                NWeekday::Every(weekday) => locale.weekday(*weekday),
+                NWeekday::Nth(n, weekday) => {
+                    let position = locale.ordinal(i32::from(*n));
+                    locale.nth_weekday(&position, &locale.weekday(*weekday))
+                }
+            })
+            .collect();
+        let weekdays = locale.and_list(&weekdays);
+        Some(if month_days.is_empty() {
+            locale.on_weekdays(&weekdays)
+        } else {
+            locale.on_weekdays_and_month_days(&weekdays, &month_days, from_end)
+        })
+    }
+
+    /// Describes the times of day, like `at 9:00 AM and 5:30 PM`, or the hours, minutes
+    /// and seconds separately if there are many combinations of them.
+    fn times_text(&self, locale: &dyn TextLocale) -> Vec<String> {
+        let seconds: &[u8] = if self.by_second.is_empty() {
+            &[0]
+        } else {
+            &self.by_second
+        };
+        let listed_times = self.by_hour.len() * self.by_minute.len() * seconds.len();
+        if listed_times > 0 && listed_times <= MAX_LISTED_TIMES {
+            let with_seconds = seconds.iter().any(|second| *second > 0);
+            let mut times = vec![];
+            for hour in &self.by_hour {
+                for minute in &self.by_minute {
+                    for second in seconds {
+                        let second = with_seconds.then_some(*second);
+                        times.push(locale.time(*hour, *minute, second));
+                    }
+                }
+            }
+            return vec![locale.at_times(&locale.and_list(&times))];
+        }
+
+        let mut parts = vec![];
+        let units = [
+            (&self.by_hour, Frequency::Hourly),
+            (&self.by_minute, Frequency::Minutely),
+            (&self.by_second, Frequency::Secondly),
+        ];
+        for (values, unit) in units {
+            // The minutes and seconds of validated rules are often just `0`.
+            if values.is_empty() || (unit != Frequency::Hourly && values[..] == [0]) {
+                continue;
+            }
+            let list: Vec<_> = values.iter().map(u8::to_string).collect();
+            parts.push(locale.at_units(unit, &locale.and_list(&list), values.len() > 1));
+        }
+        parts
+    }
+}
+
+/// Returns `true` if the weekdays are exactly the working days, Monday to Friday.
+fn is_working_days(weekdays: &[NWeekday]) -> bool {
+    weekdays.len() == WORKING_DAYS.len()
+        && WORKING_DAYS
+            .iter()
+            .all(|day| weekdays.contains(&NWeekday::Every(*day)))
+}
+
+/// Formats positions as ordinal numbers, the positions from the start first.
+fn ordinals<T: Copy + Default + PartialOrd + Into<i32>>(
+    locale: &dyn TextLocale,
+    positions: &[T],
+) -> Vec<String> {
+    positives_first(positions)
+        .map(|position| locale.ordinal(position.into()))
+        .collect()
+}
+
+/// Returns the positions from the start, like `1st`, before the positions from the end,
+/// like `last`, which are sorted first in the rule parts.
+fn positives_first<T: Copy + Default + PartialOrd>(values: &[T]) -> impl Iterator<Item = T> + '_ {
+    let positive = values.iter().filter(|value| **value > T::default());
+    let negative = values.iter().filter(|value| **value < T::default());
+    positive.chain(negative).copied()
+}
+
+/// Describes the next recurrence relative to `now`, like `next: in 3 days (Thu, Mar 14,
+/// 9:00 AM)`. The date is in the timezone of `next`, with the year if it is not the year
+/// of `now`.
+pub(crate) fn next_text(now: &DateTime, next: &DateTime) -> String {
+    let now = now.with_timezone(&next.timezone());
+    let minutes = (*next - now).num_minutes();
+    let days = (next.date_naive() - now.date_naive()).num_days();
+    let months = (next.year() - now.year()) * 12 + i32::try_from(next.month()).unwrap_or_default()
+        - i32::try_from(now.month()).unwrap_or_default()
+        - i32::from(next.day() < now.day());
+    let relative = if minutes < 1 {
+        "in less than a minute".into()
+    } else if minutes < 60 {
+        in_units(minutes, "minute")
+    } else if minutes < 24 * 60 {
+        in_units(minutes / 60, "hour")
+    } else if days == 1 {
+        "tomorrow".into()
+    } else if days < 14 {
+        in_units(days, "day")
+    } else if months < 2 {
+        in_units(days / 7, "week")
+    } else if months < 24 {
+        in_units(months.into(), "month")
+    } else {
+        in_units((months / 12).into(), "year")
+    };
+
+    let date = if next.year() == now.year() {
+        next.format("%a, %b %-d")
+    } else {
+        next.format("%a, %b %-d, %Y")
+    };
+    let second = (next.second() > 0).then(|| next.second() as u8);
+    let time = Language::English.time(next.hour() as u8, next.minute() as u8, second);
+    format!("next: {} ({}, {})", relative, date, time)
+}
+
+/// Formats an amount of time from now, like `in 1 day` or `in 3 days`.
+fn in_units(amount: i64, unit: &str) -> String {
+    match amount {
+        1 => format!("in 1 {}", unit),
+        _ => format!("in {} {}s", amount, unit),
+    }
+}
+
+impl RRule<Unvalidated> {
+    /// Parses an English description of a rule, like `every weekday at 9am`, `monthly on
+    /// the last Friday` or `every 3rd Tuesday`.
+    ///
+    /// The description starts with its frequency, either `every` followed by an optional
+    /// interval and a unit, weekdays, months or positions of weekdays, or an adverb like
+    /// `daily`, `weekly` or `fortnightly`. It can be followed by:
+    /// - `on` and weekdays, month days, positions of weekdays, like `the 2nd Monday`,
+    ///   or year days, like `the 100th day of the year`.
+    /// - `in` and months or week numbers, like `in the 1st week of the year`.
+    /// - `at` and times of day, like `9am` or `17:30`, or minutes, like `at minutes 0
+    ///   and 30`.
+    /// - `for 10 times`.
+    /// - `until` and a date, like `Jan 30, 2013` or `2013-01-30`. The rule ends at the end
+    ///   of that day in UTC.
+    ///
+    /// The descriptions of [`RRule::to_text`] can be parsed back, except for the ones with
+    /// `BYEASTER`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::UnrecognizedText`] with the first word that isn't understood,
+    /// or the whole text if it ends early or has times of day that can't be expressed by
+    /// a rule, like `9:00 and 17:30`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{RRule, Unvalidated};
+    ///
+    /// let rrule = RRule::from_text("every weekday at 9am").unwrap();
+    /// assert_eq!(
+    ///     rrule.to_string(),
+    ///     "FREQ=WEEKLY;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=MO,TU,WE,TH,FR"
+    /// );
+    ///
+    /// let rrule = RRule::from_text("monthly on the last Friday").unwrap();
+    /// assert_eq!(rrule.to_string(), "FREQ=MONTHLY;BYDAY=-1FR");
+    ///
+    /// let rrule = RRule::from_text("every 3rd Tuesday").unwrap();
+    /// assert_eq!(rrule.to_string(), "FREQ=MONTHLY;BYDAY=3TU");
+    /// ```
+    pub fn from_text(text: &str) -> Result<Self, ParseError> {
+        TextParser::new(text).parse()
+    }
+}
+
+/// Parser of the English descriptions of [`RRule::from_text`].
+struct TextParser<'a> {
+    /// The description, for the errors.
+    text: &'a str,
+    /// The lowercase words of the description, without punctuation.
+    words: Vec<String>,
+    /// The index of the next word.
+    pos: usize,
+    rrule: RRule<Unvalidated>,
+}
+
+impl<'a> TextParser<'a> {
+    fn new(text: &'a str) -> Self {
+        let words = text
+            .to_lowercase()
+            .replace("a.m.", "am")
+            .replace("p.m.", "pm")
+            .replace([',', '.'], " ")
+            .split_whitespace()
+            .map(Into::into)
+            .collect();
+        Self {
+            text,
+            words,
+            pos: 0,
+            rrule: RRule::default(),
+        }
+    }
+
+    fn parse(mut self) -> Result<RRule<Unvalidated>, ParseError> {
+        let first = self.next_word()?;
+        match first.as_str() {
+            "every" | "each" => self.every()?,
+            "fortnightly" | "biweekly" | "bi-weekly" => {
+                self.rrule.freq = Frequency::Weekly;
+                self.rrule.interval = 2;
+            }
+            word => self.rrule.freq = adverb_frequency(word).ok_or_else(|| unrecognized(word))?,
+        }
+        while let Some(word) = self.peek() {
+            let word = word.to_string();
+            self.pos += 1;
+            match word.as_str() {
+                "on" => self.on()?,
+                "in" => self.in_months_or_weeks()?,
+                "at" => self.at()?,
+                "for" => self.count()?,
+                "until" | "till" | "through" => self.until()?,
+                "and" => {}
+                // The `of the month` of `on the last day of the month`.
+                "of" if self.eat("the") && self.eat_unit().is_some() => {}
+                _ if number(&word).is_some() && self.is_next_times() => {
+                    self.pos -= 1;
+                    self.count()?;
+                }
+                _ => return Err(unrecognized(&word)),
+            }
+        }
+        Ok(self.rrule)
+    }
+
+    /// Parses the frequency after `every`, like `2 weeks`, `weekday`, `Monday and
+    /// Friday`, `3rd Tuesday` or `January`.
+    fn every(&mut self) -> Result<(), ParseError> {
+        if self.eat("other") {
+            self.rrule.interval = 2;
+        } else if let Some(interval) = self.peek().and_then(number) {
+            self.pos += 1;
+            self.rrule.interval = u16::try_from(interval)
+                .ok()
+                .filter(|interval| *interval > 0)
+                .ok_or_else(|| unrecognized(&interval.to_string()))?;
+        } else {
+            let start = self.pos;
+            let positions = self.ordinals();
+            if let [position] = positions[..] {
+                // Like `every 2nd week`.
+                if position > 0 && self.peek().and_then(unit_frequency).is_some() {
+                    self.rrule.interval =
+                        u16::try_from(position).map_err(|_| unrecognized(&position.to_string()))?;
+                }
+            }
+            if !positions.is_empty() && self.rrule.interval == 1 {
+                // Like `every 3rd Tuesday` or `every last weekday` of the month.
+                if self.nth_weekdays(&positions)? {
+                    self.rrule.freq = Frequency::Monthly;
+                    return Ok(());
+                }
+                // `every second` is a frequency.
+                self.pos = start;
+            }
+        }
+
+        if let Some(freq) = self.eat_unit() {
+            self.rrule.freq = freq;
+            return Ok(());
+        }
+        if self.rrule.interval > 1 && self.peek().is_some() {
+            return Err(unrecognized(&self.words[self.pos]));
+        }
+        self.rrule.freq = Frequency::Weekly;
+        if self.eat_any(&["weekday", "weekdays"]) {
+            self.rrule.by_weekday = WORKING_DAYS.into_iter().map(NWeekday::Every).collect();
+        } else if self.eat_any(&["weekend", "weekends"]) {
+            self.eat("day");
+            self.rrule.by_weekday =
+                smallvec![NWeekday::Every(Weekday::Sat), NWeekday::Every(Weekday::Sun)];
+        } else if self.peek().and_then(weekday).is_some() {
+            let weekdays = self.weekdays("and");
+            self.rrule.by_weekday = weekdays.into_iter().map(NWeekday::Every).collect();
+        } else if self.peek().and_then(month).is_some() {
+            // Like `every January 1st`.
+            self.rrule.freq = Frequency::Yearly;
+            self.months();
+            let days = self.ordinals();
+            self.push_month_days(&days)?;
+        } else {
+            let word = self.peek().unwrap_or(self.text);
+            return Err(unrecognized(word));
+        }
+        Ok(())
+    }
+
+    /// Parses the days after `on`, like `Monday and Friday`, `the 1st and 15th`, `Friday
+    /// the 13th`, `the 2nd last Monday`, `the last weekday` or `the 100th day of the
+    /// year`.
+    fn on(&mut self) -> Result<(), ParseError> {
+        loop {
+            self.eat("the");
+            if self.eat_any(&["weekdays", "weekday"]) {
+                let weekdays = WORKING_DAYS.map(NWeekday::Every);
+                self.rrule.by_weekday.extend(weekdays);
+            } else if self.eat_any(&["weekends", "weekend"]) {
+                self.eat("day");
+                let weekdays = [Weekday::Sat, Weekday::Sun].map(NWeekday::Every);
+                self.rrule.by_weekday.extend(weekdays);
+            } else if self.peek().and_then(weekday).is_some() {
+                // Like `Monday and Friday` or `Friday the 13th`.
+                let weekdays = self.weekdays("and").into_iter().map(NWeekday::Every);
+                self.rrule.by_weekday.extend(weekdays);
+                let days = self.ordinals();
+                self.push_month_days(&days)?;
+            } else if self.peek().and_then(month).is_some() {
+                // Like `January 1st`.
+                self.months();
+                let days = self.ordinals();
+                self.push_month_days(&days)?;
+            } else {
+                let positions = self.ordinals();
+                if positions.is_empty() {
+                    let word = self.peek().unwrap_or(self.text);
+                    return Err(unrecognized(word));
+                }
+                if !self.nth_weekdays(&positions)? {
+                    self.position_days(&positions)?;
+                }
+            }
+            // The `and` before the next days, unless it is before another part.
+            let is_next_days = self.peek() == Some("and")
+                && self.words.get(self.pos + 1).is_some_and(|word| {
+                    !matches!(word.as_str(), "on" | "in" | "at" | "for" | "until")
+                });
+            if !is_next_days {
+                return Ok(());
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Parses the days after positions, like `day`, `day of the year`, `occurrence` or
+    /// nothing for month days.
+    fn position_days(&mut self, positions: &[i32]) -> Result<(), ParseError> {
+        if self.eat("occurrence") {
+            self.rrule.by_set_pos.extend_from_slice(positions);
+            return Ok(());
+        }
+        if self.eat("day") && self.eat_of_the("year") {
+            for position in positions {
+                let day =
+                    i16::try_from(*position).map_err(|_| unrecognized(&position.to_string()))?;
+                self.rrule.by_year_day.push(day);
+            }
+            return Ok(());
+        }
+        self.eat_of_the("month");
+        self.push_month_days(positions)
+    }
+
+    /// Parses weekdays after positions, like the `Tuesday` of `3rd Tuesday` or the
+    /// `Saturday or Sunday` of `1st Saturday or Sunday`. Returns `false` if there are
+    /// no weekdays after the positions.
+    fn nth_weekdays(&mut self, positions: &[i32]) -> Result<bool, ParseError> {
+        let weekdays = if self.eat("weekday") {
+            WORKING_DAYS.to_vec()
+        } else if self.eat("weekend") {
+            self.eat("day");
+            vec![Weekday::Sat, Weekday::Sun]
+        } else {
+            self.weekdays("or")
+        };
+        match weekdays[..] {
+            [] => return Ok(false),
+            // Like `the 2nd Monday`.
+            [weekday] => {
+                for position in positions {
+                    let n = i16::try_from(*position)
+                        .map_err(|_| unrecognized(&position.to_string()))?;
+                    self.rrule.by_weekday.push(NWeekday::Nth(n, weekday));
+                }
+            }
+            // Like `the last weekday`, which is the last of the weekdays.
+            _ => {
+                let weekdays = weekdays.into_iter().map(NWeekday::Every);
+                self.rrule.by_weekday.extend(weekdays);
+                self.rrule.by_set_pos.extend_from_slice(positions);
+            }
+        }
+        self.eat_of_the("month");
+        self.eat_of_the("year");
+        // Like `the 4th Thursday of November`.
+        let is_next_month = self.peek() == Some("of")
+            && self
+                .words
+                .get(self.pos + 1)
+                .and_then(|word| month(word))
+                .is_some();
+        if is_next_month {
+            self.pos += 1;
+            self.months();
+        }
+        Ok(true)
+    }
+
+    /// Parses the months or week numbers after `in`, like `January and July` or `the 1st
+    /// week of the year`.
+    fn in_months_or_weeks(&mut self) -> Result<(), ParseError> {
+        self.eat("the");
+        if self.months() {
+            return Ok(());
+        }
+        let weeks = if self.eat_any(&["week", "weeks"]) {
+            // Like `weeks 1 and 20`.
+            let weeks = self.numbers().into_iter();
+            weeks
+                .map(|week| i32::try_from(week).unwrap_or(i32::MAX))
+                .collect()
+        } else {
+            let weeks = self.ordinals();
+            if !self.eat_any(&["week", "weeks"]) {
+                let word = self.peek().unwrap_or(self.text);
+                return Err(unrecognized(word));
+            }
+            weeks
+        };
+        self.eat_of_the("year");
+        for week in weeks {
+            let week = i8::try_from(week).map_err(|_| unrecognized(&week.to_string()))?;
+            self.rrule.by_week_no.push(week);
+        }
+        Ok(())
+    }
+
+    /// Parses the times of day after `at`, like `9am and 5pm`, or the hours, minutes or
+    /// seconds, like `minutes 0 and 30`.
+    fn at(&mut self) -> Result<(), ParseError> {
+        let units = [
+            (["hour", "hours"], 24),
+            (["minute", "minutes"], 60),
+            (["second", "seconds"], 60),
+        ];
+        for (idx, (names, limit)) in units.into_iter().enumerate() {
+            if !self.eat_any(&names) {
+                continue;
+            }
+            let mut values = vec![];
+            for value in self.numbers() {
+                let value = u8::try_from(value)
+                    .ok()
+                    .filter(|value| *value < limit)
+                    .ok_or_else(|| unrecognized(&value.to_string()))?;
+                values.push(value);
+            }
+            if values.is_empty() {
+                return Err(unrecognized(self.peek().unwrap_or(self.text)));
+            }
+            match idx {
+                0 => self.rrule.by_hour = values.into(),
+                1 => self.rrule.by_minute = values.into(),
+                _ => self.rrule.by_second = values.into(),
+            }
+            return Ok(());
+        }
+
+        let mut times = BTreeSet::new();
+        loop {
+            times.insert(self.time_of_day()?);
+            if self.peek() == Some("and") && self.is_time_at(self.pos + 1) {
+                self.pos += 1;
+            } else if !self.is_time_at(self.pos) {
+                break;
+            }
+        }
+        let hours: BTreeSet<_> = times.iter().map(|(hour, _, _)| *hour).collect();
+        let minutes: BTreeSet<_> = times.iter().map(|(_, minute, _)| *minute).collect();
+        let seconds: BTreeSet<_> = times.iter().map(|(_, _, second)| *second).collect();
+        // The rule has every combination of the hours, minutes and seconds.
+        if hours.len() * minutes.len() * seconds.len() != times.len() {
+            return Err(unrecognized(self.text));
+        }
+        self.rrule.by_hour = hours.into_iter().collect();
+        self.rrule.by_minute = minutes.into_iter().collect();
+        self.rrule.by_second = seconds.into_iter().collect();
+        Ok(())
+    }
+
+    /// Parses a time of day, like `noon`, `9am`, `9:30 pm` or `17:00:15`.
+    fn time_of_day(&mut self) -> Result<(u8, u8, u8), ParseError> {
+        let word = self.next_word()?;
+        match word.as_str() {
+            "noon" | "midday" => return Ok((12, 0, 0)),
+            "midnight" => return Ok((0, 0, 0)),
+            _ => {}
+        }
+        let invalid = || unrecognized(&word);
+        let (time, mut period) = match word.strip_suffix("am").or(word.strip_suffix("pm")) {
+            Some(time) => (time, Some(&word[time.len()..])),
+            None => (word.as_str(), None),
+        };
+        if period.is_none() && self.eat_any(&["am", "pm"]) {
+            period = Some(if self.words[self.pos - 1] == "am" {
+                "am"
+            } else {
+                "pm"
+            });
+        }
+        let mut parts = time.split(':').map(|part| {
+            (!part.is_empty() && part.len() <= 2)
+                .then(|| part.parse::<u8>().ok())
+                .flatten()
+        });
+        let hour = parts.next().flatten().ok_or_else(invalid)?;
+        let minute = parts
+            .next()
+            .map_or(Some(0), |minute| minute)
+            .ok_or_else(invalid)?;
+        let second = parts
+            .next()
+            .map_or(Some(0), |second| second)
+            .ok_or_else(invalid)?;
+        if parts.next().is_some() || minute > 59 || second > 59 {
+            return Err(invalid());
+        }
+        let hour = match period {
+            Some(_) if !(1..=12).contains(&hour) => return Err(invalid()),
+            Some("am") => hour % 12,
+            Some(_) => hour % 12 + 12,
+            None if hour > 23 => return Err(invalid()),
+            None => hour,
+        };
+        Ok((hour, minute, second))
+    }
+
+    /// Returns `true` if the word at `idx` is a time of day.
+    fn is_time_at(&self, idx: usize) -> bool {
+        self.words.get(idx).is_some_and(|word| {
+            matches!(word.as_str(), "noon" | "midday" | "midnight")
+                || word.starts_with(|c: char| c.is_ascii_digit())
+        })
+    }
+
+    /// Parses the count after `for`, like `10 times`.
+    fn count(&mut self) -> Result<(), ParseError> {
+        let word = self.next_word()?;
+        let count = number(&word).ok_or_else(|| unrecognized(&word))?;
+        self.eat_any(&["times", "time", "occurrences", "occurrence"]);
+        self.rrule.count = Some(count);
+        Ok(())
+    }
+
+    /// Parses the date after `until`, like `Jan 30, 2013`, `30 January 2013` or
+    /// `2013-01-30`, and ends the rule at the end of that day in UTC.
+    fn until(&mut self) -> Result<(), ParseError> {
+        let word = self.next_word()?;
+        let date = if let Ok(date) = NaiveDate::parse_from_str(&word, "%Y-%m-%d") {
+            Some(date)
+        } else if let Some(month) = month(&word) {
+            let day = self.next_word()?;
+            let day = day_of_month(&day).ok_or_else(|| unrecognized(&day))?;
+            let year = self.next_word()?;
+            let year = year.parse().map_err(|_| unrecognized(&year))?;
+            NaiveDate::from_ymd_opt(year, u32::from(month), day)
+        } else {
+            let day = day_of_month(&word).ok_or_else(|| unrecognized(&word))?;
+            let month_name = self.next_word()?;
+            let month = month(&month_name).ok_or_else(|| unrecognized(&month_name))?;
+            let year = self.next_word()?;
+            let year = year.parse().map_err(|_| unrecognized(&year))?;
+            NaiveDate::from_ymd_opt(year, u32::from(month), day)
+        };
+        let until = date
+            .and_then(|date| date.and_hms_opt(23, 59, 59))
+            .and_then(|until| Tz::UTC.from_local_datetime(&until).single())
+            .ok_or_else(|| unrecognized(self.text))?;
+        self.rrule.until = Some(until);
+        Ok(())
+    }
+
+    /// Parses a list of positions, like `1st, 15th and 2nd last`, with an optional `the`
+    /// before each of them.
+    fn ordinals(&mut self) -> Vec<i32> {
+        let mut positions = vec![];
+        loop {
+            let start = self.pos;
+            if !positions.is_empty() {
+                self.eat("and");
+            }
+            self.eat("the");
+            let Some(position) = self.ordinal() else {
+                self.pos = start;
+                return positions;
+            };
+            positions.push(position);
+        }
+    }
+
+    /// Parses a position, like `3rd`, `third`, `last` or `2nd to last`.
+    fn ordinal(&mut self) -> Option<i32> {
+        if self.eat("last") {
+            return Some(-1);
+        }
+        let position = self.peek().and_then(ordinal_number)?;
+        self.pos += 1;
+        let is_from_end = self.peek() == Some("last")
+            || (self.peek() == Some("to")
+                && self
+                    .words
+                    .get(self.pos + 1)
+                    .is_some_and(|word| word == "last"));
+        if is_from_end {
+            self.eat("to");
+            self.eat("last");
+            return Some(-position);
+        }
+        Some(position)
+    }
+
+    /// Parses a list of numbers, like `0, 15 and 30`.
+    fn numbers(&mut self) -> Vec<u32> {
+        let mut numbers = vec![];
+        while let Some(value) = self.peek().and_then(number) {
+            self.pos += 1;
+            numbers.push(value);
+            let is_next_number = self.peek() == Some("and")
+                && self
+                    .words
+                    .get(self.pos + 1)
+                    .and_then(|word| number(word))
+                    .is_some();
+            if is_next_number {
+                self.pos += 1;
+            }
+        }
+        numbers
+    }
+
+    /// Parses a list of weekdays joined by `conjunction`, like `Monday and Friday`.
+    fn weekdays(&mut self, conjunction: &str) -> Vec<Weekday> {
+        let mut weekdays = vec![];
+        while let Some(day) = self.peek().and_then(weekday) {
+            self.pos += 1;
+            weekdays.push(day);
+            let is_next_weekday = self.peek() == Some(conjunction)
+                && self
+                    .words
+                    .get(self.pos + 1)
+                    .and_then(|word| weekday(word))
+                    .is_some();
+            if is_next_weekday {
+                self.pos += 1;
+            }
+        }
+        weekdays
+    }
+
+    /// Parses a list of months, like `January and July`, and returns `false` if there
+    /// are none.
+    fn months(&mut self) -> bool {
+        let start = self.pos;
+        while let Some(value) = self.peek().and_then(month) {
+            self.pos += 1;
+            self.rrule.by_month.push(value);
+            let is_next_month = self.peek() == Some("and")
+                && self
+                    .words
+                    .get(self.pos + 1)
+                    .and_then(|word| month(word))
+                    .is_some();
+            if is_next_month {
+                self.pos += 1;
+            }
+        }
+        self.pos > start
+    }
+
+    fn push_month_days(&mut self, days: &[i32]) -> Result<(), ParseError> {
+        for day in days {
+            let day = i8::try_from(*day).map_err(|_| unrecognized(&day.to_string()))?;
+            self.rrule.by_month_day.push(day);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the next word is `times`, after the number of `10 times`.
+    fn is_next_times(&self) -> bool {
+        self.peek()
+            .is_some_and(|word| word == "times" || word == "time")
+    }
+
+    /// Consumes a unit of time, like `week` or `days`, and returns its frequency.
+    fn eat_unit(&mut self) -> Option<Frequency> {
+        let freq = self.peek().and_then(unit_frequency)?;
+        self.pos += 1;
+        Some(freq)
+    }
+
+    /// Consumes `of the` and `unit`, like `of the year`, if they are next.
+    fn eat_of_the(&mut self, unit: &str) -> bool {
+        let is_next = self
+            .words
+            .get(self.pos..self.pos + 3)
+            .is_some_and(|words| words[0] == "of" && words[1] == "the" && words[2] == unit);
+        if is_next {
+            self.pos += 3;
+        }
+        is_next
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.words.get(self.pos).map(String::as_str)
+    }
+
+    /// Returns the next word, or an error with the whole text if it ends.
+    fn next_word(&mut self) -> Result<String, ParseError> {
+        let word = self
+            .words
+            .get(self.pos)
+            .ok_or_else(|| unrecognized(self.text))?;
+        self.pos += 1;
+        Ok(word.clone())
+    }
+
+    /// Consumes `word` if it is next.
+    fn eat(&mut self, word: &str) -> bool {
+        self.eat_any(&[word])
+    }
+
+    /// Consumes one of `words` if it is next.
+    fn eat_any(&mut self, words: &[&str]) -> bool {
+        let is_next = self.peek().is_some_and(|next| words.contains(&next));
+        if is_next {
+            self.pos += 1;
+        }
+        is_next
+    }
+}
+
+fn unrecognized(text: &str) -> ParseError {
+    ParseError::UnrecognizedText(text.into())
+}
+
+/// Returns the frequency of an adverb, like `weekly`.
+fn adverb_frequency(word: &str) -> Option<Frequency> {
+    Some(match word {
+        "yearly" | "annually" => Frequency::Yearly,
+        "monthly" => Frequency::Monthly,
+        "weekly" => Frequency::Weekly,
+        "daily" => Frequency::Daily,
+        "hourly" => Frequency::Hourly,
+        "minutely" => Frequency::Minutely,
+        "secondly" => Frequency::Secondly,
+        _ => return None,
+    })
+}
+
+/// Returns the frequency of a unit of time, like `week` or `days`.
+fn unit_frequency(word: &str) -> Option<Frequency> {
+    Some(match word.strip_suffix('s').unwrap_or(word) {
+        "year" => Frequency::Yearly,
+        "month" => Frequency::Monthly,
+        "week" => Frequency::Weekly,
+        "day" => Frequency::Daily,
+        "hour" => Frequency::Hourly,
+        "minute" => Frequency::Minutely,
+        "second" => Frequency::Secondly,
+        _ => return None,
+    })
+}
+
+/// Parses a weekday, like `monday`, `mon` or `mondays`.
+fn weekday(word: &str) -> Option<Weekday> {
+    let word = word.strip_suffix('s').unwrap_or(word);
+    WEEKDAYS.into_iter().find(|day| {
+        let name = Language::English.weekday(*day).to_lowercase();
+        word == name || (word.len() >= 3 && name.starts_with(word))
+    })
+}
+
+/// Parses a month, like `january` or `jan`, from 1 to 12.
+fn month(word: &str) -> Option<u8> {
+    (1..=12).find(|month| {
+        let name = Language::English.month(*month).to_lowercase();
+        word == name || (word.len() >= 3 && name.starts_with(word))
+    })
+}
+
+/// Parses a number, like `3` or `three`.
+fn number(word: &str) -> Option<u32> {
+    if let Ok(number) = word.parse() {
+        return Some(number);
+    }
+    let idx = NUMBER_NAMES.iter().position(|name| *name == word)?;
+    u32::try_from(idx + 1).ok()
+}
+
+/// Parses an ordinal number, like `3rd` or `third`.
+fn ordinal_number(word: &str) -> Option<i32> {
+    if let Some(idx) = ORDINAL_NAMES.iter().position(|name| *name == word) {
+        return i32::try_from(idx + 1).ok();
+    }
+    let digits = word
+        .strip_suffix("st")
+        .or_else(|| word.strip_suffix("nd"))
+        .or_else(|| word.strip_suffix("rd"))
+        .or_else(|| word.strip_suffix("th"))?;
+    let number: i32 = digits.parse().ok().filter(|number| *number > 0)?;
+    (Language::English.ordinal(number) == word).then_some(number)
+}
+
+/// Parses a day of a month, like `30` or `30th`.
+fn day_of_month(word: &str) -> Option<u32> {
+    let day = word
+        .parse()
+        .ok()
+        .or_else(|| ordinal_number(word).and_then(|day| u32::try_from(day).ok()))?;
+    (1..=31).contains(&day).then_some(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{Language, ParseError, RRule, RRuleSet, Tz, Unvalidated};
+
+    #[test]
+    fn describes_rules_in_english() {
+        let tests = [
+            ("FREQ=DAILY", "every day"),
+            (
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR",
+                "every 2 weeks on Monday and Friday",
+            ),
+            ("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR", "every weekday"),
+            (
+                "FREQ=WEEKLY;INTERVAL=3;BYDAY=MO,TU,WE,TH,FR",
+                "every 3 weeks on weekdays",
+            ),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=1,15,-1",
+                "every month on the 1st, 15th and last day",
+            ),
+            (
+                "FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13",
+                "every month on Friday the 13th",
+            ),
+            (
+                "FREQ=MONTHLY;BYDAY=1MO,-2FR",
+                "every month on the 1st Monday and the 2nd last Friday",
+            ),
+            (
+                "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+                "every month on the last weekday",
+            ),
+            (
+                "FREQ=MONTHLY;BYDAY=SA,SU;BYSETPOS=1",
+                "every month on the 1st Saturday or Sunday",
+            ),
+            (
+                "FREQ=YEARLY;BYMONTH=1,7;BYMONTHDAY=22",
+                "every year in January and July on the 22nd",
+            ),
+            (
+                "FREQ=YEARLY;BYWEEKNO=1,-1",
+                "every year in the 1st and last week of the year",
+            ),
+            (
+                "FREQ=YEARLY;BYYEARDAY=100,111",
+                "every year on the 100th and 111th day of the year",
+            ),
+            (
+                "FREQ=DAILY;BYHOUR=0,12,17;BYMINUTE=30",
+                "every day at 12:30 AM, 12:30 PM and 5:30 PM",
+            ),
+            (
+                "FREQ=DAILY;BYHOUR=9;BYMINUTE=0;BYSECOND=15",
+                "every day at 9:00:15 AM",
+            ),
+            (
+                "FREQ=HOURLY;INTERVAL=6;BYMINUTE=0,15,30,45",
+                "every 6 hours at minutes 0, 15, 30 and 45",
+            ),
+            ("FREQ=MINUTELY;BYSECOND=0", "every minute"),
+            ("FREQ=DAILY;COUNT=1", "every day for 1 time"),
+            (
+                "FREQ=YEARLY;UNTIL=20301231T000000Z",
+                "every year until Dec 31, 2030",
+            ),
+        ];
+        for (input, expected_output) in tests {
+            let rrule: RRule<Unvalidated> = input.parse().unwrap();
+            assert_eq!(rrule.to_text(), expected_output, "{}", input);
+            let parsed = RRule::from_text(expected_output).unwrap();
+            assert_eq!(parsed.to_text(), expected_output);
+        }
+    }
+
+    #[test]
+    fn describes_rules_in_other_languages() {
+        let tests = [
+            (
+                "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9;BYMINUTE=0",
+                [
+                    "jeden Werktag um 9:00",
+                    "cada día laborable a las 9:00",
+                    "chaque jour ouvrable à 9:00",
+                ],
+            ),
+            (
+                "FREQ=MONTHLY;BYMONTHDAY=1,-1;COUNT=1",
+                [
+                    "jeden Monat am 1. und letzten Tag insgesamt 1 Mal",
+                    "cada mes el 1.º y último día un total de 1 vez",
+                    "chaque mois le 1er et dernier jour pour 1 occurrence",
+                ],
+            ),
+            (
+                "FREQ=YEARLY;INTERVAL=2;BYMONTH=3;UNTIL=20301231T000000Z",
+                [
+                    "alle 2 Jahre im März bis 31. Dezember 2030",
+                    "cada 2 años en marzo hasta el 31 de diciembre de 2030",
+                    "tous les 2 ans en mars jusqu'au 31 décembre 2030",
+                ],
+            ),
+        ];
+        for (input, expected_outputs) in tests {
+            let rrule: RRule<Unvalidated> = input.parse().unwrap();
+            let languages = [Language::German, Language::Spanish, Language::French];
+            for (language, expected_output) in languages.into_iter().zip(expected_outputs) {
+                assert_eq!(rrule.to_text_in(&language), expected_output, "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn describes_the_months_of_the_chinese_calendar() {
+        let rrule: RRule<Unvalidated> = "RSCALE=CHINESE;FREQ=YEARLY;BYMONTH=5,5L;BYMONTHDAY=1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            rrule.to_text(),
+            "every year in the 5th month and the leap 5th month on the 1st"
+        );
+        assert_eq!(
+            rrule.to_text_in(&Language::German),
+            "jedes Jahr im 5. Monat und 5. Schaltmonat am 1."
+        );
+    }
+
+    #[test]
+    fn parses_english_descriptions() {
+        let tests = [
+            (
+                "every weekday at 9am",
+                "FREQ=WEEKLY;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=MO,TU,WE,TH,FR",
+            ),
+            ("monthly on the last Friday", "FREQ=MONTHLY;BYDAY=-1FR"),
+            ("every 3rd Tuesday", "FREQ=MONTHLY;BYDAY=3TU"),
+            (
+                "Every other week on Tuesdays and Thursdays",
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH",
+            ),
+            ("every second day", "FREQ=DAILY;INTERVAL=2"),
+            ("every second", "FREQ=SECONDLY"),
+            (
+                "fortnightly on Mon, Wed, Fri",
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR",
+            ),
+            (
+                "every weekend at noon",
+                "FREQ=WEEKLY;BYHOUR=12;BYMINUTE=0;BYSECOND=0;BYDAY=SA,SU",
+            ),
+            (
+                "every month on the second to last day",
+                "FREQ=MONTHLY;BYMONTHDAY=-2",
+            ),
+            ("every January 1st", "FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1"),
+            (
+                "yearly on the 4th Thursday of November",
+                "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH",
+            ),
+            (
+                "every day at 8:15 a.m. and 8:15 p.m.",
+                "FREQ=DAILY;BYHOUR=8,20;BYMINUTE=15;BYSECOND=0",
+            ),
+            ("every hour at minute 30", "FREQ=HOURLY;BYMINUTE=30"),
+            ("daily 5 times", "FREQ=DAILY;COUNT=5"),
+            (
+                "every day until 2030-06-15",
+                "FREQ=DAILY;UNTIL=20300615T235959Z",
+            ),
+            (
+                "every week until 15th June 2030",
+                "FREQ=WEEKLY;UNTIL=20300615T235959Z",
+            ),
+        ];
+        for (input, expected_output) in tests {
+            let rrule = RRule::from_text(input).unwrap();
+            assert_eq!(rrule.to_string(), expected_output, "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_unrecognized_descriptions() {
+        let tests = [
+            ("", ""),
+            ("sometimes", "sometimes"),
+            ("every", "every"),
+            ("every 2", "every 2"),
+            ("every third", "third"),
+            ("every day on", "every day on"),
+            ("every day at 25:00", "25:00"),
+            ("every day at 13pm", "13pm"),
+            ("every day at 9:00 and 17:30", "every day at 9:00 and 17:30"),
+            ("every month on the 200th", "200"),
+            ("every day until Feb 30 2030", "every day until Feb 30 2030"),
+            ("every day with lunch", "with"),
+        ];
+        for (input, token) in tests {
+            assert_eq!(
+                RRule::from_text(input),
+                Err(ParseError::UnrecognizedText(token.into())),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn describes_the_next_recurrence_relative_to_now() {
+        let tests = [
+            (
+                (2024, 3, 14, 8, 59, 30),
+                "in less than a minute (Thu, Mar 14, 9:00 AM)",
+            ),
+            (
+                (2024, 3, 14, 8, 15, 0),
+                "in 45 minutes (Thu, Mar 14, 9:00 AM)",
+            ),
+            (
+                (2024, 3, 13, 9, 30, 0),
+                "in 23 hours (Thu, Mar 14, 9:00 AM)",
+            ),
+            ((2024, 3, 13, 8, 0, 0), "tomorrow (Thu, Mar 14, 9:00 AM)"),
+            ((2024, 3, 1, 8, 0, 0), "in 13 days (Thu, Mar 14, 9:00 AM)"),
+            ((2024, 2, 1, 8, 0, 0), "in 6 weeks (Thu, Mar 14, 9:00 AM)"),
+            (
+                (2023, 12, 15, 8, 0, 0),
+                "in 2 months (Thu, Mar 14, 2024, 9:00 AM)",
+            ),
+            (
+                (2021, 3, 14, 8, 0, 0),
+                "in 3 years (Thu, Mar 14, 2024, 9:00 AM)",
+            ),
+        ];
+        let rrule_set: RRuleSet = "DTSTART:20210314T090000Z\nRDATE:20240314T090000Z"
+            .parse()
+            .unwrap();
+        for ((year, month, day, hour, minute, second), expected_output) in tests {
+            let now = Tz::UTC
+                .with_ymd_and_hms(year, month, day, hour, minute, second)
+                .unwrap();
+            let expected_output = format!("next: {}", expected_output);
+            assert_eq!(rrule_set.next_to_text(now), Some(expected_output));
+        }
+    }
+
+    #[test]
+    fn describes_the_parts_taken_from_the_start_date() {
+        let rrule_set: RRuleSet = "DTSTART:20210115T093000Z\nRRULE:FREQ=MONTHLY;COUNT=3"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            rrule_set.get_rrule()[0].to_text(),
+            "every month on the 15th at 9:30 AM for 3 times"
+        );
+    }
+}
@@ -0,0 +1,595 @@
+//! The words and phrases of the descriptions of rules in several languages.
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use super::Frequency;
+
+/// The words and phrases of the descriptions of [`crate::RRule::to_text_in`] in a
+/// language.
+///
+/// The lists of the phrases, like `days` in [`TextLocale::on_month_days`], are already
+/// translated and joined by [`TextLocale::and_list`]. [`Language`] has the built-in
+/// languages, and other languages can be added by implementing this trait.
+pub trait TextLocale {
+    /// Describes the frequency and the interval, like `every 2 weeks`.
+    fn every(&self, freq: Frequency, interval: u16) -> String;
+
+    /// Describes the frequency of a rule on the working days, Monday to Friday, like
+    /// `every weekday`.
+    fn every_weekday(&self) -> String;
+
+    /// Returns the name of a weekday, like `Monday`.
+    fn weekday(&self, weekday: Weekday) -> String;
+
+    /// Returns the name of a month from 1 to 12, like `January`.
+    fn month(&self, month: u8) -> String;
+
+    /// Returns the name of a month of the Chinese calendar of `RSCALE=CHINESE`, like
+    /// `the 5th month`, or `the leap 5th month` if `leap` is `true`.
+    ///
+    /// The default is the notation of `BYMONTH` of RFC 7529, like `5` or `5L`.
+    fn lunar_month(&self, month: u8, leap: bool) -> String {
+        format!("{}{}", month, if leap { "L" } else { "" })
+    }
+
+    /// Formats a position as an ordinal number, like `1st`, or `2nd last` for `-2`.
+    fn ordinal(&self, position: i32) -> String;
+
+    /// Joins items with `and`, like `a, b and c`.
+    fn and_list(&self, items: &[String]) -> String;
+
+    /// Joins items with `or`, like `a, b or c`.
+    fn or_list(&self, items: &[String]) -> String;
+
+    /// Describes the months, like `in January and July`.
+    fn in_months(&self, months: &str) -> String;
+
+    /// Describes the positions of the weeks in the year, like `in the 1st and last week
+    /// of the year`.
+    fn in_weeks(&self, weeks: &str) -> String;
+
+    /// Describes the positions of the days in the year, like `on the 100th day of the
+    /// year`.
+    fn on_year_days(&self, days: &str) -> String;
+
+    /// Describes the positions of the days in the month, like `on the 1st and 15th`.
+    /// `from_end` is `true` if some of them are from the end of the month, like `last`.
+    fn on_month_days(&self, days: &str, from_end: bool) -> String;
+
+    /// Describes the weekdays, like `on Monday and Friday` or `on the 2nd Monday`.
+    fn on_weekdays(&self, weekdays: &str) -> String;
+
+    /// Describes weekdays that are on month days, like `on Friday the 13th`.
+    fn on_weekdays_and_month_days(&self, weekdays: &str, days: &str, from_end: bool) -> String;
+
+    /// Describes the working days, Monday to Friday, like `on weekdays`.
+    fn on_working_days(&self) -> String;
+
+    /// Describes the weekday at a position in the month or year, like `the 2nd Monday`.
+    fn nth_weekday(&self, position: &str, weekday: &str) -> String;
+
+    /// Describes the positions of `BYSETPOS` among the days, like `on the last weekday`,
+    /// or among the recurrences if `days` is `None`, like `on the 1st occurrence`.
+    fn on_positions(&self, positions: &str, days: Option<&str>) -> String;
+
+    /// Returns the name of a working day, for [`TextLocale::on_positions`], like
+    /// `weekday`.
+    fn working_day(&self) -> String;
+
+    /// Returns the name of any day, for [`TextLocale::on_positions`], like `day`.
+    fn any_day(&self) -> String;
+
+    /// Describes the days from Easter Sunday of `BYEASTER`, like `2 days after Easter
+    /// Sunday`.
+    fn easter(&self, days: i16) -> String;
+
+    /// Describes the times of day, like `at 9:00 AM and 5:30 PM`.
+    fn at_times(&self, times: &str) -> String;
+
+    /// Formats a time of day, like `9:00 AM`. The seconds are `None` if all the times of
+    /// the rule are at whole minutes.
+    fn time(&self, hour: u8, minute: u8, second: Option<u8>) -> String;
+
+    /// Describes the hours, minutes or seconds, for [`Frequency::Hourly`],
+    /// [`Frequency::Minutely`] and [`Frequency::Secondly`], like `at minutes 0 and 30`.
+    fn at_units(&self, unit: Frequency, values: &str, plural: bool) -> String;
+
+    /// Describes the `COUNT`, like `for 10 times`.
+    fn count(&self, count: u32) -> String;
+
+    /// Describes the `UNTIL`, like `until Jan 30, 2013`.
+    fn until(&self, date: NaiveDate) -> String;
+}
+
+/// The built-in languages of the descriptions of [`crate::RRule::to_text_in`].
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{Language, RRule, Unvalidated};
+///
+/// let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR;COUNT=3".parse().unwrap();
+/// assert_eq!(rrule.to_text_in(&Language::English), "every 2 months on the last Friday for 3 times");
+/// assert_eq!(
+///     rrule.to_text_in(&Language::German),
+///     "alle 2 Monate am letzten Freitag insgesamt 3 Mal"
+/// );
+/// assert_eq!(
+///     rrule.to_text_in(&Language::Spanish),
+///     "cada 2 meses el último viernes un total de 3 veces"
+/// );
+/// assert_eq!(
+///     rrule.to_text_in(&Language::French),
+///     "tous les 2 mois le dernier vendredi pour 3 occurrences"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// English, like `every 2 weeks on Monday`.
+    English,
+    /// German, like `alle 2 Wochen am Montag`.
+    German,
+    /// Spanish, like `cada 2 semanas el lunes`.
+    Spanish,
+    /// French, like `toutes les 2 semaines le lundi`.
+    French,
+}
+
+/// The names of the weekdays from Monday, in the order of the variants of [`Language`].
+const WEEKDAY_NAMES: [[&str; 7]; 4] = [
+    [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    [
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+        "Sonntag",
+    ],
+    [
+        "lunes",
+        "martes",
+        "miércoles",
+        "jueves",
+        "viernes",
+        "sábado",
+        "domingo",
+    ],
+    [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+];
+
+/// The names of the months from January, in the order of the variants of [`Language`].
+const MONTH_NAMES: [[&str; 12]; 4] = [
+    [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+];
+
+impl TextLocale for Language {
+    fn every(&self, freq: Frequency, interval: u16) -> String {
+        // The singular and plural names of the unit, and the word for `every` with the
+        // singular, which has the gender of the unit in German.
+        let (every, singular, plural) = match (self, freq) {
+            (Self::English, _) => {
+                let unit = match freq {
+                    Frequency::Yearly => "year",
+                    Frequency::Monthly => "month",
+                    Frequency::Weekly => "week",
+                    Frequency::Daily => "day",
+                    Frequency::Hourly => "hour",
+                    Frequency::Minutely => "minute",
+                    Frequency::Secondly => "second",
+                };
+                return match interval {
+                    1 => format!("every {}", unit),
+                    _ => format!("every {} {}s", interval, unit),
+                };
+            }
+            (Self::German, Frequency::Yearly) => ("jedes", "Jahr", "Jahre"),
+            (Self::German, Frequency::Monthly) => ("jeden", "Monat", "Monate"),
+            (Self::German, Frequency::Weekly) => ("jede", "Woche", "Wochen"),
+            (Self::German, Frequency::Daily) => ("jeden", "Tag", "Tage"),
+            (Self::German, Frequency::Hourly) => ("jede", "Stunde", "Stunden"),
+            (Self::German, Frequency::Minutely) => ("jede", "Minute", "Minuten"),
+            (Self::German, Frequency::Secondly) => ("jede", "Sekunde", "Sekunden"),
+            (Self::Spanish, Frequency::Yearly) => ("cada", "año", "años"),
+            (Self::Spanish, Frequency::Monthly) => ("cada", "mes", "meses"),
+            (Self::Spanish, Frequency::Weekly) => ("cada", "semana", "semanas"),
+            (Self::Spanish, Frequency::Daily) => ("cada", "día", "días"),
+            (Self::Spanish, Frequency::Hourly) => ("cada", "hora", "horas"),
+            (Self::Spanish, Frequency::Minutely) => ("cada", "minuto", "minutos"),
+            (Self::Spanish, Frequency::Secondly) => ("cada", "segundo", "segundos"),
+            (Self::French, Frequency::Yearly) => ("tous les", "année", "ans"),
+            (Self::French, Frequency::Monthly) => ("tous les", "mois", "mois"),
+            (Self::French, Frequency::Weekly) => ("toutes les", "semaine", "semaines"),
+            (Self::French, Frequency::Daily) => ("tous les", "jour", "jours"),
+            (Self::French, Frequency::Hourly) => ("toutes les", "heure", "heures"),
+            (Self::French, Frequency::Minutely) => ("toutes les", "minute", "minutes"),
+            (Self::French, Frequency::Secondly) => ("toutes les", "seconde", "secondes"),
+        };
+        match (self, interval) {
+            (Self::German, 1) | (Self::Spanish, 1) => format!("{} {}", every, singular),
+            (Self::German, _) => format!("alle {} {}", interval, plural),
+            (Self::French, 1) => format!("chaque {}", singular),
+            _ => format!("{} {} {}", every, interval, plural),
+        }
+    }
+
+    fn every_weekday(&self) -> String {
+        match self {
+            Self::English => "every weekday",
+            Self::German => "jeden Werktag",
+            Self::Spanish => "cada día laborable",
+            Self::French => "chaque jour ouvrable",
+        }
+        .into()
+    }
+
+    fn weekday(&self, weekday: Weekday) -> String {
+        let idx = weekday.num_days_from_monday() as usize;
+        WEEKDAY_NAMES[*self as usize][idx].into()
+    }
+
+    fn month(&self, month: u8) -> String {
+        let names = &MONTH_NAMES[*self as usize];
+        let name = names.get(usize::from(month).wrapping_sub(1));
+        name.map_or_else(|| month.to_string(), |name| (*name).into())
+    }
+
+    fn lunar_month(&self, month: u8, leap: bool) -> String {
+        let n = self.ordinal(i32::from(month));
+        match (self, leap) {
+            (Self::English, false) => format!("the {} month", n),
+            (Self::English, true) => format!("the leap {} month", n),
+            (Self::German, false) => format!("{} Monat", n),
+            (Self::German, true) => format!("{} Schaltmonat", n),
+            (Self::Spanish, false) => format!("el {} mes", n),
+            (Self::Spanish, true) => format!("el {} mes intercalar", n),
+            (Self::French, false) => format!("{} mois", n),
+            (Self::French, true) => format!("{} mois intercalaire", n),
+        }
+    }
+
+    fn ordinal(&self, position: i32) -> String {
+        let n = position.unsigned_abs();
+        match (self, position) {
+            (Self::English, -1) => "last".into(),
+            (Self::English, _) => {
+                let suffix = match (n % 10, n % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                match position {
+                    1.. => format!("{}{}", n, suffix),
+                    _ => format!("{}{} last", n, suffix),
+                }
+            }
+            (Self::German, -1) => "letzten".into(),
+            (Self::German, -2) => "vorletzten".into(),
+            (Self::German, 1..) => format!("{}.", n),
+            (Self::German, _) => format!("{}.-letzten", n),
+            (Self::Spanish, -1) => "último".into(),
+            (Self::Spanish, -2) => "penúltimo".into(),
+            (Self::Spanish, 1..) => format!("{}.º", n),
+            (Self::Spanish, _) => format!("{}.º desde el final", n),
+            (Self::French, -1) => "dernier".into(),
+            (Self::French, -2) => "avant-dernier".into(),
+            (Self::French, 1) => "1er".into(),
+            (Self::French, 1..) => format!("{}e", n),
+            (Self::French, _) => format!("{}e avant la fin", n),
+        }
+    }
+
+    fn and_list(&self, items: &[String]) -> String {
+        let and = match self {
+            Self::English => "and",
+            Self::German => "und",
+            Self::Spanish => "y",
+            Self::French => "et",
+        };
+        join(items, and)
+    }
+
+    fn or_list(&self, items: &[String]) -> String {
+        let or = match self {
+            Self::English => "or",
+            Self::German => "oder",
+            Self::Spanish => "o",
+            Self::French => "ou",
+        };
+        join(items, or)
+    }
+
+    fn in_months(&self, months: &str) -> String {
+        match self {
+            Self::English => format!("in {}", months),
+            Self::German => format!("im {}", months),
+            Self::Spanish | Self::French => format!("en {}", months),
+        }
+    }
+
+    fn in_weeks(&self, weeks: &str) -> String {
+        match self {
+            Self::English => format!("in the {} week of the year", weeks),
+            Self::German => format!("in der {} Woche des Jahres", weeks),
+            Self::Spanish => format!("en la semana {} del año", weeks),
+            Self::French => format!("pendant la semaine {} de l'année", weeks),
+        }
+    }
+
+    fn on_year_days(&self, days: &str) -> String {
+        match self {
+            Self::English => format!("on the {} day of the year", days),
+            Self::German => format!("am {} Tag des Jahres", days),
+            Self::Spanish => format!("el {} día del año", days),
+            Self::French => format!("le {} jour de l'année", days),
+        }
+    }
+
+    fn on_month_days(&self, days: &str, from_end: bool) -> String {
+        match (self, from_end) {
+            (Self::English, false) => format!("on the {}", days),
+            (Self::English, true) => format!("on the {} day", days),
+            (Self::German, false) => format!("am {}", days),
+            (Self::German, true) => format!("am {} Tag", days),
+            (Self::Spanish, false) => format!("el día {}", days),
+            (Self::Spanish, true) => format!("el {} día", days),
+            (Self::French, false) => format!("le {}", days),
+            (Self::French, true) => format!("le {} jour", days),
+        }
+    }
+
+    fn on_weekdays(&self, weekdays: &str) -> String {
+        match self {
+            Self::English => format!("on {}", weekdays),
+            Self::German => format!("am {}", weekdays),
+            Self::Spanish => format!("el {}", weekdays),
+            Self::French => format!("le {}", weekdays),
+        }
+    }
+
+    fn on_weekdays_and_month_days(&self, weekdays: &str, days: &str, from_end: bool) -> String {
+        match (self, from_end) {
+            (Self::English, false) => format!("on {} the {}", weekdays, days),
+            (Self::English, true) => format!("on {} the {} day", weekdays, days),
+            (Self::German, false) => format!("am {}, dem {}", weekdays, days),
+            (Self::German, true) => format!("am {}, dem {} Tag", weekdays, days),
+            (Self::Spanish, false) => format!("el {} día {}", weekdays, days),
+            (Self::Spanish, true) => format!("el {}, {} día", weekdays, days),
+            (Self::French, false) => format!("le {} {}", weekdays, days),
+            (Self::French, true) => format!("le {}, {} jour", weekdays, days),
+        }
+    }
+
+    fn on_working_days(&self) -> String {
+        match self {
+            Self::English => "on weekdays",
+            Self::German => "an Werktagen",
+            Self::Spanish => "los días laborables",
+            Self::French => "en semaine",
+        }
+        .into()
+    }
+
+    fn nth_weekday(&self, position: &str, weekday: &str) -> String {
+        match self {
+            Self::English => format!("the {} {}", position, weekday),
+            _ => format!("{} {}", position, weekday),
+        }
+    }
+
+    fn on_positions(&self, positions: &str, days: Option<&str>) -> String {
+        match (self, days) {
+            (Self::English, Some(days)) => format!("on the {} {}", positions, days),
+            (Self::English, None) => format!("on the {} occurrence", positions),
+            (Self::German, Some(days)) => format!("am {} {}", positions, days),
+            (Self::German, None) => format!("beim {} Termin", positions),
+            (Self::Spanish, Some(days)) => format!("el {} {}", positions, days),
+            (Self::Spanish, None) => format!("en la repetición {}", positions),
+            (Self::French, Some(days)) => format!("le {} {}", positions, days),
+            (Self::French, None) => format!("à l'occurrence {}", positions),
+        }
+    }
+
+    fn working_day(&self) -> String {
+        match self {
+            Self::English => "weekday",
+            Self::German => "Werktag",
+            Self::Spanish => "día laborable",
+            Self::French => "jour ouvrable",
+        }
+        .into()
+    }
+
+    fn any_day(&self) -> String {
+        match self {
+            Self::English => "day",
+            Self::German => "Tag",
+            Self::Spanish => "día",
+            Self::French => "jour",
+        }
+        .into()
+    }
+
+    fn easter(&self, days: i16) -> String {
+        let n = days.unsigned_abs();
+        let one = n == 1;
+        match (self, days) {
+            (Self::English, 0) => "on Easter Sunday".into(),
+            (Self::English, _) => {
+                let unit = if one { "day" } else { "days" };
+                let direction = if days > 0 { "after" } else { "before" };
+                format!("{} {} {} Easter Sunday", n, unit, direction)
+            }
+            (Self::German, 0) => "am Ostersonntag".into(),
+            (Self::German, _) => {
+                let unit = if one { "Tag" } else { "Tage" };
+                let direction = if days > 0 { "nach" } else { "vor" };
+                format!("{} {} {} Ostersonntag", n, unit, direction)
+            }
+            (Self::Spanish, 0) => "el Domingo de Pascua".into(),
+            (Self::Spanish, _) => {
+                let unit = if one { "día" } else { "días" };
+                let direction = if days > 0 { "después" } else { "antes" };
+                format!("{} {} {} del Domingo de Pascua", n, unit, direction)
+            }
+            (Self::French, 0) => "le dimanche de Pâques".into(),
+            (Self::French, _) => {
+                let unit = if one { "jour" } else { "jours" };
+                let direction = if days > 0 { "après" } else { "avant" };
+                format!("{} {} {} le dimanche de Pâques", n, unit, direction)
+            }
+        }
+    }
+
+    fn at_times(&self, times: &str) -> String {
+        match self {
+            Self::English => format!("at {}", times),
+            Self::German => format!("um {}", times),
+            Self::Spanish => format!("a las {}", times),
+            Self::French => format!("à {}", times),
+        }
+    }
+
+    fn time(&self, hour: u8, minute: u8, second: Option<u8>) -> String {
+        let (hour, period) = match self {
+            // The 12-hour clock.
+            Self::English => {
+                let period = if hour < 12 { " AM" } else { " PM" };
+                match hour % 12 {
+                    0 => (12, period),
+                    hour => (hour, period),
+                }
+            }
+            _ => (hour, ""),
+        };
+        match second {
+            Some(second) => format!("{}:{:02}:{:02}{}", hour, minute, second, period),
+            None => format!("{}:{:02}{}", hour, minute, period),
+        }
+    }
+
+    fn at_units(&self, unit: Frequency, values: &str, plural: bool) -> String {
+        let (singular_unit, plural_unit) = match (self, unit) {
+            (Self::English, Frequency::Hourly) => ("at hour", "at hours"),
+            (Self::English, Frequency::Minutely) => ("at minute", "at minutes"),
+            (Self::English, _) => ("at second", "at seconds"),
+            (Self::German, Frequency::Hourly) => ("zur Stunde", "zu den Stunden"),
+            (Self::German, Frequency::Minutely) => ("zur Minute", "zu den Minuten"),
+            (Self::German, _) => ("zur Sekunde", "zu den Sekunden"),
+            (Self::Spanish, Frequency::Hourly) => ("a la hora", "a las horas"),
+            (Self::Spanish, Frequency::Minutely) => ("en el minuto", "en los minutos"),
+            (Self::Spanish, _) => ("en el segundo", "en los segundos"),
+            (Self::French, Frequency::Hourly) => ("à l'heure", "aux heures"),
+            (Self::French, Frequency::Minutely) => ("à la minute", "aux minutes"),
+            (Self::French, _) => ("à la seconde", "aux secondes"),
+        };
+        let unit = if plural { plural_unit } else { singular_unit };
+        format!("{} {}", unit, values)
+    }
+
+    fn count(&self, count: u32) -> String {
+        let one = count == 1;
+        match self {
+            Self::English => format!("for {} {}", count, if one { "time" } else { "times" }),
+            Self::German => format!("insgesamt {} Mal", count),
+            Self::Spanish => format!(
+                "un total de {} {}",
+                count,
+                if one { "vez" } else { "veces" }
+            ),
+            Self::French => {
+                let unit = if one { "occurrence" } else { "occurrences" };
+                format!("pour {} {}", count, unit)
+            }
+        }
+    }
+
+    fn until(&self, date: NaiveDate) -> String {
+        let month = MONTH_NAMES[*self as usize][date.month0() as usize];
+        let (day, year) = (date.day(), date.year());
+        match self {
+            Self::English => format!("until {}", date.format("%b %-d, %Y")),
+            Self::German => format!("bis {}. {} {}", day, month, year),
+            Self::Spanish => format!("hasta el {} de {} de {}", day, month, year),
+            Self::French if day == 1 => format!("jusqu'au 1er {} {}", month, year),
+            Self::French => format!("jusqu'au {} {} {}", day, month, year),
+        }
+    }
+}
+
+/// Joins the items with commas and `conjunction` before the last one, like `a, b and c`.
+pub(crate) fn join(items: &[String], conjunction: &str) -> String {
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => {
+            format!("{} {} {}", rest.join(", "), conjunction, last)
+        }
+        _ => items.concat(),
+    }
+}
@@ -1,10 +1,17 @@
+use super::VTimezone;
 use chrono::Local;
+#[cfg(not(feature = "chrono-tz"))]
+use chrono::Utc;
 
 /// A wrapper around `chrono_tz::Tz` that is able to represent `Local` timezone also.
 ///
+/// Without the `chrono-tz` feature, only the `Local` timezone, UTC and timezones with a
+/// fixed offset (see [`VTimezone::fixed`]) are available.
+///
 /// # Usage
 ///
-/// ```
+#[cfg_attr(feature = "chrono-tz", doc = "```")]
+#[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
 /// use rrule::Tz;
 ///
 /// let utc = Tz::UTC;
@@ -18,7 +25,13 @@ pub enum Tz {
     /// Local timezone
     Local(Local),
     /// Timezone represented by `chrono_tz::Tz`
+    #[cfg(feature = "chrono-tz")]
     Tz(chrono_tz::Tz),
+    /// UTC, when the `chrono-tz` feature is disabled
+    #[cfg(not(feature = "chrono-tz"))]
+    Utc(Utc),
+    /// Timezone defined by a `VTIMEZONE` component, see [`VTimezone`]
+    Custom(&'static VTimezone),
 }
 
 impl Tz {
@@ -27,23 +40,33 @@ impl Tz {
     pub fn name(&self) -> &str {
         match self {
             Self::Local(_) => "Local",
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => tz.name(),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(_) => "UTC",
+            Self::Custom(tz) => tz.tzid(),
         }
     }
 
     /// Check if timezone is the Local timezone
     #[must_use]
     pub fn is_local(&self) -> bool {
-        match self {
-            Self::Local(_) => true,
-            Self::Tz(_) => false,
-        }
+        matches!(self, Self::Local(_))
     }
 
     /// Local timezone
     #[allow(non_upper_case_globals)]
     pub const LOCAL: Self = Self::Local(Local);
+}
 
+#[cfg(not(feature = "chrono-tz"))]
+impl Tz {
+    /// UTC timezone
+    pub const UTC: Self = Self::Utc(Utc);
+}
+
+#[cfg(feature = "chrono-tz")]
+impl Tz {
     // Duplicating all chrono_tz variants with the following program:
     //    for tz in chrono_tz::TZ_VARIANTS {
     //        let tz_name = tz.name();
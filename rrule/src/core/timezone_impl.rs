@@ -1,12 +1,16 @@
-use chrono::{FixedOffset, Local, Offset, TimeZone, Utc};
+use chrono::{FixedOffset, Local, NaiveTime, Offset, TimeZone, Utc};
 
-use super::Tz;
+use super::{Tz, VTimezone};
 
 impl PartialEq for Tz {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Local(_), Self::Local(_)) => true,
+            #[cfg(feature = "chrono-tz")]
             (Self::Tz(l0), Self::Tz(r0)) => l0 == r0,
+            #[cfg(not(feature = "chrono-tz"))]
+            (Self::Utc(_), Self::Utc(_)) => true,
+            (Self::Custom(l0), Self::Custom(r0)) => l0 == r0,
             _ => false,
         }
     }
@@ -20,10 +24,11 @@ impl From<Local> for Tz {
 
 impl From<Utc> for Tz {
     fn from(_tz: Utc) -> Self {
-        Self::Tz(chrono_tz::UTC)
+        Self::UTC
     }
 }
 
+#[cfg(feature = "chrono-tz")]
 impl From<chrono_tz::Tz> for Tz {
     fn from(tz: chrono_tz::Tz) -> Self {
         Self::Tz(tz)
@@ -34,7 +39,11 @@ impl std::fmt::Debug for Tz {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Local(tz) => tz.fmt(f),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => tz.fmt(f),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(tz) => tz.fmt(f),
+            Self::Custom(tz) => tz.tzid().fmt(f),
         }
     }
 }
@@ -43,7 +52,11 @@ impl std::fmt::Display for Tz {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Local(_tz) => write!(f, "Local"),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => tz.fmt(f),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(tz) => tz.fmt(f),
+            Self::Custom(tz) => write!(f, "{}", tz.tzid()),
         }
     }
 }
@@ -51,14 +64,21 @@ impl std::fmt::Display for Tz {
 #[derive(Clone, Copy)]
 pub enum RRuleOffset {
     Local(FixedOffset),
+    #[cfg(feature = "chrono-tz")]
     Tz(<chrono_tz::Tz as TimeZone>::Offset),
+    #[cfg(not(feature = "chrono-tz"))]
+    Utc(Utc),
+    Custom(FixedOffset, &'static VTimezone),
 }
 
 impl std::fmt::Debug for RRuleOffset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Local(offset) => offset.fmt(f),
+            Self::Local(offset) | Self::Custom(offset, _) => offset.fmt(f),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(offset) => offset.fmt(f),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(offset) => offset.fmt(f),
         }
     }
 }
@@ -66,8 +86,11 @@ impl std::fmt::Debug for RRuleOffset {
 impl std::fmt::Display for RRuleOffset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Local(offset) => offset.fmt(f),
+            Self::Local(offset) | Self::Custom(offset, _) => offset.fmt(f),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(offset) => offset.fmt(f),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(offset) => offset.fmt(f),
         }
     }
 }
@@ -75,8 +98,11 @@ impl std::fmt::Display for RRuleOffset {
 impl Offset for RRuleOffset {
     fn fix(&self) -> FixedOffset {
         match self {
-            Self::Local(tz) => tz.fix(),
+            Self::Local(tz) | Self::Custom(tz, _) => tz.fix(),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => tz.fix(),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(tz) => tz.fix(),
         }
     }
 }
@@ -87,7 +113,11 @@ impl TimeZone for Tz {
     fn from_offset(offset: &Self::Offset) -> Self {
         match offset {
             RRuleOffset::Local(offset) => Self::Local(Local::from_offset(offset)),
+            #[cfg(feature = "chrono-tz")]
             RRuleOffset::Tz(offset) => Self::Tz(chrono_tz::Tz::from_offset(offset)),
+            #[cfg(not(feature = "chrono-tz"))]
+            RRuleOffset::Utc(offset) => Self::Utc(*offset),
+            RRuleOffset::Custom(_, tz) => Self::Custom(tz),
         }
     }
 
@@ -100,9 +130,17 @@ impl TimeZone for Tz {
             Self::Local(tz) => tz
                 .from_local_date(local)
                 .map(|date| RRuleOffset::Local(*date.offset())),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => tz
                 .from_local_date(local)
                 .map(|date| RRuleOffset::Tz(*date.offset())),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(tz) => tz
+                .from_local_date(local)
+                .map(|date| RRuleOffset::Utc(*date.offset())),
+            Self::Custom(tz) => tz
+                .offset_at_local(&local.and_time(NaiveTime::MIN))
+                .map(|offset| RRuleOffset::Custom(offset, tz)),
         }
     }
 
@@ -114,9 +152,17 @@ impl TimeZone for Tz {
             Self::Local(tz) => tz
                 .from_local_datetime(local)
                 .map(|date| RRuleOffset::Local(*date.offset())),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => tz
                 .from_local_datetime(local)
                 .map(|date| RRuleOffset::Tz(*date.offset())),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(tz) => tz
+                .from_local_datetime(local)
+                .map(|date| RRuleOffset::Utc(*date.offset())),
+            Self::Custom(tz) => tz
+                .offset_at_local(local)
+                .map(|offset| RRuleOffset::Custom(offset, tz)),
         }
     }
 
@@ -124,14 +170,24 @@ impl TimeZone for Tz {
     fn offset_from_utc_date(&self, utc: &chrono::NaiveDate) -> Self::Offset {
         match self {
             Self::Local(tz) => RRuleOffset::Local(*tz.from_utc_date(utc).offset()),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => RRuleOffset::Tz(*tz.from_utc_date(utc).offset()),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(tz) => RRuleOffset::Utc(*tz.from_utc_date(utc).offset()),
+            Self::Custom(tz) => {
+                RRuleOffset::Custom(tz.offset_at(&utc.and_time(NaiveTime::MIN)), tz)
+            }
         }
     }
 
     fn offset_from_utc_datetime(&self, utc: &chrono::NaiveDateTime) -> Self::Offset {
         match self {
             Self::Local(tz) => RRuleOffset::Local(*tz.from_utc_datetime(utc).offset()),
+            #[cfg(feature = "chrono-tz")]
             Self::Tz(tz) => RRuleOffset::Tz(*tz.from_utc_datetime(utc).offset()),
+            #[cfg(not(feature = "chrono-tz"))]
+            Self::Utc(tz) => RRuleOffset::Utc(*tz.from_utc_datetime(utc).offset()),
+            Self::Custom(tz) => RRuleOffset::Custom(tz.offset_at(utc), tz),
         }
     }
 }
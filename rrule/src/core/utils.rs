@@ -1,9 +1,10 @@
 use super::DateTime;
 use crate::iter::rrule_iter::WasLimited;
 use crate::RRuleResult;
+use std::collections::BTreeMap;
 use std::ops::{
     Bound::{Excluded, Unbounded},
-    RangeBounds,
+    Range, RangeBounds,
 };
 
 /// Helper function to collect dates given some filters.
@@ -15,7 +16,7 @@ pub(super) fn collect_with_error<T>(
     start: &Option<DateTime>,
     end: &Option<DateTime>,
     inclusive: bool,
-    limit: Option<u16>,
+    limit: Option<usize>,
 ) -> RRuleResult
 where
     T: Iterator<Item = DateTime> + WasLimited,
@@ -24,7 +25,7 @@ where
     let mut was_limited = false;
     // This loop should always end because `.next()` has build in limits
     // Once a limit is tripped it will break in the `None` case.
-    while limit.is_none() || matches!(limit, Some(limit) if usize::from(limit) > list.len()) {
+    while limit.is_none() || matches!(limit, Some(limit) if limit > list.len()) {
         if let Some(value) = iterator.next() {
             if is_in_range(&value, start, end, inclusive) {
                 list.push(value);
@@ -39,7 +40,7 @@ where
         }
     }
 
-    was_limited = was_limited || matches!(limit, Some(limit) if usize::from(limit) == list.len());
+    was_limited = was_limited || matches!(limit, Some(limit) if limit == list.len());
 
     RRuleResult {
         dates: list,
@@ -47,6 +48,29 @@ where
     }
 }
 
+/// Helper function to group the dates within `range` by a calendar period in one pass.
+///
+/// The iterator must be sorted, the iteration stops at the end of the range.
+pub(super) fn group_dates_by<T, K, F>(
+    iterator: T,
+    range: &Range<DateTime>,
+    period: F,
+) -> BTreeMap<K, Vec<DateTime>>
+where
+    T: Iterator<Item = DateTime>,
+    K: Ord,
+    F: Fn(&DateTime) -> K,
+{
+    let mut groups: BTreeMap<K, Vec<DateTime>> = BTreeMap::new();
+    for date in iterator
+        .skip_while(|date| *date < range.start)
+        .take_while(|date| *date < range.end)
+    {
+        groups.entry(period(&date)).or_default().push(date);
+    }
+    groups
+}
+
 /// Checks if `date` is after `end`.
 fn has_reached_the_end(date: &DateTime, end: &Option<DateTime>, inclusive: bool) -> bool {
     if inclusive {
@@ -87,6 +111,33 @@ pub(super) fn is_in_range(
     }
 }
 
+/// Folds the content lines of `input` that are longer than 75 octets, by continuing them
+/// on the next line after a line break and a space.
+/// All lines are separated by `\r\n`, as required by the iCalendar format.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc5545#section-3.1>.
+pub(super) fn fold_lines(input: &str) -> String {
+    const MAX_LINE_OCTETS: usize = 75;
+
+    let mut folded = String::with_capacity(input.len());
+    for (idx, line) in input.lines().enumerate() {
+        if idx > 0 {
+            folded.push_str("\r\n");
+        }
+        let mut line_octets = 0;
+        for c in line.chars() {
+            // Never split a multi-octet character.
+            if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+                folded.push_str("\r\n ");
+                line_octets = 1;
+            }
+            folded.push(c);
+            line_octets += c.len_utf8();
+        }
+    }
+    folded
+}
+
 #[cfg(test)]
 mod tests {
     use crate::core::Tz;
@@ -96,6 +147,18 @@ mod tests {
 
     const UTC: Tz = Tz::UTC;
 
+    #[test]
+    fn folds_long_lines() {
+        let long_line = format!("RDATE:{}", ["20210101T090000Z"; 5].join(","));
+        let folded = fold_lines(&format!("DTSTART:20210101T090000Z\n{}", long_line));
+        let lines: Vec<_> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "DTSTART:20210101T090000Z");
+        assert_eq!(lines[1].len(), 75);
+        assert_eq!(lines[2], " 0210101T090000Z");
+        assert_eq!(lines[1..].concat().replace(' ', ""), long_line);
+    }
+
     #[test]
     fn in_range_exclusive_start_to_end() {
         let inclusive = false;
@@ -0,0 +1,429 @@
+//! Support for timezones defined by `VTIMEZONE` components.
+//!
+//! See <https://datatracker.ietf.org/doc/html/rfc5545#section-3.6.5>.
+use super::Tz;
+use crate::parser::{unfold_lines, ParseError};
+use crate::{RRule, RRuleError, Unvalidated};
+use chrono::{Datelike, Duration, FixedOffset, LocalResult, NaiveDateTime, TimeZone};
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+/// The last year for which the transitions of a [`VTimezone`] are computed.
+/// The offset of the last transition is used after that year.
+const LAST_TRANSITION_YEAR: i32 = 2200;
+
+/// The timezones that were converted into a [`Tz`], so that each distinct timezone
+/// is only kept in memory once.
+static TIMEZONES: Mutex<Vec<&'static VTimezone>> = Mutex::new(Vec::new());
+
+/// A timezone defined by a `VTIMEZONE` component, e.g. for a `TZID` that isn't an
+/// IANA timezone name, like `W. Europe Standard Time`.
+///
+/// The transitions between the `STANDARD` and `DAYLIGHT` observances are computed
+/// up to the year 2200, and the last offset is used after that.
+///
+/// `VTIMEZONE` components in the input of an [`crate::RRuleSet`] are used for the `TZID`s
+/// that are not known IANA timezone names.
+///
+/// # Usage
+///
+/// ```
+/// use chrono::TimeZone;
+/// use rrule::{Tz, VTimezone};
+///
+/// let vtimezone: VTimezone = "BEGIN:VTIMEZONE
+/// TZID:W. Europe Standard Time
+/// BEGIN:STANDARD
+/// DTSTART:16010101T030000
+/// TZOFFSETFROM:+0200
+/// TZOFFSETTO:+0100
+/// RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10
+/// END:STANDARD
+/// BEGIN:DAYLIGHT
+/// DTSTART:16010101T020000
+/// TZOFFSETFROM:+0100
+/// TZOFFSETTO:+0200
+/// RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3
+/// END:DAYLIGHT
+/// END:VTIMEZONE"
+///     .parse()
+///     .unwrap();
+/// let tz = Tz::from(vtimezone);
+/// let summer = tz.with_ymd_and_hms(2021, 7, 1, 9, 0, 0).unwrap();
+/// assert_eq!(summer.to_rfc3339(), "2021-07-01T09:00:00+02:00");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VTimezone {
+    tzid: String,
+    /// The offset before the first transition.
+    initial_offset: FixedOffset,
+    /// The UTC datetimes at which the offset changes, with the new offset, in order.
+    transitions: Vec<(NaiveDateTime, FixedOffset)>,
+    /// All the offsets of the timezone.
+    offsets: Vec<FixedOffset>,
+}
+
+/// A `STANDARD` or `DAYLIGHT` sub-component of a `VTIMEZONE`.
+#[derive(Default)]
+struct Observance {
+    dt_start: Option<NaiveDateTime>,
+    offset_from: Option<FixedOffset>,
+    offset_to: Option<FixedOffset>,
+    rrule: Option<String>,
+    rdates: Vec<NaiveDateTime>,
+}
+
+impl Observance {
+    /// Returns the transitions to this observance, as UTC datetimes with the new offset.
+    fn transitions(&self) -> Result<Vec<(NaiveDateTime, FixedOffset)>, ParseError> {
+        let (Some(dt_start), Some(offset_from), Some(offset_to)) =
+            (self.dt_start, self.offset_from, self.offset_to)
+        else {
+            return Err(ParseError::InvalidVTimezone(
+                "An observance needs a `DTSTART`, `TZOFFSETFROM` and `TZOFFSETTO`".into(),
+            ));
+        };
+
+        // The onsets are in the local time before the transition, which is iterated as UTC.
+        let mut onsets = vec![dt_start];
+        onsets.extend(&self.rdates);
+        if let Some(rrule) = &self.rrule {
+            let mut rrule: RRule<Unvalidated> = rrule.parse().map_err(to_parse_error)?;
+            rrule.until = rrule.until.map(|until| {
+                let local = if until.timezone().is_local() {
+                    until.naive_local()
+                } else {
+                    until.naive_utc() + Duration::seconds(offset_from.local_minus_utc().into())
+                };
+                Tz::UTC.from_utc_datetime(&local)
+            });
+            let rrule_set = rrule
+                .build(Tz::UTC.from_utc_datetime(&dt_start))
+                .map_err(to_parse_error)?;
+            onsets.extend(
+                rrule_set
+                    .into_iter()
+                    .map(|onset| onset.naive_utc())
+                    .take_while(|onset| onset.year() <= LAST_TRANSITION_YEAR),
+            );
+        }
+
+        Ok(onsets
+            .into_iter()
+            .filter_map(|onset| {
+                onset.checked_sub_signed(Duration::seconds(offset_from.local_minus_utc().into()))
+            })
+            .map(|utc| (utc, offset_to))
+            .collect())
+    }
+}
+
+fn to_parse_error(err: RRuleError) -> ParseError {
+    match err {
+        RRuleError::ParserError(err) => err,
+        err => ParseError::InvalidVTimezone(err.to_string()),
+    }
+}
+
+/// Parses a UTC offset like `+0100`, `-0530` or `+013045`.
+fn parse_utc_offset(value: &str) -> Result<FixedOffset, ParseError> {
+    let invalid = || ParseError::InvalidVTimezone(format!("`{}` is not a valid UTC offset", value));
+    let sign = match value.as_bytes().first() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => return Err(invalid()),
+    };
+    let digits = &value[1..];
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let parts: Vec<i32> = (0..digits.len())
+        .step_by(2)
+        .map(|idx| digits[idx..idx + 2].parse().unwrap_or_default())
+        .collect();
+    let seconds = parts[0] * 3600 + parts[1] * 60 + parts.get(2).copied().unwrap_or_default();
+    FixedOffset::east_opt(sign * seconds).ok_or_else(invalid)
+}
+
+/// Parses a date-time in local time, like `19701025T030000`.
+fn parse_local_datetime(value: &str) -> Result<NaiveDateTime, ParseError> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%S").map_err(|_| {
+        ParseError::InvalidDateTime {
+            value: value.into(),
+            property: "DTSTART".into(),
+        }
+    })
+}
+
+impl VTimezone {
+    /// Creates a timezone with a fixed offset, e.g. for a `TZID` like `UTC+05:30`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::{FixedOffset, TimeZone};
+    /// use rrule::{Tz, VTimezone};
+    ///
+    /// let offset = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+    /// let tz = Tz::from(VTimezone::fixed("UTC+05:30", offset));
+    /// let date = tz.with_ymd_and_hms(2021, 7, 1, 9, 0, 0).unwrap();
+    /// assert_eq!(date.to_rfc3339(), "2021-07-01T09:00:00+05:30");
+    /// ```
+    #[must_use]
+    pub fn fixed(tzid: impl Into<String>, offset: FixedOffset) -> Self {
+        Self {
+            tzid: tzid.into(),
+            initial_offset: offset,
+            transitions: vec![],
+            offsets: vec![offset],
+        }
+    }
+
+    /// Returns the `TZID` of the timezone.
+    #[must_use]
+    pub fn tzid(&self) -> &str {
+        &self.tzid
+    }
+
+    /// Returns the offset of the timezone at a UTC datetime.
+    #[must_use]
+    pub fn offset_at(&self, utc: &NaiveDateTime) -> FixedOffset {
+        let idx = self
+            .transitions
+            .partition_point(|(transition, _)| transition <= utc);
+        match idx.checked_sub(1) {
+            Some(idx) => self.transitions[idx].1,
+            None => self.initial_offset,
+        }
+    }
+
+    /// Returns the offset of the timezone at a local datetime, which can be ambiguous
+    /// or not exist around a transition.
+    pub(crate) fn offset_at_local(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        // The offsets that map the local datetime to a UTC datetime with the same offset,
+        // in order of the UTC datetimes.
+        let mut offsets: Vec<FixedOffset> = self
+            .offsets
+            .iter()
+            .filter(|offset| {
+                local
+                    .checked_sub_signed(Duration::seconds(offset.local_minus_utc().into()))
+                    .is_some_and(|utc| self.offset_at(&utc) == **offset)
+            })
+            .copied()
+            .collect();
+        offsets.sort_by_key(|offset| -offset.local_minus_utc());
+        match offsets[..] {
+            [] => LocalResult::None,
+            [offset] => LocalResult::Single(offset),
+            [earliest, .., latest] => LocalResult::Ambiguous(earliest, latest),
+        }
+    }
+}
+
+impl FromStr for VTimezone {
+    type Err = ParseError;
+
+    /// Parses a `VTIMEZONE` component, from `BEGIN:VTIMEZONE` to `END:VTIMEZONE`.
+    ///
+    /// Properties other than `TZID`, `DTSTART`, `TZOFFSETFROM`, `TZOFFSETTO`,
+    /// `RRULE` and `RDATE` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`], if the component or one of its properties is invalid.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = unfold_lines(s);
+        let mut tzid = None;
+        let mut observances = vec![];
+        let mut observance: Option<Observance> = None;
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (name, value) = line.split_once(':').ok_or_else(|| {
+                ParseError::InvalidVTimezone(format!("`{}` is not a valid property", line))
+            })?;
+            // Parameters like `VALUE=DATE-TIME` don't change how the values are parsed.
+            let name = name.split(';').next().unwrap_or_default().to_uppercase();
+            let value = value.trim();
+            match (name.as_str(), observance.as_mut()) {
+                ("BEGIN", None) if is_observance(value) => {
+                    observance = Some(Observance::default());
+                }
+                ("END", Some(_)) if is_observance(value) => {
+                    observances.extend(observance.take());
+                }
+                ("TZID", None) => tzid = Some(value.to_string()),
+                ("DTSTART", Some(observance)) => {
+                    observance.dt_start = Some(parse_local_datetime(value)?);
+                }
+                ("TZOFFSETFROM", Some(observance)) => {
+                    observance.offset_from = Some(parse_utc_offset(value)?);
+                }
+                ("TZOFFSETTO", Some(observance)) => {
+                    observance.offset_to = Some(parse_utc_offset(value)?);
+                }
+                ("RRULE", Some(observance)) => observance.rrule = Some(value.into()),
+                ("RDATE", Some(observance)) => {
+                    for rdate in value.split(',') {
+                        observance.rdates.push(parse_local_datetime(rdate)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let tzid = tzid.ok_or_else(|| ParseError::InvalidVTimezone("Missing `TZID`".into()))?;
+        let mut transitions = vec![];
+        for observance in &observances {
+            transitions.extend(observance.transitions()?);
+        }
+        transitions.sort_by_key(|(utc, _)| *utc);
+        // Before the first observance, the offset is the one it changes from.
+        let initial_offset = observances
+            .iter()
+            .filter_map(|observance| Some((observance.dt_start?, observance.offset_from?)))
+            .min_by_key(|(dt_start, _)| *dt_start)
+            .map(|(_, offset)| offset)
+            .ok_or_else(|| {
+                ParseError::InvalidVTimezone("Missing `STANDARD` or `DAYLIGHT` component".into())
+            })?;
+        let mut offsets: Vec<FixedOffset> = transitions.iter().map(|(_, offset)| *offset).collect();
+        offsets.push(initial_offset);
+        offsets.sort_by_key(FixedOffset::local_minus_utc);
+        offsets.dedup();
+
+        Ok(Self {
+            tzid,
+            initial_offset,
+            transitions,
+            offsets,
+        })
+    }
+}
+
+fn is_observance(value: &str) -> bool {
+    value.eq_ignore_ascii_case("STANDARD") || value.eq_ignore_ascii_case("DAYLIGHT")
+}
+
+impl From<VTimezone> for Tz {
+    /// Creates a [`Tz`] from the timezone.
+    ///
+    /// As [`Tz`] is `Copy`, each distinct timezone is kept in memory until the end of the program.
+    fn from(vtimezone: VTimezone) -> Self {
+        let mut timezones = TIMEZONES.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(existing) = timezones.iter().find(|existing| ***existing == vtimezone) {
+            return Self::Custom(existing);
+        }
+        let vtimezone: &'static VTimezone = Box::leak(Box::new(vtimezone));
+        timezones.push(vtimezone);
+        Self::Custom(vtimezone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    const US_EASTERN: &str = "BEGIN:VTIMEZONE
+TZID:US-Eastern
+BEGIN:STANDARD
+DTSTART:19671029T020000
+RRULE:FREQ=YEARLY;BYDAY=1SU;BYMONTH=11
+TZOFFSETFROM:-0400
+TZOFFSETTO:-0500
+TZNAME:EST
+END:STANDARD
+BEGIN:DAYLIGHT
+DTSTART:19870405T020000
+RRULE:FREQ=YEARLY;BYDAY=1SU;BYMONTH=4;UNTIL=19980404T070000Z
+TZOFFSETFROM:-0500
+TZOFFSETTO:-0400
+TZNAME:EDT
+END:DAYLIGHT
+BEGIN:DAYLIGHT
+DTSTART:20070311T020000
+RRULE:FREQ=YEARLY;BYDAY=2SU;BYMONTH=3
+TZOFFSETFROM:-0500
+TZOFFSETTO:-0400
+TZNAME:EDT
+END:DAYLIGHT
+END:VTIMEZONE";
+
+    fn datetime(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .and_then(|date| date.and_hms_opt(h, min, 0))
+            .unwrap()
+    }
+
+    fn offset(hours: i32) -> FixedOffset {
+        FixedOffset::east_opt(hours * 3600).unwrap()
+    }
+
+    #[test]
+    fn parses_utc_offsets() {
+        let tests = [
+            ("+0100", Some(3600)),
+            ("-0530", Some(-19_800)),
+            ("+013045", Some(5445)),
+            ("0100", None),
+            ("+1", None),
+            ("+01:00", None),
+        ];
+        for (input, expected_output) in tests {
+            let output = parse_utc_offset(input)
+                .ok()
+                .map(|offset| offset.local_minus_utc());
+            assert_eq!(output, expected_output, "{input}");
+        }
+    }
+
+    #[test]
+    fn computes_offsets_from_observances() {
+        let vtimezone: VTimezone = US_EASTERN.parse().unwrap();
+        assert_eq!(vtimezone.tzid(), "US-Eastern");
+        let tests = [
+            (datetime(1960, 1, 1, 0, 0), -4),
+            (datetime(1990, 1, 1, 0, 0), -5),
+            (datetime(1990, 4, 1, 6, 59), -5),
+            (datetime(1990, 4, 1, 7, 0), -4),
+            (datetime(2000, 4, 2, 12, 0), -5),
+            (datetime(2021, 3, 14, 6, 59), -5),
+            (datetime(2021, 3, 14, 7, 0), -4),
+            (datetime(2021, 11, 7, 5, 59), -4),
+            (datetime(2021, 11, 7, 6, 0), -5),
+        ];
+        for (utc, hours) in tests {
+            assert_eq!(vtimezone.offset_at(&utc), offset(hours), "{utc}");
+        }
+    }
+
+    #[test]
+    fn resolves_local_datetimes_around_transitions() {
+        let vtimezone: VTimezone = US_EASTERN.parse().unwrap();
+        assert_eq!(
+            vtimezone.offset_at_local(&datetime(2021, 3, 14, 2, 30)),
+            LocalResult::None
+        );
+        assert_eq!(
+            vtimezone.offset_at_local(&datetime(2021, 11, 7, 1, 30)),
+            LocalResult::Ambiguous(offset(-4), offset(-5))
+        );
+        assert_eq!(
+            vtimezone.offset_at_local(&datetime(2021, 7, 1, 9, 0)),
+            LocalResult::Single(offset(-4))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_vtimezones() {
+        let tests = [
+            "BEGIN:VTIMEZONE\nEND:VTIMEZONE",
+            "BEGIN:VTIMEZONE\nTZID:Test\nEND:VTIMEZONE",
+            "BEGIN:VTIMEZONE\nTZID:Test\nBEGIN:STANDARD\nDTSTART:19700101T000000\nTZOFFSETTO:+0100\nEND:STANDARD\nEND:VTIMEZONE",
+            "BEGIN:VTIMEZONE\nTZID:Test\nBEGIN:STANDARD\nDTSTART:19700101T000000\nTZOFFSETFROM:+0100\nTZOFFSETTO:+01\nEND:STANDARD\nEND:VTIMEZONE",
+        ];
+        for input in tests {
+            assert!(input.parse::<VTimezone>().is_err(), "{input}");
+        }
+    }
+}
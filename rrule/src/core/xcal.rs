@@ -0,0 +1,281 @@
+//! Support for the xCal representation of the properties of an [`RRuleSet`].
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc6321>.
+use super::datetime::{datetime_to_ical_format, tzid_parameter};
+use super::DateTime;
+use crate::{NWeekday, ParseError, RRule, RRuleError, RRuleSet, Skip};
+use chrono::Weekday;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// The names of the properties that are read from an xCal document.
+const PROPERTIES: [&str; 5] = ["dtstart", "rrule", "rdate", "exrule", "exdate"];
+
+/// A property read from an xCal document.
+struct XCalProperty {
+    name: String,
+    tzid: Option<String>,
+    /// The values of a date property in the iCalendar format.
+    values: Vec<String>,
+    /// The rule parts of a recurrence rule in the iCalendar format.
+    recur: Vec<(String, String)>,
+}
+
+impl XCalProperty {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_uppercase(),
+            tzid: None,
+            values: vec![],
+            recur: vec![],
+        }
+    }
+
+    /// Adds the text of an element inside of the property, given the names of
+    /// the elements from the property down to the element.
+    fn add_text(&mut self, path: &[String], text: &str) {
+        match path {
+            [.., parent, element] if parent == "tzid" && element == "text" => {
+                self.tzid = Some(text.into());
+            }
+            [.., element] if element == "date-time" || element == "date" => {
+                self.values.push(xcal_to_ical_date(text));
+            }
+            [.., parent, element] if parent == "recur" => {
+                let value = if element == "until" {
+                    xcal_to_ical_date(text)
+                } else {
+                    text.into()
+                };
+                match self.recur.iter_mut().find(|(name, _)| *name == *element) {
+                    Some((_, values)) => {
+                        values.push(',');
+                        values.push_str(&value);
+                    }
+                    None => self.recur.push((element.clone(), value)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the property as an iCalendar content line.
+    fn to_content_line(&self) -> String {
+        if self.recur.is_empty() {
+            let params = self
+                .tzid
+                .as_ref()
+                .map(|tzid| tzid_parameter(tzid))
+                .unwrap_or_default();
+            format!("{}{}:{}", self.name, params, self.values.join(","))
+        } else {
+            let value = self
+                .recur
+                .iter()
+                .map(|(name, values)| format!("{}={}", name.to_uppercase(), values))
+                .collect::<Vec<_>>()
+                .join(";");
+            format!("{}:{}", self.name, value)
+        }
+    }
+}
+
+/// Converts a date or date-time from the xCal format, e.g. `2021-01-01T09:00:00Z`,
+/// to the iCalendar format, e.g. `20210101T090000Z`.
+fn xcal_to_ical_date(value: &str) -> String {
+    value.replace(['-', ':'], "")
+}
+
+/// Formats a date property, e.g. `<rdate><date-time>2021-01-01T09:00:00Z</date-time></rdate>`.
+fn date_property(name: &str, date: &DateTime) -> String {
+    let ical = datetime_to_ical_format(date);
+    let (params, value) = ical.rsplit_once(':').unwrap_or_default();
+    let parameters = params
+        .strip_prefix(";TZID=")
+        .map(|tzid| tzid.trim_matches('"'))
+        .map(|tzid| {
+            format!(
+                "<parameters><tzid><text>{}</text></tzid></parameters>",
+                tzid
+            )
+        })
+        .unwrap_or_default();
+    let maybe_zulu = if value.ends_with('Z') { "Z" } else { "" };
+    format!(
+        "<{name}>{}<date-time>{}{}</date-time></{name}>",
+        parameters,
+        date.format("%Y-%m-%dT%H:%M:%S"),
+        maybe_zulu
+    )
+}
+
+/// Formats a recurrence rule property, e.g. `<rrule><recur><freq>DAILY</freq></recur></rrule>`.
+///
+/// The rule parts are written in the order of the xCal schema.
+fn recur_property(name: &str, rrule: &RRule) -> String {
+    fn parts<T: ToString>(name: &'static str, values: &[T]) -> Vec<(&'static str, String)> {
+        values
+            .iter()
+            .map(|value| (name, value.to_string()))
+            .collect()
+    }
+
+    let mut recur = vec![("freq", rrule.freq.to_string())];
+    if let Some(until) = &rrule.until {
+        let maybe_zulu = if until.timezone().is_local() { "" } else { "Z" };
+        recur.push((
+            "until",
+            format!("{}{}", until.format("%Y-%m-%dT%H:%M:%S"), maybe_zulu),
+        ));
+    }
+    if let Some(count) = rrule.count {
+        recur.push(("count", count.to_string()));
+    }
+    if rrule.interval != 1 {
+        recur.push(("interval", rrule.interval.to_string()));
+    }
+    recur.extend(parts("bysecond", &rrule.by_second));
+    recur.extend(parts("byminute", &rrule.by_minute));
+    recur.extend(parts("byhour", &rrule.by_hour));
+    recur.extend(parts("byday", &rrule.by_weekday));
+    recur.extend(parts("bymonthday", &rrule.by_month_day));
+    recur.extend(parts("bymonthday", &rrule.by_n_month_day));
+    recur.extend(parts("byyearday", &rrule.by_year_day));
+    recur.extend(parts("byweekno", &rrule.by_week_no));
+    recur.extend(parts("bymonth", &rrule.month_values()));
+    recur.extend(parts("bysetpos", &rrule.by_set_pos));
+    if rrule.week_start != Weekday::Mon {
+        recur.push(("wkst", NWeekday::Every(rrule.week_start).to_string()));
+    }
+    if let Some(by_easter) = rrule.by_easter {
+        recur.push(("byeaster", by_easter.to_string()));
+    }
+    if let Some(rscale) = rrule.rscale {
+        recur.push(("rscale", rscale.to_string()));
+    }
+    if rrule.skip != Skip::Omit {
+        recur.push(("skip", rrule.skip.to_string()));
+    }
+
+    let recur: String = recur
+        .into_iter()
+        .map(|(part, value)| format!("<{part}>{}</{part}>", value))
+        .collect();
+    format!("<{name}><recur>{}</recur></{name}>", recur)
+}
+
+impl RRuleSet {
+    /// Returns the properties of the set as xCal (RFC 6321) elements.
+    ///
+    /// The `dtstart`, `rrule`, `rdate`, `exrule` and `exdate` elements are written in that order,
+    /// without a surrounding component, so they can be added to the `properties` of a `vevent`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3".parse().unwrap();
+    /// assert_eq!(
+    ///     rrule_set.to_xcal(),
+    ///     "<dtstart><date-time>2021-01-01T09:00:00Z</date-time></dtstart>\
+    ///      <rrule><recur><freq>DAILY</freq><count>3</count>\
+    ///      <bysecond>0</bysecond><byminute>0</byminute><byhour>9</byhour></recur></rrule>"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_xcal(&self) -> String {
+        let mut xcal = date_property("dtstart", &self.dt_start);
+        for rrule in &self.rrule {
+            xcal.push_str(&recur_property("rrule", rrule));
+        }
+        for rdate in &self.rdate {
+            xcal.push_str(&date_property("rdate", rdate));
+        }
+        for exrule in &self.exrule {
+            xcal.push_str(&recur_property("exrule", exrule));
+        }
+        for exdate in &self.exdate {
+            xcal.push_str(&date_property("exdate", exdate));
+        }
+        for day in &self.exdate_days {
+            xcal.push_str(&format!(
+                "<exdate><date>{}</date></exdate>",
+                day.format("%Y-%m-%d")
+            ));
+        }
+        xcal
+    }
+
+    /// Creates an [`RRuleSet`] from the `dtstart`, `rrule`, `rdate`, `exrule` and `exdate`
+    /// properties in an xCal (RFC 6321) document.
+    ///
+    /// All other elements are ignored, so the input should contain a single component.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError`] if the input is not valid XML, or if the properties are not valid.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set = RRuleSet::from_xcal(
+    ///     r#"<vevent xmlns="urn:ietf:params:xml:ns:icalendar-2.0"><properties>
+    ///         <dtstart><date-time>2021-01-01T09:00:00Z</date-time></dtstart>
+    ///         <rrule><recur><freq>DAILY</freq><count>3</count></recur></rrule>
+    ///     </properties></vevent>"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(rrule_set.all(10).dates.len(), 3);
+    /// ```
+    pub fn from_xcal(xml: &str) -> Result<Self, RRuleError> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut path: Vec<String> = vec![];
+        let mut properties: Vec<XCalProperty> = vec![];
+        // The depth of the property that is being read.
+        let mut property_depth = None;
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|err| ParseError::InvalidXCal(err.to_string()))?;
+            match event {
+                Event::Start(element) => {
+                    let name =
+                        String::from_utf8_lossy(element.local_name().as_ref()).to_lowercase();
+                    if property_depth.is_none() && PROPERTIES.contains(&name.as_str()) {
+                        properties.push(XCalProperty::new(&name));
+                        property_depth = Some(path.len());
+                    }
+                    path.push(name);
+                }
+                Event::End(_) => {
+                    path.pop();
+                    if property_depth == Some(path.len()) {
+                        property_depth = None;
+                    }
+                }
+                Event::Text(text) => {
+                    let text = text
+                        .unescape()
+                        .map_err(|err| ParseError::InvalidXCal(err.to_string()))?;
+                    if let (Some(depth), Some(property)) = (property_depth, properties.last_mut()) {
+                        property.add_text(&path[depth..], &text);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        properties
+            .iter()
+            .map(XCalProperty::to_content_line)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .parse()
+    }
+}
@@ -16,6 +16,9 @@ pub enum RRuleError {
     /// Iterator error
     #[error("RRule iterator error: {0}")]
     IterError(String),
+    /// The iteration limit was reached before all the recurrences were collected
+    #[error("RRule iteration limit of `{0}` recurrences exceeded")]
+    IterationLimitExceeded(u16),
 }
 
 impl RRuleError {
@@ -0,0 +1,129 @@
+//! A cache of the calendar tables of the years and months, which is shared by the
+//! iterations of all the rules.
+//!
+//! The tables only depend on the year, the month and a few rule parts, so rules with the
+//! same shape, like all the `WEEKLY` rules without `BYWEEKNO`, share the tables of a year.
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::Weekday;
+use lazy_static::lazy_static;
+
+use super::{monthinfo::MonthInfo, yearinfo::YearInfo};
+use crate::core::ByRule;
+use crate::{Frequency, NWeekday, RRule};
+
+/// The number of tables after which a cache is cleared, to bound its memory.
+const MAX_CACHED_TABLES: usize = 4096;
+
+/// The year and the rule parts that the tables of a year depend on.
+#[derive(PartialEq, Eq, Hash)]
+struct YearKey {
+    year: i32,
+    week_start: Weekday,
+    by_week_no: ByRule<i8>,
+}
+
+/// The month and the rule parts that the tables of a month depend on.
+#[derive(PartialEq, Eq, Hash)]
+struct MonthKey {
+    year: i32,
+    month: u8,
+    freq: Frequency,
+    by_month: ByRule<u8>,
+    by_weekday: ByRule<NWeekday>,
+}
+
+lazy_static! {
+    static ref YEAR_INFOS: Mutex<HashMap<YearKey, Arc<YearInfo>>> = Mutex::default();
+    static ref MONTH_INFOS: Mutex<HashMap<MonthKey, Arc<MonthInfo>>> = Mutex::default();
+}
+
+/// Returns the tables of `year` for the rule.
+pub(crate) fn year_info(year: i32, rrule: &RRule) -> Arc<YearInfo> {
+    let key = YearKey {
+        year,
+        week_start: rrule.week_start,
+        by_week_no: rrule.by_week_no.clone(),
+    };
+    get_or_insert(&YEAR_INFOS, key, || YearInfo::new(year, rrule))
+}
+
+/// Returns the tables of `month` of the year of `year_info` for the rule.
+pub(crate) fn month_info(year_info: &YearInfo, month: u8, rrule: &RRule) -> Arc<MonthInfo> {
+    let key = MonthKey {
+        year: year_info.year,
+        month,
+        freq: rrule.freq,
+        by_month: rrule.by_month.clone(),
+        by_weekday: rrule.by_weekday.clone(),
+    };
+    get_or_insert(&MONTH_INFOS, key, || {
+        MonthInfo::new(year_info, month, rrule)
+    })
+}
+
+fn get_or_insert<K: Eq + Hash, V>(
+    cache: &Mutex<HashMap<K, Arc<V>>>,
+    key: K,
+    new: impl FnOnce() -> V,
+) -> Arc<V> {
+    // The tables in a poisoned cache are still complete, as they are inserted at once.
+    let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(value) = cache.get(&key) {
+        return Arc::clone(value);
+    }
+    if cache.len() >= MAX_CACHED_TABLES {
+        cache.clear();
+    }
+    let value = Arc::new(new());
+    cache.insert(key, Arc::clone(&value));
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Weekday};
+
+    use super::*;
+    use crate::core::Tz;
+    use crate::Unvalidated;
+
+    fn validate(rrule: RRule<Unvalidated>) -> RRule {
+        rrule
+            .validate(Tz::UTC.with_ymd_and_hms(1997, 1, 1, 0, 0, 0).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn shares_the_tables_of_rules_with_the_same_shape() {
+        let weekly = RRule {
+            freq: Frequency::Weekly,
+            ..Default::default()
+        };
+        let daily = validate(RRule {
+            freq: Frequency::Daily,
+            interval: 3,
+            ..weekly.clone()
+        });
+        let other_week_start = validate(RRule {
+            week_start: Weekday::Sun,
+            ..weekly.clone()
+        });
+        let weekly = validate(weekly);
+
+        assert!(Arc::ptr_eq(
+            &year_info(1997, &weekly),
+            &year_info(1997, &daily)
+        ));
+        assert!(!Arc::ptr_eq(
+            &year_info(1997, &weekly),
+            &year_info(1998, &weekly)
+        ));
+        assert!(!Arc::ptr_eq(
+            &year_info(1997, &weekly),
+            &year_info(1997, &other_week_start)
+        ));
+    }
+}
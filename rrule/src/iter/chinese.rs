@@ -0,0 +1,632 @@
+//! The Chinese calendar of `RSCALE=CHINESE`, see [RFC 7529](https://datatracker.ietf.org/doc/html/rfc7529).
+//!
+//! The months start on the day of a new moon in China (UTC+8), and a year has a leap
+//! month when 13 months start between two winter solstices: the first month without a
+//! major solar term is then the leap month, and repeats the number of the month before
+//! it. The rules are the ones of "Calendrical Calculations" by Reingold and Dershowitz,
+//! with the new moons and the longitude of the sun computed as in "Astronomical
+//! Algorithms" by Meeus.
+//!
+//! The days are counted from the 1st of January of the year 1, like
+//! [`Datelike::num_days_from_ce`].
+#![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+
+use crate::core::DateTime;
+use crate::validator::YEAR_RANGE;
+use crate::{Frequency, RRule, RScale, Skip};
+use chrono::{Datelike, NaiveDate};
+
+/// The offset of the time in China from universal time, in days.
+const CHINA_OFFSET: f64 = 8.0 / 24.0;
+
+/// The mean time between two new moons, in days.
+const MEAN_SYNODIC_MONTH: f64 = 29.530_588_861;
+
+/// The mean time between two winter solstices, in days.
+const MEAN_TROPICAL_YEAR: f64 = 365.242_189;
+
+/// The Julian day of the start of the day 0.
+const JULIAN_DAY_OFFSET: f64 = 1_721_424.5;
+
+/// The day of the 1st of January 2000.
+const J2000_DAY: f64 = 730_120.0;
+
+/// The date of a day in the Chinese calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChineseDate {
+    /// The number of the month, from 1 to 12.
+    pub month: u8,
+    /// If the month is the leap month that follows the month with the same number.
+    pub leap: bool,
+    /// The day of the month, from 1 to 30.
+    pub day: u8,
+}
+
+impl ChineseDate {
+    /// Returns the date of `date` in the Chinese calendar.
+    pub(crate) fn from_date(date: NaiveDate) -> Self {
+        let date = i64::from(date.num_days_from_ce());
+        let solstice = winter_solstice_on_or_before(date);
+        let next_solstice = winter_solstice_on_or_before(solstice + 370);
+        let month_12 = new_moon_on_or_after(solstice + 1);
+        let next_month_11 = new_moon_before(next_solstice + 1);
+        let month_start = new_moon_before(date + 1);
+        let has_leap_month = months_between(month_12, next_month_11) == 12;
+        let after_leap_month = has_leap_month && has_leap_month_since(month_12, month_start);
+        let month = (months_between(month_12, month_start) - i64::from(after_leap_month) - 1)
+            .rem_euclid(12)
+            + 1;
+        let leap = has_leap_month
+            && has_no_major_solar_term(month_start)
+            && !has_leap_month_since(month_12, new_moon_before(month_start));
+        Self {
+            month: u8::try_from(month).expect("range 1-12 is covered by u8"),
+            leap,
+            day: u8::try_from(date - month_start + 1).expect("range 1-30 is covered by u8"),
+        }
+    }
+}
+
+/// A month of the Chinese calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChineseMonth {
+    /// The number of the month, from 1 to 12.
+    pub number: u8,
+    /// If the month is the leap month that follows the month with the same number.
+    pub leap: bool,
+    /// The first day of the month.
+    pub start: NaiveDate,
+    /// The number of days of the month, 29 or 30.
+    pub days: u8,
+}
+
+impl ChineseMonth {
+    /// Returns the day `day` of the month, counted from the end of the month when it is
+    /// negative, or `None` if the month doesn't have it.
+    fn day(&self, day: i8) -> Option<NaiveDate> {
+        let days = i8::try_from(self.days).expect("range 29-30 is covered by i8");
+        let offset = match day {
+            1.. if day <= days => day - 1,
+            ..=-1 if -day <= days => days + day,
+            _ => return None,
+        };
+        self.start
+            .checked_add_signed(chrono::Duration::days(i64::from(offset)))
+    }
+
+    /// Returns the day after the last day of the month.
+    fn end(&self) -> Option<NaiveDate> {
+        self.start
+            .checked_add_signed(chrono::Duration::days(i64::from(self.days)))
+    }
+}
+
+/// Returns the months of the Chinese year that starts in the Gregorian year `year`, in
+/// order, or `None` if the year is outside of the supported range.
+pub(crate) fn year_months(year: i32) -> Option<Vec<ChineseMonth>> {
+    let mid_year = |year: i32| {
+        NaiveDate::from_ymd_opt(year, 7, 1).map(|date| i64::from(date.num_days_from_ce()))
+    };
+    let start = new_year_in_sui(mid_year(year)?);
+    let end = new_year_in_sui(mid_year(year.checked_add(1)?)?);
+    let mut months = Vec::with_capacity(13);
+    let mut month_start = start;
+    while month_start < end {
+        let next_month_start = new_moon_on_or_after(month_start + 1);
+        let start = NaiveDate::from_num_days_from_ce_opt(i32::try_from(month_start).ok()?)?;
+        let date = ChineseDate::from_date(start);
+        months.push(ChineseMonth {
+            number: date.month,
+            leap: date.leap,
+            start,
+            days: u8::try_from(next_month_start - month_start).ok()?,
+        });
+        month_start = next_month_start;
+    }
+    Some(months)
+}
+
+/// Returns the Gregorian year in which the Chinese year of `date` starts.
+pub(crate) fn year_of(date: NaiveDate) -> i32 {
+    let days = i64::from(date.num_days_from_ce());
+    let new_year = NaiveDate::from_ymd_opt(date.year(), 7, 1)
+        .map(|mid_year| new_year_in_sui(i64::from(mid_year.num_days_from_ce())));
+    match new_year {
+        Some(new_year) if days < new_year => date.year() - 1,
+        _ => date.year(),
+    }
+}
+
+/// Returns the number of mean months between the starts of two months.
+fn months_between(start: i64, end: i64) -> i64 {
+    ((end - start) as f64 / MEAN_SYNODIC_MONTH).round() as i64
+}
+
+/// Returns the first day of the Chinese year of the sui, the time between two winter
+/// solstices, of `date`.
+fn new_year_in_sui(date: i64) -> i64 {
+    let solstice = winter_solstice_on_or_before(date);
+    let next_solstice = winter_solstice_on_or_before(solstice + 370);
+    let month_12 = new_moon_on_or_after(solstice + 1);
+    let month_13 = new_moon_on_or_after(month_12 + 1);
+    let next_month_11 = new_moon_before(next_solstice + 1);
+    // The 11th or the 12th month is a leap month, so the year starts one month later.
+    if months_between(month_12, next_month_11) == 12
+        && (has_no_major_solar_term(month_12) || has_no_major_solar_term(month_13))
+    {
+        new_moon_on_or_after(month_13 + 1)
+    } else {
+        month_13
+    }
+}
+
+/// Returns `true` if one of the months from the month starting on `first_month` up to
+/// the month starting on `month_start` has no major solar term.
+fn has_leap_month_since(first_month: i64, mut month_start: i64) -> bool {
+    while month_start >= first_month {
+        if has_no_major_solar_term(month_start) {
+            return true;
+        }
+        month_start = new_moon_before(month_start);
+    }
+    false
+}
+
+/// Returns `true` if the month starting on `month_start` has no major solar term, which
+/// are the multiples of 30° of the longitude of the sun.
+fn has_no_major_solar_term(month_start: i64) -> bool {
+    major_solar_term(month_start) == major_solar_term(new_moon_on_or_after(month_start + 1))
+}
+
+/// Returns the index of the last major solar term before the start of the day `date`.
+fn major_solar_term(date: i64) -> i64 {
+    (solar_longitude(midnight_in_china(date)) / 30.0).floor() as i64
+}
+
+/// Returns the day of the winter solstice on or before `date`.
+fn winter_solstice_on_or_before(date: i64) -> i64 {
+    let approx = estimate_prior_solar_longitude(270.0, midnight_in_china(date + 1));
+    let mut day = approx.floor() as i64 - 1;
+    while (solar_longitude(midnight_in_china(day + 1)) - 270.0).rem_euclid(360.0) >= 180.0 {
+        day += 1;
+    }
+    day
+}
+
+/// Returns the first day of the month that starts on or after `date`.
+fn new_moon_on_or_after(date: i64) -> i64 {
+    local_day(new_moon_at_or_after(midnight_in_china(date)))
+}
+
+/// Returns the first day of the month that starts before `date`.
+fn new_moon_before(date: i64) -> i64 {
+    local_day(new_moon_moment_before(midnight_in_china(date)))
+}
+
+/// Returns the start of the day `date` in China, as a moment in universal time.
+fn midnight_in_china(date: i64) -> f64 {
+    date as f64 - CHINA_OFFSET
+}
+
+/// Returns the day in China of a moment in universal time.
+fn local_day(moment: f64) -> i64 {
+    (moment + CHINA_OFFSET).floor() as i64
+}
+
+/// Returns the moment before `moment` when the longitude of the sun was about `longitude`.
+fn estimate_prior_solar_longitude(longitude: f64, moment: f64) -> f64 {
+    let rate = MEAN_TROPICAL_YEAR / 360.0;
+    let tau = moment - rate * (solar_longitude(moment) - longitude).rem_euclid(360.0);
+    let delta = (solar_longitude(tau) - longitude + 180.0).rem_euclid(360.0) - 180.0;
+    moment.min(tau - rate * delta)
+}
+
+/// Returns the moment of the first new moon at or after `moment`.
+fn new_moon_at_or_after(moment: f64) -> f64 {
+    let mut n = ((moment - nth_new_moon(0)) / MEAN_SYNODIC_MONTH).floor() as i64;
+    while nth_new_moon(n) < moment {
+        n += 1;
+    }
+    while nth_new_moon(n - 1) >= moment {
+        n -= 1;
+    }
+    nth_new_moon(n)
+}
+
+/// Returns the moment of the last new moon before `moment`.
+fn new_moon_moment_before(moment: f64) -> f64 {
+    let mut n = ((moment - nth_new_moon(0)) / MEAN_SYNODIC_MONTH).floor() as i64;
+    while nth_new_moon(n) >= moment {
+        n -= 1;
+    }
+    while nth_new_moon(n + 1) < moment {
+        n += 1;
+    }
+    nth_new_moon(n)
+}
+
+/// Returns the moment of the `n`th new moon after the one of the 6th of January 2000, in
+/// universal time (chapter 49 of "Astronomical Algorithms").
+#[allow(clippy::many_single_char_names)]
+fn nth_new_moon(n: i64) -> f64 {
+    let k = n as f64;
+    let t = k / 1_236.85;
+    let (t2, t3, t4) = (t * t, t * t * t, t * t * t * t);
+    let jde = 2_451_550.097_66 + 29.530_588_861 * k + 0.000_154_37 * t2 - 0.000_000_150 * t3
+        + 0.000_000_000_73 * t4;
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t2;
+    let m = (2.553_4 + 29.105_356_70 * k - 0.000_001_4 * t2 - 0.000_000_11 * t3).to_radians();
+    let m1 = (201.564_3 + 385.816_935_28 * k + 0.010_758_2 * t2 + 0.000_012_38 * t3
+        - 0.000_000_058 * t4)
+        .to_radians();
+    let f = (160.710_8 + 390.670_502_84 * k - 0.001_611_8 * t2 - 0.000_002_27 * t3
+        + 0.000_000_011 * t4)
+        .to_radians();
+    let omega = (124.774_6 - 1.563_755_88 * k + 0.002_067_2 * t2 + 0.000_002_15 * t3).to_radians();
+    let correction = -0.407_20 * m1.sin()
+        + 0.172_41 * e * m.sin()
+        + 0.016_08 * (2.0 * m1).sin()
+        + 0.010_39 * (2.0 * f).sin()
+        + 0.007_39 * e * (m1 - m).sin()
+        - 0.005_14 * e * (m1 + m).sin()
+        + 0.002_08 * e * e * (2.0 * m).sin()
+        - 0.001_11 * (m1 - 2.0 * f).sin()
+        - 0.000_57 * (m1 + 2.0 * f).sin()
+        + 0.000_56 * e * (2.0 * m1 + m).sin()
+        - 0.000_42 * (3.0 * m1).sin()
+        + 0.000_42 * e * (m + 2.0 * f).sin()
+        + 0.000_38 * e * (m - 2.0 * f).sin()
+        - 0.000_24 * e * (2.0 * m1 - m).sin()
+        - 0.000_17 * omega.sin()
+        - 0.000_07 * (m1 + 2.0 * m).sin()
+        + 0.000_04 * (2.0 * m1 - 2.0 * f).sin()
+        + 0.000_04 * (3.0 * m).sin()
+        + 0.000_03 * (m1 + m - 2.0 * f).sin()
+        + 0.000_03 * (2.0 * m1 + 2.0 * f).sin()
+        - 0.000_03 * (m1 + m + 2.0 * f).sin()
+        + 0.000_03 * (m1 - m + 2.0 * f).sin()
+        - 0.000_02 * (m1 - m - 2.0 * f).sin()
+        - 0.000_02 * (3.0 * m1 + m).sin()
+        + 0.000_02 * (4.0 * m1).sin();
+    let planetary: f64 = [
+        (0.000_325, 299.77 + 0.107_408 * k - 0.009_173 * t2),
+        (0.000_165, 251.88 + 0.016_321 * k),
+        (0.000_164, 251.83 + 26.651_886 * k),
+        (0.000_126, 349.42 + 36.412_478 * k),
+        (0.000_110, 84.66 + 18.206_239 * k),
+        (0.000_062, 141.74 + 53.303_771 * k),
+        (0.000_060, 207.14 + 2.453_732 * k),
+        (0.000_056, 154.84 + 7.306_860 * k),
+        (0.000_047, 34.52 + 27.261_239 * k),
+        (0.000_042, 207.19 + 0.121_824 * k),
+        (0.000_040, 291.34 + 1.844_379 * k),
+        (0.000_037, 161.72 + 24.198_154 * k),
+        (0.000_035, 239.56 + 25.513_099 * k),
+        (0.000_023, 331.55 + 3.592_518 * k),
+    ]
+    .into_iter()
+    .map(|(coefficient, angle)| coefficient * angle.to_radians().sin())
+    .sum();
+    let moment = jde + correction + planetary - JULIAN_DAY_OFFSET;
+    moment - delta_t(moment)
+}
+
+/// Returns the apparent longitude of the sun at a moment in universal time, in degrees
+/// (chapter 25 of "Astronomical Algorithms").
+fn solar_longitude(moment: f64) -> f64 {
+    let t = (moment + delta_t(moment) - J2000_DAY - 0.5) / 36_525.0;
+    let mean_longitude = 280.466_46 + 36_000.769_83 * t + 0.000_303_2 * t * t;
+    let anomaly = (357.529_11 + 35_999.050_29 * t - 0.000_153_7 * t * t).to_radians();
+    let center = (1.914_602 - 0.004_817 * t - 0.000_014 * t * t) * anomaly.sin()
+        + (0.019_993 - 0.000_101 * t) * (2.0 * anomaly).sin()
+        + 0.000_289 * (3.0 * anomaly).sin();
+    let omega = (125.04 - 1_934.136 * t).to_radians();
+    (mean_longitude + center - 0.005_69 - 0.004_78 * omega.sin()).rem_euclid(360.0)
+}
+
+/// Returns the difference between the terrestrial time of the astronomical algorithms and
+/// universal time at `moment`, in days, with the polynomials of Espenak and Meeus.
+fn delta_t(moment: f64) -> f64 {
+    let year = 2000.0 + (moment - J2000_DAY) / 365.2425;
+    let long_term = |year: f64| -20.0 + 32.0 * ((year - 1820.0) / 100.0).powi(2);
+    let seconds = if year < 1900.0 {
+        long_term(year)
+    } else if year < 1920.0 {
+        let t = year - 1900.0;
+        -2.79 + 1.494_119 * t - 0.059_893_9 * t.powi(2) + 0.006_196_6 * t.powi(3)
+            - 0.000_197 * t.powi(4)
+    } else if year < 1941.0 {
+        let t = year - 1920.0;
+        21.20 + 0.844_93 * t - 0.076_100 * t.powi(2) + 0.002_093_6 * t.powi(3)
+    } else if year < 1961.0 {
+        let t = year - 1950.0;
+        29.07 + 0.407 * t - t.powi(2) / 233.0 + t.powi(3) / 2_547.0
+    } else if year < 1986.0 {
+        let t = year - 1975.0;
+        45.45 + 1.067 * t - t.powi(2) / 260.0 - t.powi(3) / 718.0
+    } else if year < 2005.0 {
+        let t = year - 2000.0;
+        63.86 + 0.334_5 * t - 0.060_374 * t.powi(2)
+            + 0.001_727_5 * t.powi(3)
+            + 0.000_651_814 * t.powi(4)
+            + 0.000_023_735_99 * t.powi(5)
+    } else if year < 2050.0 {
+        let t = year - 2000.0;
+        62.92 + 0.322_17 * t + 0.005_589 * t.powi(2)
+    } else if year < 2150.0 {
+        long_term(year) - 0.562_8 * (2150.0 - year)
+    } else {
+        long_term(year)
+    };
+    seconds / 86_400.0
+}
+
+/// The periods of a `YEARLY` or `MONTHLY` rule with `RSCALE=CHINESE`, whose months and
+/// month days are the ones of the Chinese calendar.
+#[derive(Debug, Clone)]
+pub(crate) struct ChinesePeriods {
+    /// Either [`Frequency::Yearly`] or [`Frequency::Monthly`].
+    freq: Frequency,
+    interval: usize,
+    /// The Chinese year of the next period.
+    year: i32,
+    /// The months of `year`, for `MONTHLY` rules.
+    months: Vec<ChineseMonth>,
+    /// The index in `months` of the month of the next period, for `MONTHLY` rules.
+    month: usize,
+    /// The months of the rule and if they are leap months, in order.
+    by_month: Vec<(u8, bool)>,
+    /// The month days of the rule, positive and negative.
+    month_days: Vec<i8>,
+    skip: Skip,
+}
+
+impl ChinesePeriods {
+    /// Returns the periods of the rule from `dt_start`, or `None` if the rule isn't in the
+    /// Chinese calendar. The validation makes sure that the rule only has `BYMONTH`,
+    /// `BYMONTHDAY` and the times.
+    pub(crate) fn new(rrule: &RRule, dt_start: &DateTime) -> Option<Self> {
+        if rrule.rscale != Some(RScale::Chinese) || rrule.interval == 0 {
+            return None;
+        }
+        let date = dt_start.date_naive();
+        let year = year_of(date);
+        let (months, month) = match rrule.freq {
+            Frequency::Yearly => (vec![], 0),
+            Frequency::Monthly => {
+                let months = year_months(year)?;
+                let month = months.iter().rposition(|month| month.start <= date)?;
+                (months, month)
+            }
+            _ => return None,
+        };
+        let mut by_month: Vec<_> = rrule
+            .by_month
+            .iter()
+            .map(|month| (*month, false))
+            .chain(rrule.by_leap_month.iter().map(|month| (*month, true)))
+            .collect();
+        by_month.sort_unstable();
+        Some(Self {
+            freq: rrule.freq,
+            interval: usize::from(rrule.interval),
+            year,
+            months,
+            month,
+            by_month,
+            month_days: rrule
+                .by_month_day
+                .iter()
+                .chain(&rrule.by_n_month_day)
+                .copied()
+                .collect(),
+            skip: rrule.skip,
+        })
+    }
+
+    /// Replaces the content of `days` with the days of the next period, in order.
+    ///
+    /// Returns `false` when the next period is outside of the supported range of years,
+    /// where the iteration ends.
+    pub(crate) fn next_days(&mut self, days: &mut Vec<NaiveDate>) -> bool {
+        days.clear();
+        if !YEAR_RANGE.contains(&self.year) {
+            return false;
+        }
+        if self.freq == Frequency::Yearly {
+            let Some(months) = year_months(self.year) else {
+                return false;
+            };
+            let year = self.year;
+            self.year = year.saturating_add(i32::try_from(self.interval).unwrap_or(i32::MAX));
+            if self.by_month.is_empty() {
+                for month in &months {
+                    self.extend_month_days(month, days);
+                }
+            }
+            for (number, leap) in &self.by_month {
+                let position = |leap: bool| {
+                    months
+                        .iter()
+                        .position(|m| m.number == *number && m.leap == leap)
+                };
+                // A leap month that the year doesn't have is replaced by the month before or
+                // after it, as `SKIP` defines.
+                let index = match (position(*leap), self.skip) {
+                    (Some(index), _) => index,
+                    (None, Skip::Omit) => continue,
+                    (None, Skip::Backward) => match position(false) {
+                        Some(index) => index,
+                        None => continue,
+                    },
+                    (None, Skip::Forward) => match position(false) {
+                        Some(index) => index + 1,
+                        None => continue,
+                    },
+                };
+                let month = match months.get(index) {
+                    Some(month) => Some(*month),
+                    None => year
+                        .checked_add(1)
+                        .and_then(year_months)
+                        .and_then(|months| months.first().copied()),
+                };
+                if let Some(month) = month {
+                    self.extend_month_days(&month, days);
+                }
+            }
+        } else {
+            let month = self.months[self.month];
+            self.month += self.interval;
+            while self.month >= self.months.len() {
+                self.month -= self.months.len();
+                self.year = self.year.saturating_add(1);
+                match year_months(self.year) {
+                    Some(months) if YEAR_RANGE.contains(&self.year) => self.months = months,
+                    // The iteration ends with the next period.
+                    _ => {
+                        self.year = i32::MAX;
+                        break;
+                    }
+                }
+            }
+            if self.by_month.is_empty() || self.by_month.contains(&(month.number, month.leap)) {
+                self.extend_month_days(&month, days);
+            }
+        }
+        days.sort_unstable();
+        days.dedup();
+        true
+    }
+
+    /// Adds the month days of the rule in `month` to `days`, with the days that replace
+    /// the month days that the month doesn't have, as `SKIP` defines.
+    fn extend_month_days(&self, month: &ChineseMonth, days: &mut Vec<NaiveDate>) {
+        days.extend(self.month_days.iter().filter_map(|day| {
+            month.day(*day).or_else(|| match self.skip {
+                Skip::Omit => None,
+                Skip::Backward => month.day(-1),
+                Skip::Forward => month.end(),
+            })
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn finds_the_new_years() {
+        let new_years = [
+            date(2000, 2, 5),
+            date(2001, 1, 24),
+            date(2002, 2, 12),
+            date(2003, 2, 1),
+            date(2004, 1, 22),
+            date(2005, 2, 9),
+            date(2006, 1, 29),
+            date(2007, 2, 18),
+            date(2008, 2, 7),
+            date(2009, 1, 26),
+            date(2010, 2, 14),
+            date(2011, 2, 3),
+            date(2012, 1, 23),
+            date(2013, 2, 10),
+            date(2014, 1, 31),
+            date(2015, 2, 19),
+            date(2016, 2, 8),
+            date(2017, 1, 28),
+            date(2018, 2, 16),
+            date(2019, 2, 5),
+            date(2020, 1, 25),
+            date(2021, 2, 12),
+            date(2022, 2, 1),
+            date(2023, 1, 22),
+            date(2024, 2, 10),
+            date(2025, 1, 29),
+            date(2026, 2, 17),
+            date(2027, 2, 6),
+            date(2028, 1, 26),
+            date(2029, 2, 13),
+            date(2030, 2, 3),
+        ];
+        for new_year in new_years {
+            let months = year_months(new_year.year()).unwrap();
+            assert_eq!(months[0].start, new_year);
+            assert_eq!((months[0].number, months[0].leap), (1, false));
+            assert_eq!(year_of(new_year), new_year.year());
+            assert_eq!(year_of(new_year.pred_opt().unwrap()), new_year.year() - 1);
+            assert_eq!(
+                ChineseDate::from_date(new_year),
+                ChineseDate {
+                    month: 1,
+                    leap: false,
+                    day: 1
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn finds_the_leap_months() {
+        let leap_months = [
+            (2001, Some(4)),
+            (2002, None),
+            (2004, Some(2)),
+            (2006, Some(7)),
+            (2009, Some(5)),
+            (2012, Some(4)),
+            (2014, Some(9)),
+            (2017, Some(6)),
+            (2020, Some(4)),
+            (2021, None),
+            (2023, Some(2)),
+            (2025, Some(6)),
+            (2028, Some(5)),
+            // The leap month is after the 11th month, after the winter solstice.
+            (2033, Some(11)),
+        ];
+        for (year, leap_month) in leap_months {
+            let months = year_months(year).unwrap();
+            let leap = months.iter().position(|month| month.leap);
+            assert_eq!(leap.map(|index| months[index].number), leap_month, "{year}");
+            assert_eq!(months.len(), if leap_month.is_some() { 13 } else { 12 });
+            // The months follow each other, and the leap month repeats the number of the
+            // month before it.
+            for (index, pair) in months.windows(2).enumerate() {
+                assert_eq!(pair[0].end(), Some(pair[1].start), "{year}");
+                let number = if Some(index + 1) == leap { 0 } else { 1 };
+                assert_eq!(pair[1].number, pair[0].number + number, "{year}");
+            }
+        }
+    }
+
+    #[test]
+    fn converts_dates() {
+        let tests = [
+            // Mid-Autumn Festival.
+            (date(2023, 9, 29), (8, false, 15)),
+            (date(2024, 9, 17), (8, false, 15)),
+            (date(2025, 10, 6), (8, false, 15)),
+            // Dragon Boat Festival.
+            (date(2023, 6, 22), (5, false, 5)),
+            (date(2024, 6, 10), (5, false, 5)),
+            // In the leap 6th month of 2025.
+            (date(2025, 7, 25), (6, true, 1)),
+            (date(2025, 8, 22), (6, true, 29)),
+            (date(2025, 8, 23), (7, false, 1)),
+        ];
+        for (date, (month, leap, day)) in tests {
+            assert_eq!(
+                ChineseDate::from_date(date),
+                ChineseDate { month, leap, day },
+                "{date}"
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use chrono::{Datelike, TimeZone, Timelike, Utc, Weekday};
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc, Weekday};
 
 use crate::{core::DateTime, Frequency, RRule, RRuleError};
 
@@ -57,6 +57,68 @@ impl DateTimeIter {
         }
     }
 
+    /// Moves the datetime forward by `periods` increments of the frequency at once, like
+    /// `periods` calls of [`Self::increment`] when none of the periods in between is left
+    /// out because of `BYHOUR`, `BYMINUTE` or `BYSECOND`.
+    ///
+    /// For `YEARLY` rules starting on the 29th of February, the day can differ from the
+    /// one of the increments, which doesn't change the days of the year.
+    pub fn advance_periods(&mut self, rrule: &RRule, periods: u32) -> Result<(), RRuleError> {
+        if periods == 0 {
+            return Ok(());
+        }
+        let interval = u32::from(rrule.interval);
+        let steps = i64::from(checked_mul_u32(
+            periods,
+            interval,
+            Some("please decrease `INTERVAL`"),
+        )?);
+        match rrule.freq {
+            Frequency::Yearly => {
+                self.year = i64::from(self.year)
+                    .checked_add(steps)
+                    .and_then(|year| i32::try_from(year).ok())
+                    .ok_or_else(|| RRuleError::new_iter_err("Encountered a too high year."))?;
+                checks::check_year_range(self.year)?;
+                self.fix_day()
+            }
+            Frequency::Monthly => {
+                let month = i64::from(self.year) * 12 + i64::from(self.month - 1) + steps;
+                self.year = i32::try_from(month.div_euclid(12))
+                    .map_err(|_| RRuleError::new_iter_err("Encountered a too high year."))?;
+                checks::check_year_range(self.year)?;
+                self.month =
+                    u32::try_from(month.rem_euclid(12) + 1).expect("range 1-12 is covered by u32");
+                Ok(())
+            }
+            Frequency::Weekly => {
+                // The first increment moves to the start of a week, the next ones add
+                // whole weeks.
+                self.increment_weekly(rrule.interval, rrule.week_start)?;
+                self.add_seconds((steps - i64::from(interval)) * 7 * i64::from(SECONDS_IN_A_DAY))
+            }
+            Frequency::Daily => self.add_seconds(steps * i64::from(SECONDS_IN_A_DAY)),
+            Frequency::Hourly => self.add_seconds(steps * 60 * 60),
+            Frequency::Minutely => self.add_seconds(steps * 60),
+            Frequency::Secondly => self.add_seconds(steps),
+        }
+    }
+
+    fn add_seconds(&mut self, seconds: i64) -> Result<(), RRuleError> {
+        let datetime = NaiveDate::from_ymd_opt(self.year, self.month, self.day)
+            .and_then(|date| date.and_hms_opt(self.hour, self.minute, self.second))
+            .and_then(|datetime| datetime.checked_add_signed(Duration::seconds(seconds)))
+            .ok_or_else(|| RRuleError::new_iter_err("Encountered a date out of range."))?;
+        checks::check_year_range(datetime.year())?;
+        self.year = datetime.year();
+        self.month = datetime.month();
+        self.day = datetime.day();
+        self.hour = datetime.hour();
+        self.minute = datetime.minute();
+        self.second = datetime.second();
+        Ok(())
+    }
+
     fn increment_yearly(&mut self, interval: u16) -> Result<(), RRuleError> {
         self.year += i32::from(interval);
         checks::check_year_range(self.year)?;
@@ -76,7 +138,10 @@ impl DateTimeIter {
                 self.month = 12;
                 year_div -= 1;
             }
-            self.increment_yearly(year_div)?;
+            // The day doesn't matter for the month, so it's left as is instead of moving
+            // to the next month when the new month is shorter.
+            self.year += i32::from(year_div);
+            checks::check_year_range(self.year)?;
         }
         Ok(())
     }
@@ -180,7 +245,7 @@ impl DateTimeIter {
                 Some("please decrease `INTERVAL`"),
             )?;
             let new_hours = u8::try_from(self.hour % 24).expect("range 0-23 is covered by u8");
-            if by_hour.is_empty() || by_hour.iter().any(|bh| *bh == new_hours) {
+            if by_hour.is_empty() || by_hour.contains(&new_hours) {
                 break;
             }
             if prev_hours.contains(&new_hours) {
@@ -543,7 +608,7 @@ mod tests {
             let rrule = RRule {
                 interval,
                 freq: Frequency::Hourly,
-                by_hour,
+                by_hour: by_hour.into(),
                 ..Default::default()
             }
             .validate(UTC.with_ymd_and_hms(1997, 1, 1, 1, 1, 1).unwrap())
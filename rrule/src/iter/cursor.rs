@@ -0,0 +1,157 @@
+use crate::core::DateTime;
+use crate::{ParseError, RRuleError};
+use chrono::{TimeZone, Utc};
+#[cfg(feature = "serde")]
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The position of an [`crate::RRuleSetIter`], used to resume the iteration later.
+///
+/// Get one from [`crate::RRuleSetIter::cursor`] and continue after the last returned
+/// recurrence with [`crate::RRuleSet::iter_from_cursor`]. A cursor can be turned into
+/// a token with [`ToString`] and parsed back with [`FromStr`], e.g. for pagination.
+///
+/// The token is the timestamp of the last recurrence, followed by `.` and the number of
+/// recurrences returned at that instant when there is more than one, which can happen
+/// with [`crate::DuplicatePolicy::Keep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
+pub struct IterCursor {
+    /// Timestamp of the last recurrence returned by the iterator.
+    last: i64,
+    /// The number of recurrences at the instant of `last` returned by the iterator.
+    emitted_at_last: u32,
+}
+
+impl IterCursor {
+    pub(crate) fn new(last: &DateTime, emitted_at_last: u32) -> Self {
+        Self {
+            last: last.timestamp(),
+            emitted_at_last,
+        }
+    }
+
+    /// Timestamp of the last recurrence returned by the iterator.
+    pub(crate) fn last(&self) -> i64 {
+        self.last
+    }
+
+    /// The number of recurrences at the instant of the last one returned by the iterator.
+    pub(crate) fn emitted_at_last(&self) -> u32 {
+        self.emitted_at_last
+    }
+}
+
+/// The progress of an [`crate::RRuleSetIter`] that can be persisted and used to
+/// resume the iteration, possibly on another host.
+///
+/// Get one from [`crate::RRuleSetIter::state`] and continue after the last returned
+/// recurrence with [`crate::RRuleSet::iter_from_state`]. With the `serde` feature
+/// this is serialized as
+/// `{"last": "<RFC 3339 datetime>", "emitted": <number>, "emitted_at_last": <number>}`,
+/// and `emitted_at_last` defaults to `1` when it is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_with::serde::Serialize, serde_with::serde::Deserialize),
+    serde(crate = "serde_with::serde")
+)]
+pub struct IterState {
+    /// The last recurrence returned by the iterator.
+    pub last: chrono::DateTime<Utc>,
+    /// The number of recurrences returned by the iterator so far,
+    /// including the ones returned before it was resumed.
+    pub emitted: u64,
+    /// The number of recurrences at the instant of `last` returned by the iterator, which
+    /// is more than `1` when there are duplicates, see [`crate::DuplicatePolicy::Keep`].
+    #[cfg_attr(feature = "serde", serde(default = "default_emitted_at_last"))]
+    pub emitted_at_last: u32,
+}
+
+#[cfg(feature = "serde")]
+fn default_emitted_at_last() -> u32 {
+    1
+}
+
+impl From<IterState> for IterCursor {
+    fn from(state: IterState) -> Self {
+        Self {
+            last: state.last.timestamp(),
+            emitted_at_last: state.emitted_at_last,
+        }
+    }
+}
+
+impl IterState {
+    pub(crate) fn new(last: &DateTime, emitted: u64, emitted_at_last: u32) -> Self {
+        Self {
+            last: Utc.from_utc_datetime(&last.naive_utc()),
+            emitted,
+            emitted_at_last,
+        }
+    }
+}
+
+impl Display for IterCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.last)?;
+        if self.emitted_at_last != 1 {
+            write!(f, ".{}", self.emitted_at_last)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for IterCursor {
+    type Err = RRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::InvalidCursor(s.into());
+        let (last, emitted_at_last) = match s.split_once('.') {
+            Some((last, emitted_at_last)) => (
+                last,
+                emitted_at_last
+                    .parse()
+                    .ok()
+                    .filter(|emitted_at_last| *emitted_at_last > 0)
+                    .ok_or_else(invalid)?,
+            ),
+            None => (s, 1),
+        };
+        let last = last.parse().map_err(|_| invalid())?;
+        Ok(Self {
+            last,
+            emitted_at_last,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_token_roundtrip() {
+        let cursor = IterCursor {
+            last: 1_609_491_600,
+            emitted_at_last: 1,
+        };
+        assert_eq!(cursor.to_string(), "1609491600");
+        assert_eq!("1609491600".parse::<IterCursor>(), Ok(cursor));
+
+        let cursor = IterCursor {
+            last: 1_609_491_600,
+            emitted_at_last: 2,
+        };
+        assert_eq!(cursor.to_string(), "1609491600.2");
+        assert_eq!("1609491600.2".parse::<IterCursor>(), Ok(cursor));
+
+        for token in ["page2", "1609491600.0", "1609491600.", ".2"] {
+            assert_eq!(
+                token.parse::<IterCursor>(),
+                Err(ParseError::InvalidCursor(token.into()).into())
+            );
+        }
+    }
+}
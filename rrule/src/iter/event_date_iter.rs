@@ -0,0 +1,37 @@
+use super::RRuleSetIter;
+use crate::core::{DateTime, EventDate};
+
+/// Iterator over the [`EventDate`]s of an [`crate::RRuleSet`], created with
+/// [`crate::RRuleSet::iter_event_dates`].
+///
+/// The recurrences of an all-day set are returned as [`EventDate::Date`], and those of
+/// other sets as [`EventDate::DateTime`].
+#[derive(Debug, Clone)]
+pub struct EventDateIter<'a> {
+    iter: RRuleSetIter<'a>,
+    all_day: bool,
+}
+
+impl<'a> EventDateIter<'a> {
+    pub(crate) fn new(iter: RRuleSetIter<'a>, all_day: bool) -> Self {
+        Self { iter, all_day }
+    }
+}
+
+impl<'a> Iterator for EventDateIter<'a> {
+    type Item = EventDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let date = self.iter.next()?;
+        Some(to_event_date(date, self.all_day))
+    }
+}
+
+/// Returns the [`EventDate`] of a recurrence, which is its day if it is `all_day`.
+pub(crate) fn to_event_date(date: DateTime, all_day: bool) -> EventDate {
+    if all_day {
+        EventDate::Date(date.date_naive())
+    } else {
+        EventDate::DateTime(date)
+    }
+}
@@ -0,0 +1,253 @@
+//! A trace of the decisions about the candidate dates of a rule, see [`RRule::explain`].
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use chrono::{Datelike, NaiveDate, TimeZone};
+
+use super::counter_date::DateTimeIter;
+use super::utils::{add_time_to_date, from_ordinal};
+use super::{build_pos_list, filters, IterInfo, RRuleIter, MAX_ITER_LOOP};
+use crate::core::{get_hour, get_minute, get_second, DateTime};
+use crate::{DstFoldPolicy, DstGapPolicy, Frequency, RRule, RScale};
+
+/// A part of a rule that accepted or rejected a candidate of [`RRule::explain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RulePart {
+    /// The `BYMONTH` rule part.
+    ByMonth,
+    /// The `BYWEEKNO` rule part.
+    ByWeekNo,
+    /// The `BYDAY` rule part, with or without the positions of the weekdays.
+    ByDay,
+    /// The `BYEASTER` rule part.
+    ByEaster,
+    /// The `BYMONTHDAY` rule part.
+    ByMonthDay,
+    /// The `BYYEARDAY` rule part.
+    ByYearDay,
+    /// The `SKIP` rule part, which accepts a day in place of a month day that doesn't
+    /// exist.
+    Skip,
+    /// The `BYSETPOS` rule part.
+    BySetPos,
+    /// The start of the rule, which rejects the candidates before it.
+    DtStart,
+    /// The `UNTIL` rule part.
+    Until,
+    /// The `COUNT` rule part.
+    Count,
+}
+
+impl Display for RulePart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::ByMonth => "BYMONTH",
+            Self::ByWeekNo => "BYWEEKNO",
+            Self::ByDay => "BYDAY",
+            Self::ByEaster => "BYEASTER",
+            Self::ByMonthDay => "BYMONTHDAY",
+            Self::ByYearDay => "BYYEARDAY",
+            Self::Skip => "SKIP",
+            Self::BySetPos => "BYSETPOS",
+            Self::DtStart => "DTSTART",
+            Self::Until => "UNTIL",
+            Self::Count => "COUNT",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The decision about a candidate date of a rule, see [`RRule::explain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    /// The candidate date.
+    dt: DateTime,
+    /// If the candidate is a recurrence of the rule.
+    accepted: bool,
+    /// The rule part that decided about the candidate, or `None` if it passed all of them.
+    rule_part: Option<RulePart>,
+}
+
+impl Explanation {
+    /// Returns the candidate date.
+    #[must_use]
+    pub fn get_dt(&self) -> &DateTime {
+        &self.dt
+    }
+
+    /// Returns `true` if the candidate is a recurrence of the rule.
+    #[must_use]
+    pub fn is_accepted(&self) -> bool {
+        self.accepted
+    }
+
+    /// Returns the rule part that rejected the candidate, or that accepted it although
+    /// it would be rejected otherwise, like `BYSETPOS` or `SKIP`. Returns `None` for a
+    /// candidate that passed all the rule parts.
+    #[must_use]
+    pub fn get_rule_part(&self) -> Option<RulePart> {
+        self.rule_part
+    }
+}
+
+impl Display for Explanation {
+    /// Formats the decision, like `2021-01-04 09:00:00 UTC rejected by BYDAY`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let decision = if self.accepted {
+            "accepted"
+        } else {
+            "rejected"
+        };
+        match self.rule_part {
+            Some(rule_part) => write!(f, "{} {} by {}", self.dt, decision, rule_part),
+            None => write!(f, "{} {}", self.dt, decision),
+        }
+    }
+}
+
+/// Returns the decisions about the candidates of the rule within `range`, in the order of
+/// the candidates.
+///
+/// The candidates are the days of the periods of the frequency, at the times of the rule,
+/// like the generation of the recurrences in [`super::RRuleIter`] goes through them. The
+/// trace ends at the first candidate rejected by `UNTIL` or `COUNT`, or after
+/// [`MAX_ITER_LOOP`] periods. The candidates of a rule of the Chinese calendar aren't
+/// traced, only its recurrences are.
+pub(crate) fn explain(
+    rrule: &RRule,
+    dt_start: &DateTime,
+    range: &Range<DateTime>,
+) -> Vec<Explanation> {
+    let mut explanations = vec![];
+    if rrule.interval == 0 {
+        return explanations;
+    }
+    if rrule.rscale == Some(RScale::Chinese) {
+        return RRuleIter::new(rrule, dt_start, true)
+            .skip_while(|dt| *dt < range.start)
+            .take_while(|dt| *dt < range.end)
+            .map(|dt| Explanation {
+                dt,
+                accepted: true,
+                rule_part: None,
+            })
+            .collect();
+    }
+
+    let tz = dt_start.timezone();
+    let end = range.end.with_timezone(&tz).date_naive();
+    let mut ii = IterInfo::new(rrule, dt_start);
+    let mut counter_date: DateTimeIter = dt_start.into();
+    let mut timeset = ii.get_timeset(
+        get_hour(dt_start),
+        get_minute(dt_start),
+        get_second(dt_start),
+    );
+    let mut count = rrule.count;
+
+    for _ in 0..MAX_ITER_LOOP {
+        let is_past_end =
+            NaiveDate::from_ymd_opt(counter_date.year, counter_date.month, counter_date.day)
+                .map_or(true, |date| date > end);
+        if is_past_end {
+            break;
+        }
+
+        let (year, month, day) = (counter_date.year, counter_date.month, counter_date.day);
+        let dayset = ii.get_dayset(rrule.freq, year, month, day);
+        // The substitutes of `SKIP` can be outside of the period.
+        let mut days = ii.candidate_days(rrule.freq, year, month, day);
+        days.extend(&dayset);
+        days.sort_unstable();
+        days.dedup();
+
+        let mut candidates = vec![];
+        for day in days {
+            let filtered_by = filters::filtered_by(&ii, day);
+            let (accepted, rule_part) = if dayset.contains(&day) {
+                (true, filtered_by.map(|_| RulePart::Skip))
+            } else {
+                (false, filtered_by)
+            };
+            let day = i64::try_from(day).expect("the days of a year fit within an i64");
+            let date = from_ordinal(ii.year_ordinal() + day);
+            #[allow(deprecated)]
+            let date = tz.ymd(date.year(), date.month(), date.day());
+            for time in &timeset {
+                for dt in add_time_to_date(
+                    date,
+                    *time,
+                    DstGapPolicy::default(),
+                    DstFoldPolicy::default(),
+                ) {
+                    candidates.push(Explanation {
+                        dt,
+                        accepted,
+                        rule_part,
+                    });
+                }
+            }
+        }
+        candidates.sort_by_key(|candidate| candidate.dt);
+        candidates.dedup_by_key(|candidate| candidate.dt);
+
+        if !rrule.by_set_pos.is_empty() {
+            let mut pos_list = vec![];
+            build_pos_list(
+                &rrule.by_set_pos,
+                &dayset,
+                &timeset,
+                ii.year_ordinal(),
+                tz,
+                DstGapPolicy::default(),
+                DstFoldPolicy::default(),
+                &mut pos_list,
+            );
+            for candidate in candidates.iter_mut().filter(|candidate| candidate.accepted) {
+                candidate.accepted = pos_list.contains(&candidate.dt);
+                candidate.rule_part = Some(RulePart::BySetPos);
+            }
+        }
+
+        for mut candidate in candidates {
+            let mut is_last = false;
+            if candidate.accepted {
+                if candidate.dt < *dt_start {
+                    candidate.accepted = false;
+                    candidate.rule_part = Some(RulePart::DtStart);
+                } else if matches!(rrule.until, Some(until) if candidate.dt > until) {
+                    candidate.accepted = false;
+                    candidate.rule_part = Some(RulePart::Until);
+                    is_last = true;
+                } else if count == Some(0) {
+                    candidate.accepted = false;
+                    candidate.rule_part = Some(RulePart::Count);
+                    is_last = true;
+                } else if let Some(count) = &mut count {
+                    *count -= 1;
+                }
+            }
+            if range.contains(&candidate.dt) {
+                explanations.push(candidate);
+            }
+            if is_last {
+                return explanations;
+            }
+        }
+
+        if counter_date.increment(rrule, dayset.is_empty()).is_err() {
+            break;
+        }
+        if matches!(
+            rrule.freq,
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly
+        ) {
+            let hour = u8::try_from(counter_date.hour).expect("range 0-23 is covered by u8");
+            let minute = u8::try_from(counter_date.minute).expect("range 0-59 is covered by u8");
+            let second = u8::try_from(counter_date.second).expect("range 0-59 is covered by u8");
+            timeset = ii.get_timeset_unchecked(hour, minute, second);
+        }
+        ii.rebuild(&counter_date);
+    }
+    explanations
+}
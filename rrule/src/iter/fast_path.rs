@@ -0,0 +1,512 @@
+//! Arithmetic shortcuts for rules whose recurrences are evenly spaced in local time.
+use super::utils::add_time_to_date;
+use crate::core::{get_hour, get_minute, get_second, DateTime};
+use crate::validator::YEAR_RANGE;
+use crate::{DstFoldPolicy, DstGapPolicy, Frequency, NWeekday, RRule, RScale, Skip};
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Weekday};
+
+/// Maximum number of days that can be added to a start date.
+/// Covers the full range of years allowed by the validator.
+const MAX_DAYS: i64 = 366 * 20_001;
+
+/// Returns the number of days between two recurrences if every recurrence of
+/// the rule is the start date plus a multiple of that number of days,
+/// at the same local time.
+///
+/// This is the case for `DAILY` rules and `WEEKLY` rules on the weekday of the
+/// start date without any other BYxxx rule parts.
+pub(crate) fn fixed_day_step(rrule: &RRule) -> Option<i64> {
+    let dt_start = rrule.dt_start();
+    let has_only_start_time = rrule.by_hour.as_slice() == [get_hour(&dt_start)]
+        && rrule.by_minute.as_slice() == [get_minute(&dt_start)]
+        && rrule.by_second.as_slice() == [get_second(&dt_start)];
+    if !has_only_start_time
+        || !rrule.by_set_pos.is_empty()
+        || !rrule.by_month.is_empty()
+        || !rrule.by_leap_month.is_empty()
+        || !rrule.by_month_day.is_empty()
+        || !rrule.by_n_month_day.is_empty()
+        || !rrule.by_year_day.is_empty()
+        || !rrule.by_week_no.is_empty()
+        || rrule.by_easter.is_some()
+    {
+        return None;
+    }
+
+    let interval = i64::from(rrule.interval);
+    match rrule.freq {
+        Frequency::Daily if rrule.by_weekday.is_empty() => Some(interval),
+        Frequency::Weekly
+            if rrule.by_weekday.as_slice() == [NWeekday::Every(dt_start.weekday())] =>
+        {
+            Some(interval * 7)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the local time between two recurrences if every recurrence of the rule is
+/// the start date plus a multiple of that time, in local time.
+///
+/// This is the case for the rules with a [`fixed_day_step`], and `HOURLY` rules at the
+/// minute and second of the start date without any other BYxxx rule parts.
+pub(crate) fn fixed_step(rrule: &RRule) -> Option<Duration> {
+    if let Some(step) = fixed_day_step(rrule) {
+        return Some(Duration::days(step));
+    }
+
+    let dt_start = rrule.dt_start();
+    let is_plain_hourly = rrule.freq == Frequency::Hourly
+        && rrule.by_hour.is_empty()
+        && rrule.by_minute.as_slice() == [get_minute(&dt_start)]
+        && rrule.by_second.as_slice() == [get_second(&dt_start)]
+        && rrule.by_weekday.is_empty()
+        && rrule.by_set_pos.is_empty()
+        && rrule.by_month.is_empty()
+        && rrule.by_leap_month.is_empty()
+        && rrule.by_month_day.is_empty()
+        && rrule.by_n_month_day.is_empty()
+        && rrule.by_year_day.is_empty()
+        && rrule.by_week_no.is_empty()
+        && rrule.by_easter.is_none();
+    is_plain_hourly.then(|| Duration::hours(i64::from(rrule.interval)))
+}
+
+/// Returns the `n`th (zero based) date of a rule with a fixed day step,
+/// without taking `COUNT` or `UNTIL` into account.
+pub(crate) fn nth_date(dt_start: &DateTime, step: i64, n: usize) -> Option<DateTime> {
+    nth_step_date(dt_start, Duration::days(step), n)
+}
+
+/// Returns the `n`th (zero based) date of a rule with a [`fixed_step`],
+/// without taking `COUNT` or `UNTIL` into account.
+fn nth_step_date(dt_start: &DateTime, step: Duration, n: usize) -> Option<DateTime> {
+    let seconds = i64::try_from(n)
+        .ok()?
+        .checked_mul(step.num_seconds())
+        .filter(|seconds| *seconds <= MAX_DAYS * 24 * 60 * 60)?;
+    let local = dt_start
+        .naive_local()
+        .checked_add_signed(Duration::seconds(seconds))?;
+    // Build the datetime the same way the iterator does, so that local times
+    // that don't exist because of daylight saving time are handled equally.
+    #[allow(deprecated)]
+    let date = dt_start
+        .timezone()
+        .ymd_opt(local.year(), local.month(), local.day())
+        .single()?;
+    add_time_to_date(
+        date,
+        local.time(),
+        DstGapPolicy::default(),
+        DstFoldPolicy::default(),
+    )
+    .next()
+}
+
+/// Returns the index and the first date of a rule with a [`fixed_step`] that is after
+/// `dt`, or equal to it when `inclusive` is `true`, without taking `COUNT` or `UNTIL`
+/// into account.
+///
+/// The index is estimated from the local time between the start date and `dt`, so only
+/// the dates around `dt` are computed.
+pub(crate) fn first_after(
+    dt_start: &DateTime,
+    step: Duration,
+    dt: &DateTime,
+    inclusive: bool,
+) -> Option<(usize, DateTime)> {
+    let step_seconds = step.num_seconds();
+    if step_seconds <= 0 {
+        return None;
+    }
+    // The dates more than 2 days before `dt` in local time are before it, whatever the
+    // changes of the offset of the timezone in between.
+    let elapsed = dt.with_timezone(&dt_start.timezone()).naive_local()
+        - dt_start.naive_local()
+        - Duration::days(2);
+    let first = usize::try_from(elapsed.num_seconds() / step_seconds).unwrap_or(0);
+    (first..)
+        .map_while(|n| nth_step_date(dt_start, step, n).map(|date| (n, date)))
+        .find(|(_, date)| if inclusive { date >= dt } else { date > dt })
+}
+
+/// Returns the index of the last date of a rule with a fixed day step that
+/// is not after `until`, or `None` if all the dates are after `until`.
+pub(crate) fn last_index_until(dt_start: &DateTime, step: i64, until: &DateTime) -> Option<usize> {
+    let until_date = until.with_timezone(&dt_start.timezone()).date_naive();
+    let days = until_date
+        .signed_duration_since(dt_start.date_naive())
+        .num_days();
+    let n = usize::try_from(days / step).ok()?;
+    // The date on the last day might still be later than `until` on that day.
+    match nth_date(dt_start, step, n) {
+        Some(date) if date <= *until => Some(n),
+        _ => n.checked_sub(1),
+    }
+}
+
+/// Returns `true` if iterating over the rule from `dt_start` is known to
+/// yield exactly `COUNT` dates.
+pub(crate) fn yields_exact_count(rrule: &RRule, dt_start: &DateTime) -> bool {
+    let (Some(count), None) = (rrule.count, rrule.until) else {
+        return false;
+    };
+    // The BYxxx rule parts are filled in based on the start date of the rule.
+    if rrule.dt_start.as_ref() != Some(dt_start) {
+        return false;
+    }
+    let Some(step) = fixed_day_step(rrule) else {
+        return false;
+    };
+    usize::try_from(count)
+        .ok()
+        .and_then(|count| count.checked_sub(1))
+        .and_then(|n| nth_date(dt_start, step, n))
+        .is_some_and(|last| YEAR_RANGE.contains(&last.year()))
+}
+
+/// The days of the periods of a `DAILY`, `WEEKLY` or `MONTHLY` rule whose recurrences
+/// are on fixed days of their period, computed with date arithmetic instead of the
+/// tables of the years that [`super::IterInfo`] builds.
+#[derive(Debug, Clone)]
+pub(crate) struct SimplePeriods {
+    /// The first day of the first period.
+    start: NaiveDate,
+    /// The index of the next period.
+    period: i64,
+    kind: SimplePeriodKind,
+}
+
+#[derive(Debug, Clone)]
+enum SimplePeriodKind {
+    /// Every `interval` days.
+    Daily { interval: i64 },
+    /// Every `interval` weeks, on the weekdays.
+    Weekly {
+        interval: i64,
+        weekdays: Vec<Weekday>,
+    },
+    /// Every `interval` months, on the month days, which are sorted and positive.
+    Monthly { interval: i64, month_days: Vec<u32> },
+}
+
+impl SimplePeriods {
+    /// Returns the periods of the rule from `dt_start`, or `None` if the rule has BYxxx
+    /// rule parts other than `BYDAY` with weekdays for a `WEEKLY` rule, `BYMONTHDAY` with
+    /// positive month days for a `MONTHLY` rule and the times, or if it is a rule of the
+    /// Chinese calendar.
+    pub(crate) fn new(rrule: &RRule, dt_start: &DateTime) -> Option<Self> {
+        // The BYxxx rule parts are filled in based on the start date of the rule.
+        if rrule.dt_start.as_ref() != Some(dt_start)
+            || rrule.interval == 0
+            || rrule.rscale == Some(RScale::Chinese)
+            || rrule.skip != Skip::Omit
+            || rrule.leap_day != Skip::Omit
+            || !rrule.by_set_pos.is_empty()
+            || !rrule.by_month.is_empty()
+            || !rrule.by_leap_month.is_empty()
+            || !rrule.by_n_month_day.is_empty()
+            || !rrule.by_year_day.is_empty()
+            || !rrule.by_week_no.is_empty()
+            || rrule.by_easter.is_some()
+        {
+            return None;
+        }
+
+        let date = dt_start.date_naive();
+        let interval = i64::from(rrule.interval);
+        let (start, kind) = match rrule.freq {
+            Frequency::Daily if rrule.by_weekday.is_empty() && rrule.by_month_day.is_empty() => {
+                (date, SimplePeriodKind::Daily { interval })
+            }
+            Frequency::Weekly if rrule.by_month_day.is_empty() => {
+                let weekdays = rrule
+                    .by_weekday
+                    .iter()
+                    .map(|weekday| match weekday {
+                        NWeekday::Every(weekday) => Some(*weekday),
+                        NWeekday::Nth(..) => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let days_from_week_start = (date.weekday().num_days_from_monday() + 7
+                    - rrule.week_start.num_days_from_monday())
+                    % 7;
+                let week_start = date - Duration::days(i64::from(days_from_week_start));
+                (week_start, SimplePeriodKind::Weekly { interval, weekdays })
+            }
+            Frequency::Monthly if rrule.by_weekday.is_empty() => {
+                let mut month_days = rrule
+                    .by_month_day
+                    .iter()
+                    .map(|day| u32::try_from(*day).ok().filter(|day| *day > 0))
+                    .collect::<Option<Vec<_>>>()?;
+                month_days.sort_unstable();
+                month_days.dedup();
+                (
+                    date.with_day(1)?,
+                    SimplePeriodKind::Monthly {
+                        interval,
+                        month_days,
+                    },
+                )
+            }
+            _ => return None,
+        };
+        Some(Self {
+            start,
+            period: 0,
+            kind,
+        })
+    }
+
+    /// Skips the periods that end before the one with `date`, so that the next period
+    /// is the one before it, see [`super::RRuleIter::seek`].
+    pub(crate) fn seek(&mut self, date: NaiveDate) {
+        let periods = match &self.kind {
+            SimplePeriodKind::Daily { interval } => (date - self.start).num_days() / interval,
+            SimplePeriodKind::Weekly { interval, .. } => {
+                (date - self.start).num_days() / (interval * 7)
+            }
+            SimplePeriodKind::Monthly { interval, .. } => {
+                let months =
+                    |date: NaiveDate| i64::from(date.year()) * 12 + i64::from(date.month0());
+                (months(date) - months(self.start)) / interval
+            }
+        };
+        self.period = self.period.max(periods - 1);
+    }
+
+    /// Replaces the content of `days` with the days of the next period, in order.
+    ///
+    /// Returns `false` when the next period starts outside of the supported range of
+    /// years, where the iteration ends.
+    pub(crate) fn next_days(&mut self, days: &mut Vec<NaiveDate>) -> bool {
+        days.clear();
+        let period = self.period;
+        self.period += 1;
+        let period_start = match &self.kind {
+            SimplePeriodKind::Daily { interval } => add_days(self.start, period * interval),
+            SimplePeriodKind::Weekly { interval, .. } => {
+                add_days(self.start, period * interval * 7)
+            }
+            SimplePeriodKind::Monthly { interval, .. } => {
+                let month = i64::from(self.start.year()) * 12
+                    + i64::from(self.start.month0())
+                    + period * interval;
+                i32::try_from(month.div_euclid(12)).ok().and_then(|year| {
+                    let month = u32::try_from(month.rem_euclid(12) + 1).ok()?;
+                    NaiveDate::from_ymd_opt(year, month, 1)
+                })
+            }
+        };
+        let Some(period_start) = period_start.filter(|date| YEAR_RANGE.contains(&date.year()))
+        else {
+            return false;
+        };
+
+        match &self.kind {
+            SimplePeriodKind::Daily { .. } => days.push(period_start),
+            SimplePeriodKind::Weekly { weekdays, .. } => days.extend(
+                (0..7)
+                    .filter_map(|day| add_days(period_start, day))
+                    .filter(|date| weekdays.contains(&date.weekday())),
+            ),
+            SimplePeriodKind::Monthly { month_days, .. } => days.extend(
+                month_days
+                    .iter()
+                    .map_while(|day| period_start.with_day(*day)),
+            ),
+        }
+        true
+    }
+}
+
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    if days.abs() > MAX_DAYS {
+        return None;
+    }
+    date.checked_add_signed(Duration::days(days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Tz;
+    use crate::iter::RRuleIter;
+    use chrono::{Month, Weekday};
+
+    #[test]
+    fn finds_fixed_day_step() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        let tests = [
+            (RRule::new(Frequency::Daily), Some(1)),
+            (RRule::new(Frequency::Daily).interval(3), Some(3)),
+            (RRule::new(Frequency::Weekly).interval(2), Some(14)),
+            (
+                RRule::new(Frequency::Weekly).by_weekday(vec![NWeekday::Every(Weekday::Fri)]),
+                Some(7),
+            ),
+            (
+                RRule::new(Frequency::Weekly).by_weekday(vec![NWeekday::Every(Weekday::Mon)]),
+                None,
+            ),
+            (
+                RRule::new(Frequency::Daily).by_weekday(vec![NWeekday::Every(Weekday::Mon)]),
+                None,
+            ),
+            (RRule::new(Frequency::Daily).by_hour(vec![9, 10]), None),
+            (RRule::new(Frequency::Monthly), None),
+            (RRule::new(Frequency::Hourly), None),
+        ];
+
+        for (rrule, expected_output) in tests {
+            let rrule = rrule.validate(dt_start).unwrap();
+            assert_eq!(fixed_day_step(&rrule), expected_output);
+        }
+    }
+
+    #[test]
+    fn finds_fixed_step() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 30, 0).unwrap();
+        let tests = [
+            (RRule::new(Frequency::Weekly), Some(Duration::days(7))),
+            (RRule::new(Frequency::Hourly), Some(Duration::hours(1))),
+            (
+                RRule::new(Frequency::Hourly).interval(5),
+                Some(Duration::hours(5)),
+            ),
+            (RRule::new(Frequency::Hourly).by_hour(vec![9, 10]), None),
+            (RRule::new(Frequency::Hourly).by_minute(vec![0, 30]), None),
+            (
+                RRule::new(Frequency::Hourly).by_weekday(vec![NWeekday::Every(Weekday::Mon)]),
+                None,
+            ),
+            (RRule::new(Frequency::Minutely), None),
+        ];
+
+        for (rrule, expected_output) in tests {
+            let rrule = rrule.validate(dt_start).unwrap();
+            assert_eq!(fixed_step(&rrule), expected_output);
+        }
+    }
+
+    #[test]
+    fn detects_exact_count() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        let tests = [
+            (RRule::new(Frequency::Daily).count(10), true),
+            (RRule::new(Frequency::Weekly).count(10), true),
+            (RRule::new(Frequency::Daily), false),
+            (
+                RRule::new(Frequency::Daily)
+                    .count(10)
+                    .until(Tz::UTC.with_ymd_and_hms(2022, 1, 1, 9, 0, 0).unwrap()),
+                false,
+            ),
+            (RRule::new(Frequency::Monthly).count(10), false),
+            (RRule::new(Frequency::Daily).count(u32::MAX), false),
+        ];
+
+        for (rrule, expected_output) in tests {
+            let rrule = rrule.validate(dt_start).unwrap();
+            assert_eq!(yields_exact_count(&rrule, &dt_start), expected_output);
+        }
+    }
+
+    #[test]
+    fn computes_nth_date() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        assert_eq!(nth_date(&dt_start, 1, 0), Some(dt_start));
+        assert_eq!(
+            nth_date(&dt_start, 7, 3),
+            Some(Tz::UTC.with_ymd_and_hms(2021, 1, 22, 9, 0, 0).unwrap())
+        );
+        assert_eq!(nth_date(&dt_start, 1, usize::MAX), None);
+    }
+
+    #[test]
+    fn computes_last_index_until() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        let tests = [
+            (Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0), 1, Some(0)),
+            (Tz::UTC.with_ymd_and_hms(2021, 1, 1, 8, 0, 0), 1, None),
+            (Tz::UTC.with_ymd_and_hms(2021, 1, 10, 9, 0, 0), 1, Some(9)),
+            (Tz::UTC.with_ymd_and_hms(2021, 1, 10, 8, 59, 59), 1, Some(8)),
+            (Tz::UTC.with_ymd_and_hms(2021, 1, 21, 23, 0, 0), 7, Some(2)),
+        ];
+
+        for (until, step, expected_output) in tests {
+            assert_eq!(
+                last_index_until(&dt_start, step, &until.unwrap()),
+                expected_output
+            );
+        }
+    }
+
+    #[test]
+    fn simple_periods_match_the_generic_iteration() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 31, 9, 0, 0).unwrap();
+        let tests = [
+            (RRule::new(Frequency::Daily).interval(3), dt_start),
+            (RRule::new(Frequency::Daily).by_hour(vec![17, 9]), dt_start),
+            (
+                RRule::new(Frequency::Weekly)
+                    .interval(2)
+                    .week_start(Weekday::Sun)
+                    .by_weekday(vec![
+                        NWeekday::Every(Weekday::Mon),
+                        NWeekday::Every(Weekday::Sun),
+                    ]),
+                dt_start,
+            ),
+            (
+                RRule::new(Frequency::Weekly)
+                    .until(Tz::UTC.with_ymd_and_hms(2022, 3, 1, 9, 0, 0).unwrap()),
+                dt_start,
+            ),
+            (RRule::new(Frequency::Monthly).count(30), dt_start),
+            (
+                RRule::new(Frequency::Monthly)
+                    .interval(5)
+                    .by_month_day(vec![30, 1, 29]),
+                dt_start,
+            ),
+            (
+                RRule::new(Frequency::Daily),
+                Tz::UTC.with_ymd_and_hms(10_000, 12, 1, 9, 0, 0).unwrap(),
+            ),
+        ];
+
+        for (rrule, dt_start) in tests {
+            let rrule = rrule.validate(dt_start).unwrap();
+            let iter = RRuleIter::new(&rrule, &dt_start, true);
+            assert!(iter.simple_periods.is_some());
+            let mut generic_iter = iter.clone();
+            generic_iter.simple_periods = None;
+            assert_eq!(
+                iter.take(500).collect::<Vec<_>>(),
+                generic_iter.take(500).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn finds_no_simple_periods_with_other_rule_parts() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 31, 9, 0, 0).unwrap();
+        let tests = [
+            RRule::new(Frequency::Yearly),
+            RRule::new(Frequency::Hourly),
+            RRule::new(Frequency::Daily).by_weekday(vec![NWeekday::Every(Weekday::Mon)]),
+            RRule::new(Frequency::Weekly).by_month(&[Month::March]),
+            RRule::new(Frequency::Monthly).by_month_day(vec![-1]),
+            RRule::new(Frequency::Monthly).by_weekday(vec![NWeekday::Nth(1, Weekday::Mon)]),
+            RRule::new(Frequency::Monthly).by_set_pos(vec![1]),
+        ];
+
+        for rrule in tests {
+            let rrule = rrule.validate(dt_start).unwrap();
+            assert!(SimplePeriods::new(&rrule, &dt_start).is_none());
+        }
+    }
+}
@@ -1,28 +1,82 @@
-use crate::{NWeekday, RRule};
+use crate::{Frequency, NWeekday, RRule, RulePart, Skip};
 
 use super::iterinfo::IterInfo;
 
 type RRuleFilter = &'static dyn Fn(&IterInfo, usize, &RRule) -> bool;
 
-const FILTERS: [RRuleFilter; 7] = [
-    &is_filtered_by_month,
+/// The filters of the days, with the rule parts that they check.
+const FILTERS: [(RulePart, RRuleFilter); 7] = [
+    (RulePart::ByMonth, &is_filtered_by_month),
+    (RulePart::ByWeekNo, &is_filtered_by_week_number),
+    (RulePart::ByDay, &is_filtered_by_weekday),
+    (RulePart::ByDay, &is_filtered_by_neg_weekday),
+    (RulePart::ByEaster, &is_filtered_by_easter),
+    (RulePart::ByMonthDay, &is_filtered_by_month_day),
+    (RulePart::ByYearDay, &is_filtered_by_year_day),
+];
+
+/// The filters that apply to a day that replaces a month day that doesn't exist.
+const SKIP_FILTERS: [RRuleFilter; 5] = [
     &is_filtered_by_week_number,
     &is_filtered_by_weekday,
     &is_filtered_by_neg_weekday,
     &is_filtered_by_easter,
-    &is_filtered_by_month_day,
     &is_filtered_by_year_day,
 ];
 
 pub(crate) fn is_filtered(ii: &IterInfo, current_day: usize) -> bool {
+    filtered_by(ii, current_day).is_some()
+}
+
+/// Returns the rule part of the first filter that filters out the day, or `None` if the
+/// day passes all the filters.
+pub(crate) fn filtered_by(ii: &IterInfo, current_day: usize) -> Option<RulePart> {
     let rrule = ii.rrule();
     FILTERS
         .into_iter()
-        .any(|filter| filter(ii, current_day, rrule))
+        .find(|(_, filter)| filter(ii, current_day, rrule))
+        .map(|(rule_part, _)| rule_part)
+}
+
+/// Returns the day that replaces the month days of the rule that don't exist in the
+/// month ending on `last_day`, like the 30th of February, as the [`Skip`] of the rule
+/// defines.
+pub(crate) fn skip_substitute(ii: &IterInfo, last_day: usize) -> Option<usize> {
+    let rrule = ii.rrule();
+    let month_len = ii.month_day_mask()[last_day];
+    let skip = if rrule.skip == Skip::Omit {
+        // Only the 29th of February in a non-leap year is moved by `leap_day`.
+        let is_missing_leap_day =
+            rrule.freq == Frequency::Yearly && month_len == 28 && rrule.by_month_day.contains(&29);
+        if !is_missing_leap_day {
+            return None;
+        }
+        rrule.leap_day
+    } else {
+        let has_missing_month_day = rrule.by_month_day.iter().any(|day| *day > month_len)
+            || rrule.by_n_month_day.iter().any(|day| *day < -month_len);
+        if !has_missing_month_day {
+            return None;
+        }
+        rrule.skip
+    };
+    let substitute = match skip {
+        Skip::Omit => return None,
+        Skip::Backward => last_day,
+        Skip::Forward => last_day + 1,
+    };
+    if is_filtered_by_month(ii, last_day, rrule) {
+        return None;
+    }
+
+    let filtered = SKIP_FILTERS
+        .into_iter()
+        .any(|filter| filter(ii, substitute, rrule));
+    (!filtered).then_some(substitute)
 }
 
 fn is_filtered_by_month(ii: &IterInfo, current_day: usize, rrule: &RRule) -> bool {
-    if rrule.by_month.is_empty() {
+    if rrule.by_month.is_empty() && rrule.by_leap_month.is_empty() {
         return false;
     }
 
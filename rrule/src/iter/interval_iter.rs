@@ -0,0 +1,51 @@
+use super::RRuleSetIter;
+use crate::core::DateTime;
+use crate::RRuleSet;
+
+/// Iterator over the recurrences of an [`crate::RRuleSet`] as `(start, end)` intervals,
+/// created with [`crate::RRuleSet::iter_intervals`].
+///
+/// The end of a recurrence is computed with [`crate::EventDuration::end_of`] from the
+/// duration of its `RDATE;VALUE=PERIOD`, if it has one, or from the duration of the set,
+/// so a duration in days keeps the local time across daylight saving time transitions.
+/// Without a duration, the end of a recurrence is its start, like an event with a
+/// `DTSTART` and no `DTEND` in RFC 5545. Recurrences whose end is out of the range of
+/// dates are skipped.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::RRuleSet;
+///
+/// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nDURATION:PT1H30M\nRRULE:FREQ=DAILY;COUNT=2"
+///     .parse()
+///     .unwrap();
+///
+/// let (start, end) = rrule_set.iter_intervals().nth(1).unwrap();
+/// assert_eq!(start.to_rfc3339(), "2021-01-02T09:00:00+00:00");
+/// assert_eq!(end.to_rfc3339(), "2021-01-02T10:30:00+00:00");
+/// ```
+#[derive(Debug, Clone)]
+pub struct IntervalIter<'a> {
+    iter: RRuleSetIter<'a>,
+    rrule_set: &'a RRuleSet,
+}
+
+impl<'a> IntervalIter<'a> {
+    pub(crate) fn new(iter: RRuleSetIter<'a>, rrule_set: &'a RRuleSet) -> Self {
+        Self { iter, rrule_set }
+    }
+}
+
+impl<'a> Iterator for IntervalIter<'a> {
+    type Item = (DateTime, DateTime);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.iter.next()?;
+            if let Some(interval) = self.rrule_set.interval_of(start) {
+                return Some(interval);
+            }
+        }
+    }
+}
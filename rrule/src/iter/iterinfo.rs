@@ -1,15 +1,16 @@
 use super::counter_date::DateTimeIter;
 #[cfg(feature = "by-easter")]
 use super::easter::easter;
-use super::{monthinfo::MonthInfo, yearinfo::YearInfo};
+use super::{cache, monthinfo::MonthInfo, yearinfo::YearInfo};
 use crate::core::{get_month, DateTime};
-use crate::{Frequency, NWeekday, RRule};
+use crate::{Frequency, NWeekday, RRule, Skip};
 use chrono::{Datelike, NaiveTime, TimeZone};
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub(crate) struct IterInfo<'a> {
-    year_info: YearInfo,
-    month_info: Option<MonthInfo>,
+    year_info: Arc<YearInfo>,
+    month_info: Option<Arc<MonthInfo>>,
     easter_mask: Option<Vec<i32>>,
     rrule: &'a RRule,
 }
@@ -19,7 +20,7 @@ impl<'a> IterInfo<'a> {
         let year = dt_start.year();
         let month = get_month(dt_start);
 
-        let year_info = YearInfo::new(year, rrule);
+        let year_info = cache::year_info(year, rrule);
         let mut ii = Self {
             rrule,
             year_info,
@@ -32,10 +33,8 @@ impl<'a> IterInfo<'a> {
     }
 
     fn rebuild_inner(&mut self, year: i32, month: u8, skip_year_info: bool) {
-        if !skip_year_info
-            && !matches!(&self.month_info, Some(month_info) if month_info.last_year == year)
-        {
-            self.year_info = YearInfo::new(year, self.rrule);
+        if !skip_year_info && self.year_info.year != year {
+            self.year_info = cache::year_info(year, self.rrule);
         }
 
         let contains_nth_by_weekday = self
@@ -47,8 +46,7 @@ impl<'a> IterInfo<'a> {
         if contains_nth_by_weekday
             && !(matches!(&self.month_info, Some(month_info) if month_info.last_month == month && month_info.last_year == year))
         {
-            let new_month_info = MonthInfo::new(&self.year_info, month, self.rrule);
-            self.month_info = Some(new_month_info);
+            self.month_info = Some(cache::month_info(&self.year_info, month, self.rrule));
         }
 
         #[cfg(feature = "by-easter")]
@@ -109,20 +107,8 @@ impl<'a> IterInfo<'a> {
         self.year_info.neg_month_day_mask
     }
 
-    pub fn year_dayset(&self) -> Vec<usize> {
-        let year_len = usize::from(self.year_len());
-        (0..year_len).collect()
-    }
-
-    pub fn month_dayset(&self, month: u32) -> Vec<usize> {
-        let month_range = self.month_range();
-        let month = usize::try_from(month).expect("target arch should have at least 32 bits");
-        let start = usize::from(month_range[month - 1]);
-        let end = usize::from(month_range[month]);
-        (start..end).collect()
-    }
-
-    pub fn weekday_set(&self, year: i32, month: u32, day: u32) -> Vec<usize> {
+    /// Appends the days of the week from the date to `set`.
+    fn weekday_set(&self, year: i32, month: u32, day: u32, set: &mut Vec<usize>) {
         let set_len = usize::from(self.year_len() + 7);
 
         let mut date_ordinal = usize::try_from(
@@ -133,8 +119,6 @@ impl<'a> IterInfo<'a> {
         )
         .expect("target arch should have at least 32 bits");
 
-        let mut set = vec![];
-
         let week_start_num_days_from_monday = self.rrule.week_start.num_days_from_monday();
 
         for _ in 0..7 {
@@ -147,8 +131,6 @@ impl<'a> IterInfo<'a> {
                 break;
             }
         }
-
-        set
     }
 
     pub fn day_dayset(year: i32, month: u32, day: u32) -> Vec<usize> {
@@ -160,46 +142,120 @@ impl<'a> IterInfo<'a> {
         vec![usize::try_from(date_ordinal).expect("target arch should have at least 32 bits")]
     }
 
-    pub fn hour_timeset(&self, hour: u8) -> Vec<NaiveTime> {
+    fn hour_timeset(&self, hour: u8) -> impl Iterator<Item = NaiveTime> + '_ {
         self.rrule
             .by_minute
             .iter()
-            .flat_map(|minute| self.min_timeset(hour, *minute))
-            .collect()
+            .flat_map(move |minute| self.min_timeset(hour, *minute))
     }
 
-    pub fn min_timeset(&self, hour: u8, minute: u8) -> Vec<NaiveTime> {
+    fn min_timeset(&self, hour: u8, minute: u8) -> impl Iterator<Item = NaiveTime> + '_ {
         self.rrule
             .by_second
             .iter()
-            .filter_map(|second| {
-                NaiveTime::from_hms_opt(u32::from(hour), u32::from(minute), u32::from(*second))
-            })
-            .collect()
+            .filter_map(move |second| Self::sec_timeset(hour, minute, *second))
     }
 
-    pub fn sec_timeset(hour: u8, minute: u8, second: u8) -> Vec<NaiveTime> {
-        if let Some(time) =
-            NaiveTime::from_hms_opt(u32::from(hour), u32::from(minute), u32::from(second))
-        {
-            vec![time]
-        } else {
-            vec![]
+    fn sec_timeset(hour: u8, minute: u8, second: u8) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(u32::from(hour), u32::from(minute), u32::from(second))
+    }
+
+    /// Returns the days of the period of the frequency, before they are filtered.
+    pub fn candidate_days(&self, freq: Frequency, year: i32, month: u32, day: u32) -> Vec<usize> {
+        let mut days = vec![];
+        self.extend_candidate_days(freq, year, month, day, &mut days);
+        days
+    }
+
+    fn extend_candidate_days(
+        &self,
+        freq: Frequency,
+        year: i32,
+        month: u32,
+        day: u32,
+        days: &mut Vec<usize>,
+    ) {
+        match freq {
+            Frequency::Yearly => days.extend(0..usize::from(self.year_len())),
+            Frequency::Monthly => {
+                let month_range = self.month_range();
+                let month =
+                    usize::try_from(month).expect("target arch should have at least 32 bits");
+                days.extend(usize::from(month_range[month - 1])..usize::from(month_range[month]));
+            }
+            Frequency::Weekly => self.weekday_set(year, month, day, days),
+            _ => days.extend(Self::day_dayset(year, month, day)),
         }
     }
 
     pub fn get_dayset(&self, freq: Frequency, year: i32, month: u32, day: u32) -> Vec<usize> {
-        let mut dayset = match freq {
-            Frequency::Yearly => self.year_dayset(),
-            Frequency::Monthly => self.month_dayset(month),
-            Frequency::Weekly => self.weekday_set(year, month, day),
-            _ => Self::day_dayset(year, month, day),
+        let mut dayset = vec![];
+        self.fill_dayset(freq, year, month, day, &mut dayset);
+        dayset
+    }
+
+    /// Replaces the content of `dayset` with the days of the period of the frequency that
+    /// pass the filters of the `RRule`, reusing its allocation.
+    pub fn fill_dayset(
+        &self,
+        freq: Frequency,
+        year: i32,
+        month: u32,
+        day: u32,
+        dayset: &mut Vec<usize>,
+    ) {
+        dayset.clear();
+        self.extend_candidate_days(freq, year, month, day, dayset);
+        let has_substitutes = self.rrule.skip != Skip::Omit || self.rrule.leap_day != Skip::Omit;
+        let candidates = if has_substitutes {
+            dayset.clone()
+        } else {
+            vec![]
         };
 
         // Filter out days according to the RRule filters.
         dayset.retain(|day| !super::filters::is_filtered(self, *day));
 
-        dayset
+        if has_substitutes {
+            dayset.extend(self.skip_substitutes(freq, month, &candidates));
+            dayset.sort_unstable();
+            dayset.dedup();
+        }
+    }
+
+    /// Returns the days that replace the month days that don't exist in the months of
+    /// the period, as the `SKIP` rule part defines.
+    fn skip_substitutes(&self, freq: Frequency, month: u32, candidates: &[usize]) -> Vec<usize> {
+        let month_range = self.month_range();
+        let last_day_of_month = |month: usize| usize::from(month_range[month]) - 1;
+        match freq {
+            Frequency::Yearly => (1..=12)
+                .filter_map(|month| super::filters::skip_substitute(self, last_day_of_month(month)))
+                .collect(),
+            Frequency::Monthly => {
+                let month =
+                    usize::try_from(month).expect("target arch should have at least 32 bits");
+                super::filters::skip_substitute(self, last_day_of_month(month))
+                    .into_iter()
+                    .collect()
+            }
+            // The periods of the other frequencies are days, which can be a substitute by
+            // themselves.
+            _ => candidates
+                .iter()
+                .copied()
+                .filter(|day| {
+                    let last_day = match self.rrule.skip {
+                        Skip::Forward => day.checked_sub(1),
+                        _ => Some(*day),
+                    };
+                    last_day.is_some_and(|last_day| {
+                        self.neg_month_day_mask()[last_day] == -1
+                            && super::filters::skip_substitute(self, last_day) == Some(*day)
+                    })
+                })
+                .collect(),
+        }
     }
 
     /// Gets a timeset without checking if the hour, minute and second are valid, according
@@ -209,10 +265,25 @@ impl<'a> IterInfo<'a> {
     /// that we get a valid `DateTime` back, and there is no need to do any duplicate
     /// validation.
     pub fn get_timeset_unchecked(&self, hour: u8, minute: u8, second: u8) -> Vec<NaiveTime> {
+        let mut timeset = vec![];
+        self.fill_timeset_unchecked(hour, minute, second, &mut timeset);
+        timeset
+    }
+
+    /// Same as [`Self::get_timeset_unchecked`], but replaces the content of `timeset`,
+    /// reusing its allocation.
+    pub fn fill_timeset_unchecked(
+        &self,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        timeset: &mut Vec<NaiveTime>,
+    ) {
+        timeset.clear();
         match self.rrule.freq {
-            Frequency::Hourly => self.hour_timeset(hour),
-            Frequency::Minutely => self.min_timeset(hour, minute),
-            Frequency::Secondly => Self::sec_timeset(hour, minute, second),
+            Frequency::Hourly => timeset.extend(self.hour_timeset(hour)),
+            Frequency::Minutely => timeset.extend(self.min_timeset(hour, minute)),
+            Frequency::Secondly => timeset.extend(Self::sec_timeset(hour, minute, second)),
             _ => unreachable!(
                 "This method is never called with an invalid frequency and is not publicly exposed"
             ),
@@ -268,7 +339,7 @@ impl<'a> IterInfo<'a> {
         }
     }
 
-    pub fn rrule(&self) -> &RRule {
+    pub fn rrule(&self) -> &'a RRule {
         self.rrule
     }
 }
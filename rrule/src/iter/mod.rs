@@ -1,23 +1,50 @@
 #![allow(clippy::module_name_repetitions)]
 
+mod cache;
 mod checks;
+pub(crate) mod chinese;
 mod counter_date;
+mod cursor;
 mod easter;
+mod event_date_iter;
+mod explain;
+pub(crate) mod fast_path;
 pub(crate) mod filters;
+mod interval_iter;
 pub(crate) mod iterinfo;
 mod masks;
 mod monthinfo;
+mod occurrence_iter;
 mod operation_errors;
 mod pos_list;
 pub(crate) mod rrule_iter;
+mod rrule_rev_iter;
 mod rruleset_iter;
+mod set_operation_iter;
+mod source_iter;
+#[cfg(feature = "tokio")]
+mod stream;
 mod utils;
 mod yearinfo;
 
+pub use cursor::{IterCursor, IterState};
+pub(crate) use event_date_iter::to_event_date;
+pub use event_date_iter::EventDateIter;
+pub(crate) use explain::explain;
+pub use explain::{Explanation, RulePart};
+pub use interval_iter::IntervalIter;
 use iterinfo::IterInfo;
+pub use occurrence_iter::{Occurrence, OccurrenceIter};
 use pos_list::build_pos_list;
-pub(crate) use rrule_iter::RRuleIter;
+pub use rrule_iter::RRuleIter;
+pub use rrule_rev_iter::RRuleRevIter;
 pub use rruleset_iter::RRuleSetIter;
+pub(crate) use set_operation_iter::SetOperation;
+pub use set_operation_iter::SetOperationIter;
+pub use source_iter::{Exclusion, OccurrenceSource, SourceIter, SourcedOccurrence};
+#[cfg(feature = "tokio")]
+pub use stream::{RRuleSetStream, RRuleSetTimerStream};
+pub(crate) use utils::add_time_to_date;
 
 /// Prevent loops when searching for the next event in the iterator.
 /// If after X number of iterations it still has not found an event,
@@ -122,6 +122,7 @@ impl MonthInfo {
 #[cfg(test)]
 mod tests {
     use chrono::{TimeZone, Weekday};
+    use smallvec::smallvec;
 
     use crate::core::Tz;
 
@@ -164,7 +165,7 @@ mod tests {
     fn get_neg_weekday_mask_with_yearly_freq_and_byweekday() {
         let rrule = RRule {
             freq: Frequency::Yearly,
-            by_weekday: vec![
+            by_weekday: smallvec![
                 NWeekday::new(None, Weekday::Mon),
                 NWeekday::new(Some(-2), Weekday::Thu),
                 NWeekday::new(Some(1), Weekday::Thu),
@@ -191,7 +192,7 @@ mod tests {
     fn get_neg_weekday_mask_with_monthly_freq_and_byweekday() {
         let rrule = RRule {
             freq: Frequency::Monthly,
-            by_weekday: vec![
+            by_weekday: smallvec![
                 NWeekday::new(None, Weekday::Mon),
                 NWeekday::new(Some(-2), Weekday::Thu),
                 NWeekday::new(Some(1), Weekday::Thu),
@@ -0,0 +1,43 @@
+use crate::core::DateTime;
+
+/// A recurrence together with its position in the sequence of recurrences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Occurrence {
+    /// The zero based index of the recurrence, e.g. `13` for the 14th recurrence.
+    pub index: usize,
+    /// The date of the recurrence.
+    pub date: DateTime,
+}
+
+/// Iterator over the [`Occurrence`]s of an [`crate::RRule`] or [`crate::RRuleSet`].
+///
+/// Created by [`crate::RRule::occurrences`] or [`crate::RRuleSet::occurrences`].
+#[derive(Debug, Clone)]
+pub struct OccurrenceIter<I> {
+    iter: I,
+    index: usize,
+}
+
+impl<I> OccurrenceIter<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        Self { iter, index: 0 }
+    }
+}
+
+impl<I: Iterator<Item = DateTime>> Iterator for OccurrenceIter<I> {
+    type Item = Occurrence;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let date = self.iter.next()?;
+        let occurrence = Occurrence {
+            index: self.index,
+            date,
+        };
+        self.index += 1;
+        Some(occurrence)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
@@ -1,18 +1,25 @@
 use super::utils::{add_time_to_date, from_ordinal, pymod};
 use crate::core::{DateTime, Tz};
+use crate::{DstFoldPolicy, DstGapPolicy};
 use chrono::NaiveTime;
 
+/// Replaces the content of `pos_list` with the sorted dates at the `by_set_pos` positions
+/// of the period, reusing its allocation.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn build_pos_list(
     by_set_pos: &[i32],
     dayset: &[usize],
     timeset: &[NaiveTime],
     year_ordinal: i64,
     tz: Tz,
-) -> Vec<DateTime> {
-    let mut pos_list = vec![];
+    dst_gap: DstGapPolicy,
+    dst_fold: DstFoldPolicy,
+    pos_list: &mut Vec<DateTime>,
+) {
+    pos_list.clear();
 
     if timeset.is_empty() {
-        return vec![];
+        return;
     }
 
     let timeset_len = u32::try_from(timeset.len())
@@ -45,22 +52,18 @@ pub(crate) fn build_pos_list(
             .expect("dayset is controlled by us and all elements are within range of i64");
 
         // Get ordinal which is UTC and apply timezone
+        #[allow(deprecated)]
         let date = from_ordinal(year_ordinal + day).date().with_timezone(&tz);
         // Create new Date + Time combination
         // Use Date and Timezone from `date`
         // Use Time from `timeset`.
         let time = timeset[time_pos];
-        let res = match add_time_to_date(date, time) {
-            Some(date) => date,
-            None => continue,
-        };
-
-        if !pos_list.contains(&res) {
-            pos_list.push(res);
+        for res in add_time_to_date(date, time, dst_gap, dst_fold) {
+            if !pos_list.contains(&res) {
+                pos_list.push(res);
+            }
         }
     }
 
     pos_list.sort();
-
-    pos_list
 }
@@ -1,14 +1,20 @@
+use super::chinese::ChinesePeriods;
 use super::counter_date::DateTimeIter;
+use super::fast_path::{self, SimplePeriods};
 use super::utils::add_time_to_date;
 use super::{build_pos_list, utils::from_ordinal, IterInfo, MAX_ITER_LOOP};
 use crate::core::{get_hour, get_minute, get_second};
-use crate::{core::DateTime, Frequency, RRule};
+use crate::{core::DateTime, DstFoldPolicy, DstGapPolicy, Frequency, RRule};
 use chrono::Datelike;
-use chrono::{NaiveTime, TimeZone};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use std::collections::VecDeque;
 
 #[derive(Debug, Clone)]
-pub(crate) struct RRuleIter<'a> {
+/// Iterator over all the dates in an [`RRule`].
+///
+/// When the rule has a `COUNT`, [`Iterator::size_hint`] is bounded by the remaining count,
+/// and is exact for `DAILY` and `WEEKLY` rules without `UNTIL` or other BYxxx rule parts.
+pub struct RRuleIter<'a> {
     /// Date the iterator is currently at.
     pub(crate) counter_date: DateTimeIter,
     pub(crate) ii: IterInfo<'a>,
@@ -22,10 +28,29 @@ pub(crate) struct RRuleIter<'a> {
     /// Number of events that should still be generated before the end.
     /// Counter always goes down after each iteration.
     pub(crate) count: Option<u32>,
+    /// If the iterator is known to generate all of the `count` events.
+    pub(crate) exact_count: bool,
     /// If the iterator should be using iterator limits.
     pub(crate) limited: bool,
     /// If the iterator has been stopped by the iterator limits.
     pub(crate) was_limited: bool,
+    /// How dates in the gap of a daylight saving time transition are handled.
+    pub(crate) dst_gap: DstGapPolicy,
+    /// How ambiguous dates at a daylight saving time transition are handled.
+    pub(crate) dst_fold: DstFoldPolicy,
+    /// The dates of the current day, kept to reuse the allocation.
+    pub(crate) day_dates: Vec<DateTime>,
+    /// The days of the current period, kept to reuse the allocation.
+    pub(crate) dayset: Vec<usize>,
+    /// The dates of the current period with `BYSETPOS`, kept to reuse the allocation.
+    pub(crate) pos_list: Vec<DateTime>,
+    /// The periods of the rule when they can be computed with date arithmetic.
+    pub(crate) simple_periods: Option<SimplePeriods>,
+    /// The periods of the rule when its months are the ones of the Chinese calendar.
+    pub(crate) chinese_periods: Option<ChinesePeriods>,
+    /// The days of the current period of `simple_periods` or `chinese_periods`, kept to
+    /// reuse the allocation.
+    pub(crate) simple_days: Vec<NaiveDate>,
 }
 
 impl<'a> RRuleIter<'a> {
@@ -37,6 +62,9 @@ impl<'a> RRuleIter<'a> {
         let second = get_second(dt_start);
         let timeset = ii.get_timeset(hour, minute, second);
         let count = ii.rrule().count;
+        let exact_count = fast_path::yields_exact_count(ii.rrule(), dt_start);
+        let simple_periods = SimplePeriods::new(ii.rrule(), dt_start);
+        let chinese_periods = ChinesePeriods::new(ii.rrule(), dt_start);
 
         RRuleIter {
             counter_date: dt_start.into(),
@@ -46,8 +74,84 @@ impl<'a> RRuleIter<'a> {
             buffer: VecDeque::new(),
             finished: false,
             count,
+            exact_count,
             limited,
             was_limited: false,
+            dst_gap: DstGapPolicy::default(),
+            dst_fold: DstFoldPolicy::default(),
+            day_dates: vec![],
+            dayset: vec![],
+            pos_list: vec![],
+            simple_periods,
+            chinese_periods,
+            simple_days: vec![],
+        }
+    }
+
+    /// Sets how dates around daylight saving time transitions are handled.
+    pub(crate) fn with_dst_policies(
+        mut self,
+        dst_gap: DstGapPolicy,
+        dst_fold: DstFoldPolicy,
+    ) -> Self {
+        self.dst_gap = dst_gap;
+        self.dst_fold = dst_fold;
+        self
+    }
+
+    /// Moves the iterator forward to shortly before the timestamp `after`, without
+    /// generating the dates of the periods of the frequency in between, like
+    /// [`fast_path::first_after`] does for the rules with a fixed step. The dates up to
+    /// `after` that are still returned afterwards have to be skipped by the caller.
+    ///
+    /// It has to be called before the first date is generated. Rules with a `COUNT`
+    /// aren't moved, as the dates before `after` are needed to count them, and neither
+    /// are `MINUTELY` rules with `BYHOUR` and `SECONDLY` rules with `BYHOUR` or `BYMINUTE`,
+    /// whose increments depend on the date they start from, and the rules in the Chinese
+    /// calendar.
+    pub(crate) fn seek(&mut self, after: i64) {
+        let rrule = self.ii.rrule();
+        if rrule.count.is_some()
+            || rrule.interval == 0
+            || !self.buffer.is_empty()
+            || self.chinese_periods.is_some()
+        {
+            return;
+        }
+        let Some(after) = self.dt_start.timezone().timestamp_opt(after, 0).single() else {
+            return;
+        };
+        // The dates more than 2 days before `after` in local time are before it, whatever
+        // the changes of the offset of the timezone in between. The iterator is moved to the
+        // period before the one with that date, as the days that `SKIP=FORWARD` moves can
+        // be in the next period.
+        let target = after.naive_local() - Duration::days(2);
+        if let Some(periods) = &mut self.simple_periods {
+            periods.seek(target.date());
+            return;
+        }
+        let Some(periods) = periods_until(rrule, &self.dt_start.naive_local(), &target)
+            .and_then(|periods| periods.checked_sub(1))
+        else {
+            return;
+        };
+        let mut counter_date = self.counter_date.clone();
+        if counter_date.advance_periods(rrule, periods).is_err() {
+            return;
+        }
+        self.counter_date = counter_date;
+        self.ii.rebuild(&self.counter_date);
+        if matches!(
+            rrule.freq,
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly
+        ) {
+            let hour = u8::try_from(self.counter_date.hour).expect("range 0-23 is covered by u8");
+            let minute =
+                u8::try_from(self.counter_date.minute).expect("range 0-59 is covered by u8");
+            let second =
+                u8::try_from(self.counter_date.second).expect("range 0-59 is covered by u8");
+            // The time might not be one of the rule, unlike after an increment.
+            self.timeset = self.ii.get_timeset(hour, minute, second);
         }
     }
 
@@ -80,6 +184,96 @@ impl<'a> RRuleIter<'a> {
         false
     }
 
+    /// Attempts to add the dates of a day at the times of the rule to the result. Returns
+    /// `true` if we should terminate the iteration.
+    fn try_add_day(&mut self, date: NaiveDate) -> bool {
+        // We apply the local-TZ here.
+        #[allow(deprecated)]
+        let date = self
+            .dt_start
+            .timezone()
+            .ymd(date.year(), date.month(), date.day());
+
+        let mut day_dates = std::mem::take(&mut self.day_dates);
+        day_dates.clear();
+        day_dates.extend(
+            self.timeset
+                .iter()
+                .flat_map(|time| add_time_to_date(date, *time, self.dst_gap, self.dst_fold)),
+        );
+        // Around a daylight saving time transition, the dates can be out of
+        // order, and times in the same gap can be shifted to the same time.
+        day_dates.sort();
+        day_dates.dedup();
+        let rrule = self.ii.rrule();
+        let finished = day_dates.iter().any(|dt| {
+            Self::try_add_datetime(
+                *dt,
+                rrule,
+                &mut self.count,
+                &mut self.buffer,
+                &self.dt_start,
+            )
+        });
+        self.day_dates = day_dates;
+        finished
+    }
+
+    /// Generates the dates of the next period of `simple_periods` or `chinese_periods`.
+    /// Returns `true` if we should terminate the iteration.
+    fn generate_simple_period(&mut self) -> bool {
+        let mut days = std::mem::take(&mut self.simple_days);
+        let has_period = match (&mut self.simple_periods, &mut self.chinese_periods) {
+            (Some(periods), _) => periods.next_days(&mut days),
+            (None, Some(periods)) => periods.next_days(&mut days),
+            (None, None) => false,
+        };
+        let finished = !has_period || days.iter().any(|date| self.try_add_day(*date));
+        self.simple_days = days;
+        finished
+    }
+
+    /// Attempts to add the dates of the days of a period to the result. Returns `true` if
+    /// we should terminate the iteration.
+    fn try_add_period(&mut self, dayset: &[usize]) -> bool {
+        let rrule = self.ii.rrule();
+        if rrule.by_set_pos.is_empty() {
+            // Loop over `start..end`
+            return dayset.iter().any(|current_day| {
+                let current_day = i64::try_from(*current_day).expect(
+                    "We control the dayset, and we know that it will always fit within an i64",
+                );
+                let year_ordinal = self.ii.year_ordinal();
+                // Ordinal conversion uses UTC: if we apply local-TZ here, then
+                // just below we'll end up double-applying.
+                self.try_add_day(from_ordinal(year_ordinal + current_day).date_naive())
+            });
+        }
+
+        let mut pos_list = std::mem::take(&mut self.pos_list);
+        build_pos_list(
+            &rrule.by_set_pos,
+            dayset,
+            &self.timeset,
+            self.ii.year_ordinal(),
+            self.dt_start.timezone(),
+            self.dst_gap,
+            self.dst_fold,
+            &mut pos_list,
+        );
+        let finished = pos_list.iter().any(|dt| {
+            Self::try_add_datetime(
+                *dt,
+                rrule,
+                &mut self.count,
+                &mut self.buffer,
+                &self.dt_start,
+            )
+        });
+        self.pos_list = pos_list;
+        finished
+    }
+
     /// Generates a list of dates that will be added to the buffer.
     /// Returns true if finished, no more items should/can be returned.
     fn generate(&mut self) -> bool {
@@ -115,69 +309,29 @@ impl<'a> RRuleIter<'a> {
                     return true;
                 }
             }
+            if self.simple_periods.is_some() || self.chinese_periods.is_some() {
+                if self.generate_simple_period() {
+                    return true;
+                }
+                continue;
+            }
             let rrule = self.ii.rrule();
 
-            let dayset = self.ii.get_dayset(
+            let mut dayset = std::mem::take(&mut self.dayset);
+            self.ii.fill_dayset(
                 rrule.freq,
                 self.counter_date.year,
                 self.counter_date.month,
                 self.counter_date.day,
+                &mut dayset,
             );
-
-            if rrule.by_set_pos.is_empty() {
-                // Loop over `start..end`
-                for current_day in &dayset {
-                    let current_day = i64::try_from(*current_day).expect(
-                        "We control the dayset, and we know that it will always fit within an i64",
-                    );
-                    let year_ordinal = self.ii.year_ordinal();
-                    // Ordinal conversion uses UTC: if we apply local-TZ here, then
-                    // just below we'll end up double-applying.
-                    let date = from_ordinal(year_ordinal + current_day);
-                    // We apply the local-TZ here.
-                    let date = self
-                        .dt_start
-                        .timezone()
-                        .ymd(date.year(), date.month(), date.day());
-
-                    for time in &self.timeset {
-                        let dt = match add_time_to_date(date, *time) {
-                            Some(dt) => dt,
-                            None => continue,
-                        };
-                        if Self::try_add_datetime(
-                            dt,
-                            rrule,
-                            &mut self.count,
-                            &mut self.buffer,
-                            &self.dt_start,
-                        ) {
-                            return true;
-                        }
-                    }
-                }
-            } else {
-                let pos_list = build_pos_list(
-                    &rrule.by_set_pos,
-                    &dayset,
-                    &self.timeset,
-                    self.ii.year_ordinal(),
-                    self.dt_start.timezone(),
-                );
-                for dt in pos_list {
-                    if Self::try_add_datetime(
-                        dt,
-                        rrule,
-                        &mut self.count,
-                        &mut self.buffer,
-                        &self.dt_start,
-                    ) {
-                        return true;
-                    }
-                }
+            let finished = self.try_add_period(&dayset);
+            let increment_day = dayset.is_empty();
+            self.dayset = dayset;
+            if finished {
+                return true;
             }
 
-            let increment_day = dayset.is_empty();
             if self.counter_date.increment(rrule, increment_day).is_err() {
                 self.finished = true;
                 return true;
@@ -193,7 +347,8 @@ impl<'a> RRuleIter<'a> {
                     u8::try_from(self.counter_date.minute).expect("range 0-59 is covered by u8");
                 let second =
                     u8::try_from(self.counter_date.second).expect("range 0-59 is covered by u8");
-                self.timeset = self.ii.get_timeset_unchecked(hour, minute, second);
+                self.ii
+                    .fill_timeset_unchecked(hour, minute, second, &mut self.timeset);
             }
 
             self.ii.rebuild(&self.counter_date);
@@ -204,6 +359,49 @@ impl<'a> RRuleIter<'a> {
     }
 }
 
+/// Returns the number of increments of the frequency from `start` to the period with
+/// `target`, in local time, or `None` if it can't be computed without incrementing.
+fn periods_until(rrule: &RRule, start: &NaiveDateTime, target: &NaiveDateTime) -> Option<u32> {
+    let interval = i64::from(rrule.interval);
+    let days = (target.date() - start.date()).num_days();
+    let seconds = (*target - *start).num_seconds();
+    let periods = match rrule.freq {
+        Frequency::Yearly => i64::from(target.year() - start.year()) / interval,
+        Frequency::Monthly => {
+            let months =
+                |date: &NaiveDateTime| i64::from(date.year()) * 12 + i64::from(date.month0());
+            (months(target) - months(start)) / interval
+        }
+        Frequency::Weekly => {
+            // After the first increment, the periods start on the week start.
+            let days_from_week_start = i64::from(
+                (start.weekday().num_days_from_monday() + 7
+                    - rrule.week_start.num_days_from_monday())
+                    % 7,
+            );
+            (days + days_from_week_start) / (interval * 7)
+        }
+        Frequency::Daily => days / interval,
+        Frequency::Hourly => seconds / (interval * 60 * 60),
+        Frequency::Minutely if rrule.by_hour.is_empty() => seconds / (interval * 60),
+        Frequency::Secondly if rrule.by_hour.is_empty() && rrule.by_minute.is_empty() => {
+            seconds / interval
+        }
+        Frequency::Minutely | Frequency::Secondly => return None,
+    };
+    u32::try_from(periods).ok()
+}
+
+impl<'a> IntoIterator for &'a RRule {
+    type Item = DateTime;
+
+    type IntoIter = RRuleIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl<'a> Iterator for RRuleIter<'a> {
     type Item = DateTime;
 
@@ -223,6 +421,20 @@ impl<'a> Iterator for RRuleIter<'a> {
         }
         self.buffer.pop_front()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.buffer.len();
+        if self.finished {
+            return (buffered, Some(buffered));
+        }
+        match self.count.and_then(|count| usize::try_from(count).ok()) {
+            Some(count) => {
+                let upper = buffered.saturating_add(count);
+                (if self.exact_count { upper } else { buffered }, Some(upper))
+            }
+            None => (buffered, None),
+        }
+    }
 }
 
 pub(crate) trait WasLimited {
@@ -234,3 +446,43 @@ impl<'a> WasLimited for RRuleIter<'a> {
         self.was_limited
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::core::Tz;
+    use crate::{RRule, Unvalidated};
+
+    #[test]
+    fn seeks_close_to_the_timestamp() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2019, 1, 1, 9, 0, 0).unwrap();
+        let after = Tz::UTC.with_ymd_and_hms(2021, 6, 15, 0, 0, 0).unwrap();
+        let rules = [
+            "FREQ=YEARLY;BYMONTH=6;BYDAY=MO,FR",
+            "FREQ=MONTHLY;BYDAY=-1FR",
+            "FREQ=WEEKLY;INTERVAL=3;BYDAY=TU,SU;WKST=SU",
+            "FREQ=DAILY;INTERVAL=5;BYHOUR=9,18",
+            "FREQ=HOURLY;INTERVAL=7;BYHOUR=1,8,15,22",
+            "FREQ=MINUTELY;INTERVAL=45;BYMINUTE=0,15",
+            "FREQ=SECONDLY;INTERVAL=900;BYSECOND=0,20",
+        ];
+        for rule in rules {
+            let rrule: RRule<Unvalidated> = rule.parse().unwrap();
+            let rrule = rrule.validate(dt_start).unwrap();
+            let mut iter = rrule.iter();
+            iter.seek(after.timestamp());
+            // The periods before the one of 2 days before `after` are skipped.
+            let first = iter.clone().next().unwrap();
+            assert!(
+                after - first < chrono::Duration::days(2 * 366),
+                "{rule} resumed at {first}"
+            );
+            assert_eq!(
+                iter.find(|date| *date > after),
+                rrule.iter().find(|date| *date > after),
+                "{rule}"
+            );
+        }
+    }
+}
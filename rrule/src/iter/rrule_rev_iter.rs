@@ -0,0 +1,59 @@
+use super::fast_path;
+use crate::core::DateTime;
+
+/// Iterator over all the dates in a bounded [`crate::RRule`], from the last to the first.
+///
+/// Created by [`crate::RRule::iter_rev`].
+#[derive(Debug, Clone)]
+pub struct RRuleRevIter {
+    inner: Inner,
+}
+
+#[derive(Debug, Clone)]
+enum Inner {
+    /// Dates computed one by one for rules with a fixed day step.
+    /// `remaining` is the number of dates that have not been returned yet.
+    FixedStep {
+        dt_start: DateTime,
+        step: i64,
+        remaining: usize,
+    },
+    /// All the dates, collected upfront.
+    Collected(std::iter::Rev<std::vec::IntoIter<DateTime>>),
+}
+
+impl RRuleRevIter {
+    pub(crate) fn fixed_step(dt_start: DateTime, step: i64, len: usize) -> Self {
+        Self {
+            inner: Inner::FixedStep {
+                dt_start,
+                step,
+                remaining: len,
+            },
+        }
+    }
+
+    pub(crate) fn collected(dates: Vec<DateTime>) -> Self {
+        Self {
+            inner: Inner::Collected(dates.into_iter().rev()),
+        }
+    }
+}
+
+impl Iterator for RRuleRevIter {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            Inner::FixedStep {
+                dt_start,
+                step,
+                remaining,
+            } => {
+                *remaining = remaining.checked_sub(1)?;
+                fast_path::nth_date(dt_start, *step, *remaining)
+            }
+            Inner::Collected(dates) => dates.next(),
+        }
+    }
+}
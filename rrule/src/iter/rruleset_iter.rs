@@ -1,231 +1,469 @@
 use super::rrule_iter::WasLimited;
-use super::{rrule_iter::RRuleIter, MAX_ITER_LOOP};
-use crate::{core::DateTime, RRuleSet};
-use std::collections::BTreeSet;
-use std::{collections::HashMap, iter::Iterator};
+use super::{rrule_iter::RRuleIter, IterCursor, IterState, MAX_ITER_LOOP};
+use super::{Exclusion, OccurrenceSource, SourcedOccurrence};
+use crate::core::{is_holiday, BusinessDays, DateTime, MAX_SHIFT_DAYS};
+use crate::{DstFoldPolicy, DstGapPolicy, DuplicatePolicy, HolidayCalendar, RRuleSet};
+use chrono::{Duration, NaiveDate};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, VecDeque};
+use std::iter::Iterator;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 /// Iterator over all the dates in an [`RRuleSet`].
+///
+/// The dates of all the rrules, rdates and moved recurrences are merged lazily (k-way merge),
+/// and exrules, exdates, holidays and overrides are applied on the fly. Dates that are moved to
+/// business days are buffered until no earlier date can follow. Dates at the same instant
+/// are handled with the [`crate::DuplicatePolicy`] of the set.
 pub struct RRuleSetIter<'a> {
-    queue: HashMap<usize, DateTime>,
+    /// The next date of each rrule iterator together with the index of that iterator.
+    /// Only contains iterators that are not exhausted, the earliest date is on top.
+    heads: BinaryHeap<Reverse<(DateTime, usize)>>,
+    /// If the `heads` have been filled with the first date of each rrule.
+    initialized: bool,
     limited: bool,
     rrule_iters: Vec<RRuleIter<'a>>,
     exrules: Vec<RRuleIter<'a>>,
-    exdates: BTreeSet<i64>,
-    /// Sorted additional dates in descending order
-    rdates: Vec<DateTime>,
+    /// The timestamps of the exdates, of the overridden recurrences and of the dates of the
+    /// exrules so far, with the exclusion they come from.
+    exdates: BTreeMap<i64, Exclusion>,
+    /// The days on which every date is excluded.
+    exdate_days: &'a [NaiveDate],
+    holidays: &'a [Arc<dyn HolidayCalendar>],
+    business_days: Option<&'a BusinessDays>,
+    dst_gap: DstGapPolicy,
+    dst_fold: DstFoldPolicy,
+    duplicates: DuplicatePolicy,
+    /// The last date of the rrules and rdates, to find the duplicates.
+    last_generated: Option<DateTime>,
+    /// The last date that was moved to a business day, to find the duplicates.
+    last_shifted: Option<DateTime>,
+    /// If the excluded dates are returned as well, with their exclusion.
+    with_exclusions: bool,
+    /// The next date that is not excluded, before it is moved to a business day.
+    pending: Option<SourcedOccurrence>,
+    /// Dates that have been moved to business days, but not yet returned.
+    /// The earliest date is on top.
+    shifted: BinaryHeap<Reverse<SourcedOccurrence>>,
+    /// The timestamp of the last date returned before the iterator was resumed. The dates
+    /// before it are skipped.
+    resumed_last: Option<i64>,
+    /// The number of dates at `resumed_last` that are still to be skipped, as they were
+    /// returned before the iterator was resumed.
+    resumed_skip: u32,
+    /// Sorted rdates and moved recurrences in descending order, with their source.
+    rdates: Vec<(DateTime, OccurrenceSource)>,
+    /// Dates that have been peeked at, but not yet returned.
+    peeked: VecDeque<SourcedOccurrence>,
+    /// The last date returned by the iterator.
+    last: Option<DateTime>,
+    /// The number of dates returned at the instant of `last`, or of `resumed_last` if no
+    /// date has been returned since the iterator was resumed.
+    emitted_at_last: u32,
+    /// The number of dates returned by the iterator, including the ones before it was resumed.
+    emitted: u64,
     was_limited: bool,
 }
 
 impl<'a> RRuleSetIter<'a> {
-    fn generate_date(
-        dates: &mut Vec<DateTime>,
-        exrules: &mut [RRuleIter],
-        exdates: &mut BTreeSet<i64>,
-        limited: bool,
-    ) -> (Option<DateTime>, bool) {
-        if dates.is_empty() {
-            return (None, false);
+    pub(crate) fn new(rrule_set: &'a RRuleSet, limited: bool) -> Self {
+        let rdates = rrule_set
+            .rdate
+            .iter()
+            .enumerate()
+            .map(|(idx, rdate)| (*rdate, OccurrenceSource::RDate(idx)));
+        let moved =
+            rrule_set
+                .overrides
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, recurrence_override)| {
+                    let start = recurrence_override.get_start()?;
+                    Some((*start, OccurrenceSource::Override(idx)))
+                });
+        // Sort in decreasing order
+        let mut rdates_sorted: Vec<_> = rdates.chain(moved).collect();
+        rdates_sorted.sort_by(|d1, d2| d2.cmp(d1));
+        let overridden = rrule_set.overrides.iter().map(|recurrence_override| {
+            (
+                recurrence_override.get_recurrence_id().timestamp(),
+                Exclusion::Overridden,
+            )
+        });
+
+        RRuleSetIter {
+            heads: BinaryHeap::with_capacity(rrule_set.rrule.len()),
+            initialized: false,
+            limited,
+            rrule_iters: rrule_set
+                .rrule
+                .iter()
+                .map(|rrule| {
+                    rrule
+                        .iter_with_ctx(limited)
+                        .with_dst_policies(rrule_set.dst_gap, rrule_set.dst_fold)
+                })
+                .collect(),
+            rdates: rdates_sorted,
+            exrules: rrule_set
+                .exrule
+                .iter()
+                .map(|exrule| {
+                    exrule
+                        .iter_with_ctx(limited)
+                        .with_dst_policies(rrule_set.dst_gap, rrule_set.dst_fold)
+                })
+                .collect(),
+            exdates: overridden
+                .chain(
+                    rrule_set
+                        .exdate
+                        .iter()
+                        .map(|exdate| (exdate.timestamp(), Exclusion::ExDate)),
+                )
+                .collect(),
+            exdate_days: &rrule_set.exdate_days,
+            holidays: &rrule_set.holidays,
+            business_days: rrule_set.business_days.as_ref(),
+            dst_gap: rrule_set.dst_gap,
+            dst_fold: rrule_set.dst_fold,
+            duplicates: rrule_set.duplicates,
+            last_generated: None,
+            last_shifted: None,
+            with_exclusions: false,
+            pending: None,
+            shifted: BinaryHeap::new(),
+            resumed_last: None,
+            resumed_skip: 0,
+            peeked: VecDeque::new(),
+            last: None,
+            emitted_at_last: 0,
+            emitted: 0,
+            was_limited: false,
         }
+    }
 
-        let mut date = dates.remove(dates.len() - 1);
-        let mut loop_counter: u32 = 0;
-        while Self::is_date_excluded(&date, exrules, exdates) {
-            if dates.is_empty() {
-                return (None, false);
-            }
-            // Prevent infinite loops
-            if limited {
-                loop_counter += 1;
-                if loop_counter >= MAX_ITER_LOOP {
-                    log::warn!(
-                        "Reached max loop counter (`{}`). \
-                See 'validator limits' in docs for more info.",
-                        MAX_ITER_LOOP
-                    );
-                    return (None, true);
-                }
-            }
-            date = dates.remove(dates.len() - 1);
+    /// Skips all the dates up to and including the last date returned at the `cursor`.
+    ///
+    /// The rrules and exrules are moved close to the cursor without generating the dates
+    /// before, see [`RRuleIter::seek`]. The dates at the instant of the cursor are
+    /// generated again to skip the ones that were already returned.
+    pub(crate) fn resume_from(mut self, cursor: &IterCursor, emitted: u64) -> Self {
+        self.emitted = emitted;
+        self.resumed_last = Some(cursor.last());
+        self.resumed_skip = cursor.emitted_at_last();
+        self.emitted_at_last = cursor.emitted_at_last();
+        let mut from = cursor.last();
+        if self.business_days.is_some() {
+            // Dates up to `MAX_SHIFT_DAYS` before the cursor can be moved after it.
+            from -= Duration::days(MAX_SHIFT_DAYS).num_seconds();
+        }
+        self.rdates.retain(|(date, _)| date.timestamp() >= from);
+        for exrule in &mut self.exrules {
+            exrule.seek(from);
         }
+        self.initialize(Some(from));
+        self
+    }
 
-        (Some(date), false)
+    /// Returns the excluded dates as well, with their exclusion.
+    pub(crate) fn with_exclusions(mut self) -> Self {
+        self.with_exclusions = true;
+        self
     }
 
-    fn generate(
-        rrule_iter: &mut RRuleIter,
-        exrules: &mut [RRuleIter],
-        exdates: &mut BTreeSet<i64>,
-        limited: bool,
-    ) -> (Option<DateTime>, bool) {
-        let mut date = match rrule_iter.next() {
-            Some(d) => d,
-            None => return (None, false),
-        };
-        let mut loop_counter: u32 = 0;
-        while Self::is_date_excluded(&date, exrules, exdates) {
-            // Prevent infinite loops
-            if limited {
-                loop_counter += 1;
-                if loop_counter >= MAX_ITER_LOOP {
-                    log::warn!(
-                        "Reached max loop counter (`{}`). \
-                    See 'validator limits' in docs for more info.",
-                        MAX_ITER_LOOP
-                    );
-                    return (None, true);
-                }
+    /// Returns the position of the iterator, or `None` if no date has been returned yet.
+    ///
+    /// The iteration can be continued after the last returned date with
+    /// [`RRuleSet::iter_from_cursor`].
+    #[must_use]
+    pub fn cursor(&self) -> Option<IterCursor> {
+        self.last
+            .as_ref()
+            .map(|last| IterCursor::new(last, self.emitted_at_last))
+    }
+
+    /// Returns the progress of the iterator, or `None` if no date has been returned yet.
+    ///
+    /// The iteration can be continued after the last returned date with
+    /// [`RRuleSet::iter_from_state`].
+    #[must_use]
+    pub fn state(&self) -> Option<IterState> {
+        self.last
+            .as_ref()
+            .map(|last| IterState::new(last, self.emitted, self.emitted_at_last))
+    }
+
+    /// Returns a reference to the next date without advancing the iterator.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    /// let mut iter = rrule_set.iter();
+    ///
+    /// let next = iter.peek().copied();
+    /// assert_eq!(iter.next(), next);
+    /// ```
+    pub fn peek(&mut self) -> Option<&DateTime> {
+        self.peek_nth(0)
+    }
+
+    /// Returns a reference to the `n`th (zero based) next date without advancing the iterator.
+    ///
+    /// The dates up to the `n`th one are generated and cached until they are returned.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3".parse().unwrap();
+    /// let mut iter = rrule_set.iter();
+    ///
+    /// let third = iter.peek_nth(2).copied();
+    /// assert_eq!(iter.peek_nth(3), None);
+    /// assert_eq!(iter.nth(2), third);
+    /// ```
+    pub fn peek_nth(&mut self, n: usize) -> Option<&DateTime> {
+        while self.peeked.len() <= n {
+            let occurrence = self.generate_next()?;
+            self.peeked.push_back(occurrence);
+        }
+        self.peeked.get(n).map(|occurrence| &occurrence.date)
+    }
+
+    /// Fills the `heads` with the first date of each rrule from the timestamp `from`.
+    fn initialize(&mut self, from: Option<i64>) {
+        for idx in 0..self.rrule_iters.len() {
+            if let Some(from) = from {
+                self.rrule_iters[idx].seek(from);
             }
+            let next_date = self.rrule_iters[idx]
+                .find(|date| !matches!(from, Some(from) if date.timestamp() < from));
+            if let Some(date) = next_date {
+                self.heads.push(Reverse((date, idx)));
+            }
+        }
+        self.initialized = true;
+    }
 
-            date = match rrule_iter.next() {
-                Some(d) => d,
-                None => return (None, false),
-            };
+    /// Pulls the next date from the rrule iterator at `idx` and adds it to the `heads`.
+    fn advance(&mut self, idx: usize) {
+        if let Some(date) = self.rrule_iters[idx].next() {
+            self.heads.push(Reverse((date, idx)));
         }
+    }
 
-        (Some(date), false)
+    /// Removes and returns the earliest date of all the rrules, rdates and moved recurrences.
+    /// On equal dates, the rdate or moved recurrence is returned first.
+    fn pop_earliest(&mut self) -> Option<(DateTime, OccurrenceSource)> {
+        let next_rrule_date = self.heads.peek().map(|Reverse((date, _))| *date);
+        match (next_rrule_date, self.rdates.last()) {
+            (Some(rrule_date), Some((rdate, _))) if rrule_date < *rdate => self.pop_rrule_date(),
+            (_, Some(_)) => self.rdates.pop(),
+            (Some(_), None) => self.pop_rrule_date(),
+            (None, None) => None,
+        }
     }
 
-    fn is_date_excluded(
+    fn pop_rrule_date(&mut self) -> Option<(DateTime, OccurrenceSource)> {
+        let Reverse((date, idx)) = self.heads.pop()?;
+        self.advance(idx);
+        Some((date, OccurrenceSource::RRule(idx)))
+    }
+
+    fn date_exclusion(
         date: &DateTime,
         exrules: &mut [RRuleIter],
-        exdates: &mut BTreeSet<i64>,
-    ) -> bool {
-        for exrule in exrules {
+        exdates: &mut BTreeMap<i64, Exclusion>,
+    ) -> Option<Exclusion> {
+        for (idx, exrule) in exrules.iter_mut().enumerate() {
             for exdate in exrule {
-                exdates.insert(exdate.timestamp());
+                exdates
+                    .entry(exdate.timestamp())
+                    .or_insert(Exclusion::ExRule(idx));
                 if exdate > *date {
                     break;
                 }
             }
         }
 
-        exdates.contains(&date.timestamp())
+        exdates.get(&date.timestamp()).copied()
     }
-}
 
-impl<'a> Iterator for RRuleSetIter<'a> {
-    type Item = DateTime;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut next_date: Option<(usize, DateTime)> = None;
-
-        // If there already was an error, return the error again.
-        if self.was_limited {
-            return None;
+    /// Returns the next date with its source, and its exclusion if the excluded dates are
+    /// returned as well.
+    pub(crate) fn next_occurrence(&mut self) -> Option<SourcedOccurrence> {
+        let occurrence = match self.peeked.pop_front() {
+            Some(occurrence) => occurrence,
+            None => self.generate_next()?,
+        };
+        if occurrence.excluded_by.is_none() {
+            let last = self
+                .last
+                .map_or(self.resumed_last, |last| Some(last.timestamp()));
+            if last == Some(occurrence.date.timestamp()) {
+                self.emitted_at_last += 1;
+            } else {
+                self.emitted_at_last = 1;
+            }
+            self.last = Some(occurrence.date);
+            self.emitted += 1;
         }
+        Some(occurrence)
+    }
 
-        for (i, rrule_iter) in self.rrule_iters.iter_mut().enumerate() {
-            let rrule_queue = self.queue.remove(&i);
-            let next_rrule_date = if let Some(d) = rrule_queue {
-                Some(d)
-            } else {
-                // should be method on self
-                let (date, was_limited) = Self::generate(
-                    rrule_iter,
-                    &mut self.exrules,
-                    &mut self.exdates,
-                    self.limited,
-                );
-
-                if was_limited {
-                    self.was_limited = true;
-                    return None;
+    /// Generates the next date, ignoring the `peeked` dates and the dates that were
+    /// returned before the iterator was resumed.
+    fn generate_next(&mut self) -> Option<SourcedOccurrence> {
+        loop {
+            let occurrence = self.generate_shifted()?;
+            let Some(resumed_last) = self.resumed_last else {
+                return Some(occurrence);
+            };
+            let timestamp = occurrence.date.timestamp();
+            if timestamp == resumed_last && self.resumed_skip > 0 {
+                if occurrence.excluded_by.is_none() {
+                    self.resumed_skip -= 1;
                 }
+            } else if timestamp >= resumed_last {
+                return Some(occurrence);
+            }
+        }
+    }
 
-                date
-            };
+    /// Generates the next date, moved to a business day if needed.
+    fn generate_shifted(&mut self) -> Option<SourcedOccurrence> {
+        let Some(business_days) = self.business_days else {
+            return self.generate_included();
+        };
 
-            if let Some(next_rrule_date) = next_rrule_date {
-                match next_date {
-                    None => next_date = Some((i, next_rrule_date)),
-                    Some((idx, date)) => {
-                        if date >= next_rrule_date {
-                            // Add previous date to its rrule queue
-                            self.queue.insert(idx, date);
-
-                            // Update next_date
-                            next_date = Some((i, next_rrule_date));
-                        } else {
-                            // Store for next iterations
-                            self.queue.insert(i, next_rrule_date);
+        loop {
+            if self.pending.is_none() {
+                self.pending = self.generate_included();
+            }
+            // A date is moved by at most `MAX_SHIFT_DAYS`, so no date that is moved later
+            // can be before the `pending` date minus that.
+            let bound = self
+                .pending
+                .map(|pending| pending.date - Duration::days(MAX_SHIFT_DAYS));
+            let earliest = self
+                .shifted
+                .peek()
+                .map(|Reverse(occurrence)| occurrence.date);
+            match (earliest, bound) {
+                (Some(earliest), Some(bound)) if earliest > bound => {}
+                (Some(_), _) => {
+                    let Reverse(mut occurrence) = self.shifted.pop()?;
+                    // Dates can be moved to the same business day.
+                    if occurrence.excluded_by.is_none()
+                        && self.duplicates == DuplicatePolicy::Merge
+                        && self.last_shifted.replace(occurrence.date) == Some(occurrence.date)
+                    {
+                        if !self.with_exclusions {
+                            continue;
                         }
+                        occurrence.excluded_by = Some(Exclusion::Duplicate);
                     }
+                    return Some(occurrence);
+                }
+                (None, None) => return None,
+                (None, Some(_)) => {}
+            }
+            // Excluded dates and moved recurrences are not moved.
+            let mut occurrence = self.pending.take()?;
+            if occurrence.excluded_by.is_none()
+                && !matches!(occurrence.source, OccurrenceSource::Override(_))
+            {
+                match business_days.shift(&occurrence.date, self.dst_gap, self.dst_fold) {
+                    Some(date) => occurrence.date = date,
+                    None => continue,
                 }
             }
+            self.shifted.push(Reverse(occurrence));
         }
+    }
 
-        let (generated_date, was_limited) = Self::generate_date(
-            &mut self.rdates,
-            &mut self.exrules,
-            &mut self.exdates,
-            self.limited,
-        );
-        if was_limited {
-            self.was_limited = true;
+    /// Generates the next date that is not excluded, or the next date if the excluded
+    /// dates are returned as well.
+    fn generate_included(&mut self) -> Option<SourcedOccurrence> {
+        // If there already was an error, return the error again.
+        if self.was_limited {
             return None;
         }
 
-        match generated_date {
-            Some(first_rdate) => {
-                let next_date = match next_date {
-                    Some(next_date) => {
-                        if next_date.1 >= first_rdate {
-                            // Add previous date to its rrule queue
-                            self.queue.insert(next_date.0, next_date.1);
+        if !self.initialized {
+            self.initialize(None);
+        }
 
-                            first_rdate
-                        } else {
-                            // add rdate back
-                            self.rdates.push(first_rdate);
+        let mut loop_counter: u32 = 0;
+        loop {
+            let (date, source) = self.pop_earliest()?;
+            let is_duplicate = self.duplicates == DuplicatePolicy::Merge
+                && self.last_generated.replace(date) == Some(date);
+            // Moved recurrences are only merged with the duplicates.
+            let exclusion = match source {
+                OccurrenceSource::Override(_) => None,
+                _ => {
+                    let exclusion =
+                        Self::date_exclusion(&date, &mut self.exrules, &mut self.exdates);
+                    let on_excluded_day = self.exdate_days.contains(&date.date_naive());
+                    on_excluded_day
+                        .then_some(Exclusion::ExDate)
+                        .or(exclusion)
+                        .or_else(|| is_holiday(self.holidays, &date).then_some(Exclusion::Holiday))
+                }
+            };
+            let excluded_by = exclusion.or_else(|| is_duplicate.then_some(Exclusion::Duplicate));
+            if excluded_by.is_none() || self.with_exclusions {
+                return Some(SourcedOccurrence {
+                    date,
+                    source,
+                    excluded_by,
+                });
+            }
 
-                            next_date.1
-                        }
-                    }
-                    None => first_rdate,
-                };
-                Some(next_date)
+            // Prevent infinite loops
+            if self.limited {
+                loop_counter += 1;
+                if loop_counter >= MAX_ITER_LOOP {
+                    log::warn!(
+                        "Reached max loop counter (`{}`). \
+                    See 'validator limits' in docs for more info.",
+                        MAX_ITER_LOOP
+                    );
+                    self.was_limited = true;
+                    return None;
+                }
             }
-            None => next_date.map(|d| d.1),
         }
     }
 }
 
+impl<'a> Iterator for RRuleSetIter<'a> {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_occurrence().map(|occurrence| occurrence.date)
+    }
+}
+
 impl<'a> IntoIterator for &'a RRuleSet {
     type Item = DateTime;
 
     type IntoIter = RRuleSetIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        // Sort in decreasing order
-        let mut rdates_sorted = self.rdate.clone();
-        rdates_sorted
-            .sort_by(|d1, d2| d2.partial_cmp(d1).expect("Could not order dates correctly"));
-
-        let limited = self.limited;
-
-        RRuleSetIter {
-            queue: HashMap::new(),
-            limited,
-            rrule_iters: self
-                .rrule
-                .iter()
-                .map(|rrule| rrule.iter_with_ctx(self.dt_start, limited))
-                .collect(),
-            rdates: rdates_sorted,
-            exrules: self
-                .exrule
-                .iter()
-                .map(|exrule| exrule.iter_with_ctx(self.dt_start, limited))
-                .collect(),
-            exdates: self.exdate.iter().map(DateTime::timestamp).collect(),
-            was_limited: false,
-        }
+        self.iter()
     }
 }
 
 impl<'a> WasLimited for RRuleSetIter<'a> {
     fn was_limited(&self) -> bool {
-        self.was_limited
+        self.was_limited || self.rrule_iters.iter().any(WasLimited::was_limited)
     }
 }
@@ -0,0 +1,93 @@
+use super::RRuleSetIter;
+use crate::core::DateTime;
+
+/// The operation of a [`SetOperationIter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+/// Iterator over the recurrences of the union, intersection or difference of two
+/// [`crate::RRuleSet`]s, created with [`crate::RRuleSet::union`],
+/// [`crate::RRuleSet::intersection`] and [`crate::RRuleSet::difference`].
+///
+/// The recurrences of both sets are compared lazily by their instant, regardless of their
+/// timezone, and every instant is returned once, in the timezone of the set it is first
+/// found in. Validation limits are only enforced on the sets on which
+/// [`crate::RRuleSet::limit`] was called.
+#[derive(Debug, Clone)]
+pub struct SetOperationIter<'a> {
+    operation: SetOperation,
+    left: RRuleSetIter<'a>,
+    right: RRuleSetIter<'a>,
+    /// The last date returned by the iterator.
+    last: Option<DateTime>,
+}
+
+impl<'a> SetOperationIter<'a> {
+    pub(crate) fn new(
+        operation: SetOperation,
+        left: RRuleSetIter<'a>,
+        right: RRuleSetIter<'a>,
+    ) -> Self {
+        Self {
+            operation,
+            left,
+            right,
+            last: None,
+        }
+    }
+
+    /// Returns the next date of the operation, which can be the same instant as the
+    /// last one.
+    fn next_candidate(&mut self) -> Option<DateTime> {
+        loop {
+            let left = self.left.peek().copied();
+            let right = self.right.peek().copied();
+            match self.operation {
+                SetOperation::Union => {
+                    return match (left, right) {
+                        (Some(left), Some(right)) if right < left => self.right.next(),
+                        (Some(_), _) => self.left.next(),
+                        (None, _) => self.right.next(),
+                    };
+                }
+                SetOperation::Intersection => {
+                    let (left, right) = (left?, right?);
+                    if left < right {
+                        self.left.next();
+                    } else if right < left {
+                        self.right.next();
+                    } else {
+                        return self.left.next();
+                    }
+                }
+                SetOperation::Difference => match (left?, right) {
+                    (left, Some(right)) if right < left => {
+                        self.right.next();
+                    }
+                    (left, Some(right)) if right == left => {
+                        self.left.next();
+                    }
+                    _ => return self.left.next(),
+                },
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for SetOperationIter<'a> {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let date = self.next_candidate()?;
+            if self.last != Some(date) {
+                self.last = Some(date);
+                return Some(date);
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use super::RRuleSetIter;
+use crate::core::DateTime;
+
+/// Where a recurrence of an [`crate::RRuleSet`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OccurrenceSource {
+    /// The rrule at this index of [`crate::RRuleSet::get_rrule`].
+    RRule(usize),
+    /// The rdate at this index of [`crate::RRuleSet::get_rdate`].
+    RDate(usize),
+    /// The moved recurrence at this index of [`crate::RRuleSet::get_overrides`].
+    Override(usize),
+}
+
+/// Why a recurrence of an [`crate::RRuleSet`] is left out.
+///
+/// A recurrence that is excluded in several ways has the first of them, in the order
+/// of the variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Exclusion {
+    /// An exdate of the set.
+    ExDate,
+    /// The exrule at this index of [`crate::RRuleSet::get_exrule`].
+    ExRule(usize),
+    /// A holiday of a calendar of [`crate::RRuleSet::exclude_holidays`].
+    Holiday,
+    /// An earlier recurrence at the same instant, see [`crate::DuplicatePolicy::Merge`].
+    Duplicate,
+    /// An override of the set, which moves or cancels the recurrence, see
+    /// [`crate::RecurrenceOverride`].
+    Overridden,
+}
+
+/// A recurrence of an [`crate::RRuleSet`] together with its source, returned by
+/// [`SourceIter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourcedOccurrence {
+    /// The date of the recurrence.
+    pub date: DateTime,
+    /// The rrule, rdate or override the recurrence comes from.
+    pub source: OccurrenceSource,
+    /// Why the recurrence is left out, if it is. Only set for iterators created with
+    /// [`SourceIter::with_exclusions`].
+    pub excluded_by: Option<Exclusion>,
+}
+
+/// Iterator over the recurrences of an [`crate::RRuleSet`] together with the rrule or
+/// rdate they come from, created with [`crate::RRuleSet::iter_with_sources`].
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{Exclusion, OccurrenceSource, RRuleSet};
+///
+/// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+///     RRULE:FREQ=DAILY;COUNT=2\n\
+///     RDATE:20210105T090000Z\n\
+///     EXDATE:20210102T090000Z"
+///     .parse()
+///     .unwrap();
+///
+/// let sources: Vec<_> = rrule_set.iter_with_sources().map(|occurrence| occurrence.source).collect();
+/// assert_eq!(sources, [OccurrenceSource::RRule(0), OccurrenceSource::RDate(0)]);
+///
+/// let excluded = rrule_set
+///     .iter_with_sources()
+///     .with_exclusions()
+///     .find(|occurrence| occurrence.excluded_by.is_some())
+///     .unwrap();
+/// assert_eq!(excluded.date.to_rfc3339(), "2021-01-02T09:00:00+00:00");
+/// assert_eq!(excluded.excluded_by, Some(Exclusion::ExDate));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SourceIter<'a> {
+    iter: RRuleSetIter<'a>,
+}
+
+impl<'a> SourceIter<'a> {
+    pub(crate) fn new(iter: RRuleSetIter<'a>) -> Self {
+        Self { iter }
+    }
+
+    /// Returns the recurrences that are left out as well, with the exclusion that leaves
+    /// them out in [`SourcedOccurrence::excluded_by`], to find out why a recurrence is
+    /// missing.
+    #[must_use]
+    pub fn with_exclusions(self) -> Self {
+        Self {
+            iter: self.iter.with_exclusions(),
+        }
+    }
+}
+
+impl<'a> Iterator for SourceIter<'a> {
+    type Item = SourcedOccurrence;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_occurrence()
+    }
+}
@@ -0,0 +1,94 @@
+use super::RRuleSetIter;
+use crate::core::DateTime;
+use chrono::Utc;
+use futures_core::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::time::Sleep;
+
+/// Stream over all the dates in an [`crate::RRuleSet`].
+///
+/// Created by [`RRuleSetIter::into_stream`] or [`crate::RRuleSet::stream`].
+#[derive(Debug, Clone)]
+pub struct RRuleSetStream<'a> {
+    iter: RRuleSetIter<'a>,
+}
+
+impl<'a> Stream for RRuleSetStream<'a> {
+    type Item = DateTime;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.get_mut().iter.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Stream over the dates in an [`crate::RRuleSet`] that yields each date once its
+/// wall-clock time has arrived.
+///
+/// Dates that are already in the past when the stream is created are skipped.
+/// Created by [`RRuleSetIter::into_timer_stream`] or [`crate::RRuleSet::timer_stream`].
+#[derive(Debug)]
+pub struct RRuleSetTimerStream<'a> {
+    iter: RRuleSetIter<'a>,
+    /// Dates before this are skipped.
+    not_before: chrono::DateTime<Utc>,
+    /// The next date and the timer that fires when it arrives.
+    next: Option<(DateTime, Pin<Box<Sleep>>)>,
+}
+
+impl<'a> Stream for RRuleSetTimerStream<'a> {
+    type Item = DateTime;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.next.is_none() {
+            let not_before = this.not_before;
+            let Some(date) = this.iter.find(|date| *date >= not_before) else {
+                return Poll::Ready(None);
+            };
+            // Dates that have arrived in the meantime are yielded right away.
+            let delay = (date.with_timezone(&Utc) - Utc::now())
+                .to_std()
+                .unwrap_or_default();
+            this.next = Some((date, Box::pin(tokio::time::sleep(delay))));
+        }
+
+        let Some((date, sleep)) = &mut this.next else {
+            return Poll::Ready(None);
+        };
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                let date = *date;
+                this.next = None;
+                Poll::Ready(Some(date))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<'a> RRuleSetIter<'a> {
+    /// Turns the iterator into a [`Stream`] of the remaining dates.
+    #[must_use]
+    pub fn into_stream(self) -> RRuleSetStream<'a> {
+        RRuleSetStream { iter: self }
+    }
+
+    /// Turns the iterator into a [`Stream`] that yields each of the remaining dates
+    /// once its wall-clock time has arrived. Dates in the past are skipped.
+    ///
+    /// Must be polled from within a Tokio runtime.
+    #[must_use]
+    pub fn into_timer_stream(self) -> RRuleSetTimerStream<'a> {
+        RRuleSetTimerStream {
+            iter: self,
+            not_before: Utc::now(),
+            next: None,
+        }
+    }
+}
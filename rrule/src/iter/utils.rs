@@ -1,7 +1,9 @@
 use std::ops;
 
 use crate::core::{duration_from_midnight, DateTime, Tz};
-use chrono::{Date, NaiveTime, TimeZone, Utc};
+use crate::{DstFoldPolicy, DstGapPolicy};
+#[allow(deprecated)]
+use chrono::{Date, Duration, LocalResult, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
 
 const UTC: Tz = Tz::UTC;
 
@@ -73,20 +75,76 @@ where
     }
 }
 
-pub(crate) fn add_time_to_date(date: Date<Tz>, time: NaiveTime) -> Option<DateTime> {
-    if let Some(dt) = date.and_time(time) {
-        return Some(dt);
+/// Combines the `date` and `time` in the timezone of the `date`. The local times that
+/// don't exist or are ambiguous because of daylight saving time are resolved with the
+/// `dst_gap` and `dst_fold` policies, so there can be no, one or two datetimes.
+#[allow(deprecated)]
+pub(crate) fn add_time_to_date(
+    date: Date<Tz>,
+    time: NaiveTime,
+    dst_gap: DstGapPolicy,
+    dst_fold: DstFoldPolicy,
+) -> impl Iterator<Item = DateTime> {
+    let local = date.naive_local().and_time(time);
+    let dates = match date.timezone().from_local_datetime(&local) {
+        LocalResult::Single(dt) => [Some(dt), None],
+        LocalResult::Ambiguous(earlier, later) => match dst_fold {
+            DstFoldPolicy::Earlier => [Some(earlier), None],
+            DstFoldPolicy::Later => [Some(later), None],
+            DstFoldPolicy::Both => [Some(earlier), Some(later)],
+        },
+        LocalResult::None => [time_in_gap(date, time, local, dst_gap), None],
+    };
+    dates.into_iter().flatten()
+}
+
+/// Resolves a local time that doesn't exist because it's in the gap of a daylight
+/// saving time transition with the `dst_gap` policy.
+#[allow(deprecated)]
+fn time_in_gap(
+    date: Date<Tz>,
+    time: NaiveTime,
+    local: NaiveDateTime,
+    dst_gap: DstGapPolicy,
+) -> Option<DateTime> {
+    if dst_gap == DstGapPolicy::Skip {
+        return None;
     }
-    // If the day is a daylight saving time, the above code might now work, and we
-    // can try to get a valid datetime by adding the `time` as a duration instead.
+    // Adding the `time` as a duration to the start of the day interprets it with the
+    // offset before the gap.
     let dt = date.and_hms_opt(0, 0, 0)?;
     let day_duration = duration_from_midnight(time);
-    dt.checked_add_signed(day_duration)
+    let dt = dt.checked_add_signed(day_duration)?;
+    if dst_gap == DstGapPolicy::ShiftForward {
+        return Some(transition_before(dt, local));
+    }
+    Some(dt)
+}
+
+/// Returns the time of the transition that causes the gap in which the `local` time is,
+/// given `dt`, which is the `local` time interpreted with the offset before the gap.
+fn transition_before(dt: DateTime, local: NaiveDateTime) -> DateTime {
+    let tz = dt.timezone();
+    let offset = dt.offset().fix();
+    // The transition is after the `local` time with the offset after the gap,
+    // and at or before `dt`.
+    let mut before = local - Duration::seconds(offset.local_minus_utc().into());
+    let mut after = dt.naive_utc();
+    while after - before > Duration::seconds(1) {
+        let mid = before + (after - before) / 2;
+        if tz.offset_from_utc_datetime(&mid).fix() == offset {
+            after = mid;
+        } else {
+            before = mid;
+        }
+    }
+    tz.from_utc_datetime(&after)
 }
 
 #[cfg(test)]
 mod test {
 
+    #[cfg(feature = "chrono-tz")]
     use chrono::Duration;
 
     use super::*;
@@ -137,6 +195,8 @@ mod test {
     }
 
     #[test]
+    #[cfg(feature = "chrono-tz")]
+    #[allow(deprecated)]
     fn adds_time_to_date() {
         const AMERICA_NEW_YORK: Tz = Tz::America__New_York;
         const AMERICA_VANCOUVER: Tz = Tz::America__Vancouver;
@@ -171,7 +231,8 @@ mod test {
         ];
 
         for (date, time, expected_output) in tests {
-            let res = add_time_to_date(date, time);
+            let res =
+                add_time_to_date(date, time, DstGapPolicy::Naive, DstFoldPolicy::Earlier).next();
             assert_eq!(res, expected_output);
         }
     }
@@ -73,7 +73,7 @@
 //! let after = Tz::UTC.with_ymd_and_hms(2012, 2, 1,10, 0, 0).unwrap();
 //! let before = Tz::UTC.with_ymd_and_hms(2012, 4, 1,9, 0, 0).unwrap();
 //!
-//! let rrule = rrule.after(after).before(before);
+//! let rrule = rrule.only_after(after).only_before(before);
 //! let result = rrule.all(100);
 //!
 //! assert_eq!(
@@ -87,6 +87,12 @@
 //!
 //! Note: All the generated recurrence will be in the same time zone as the `dt_start` property.
 //!
+//! # Sharing between threads
+//! [`RRule`], [`RRuleSet`] and their iterators are `Send` and `Sync`, so parsed rules can
+//! be kept in shared state, e.g. in an `Arc`, and expanded from many threads. The
+//! [`HolidayCalendar`]s of a set and the [`TzResolver`] of a [`ParserConfig`] are required
+//! to be `Send` and `Sync` for this.
+//!
 
 #![forbid(unsafe_code)]
 #![deny(clippy::all)]
@@ -100,8 +106,24 @@ mod parser;
 mod tests;
 mod validator;
 
-pub use crate::core::{Frequency, NWeekday, RRule, RRuleResult, RRuleSet, Tz};
+pub use crate::core::{
+    build_rruleset_from_vevent, build_rruleset_with_warnings, build_rrulesets, parse_calendar,
+    BusinessDayPolicy, DstFoldPolicy, DstGapPolicy, DuplicatePolicy, EventDate, EventDuration,
+    FloatingRRuleSet, Frequency, HolidayCalendar, Language, NWeekday, NoFreq, OccurrenceDiff,
+    OccurrenceStats, RRule, RRuleBuilder, RRuleResult, RRuleSet, RScale, RecurrenceOverride,
+    RecurringEvent, Skip, TextLocale, Tz, Unbounded, VTimezone, WithCount, WithFreq, WithUntil,
+};
+#[cfg(feature = "serde")]
+pub use crate::core::{JCalRecur, RRuleOptions};
 pub use crate::core::{Unvalidated, Validated};
 pub use chrono::Weekday;
 pub use error::{ParseError, RRuleError, ValidationError};
-pub use iter::RRuleSetIter;
+pub use iter::{
+    EventDateIter, Exclusion, Explanation, IntervalIter, IterCursor, IterState, Occurrence,
+    OccurrenceIter, OccurrenceSource, RRuleIter, RRuleRevIter, RRuleSetIter, RulePart,
+    SetOperationIter, SourceIter, SourcedOccurrence,
+};
+#[cfg(feature = "tokio")]
+pub use iter::{RRuleSetStream, RRuleSetTimerStream};
+pub use parser::{ChronoTzResolver, Compat, ParseWarning, ParserConfig, ParserLimits, TzResolver};
+pub use validator::LintWarning;
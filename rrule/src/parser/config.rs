@@ -0,0 +1,148 @@
+//! Options for parsing rrule input.
+use std::sync::Arc;
+
+use super::{ChronoTzResolver, ParseError, TzResolver};
+
+/// How strictly the input is checked against the iCalendar specification.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compat {
+    /// Rejects input that doesn't follow the specification.
+    #[default]
+    Strict,
+    /// Accepts known quirks of calendar producers, like Outlook:
+    /// - Rule parts that are given more than once, e.g. `FREQ=DAILY;FREQ=WEEKLY`. The last one wins.
+    /// - Rule parts without a value, e.g. `WKST=`. They are ignored.
+    /// - A `VALUE` parameter on `DTSTART` that doesn't match the value,
+    ///   e.g. `DTSTART;VALUE=DATE:20210101T090000`.
+    /// - Full and three letter weekday and month names in `BYDAY`, `WKST` and `BYMONTH`,
+    ///   e.g. `BYDAY=MONDAY,FRI` or `BYMONTH=JAN,February`.
+    /// - An `UNTIL` in local time while `DTSTART` is in UTC or has a timezone, which
+    ///   requires `UNTIL` to be in UTC. It is interpreted in the timezone of `DTSTART`.
+    /// - `FREQ=QUARTERLY` and `FREQ=SEMIANNUALLY` of finance software. They are read as
+    ///   `FREQ=MONTHLY` with an interval of 3 and 6 months, and written back that way.
+    Lenient,
+}
+
+/// Options for parsing an [`crate::RRuleSet`] or [`crate::RRule`].
+///
+/// Whitespace around lines and rule parts, blank lines, trailing semicolons and
+/// date values without a `VALUE=DATE` parameter are accepted in every mode.
+#[derive(Debug, Clone)]
+pub struct ParserConfig {
+    /// How strictly the input is checked.
+    pub compat: Compat,
+    /// The limits on the size of the input.
+    pub limits: ParserLimits,
+    /// Resolves the `TZID` parameters of the input. Timezones defined by `VTIMEZONE`
+    /// components of the input are used for the `TZID`s it doesn't resolve.
+    pub resolver: Arc<dyn TzResolver>,
+    /// Moves the month days that don't exist, like the 31st of a month with 30 days, to
+    /// the last day of the month, as Google Calendar does for monthly rules on the 31st.
+    ///
+    /// Rules without a `SKIP` rule part get `SKIP=BACKWARD`, see [`crate::Skip`].
+    pub clamp_month_days: bool,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            compat: Compat::default(),
+            limits: ParserLimits::default(),
+            resolver: Arc::new(ChronoTzResolver),
+            clamp_month_days: false,
+        }
+    }
+}
+
+impl ParserConfig {
+    /// Creates a config with the [`Compat::Lenient`] mode.
+    #[must_use]
+    pub fn lenient() -> Self {
+        Self {
+            compat: Compat::Lenient,
+            ..Default::default()
+        }
+    }
+}
+
+/// Limits on the size of the input, so that untrusted input can't make the parser
+/// spend a lot of time or memory, e.g. with a `BYSECOND` list of millions of values.
+///
+/// Input that exceeds a limit is rejected with [`ParseError::LimitExceeded`].
+/// There are no limits by default.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{ParseError, ParserConfig, ParserLimits, RRuleError, RRuleSet};
+///
+/// let config = ParserConfig {
+///     limits: ParserLimits::untrusted(),
+///     ..Default::default()
+/// };
+/// let by_second = vec!["1"; 1000].join(",");
+/// let input = format!("DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYSECOND={}", by_second);
+/// assert!(matches!(
+///     RRuleSet::parse_with_config(&input, &config),
+///     Err(RRuleError::ParserError(ParseError::LimitExceeded { .. }))
+/// ));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParserLimits {
+    /// The maximum number of bytes of a content line, after unfolding.
+    pub max_line_length: Option<usize>,
+    /// The maximum number of content lines.
+    pub max_content_lines: Option<usize>,
+    /// The maximum number of comma separated values of a rule part, like `BYSECOND`,
+    /// or of an `RDATE` or `EXDATE` line.
+    pub max_list_values: Option<usize>,
+}
+
+impl ParserLimits {
+    /// Returns limits that are large enough for any sensible input: lines of 4096 bytes,
+    /// 100 content lines and 400 values per list.
+    #[must_use]
+    pub fn untrusted() -> Self {
+        Self {
+            max_line_length: Some(4096),
+            max_content_lines: Some(100),
+            max_list_values: Some(400),
+        }
+    }
+
+    pub(crate) fn check_line_length(&self, line: &str) -> Result<(), ParseError> {
+        check_limit("bytes per line", self.max_line_length, line.len())
+    }
+
+    pub(crate) fn check_content_lines(&self, count: usize) -> Result<(), ParseError> {
+        check_limit("content lines", self.max_content_lines, count)
+    }
+
+    /// Checks the number of values in `value`, which is a list of rule parts if `is_rule`,
+    /// in which case the values of each rule part are checked, and a list of dates otherwise.
+    pub(crate) fn check_list_values(&self, value: &str, is_rule: bool) -> Result<(), ParseError> {
+        if is_rule {
+            value
+                .split(';')
+                .filter_map(|part| part.split_once('='))
+                .try_for_each(|(_, values)| self.check_list_values(values, false))
+        } else {
+            check_limit(
+                "values per list",
+                self.max_list_values,
+                value.split(',').count(),
+            )
+        }
+    }
+}
+
+fn check_limit(limit: &str, max: Option<usize>, found: usize) -> Result<(), ParseError> {
+    match max {
+        Some(max) if found > max => Err(ParseError::LimitExceeded {
+            limit: limit.into(),
+            max,
+            found,
+        }),
+        _ => Ok(()),
+    }
+}
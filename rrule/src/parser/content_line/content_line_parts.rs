@@ -1,4 +1,8 @@
-use crate::parser::{regex::get_property_name, ParseError};
+use crate::parser::{
+    lexer::get_property_name,
+    utils::{find_unquoted, rfind_unquoted},
+    ParseError,
+};
 
 use super::PropertyName;
 
@@ -23,20 +27,30 @@ impl<'a> ContentLineCaptures<'a> {
             }),
             property_name => {
                 let mut parameters = None;
-                if line.starts_with(&format!("{};", property_name)) {
-                    let only_colon_idx = line.find(':');
-                    if let Some(only_colon_idx) = only_colon_idx {
-                        parameters =
-                            Some(&line[property_name.to_string().len() + 1..only_colon_idx]);
+                let name = property_name.as_str();
+                // The property name is case-insensitive.
+                let has_parameters = line
+                    .get(..name.len())
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(name))
+                    && line[name.len()..].starts_with(';');
+                // Parameter values can contain a colon, e.g. `TZID="UTC+05:30"` or even
+                // `TZID=UTC+05:30`, but the values of the properties never do.
+                let colon_idx = if has_parameters {
+                    rfind_unquoted(line, ':')
+                } else {
+                    find_unquoted(line, ':')
+                };
+                if has_parameters {
+                    if let Some(colon_idx) = colon_idx {
+                        parameters = Some(&line[name.len() + 1..colon_idx]);
                     }
                 }
 
                 Ok(Self {
                     property_name,
                     parameters,
-                    value: line
-                        .split_once(':')
-                        .map(|(_name, val)| val)
+                    value: colon_idx
+                        .map(|colon_idx| line[colon_idx + 1..].trim())
                         .unwrap_or_default(),
                 })
             }
@@ -108,6 +122,14 @@ mod tests {
                     value: "FREQ=DAILY;COUNT=10",
                 },
             ),
+            (
+                "dtstart;tzid=Europe/Paris:20120201t023000",
+                ContentLineCaptures {
+                    property_name: PropertyName::DtStart,
+                    parameters: Some("tzid=Europe/Paris"),
+                    value: "20120201t023000",
+                },
+            ),
         ];
         for (input, expected_output) in tests {
             let output = ContentLineCaptures::new(input);
@@ -1,14 +1,16 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, str::FromStr};
+
+use chrono::NaiveDate;
 
 use crate::{
-    core::DateTime,
+    core::{DateTime, EventDuration, Tz},
     parser::{
         datetime::{datestring_to_date, parse_timezone},
-        ParseError,
+        ChronoTzResolver, Compat, ParseError, TzResolver,
     },
 };
 
-use super::{content_line_parts::ContentLineCaptures, parameters::parse_parameters};
+use super::{content_line_parts::ContentLineCaptures, parameters::parse_parameters, ContentLine};
 
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub enum DateParameter {
@@ -20,12 +22,13 @@ impl FromStr for DateParameter {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let param = match &s.to_uppercase()[..] {
-            "TZID" => Self::Timezone,
-            "VALUE" => Self::Value,
-            _ => return Err(ParseError::UnrecognizedParameter(s.into())),
-        };
-        Ok(param)
+        if s.eq_ignore_ascii_case("TZID") {
+            Ok(Self::Timezone)
+        } else if s.eq_ignore_ascii_case("VALUE") {
+            Ok(Self::Value)
+        } else {
+            Err(ParseError::UnrecognizedParameter(s.into()))
+        }
     }
 }
 
@@ -33,29 +36,134 @@ impl<'a> TryFrom<ContentLineCaptures<'a>> for Vec<DateTime> {
     type Error = ParseError;
 
     fn try_from(value: ContentLineCaptures) -> Result<Self, Self::Error> {
-        let parameters: HashMap<DateParameter, String> = value
-            .parameters
-            .map(parse_parameters)
-            .transpose()?
-            .unwrap_or_default();
-
-        let timezone = parameters
-            .get(&DateParameter::Timezone)
-            .map(|tz| parse_timezone(tz))
-            .transpose()?;
-        let property = format!("{}", value.property_name);
-
-        let mut dates = vec![];
-        for val in value.value.split(',') {
-            if val.is_empty() {
-                continue;
-            }
-            let datetime = datestring_to_date(val, timezone, &property)?;
-            dates.push(datetime);
-        }
+        parse_date_content_line(value, Compat::Strict, &ChronoTzResolver)
+    }
+}
+
+/// Parses an `RDATE` or `EXDATE` content line.
+pub(crate) fn parse_date_content_line(
+    value: ContentLineCaptures,
+    compat: Compat,
+    resolver: &dyn TzResolver,
+) -> Result<Vec<DateTime>, ParseError> {
+    let parameters = parse_date_parameters(&value, compat)?;
+    let timezone = parse_timezone_parameter(&parameters, resolver)?;
+    let property = value.property_name.as_str();
+    parse_values(value.value, |val| {
+        datestring_to_date(val, timezone, property)
+    })
+}
+
+/// Parses an `RDATE` content line, which has periods instead of dates with a
+/// `VALUE=PERIOD` parameter.
+pub(crate) fn parse_rdate_content_line(
+    value: ContentLineCaptures,
+    compat: Compat,
+    resolver: &dyn TzResolver,
+) -> Result<ContentLine, ParseError> {
+    let parameters = parse_date_parameters(&value, compat)?;
+    let is_period = parameters
+        .get(&DateParameter::Value)
+        .is_some_and(|value| value.eq_ignore_ascii_case("PERIOD"));
+    if is_period {
+        let timezone = parse_timezone_parameter(&parameters, resolver)?;
+        return parse_values(value.value, |val| parse_period(val, timezone))
+            .map(ContentLine::RDatePeriods);
+    }
+    if let Some(days) = parse_days(&value, &parameters) {
+        parse_timezone_parameter(&parameters, resolver)?;
+        return days.map(ContentLine::RDateDays);
+    }
+    parse_date_content_line(value, compat, resolver).map(ContentLine::RDate)
+}
 
-        Ok(dates)
+/// Parses an `EXDATE` content line, which has whole days instead of dates with a
+/// `VALUE=DATE` parameter.
+pub(crate) fn parse_exdate_content_line(
+    value: ContentLineCaptures,
+    compat: Compat,
+    resolver: &dyn TzResolver,
+) -> Result<ContentLine, ParseError> {
+    let parameters = parse_date_parameters(&value, compat)?;
+    if let Some(days) = parse_days(&value, &parameters) {
+        parse_timezone_parameter(&parameters, resolver)?;
+        return days.map(ContentLine::ExDateDays);
     }
+    parse_date_content_line(value, compat, resolver).map(ContentLine::ExDate)
+}
+
+/// Parses the values of a content line with a `VALUE=DATE` parameter as days, or returns
+/// `None` if it doesn't have the parameter or a value has a time.
+fn parse_days(
+    value: &ContentLineCaptures,
+    parameters: &HashMap<DateParameter, Cow<str>>,
+) -> Option<Result<Vec<NaiveDate>, ParseError>> {
+    let is_date = parameters
+        .get(&DateParameter::Value)
+        .is_some_and(|value| value.eq_ignore_ascii_case("DATE"));
+    let is_day = |val: &str| val.len() <= 8;
+    if !is_date || !value.value.split(',').map(str::trim).all(is_day) {
+        return None;
+    }
+    let property = value.property_name.as_str();
+    Some(parse_values(value.value, |val| {
+        NaiveDate::parse_from_str(val, "%Y%m%d").map_err(|_| ParseError::InvalidDateTime {
+            value: val.into(),
+            property: property.into(),
+        })
+    }))
+}
+
+fn parse_date_parameters<'a>(
+    value: &ContentLineCaptures<'a>,
+    compat: Compat,
+) -> Result<HashMap<DateParameter, Cow<'a, str>>, ParseError> {
+    Ok(value
+        .parameters
+        .map(|parameters| parse_parameters(parameters, compat))
+        .transpose()?
+        .unwrap_or_default())
+}
+
+fn parse_timezone_parameter(
+    parameters: &HashMap<DateParameter, Cow<str>>,
+    resolver: &dyn TzResolver,
+) -> Result<Option<Tz>, ParseError> {
+    parameters
+        .get(&DateParameter::Timezone)
+        .map(|tz| parse_timezone(tz, resolver))
+        .transpose()
+}
+
+/// Parses the comma separated values of a content line, skipping the empty ones.
+fn parse_values<T>(
+    values: &str,
+    parse: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    values
+        .split(',')
+        .map(str::trim)
+        .filter(|val| !val.is_empty())
+        .map(parse)
+        .collect()
+}
+
+/// Parses a `PERIOD` value, which is a start and either an end or a duration separated
+/// by a `/`, like `19970101T180000Z/19970102T070000Z` or `19970101T180000Z/PT5H30M`.
+///
+/// The end of a period is converted to a duration in exact seconds.
+fn parse_period(val: &str, timezone: Option<Tz>) -> Result<(DateTime, EventDuration), ParseError> {
+    let invalid = || ParseError::InvalidPeriod(val.into());
+    let (start, end) = val.split_once('/').ok_or_else(invalid)?;
+    let start = datestring_to_date(start, timezone, "RDATE").map_err(|_| invalid())?;
+    let duration = if end.starts_with(['P', 'p', '+']) {
+        end.parse().map_err(|_| invalid())?
+    } else {
+        let end = datestring_to_date(end, timezone, "RDATE").map_err(|_| invalid())?;
+        let seconds = (end - start).num_seconds();
+        EventDuration::new(0, u64::try_from(seconds).map_err(|_| invalid())?)
+    };
+    Ok((start, duration))
 }
 
 #[cfg(test)]
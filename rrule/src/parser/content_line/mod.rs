@@ -7,11 +7,17 @@ mod start_date_content_line;
 use std::fmt::Display;
 use std::str::FromStr;
 
+use chrono::NaiveDate;
+
 use crate::core::DateTime;
-use crate::RRule;
 use crate::Unvalidated;
+use crate::{EventDuration, RRule, RecurrenceOverride};
 
 pub(crate) use content_line_parts::ContentLineCaptures;
+pub(crate) use date_content_line::{
+    parse_date_content_line, parse_exdate_content_line, parse_rdate_content_line,
+};
+pub(crate) use rule_content_line::parse_rule_content_line;
 pub(crate) use start_date_content_line::StartDateContentLine;
 
 use super::ParseError;
@@ -22,6 +28,15 @@ pub(crate) enum ContentLine {
     ExRule(RRule<Unvalidated>),
     ExDate(Vec<DateTime>),
     RDate(Vec<DateTime>),
+    /// The whole days of an `RDATE;VALUE=DATE`.
+    RDateDays(Vec<NaiveDate>),
+    /// The whole days of an `EXDATE;VALUE=DATE`.
+    ExDateDays(Vec<NaiveDate>),
+    /// The start and the duration of the periods of an `RDATE;VALUE=PERIOD`.
+    RDatePeriods(Vec<(DateTime, EventDuration)>),
+    Override(RecurrenceOverride),
+    DtEnd(DateTime),
+    Duration(EventDuration),
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -31,17 +46,41 @@ pub(crate) enum PropertyName {
     ExDate,
     RDate,
     DtStart,
+    RecurrenceId,
+    DtEnd,
+    Duration,
+}
+
+impl PropertyName {
+    const ALL: [Self; 8] = [
+        Self::RRule,
+        Self::ExRule,
+        Self::ExDate,
+        Self::RDate,
+        Self::DtStart,
+        Self::RecurrenceId,
+        Self::DtEnd,
+        Self::Duration,
+    ];
+
+    /// Returns the name of the property in uppercase, like `RRULE`.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::RRule => "RRULE",
+            Self::ExRule => "EXRULE",
+            Self::ExDate => "EXDATE",
+            Self::RDate => "RDATE",
+            Self::DtStart => "DTSTART",
+            Self::RecurrenceId => "RECURRENCE-ID",
+            Self::DtEnd => "DTEND",
+            Self::Duration => "DURATION",
+        }
+    }
 }
 
 impl Display for PropertyName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Self::RRule => write!(f, "RRULE"),
-            Self::ExRule => write!(f, "EXRULE"),
-            Self::ExDate => write!(f, "EXDATE"),
-            Self::RDate => write!(f, "RDATE"),
-            Self::DtStart => write!(f, "DTSTART"),
-        }
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -49,14 +88,9 @@ impl FromStr for PropertyName {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let name = match &s[..].to_uppercase()[..] {
-            "RRULE" => Self::RRule,
-            "EXRULE" => Self::ExRule,
-            "RDATE" => Self::RDate,
-            "EXDATE" => Self::ExDate,
-            "DTSTART" => Self::DtStart,
-            _ => return Err(ParseError::UnrecognizedPropertyName(s.into())),
-        };
-        Ok(name)
+        Self::ALL
+            .into_iter()
+            .find(|name| s.eq_ignore_ascii_case(name.as_str()))
+            .ok_or_else(|| ParseError::UnrecognizedPropertyName(s.into()))
     }
 }
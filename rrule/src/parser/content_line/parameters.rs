@@ -1,23 +1,33 @@
-use std::{collections::HashMap, hash::Hash, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, hash::Hash, str::FromStr};
 
-use crate::parser::ParseError;
+use crate::parser::{utils::split_unquoted, Compat, ParseError};
 
 /// Parses a string of semicolon seperated key value pairs into a `HashMap` with
-/// predefined keys. It will return an error if duplicate keys are found.
+/// predefined keys. It will return an error if duplicate keys are found,
+/// unless `compat` is lenient, in which case the last value wins.
+/// Whitespace around the keys and values, and double quotes around the values are ignored.
+/// The values borrow from `raw_parameters`.
 pub(super) fn parse_parameters<K: FromStr<Err = ParseError> + Hash + Eq>(
     raw_parameters: &str,
-) -> Result<HashMap<K, String>, ParseError> {
+    compat: Compat,
+) -> Result<HashMap<K, Cow<'_, str>>, ParseError> {
     let mut parameters = HashMap::new();
-    for raw_parameter in raw_parameters.split(';') {
+    for raw_parameter in split_unquoted(raw_parameters, ';') {
+        let raw_parameter = raw_parameter.trim();
         if raw_parameter.is_empty() {
             continue;
         }
         let (raw_parameter, value) = raw_parameter
             .split_once('=')
             .ok_or_else(|| ParseError::InvalidParameterFormat(raw_parameter.into()))?;
+        let (raw_parameter, value) = (raw_parameter.trim(), value.trim());
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
         let parameter = K::from_str(raw_parameter)?;
 
-        if parameters.insert(parameter, value.into()).is_some() {
+        if parameters.insert(parameter, value.into()).is_some() && compat == Compat::Strict {
             return Err(ParseError::DuplicateProperty(raw_parameter.into()));
         }
     }
@@ -35,20 +45,35 @@ mod tests {
         let tests = [
             (
                 "VALUE=DATE",
-                [(DateParameter::Value, "DATE".to_string())]
+                [(DateParameter::Value, Cow::from("DATE"))]
                     .into_iter()
                     .collect::<HashMap<_, _>>(),
             ),
             (
                 "TZID=Europe/London",
-                [(DateParameter::Timezone, "Europe/London".to_string())]
+                [(DateParameter::Timezone, Cow::from("Europe/London"))]
                     .into_iter()
                     .collect::<HashMap<_, _>>(),
             ),
+            (
+                "TZID=\"UTC+05:30\"",
+                [(DateParameter::Timezone, Cow::from("UTC+05:30"))]
+                    .into_iter()
+                    .collect::<HashMap<_, _>>(),
+            ),
+            (
+                " TZID = Europe/London ; VALUE=DATE ;",
+                [
+                    (DateParameter::Timezone, Cow::from("Europe/London")),
+                    (DateParameter::Value, Cow::from("DATE")),
+                ]
+                .into_iter()
+                .collect::<HashMap<_, _>>(),
+            ),
         ];
 
         for (input, expected_output) in tests {
-            let output = parse_parameters(input);
+            let output = parse_parameters(input, Compat::Strict);
             assert_eq!(output, Ok(expected_output));
         }
     }
@@ -61,7 +86,8 @@ mod tests {
         )];
 
         for (input, expected_output) in tests {
-            let output: Result<HashMap<DateParameter, String>, _> = parse_parameters(input);
+            let output: Result<HashMap<DateParameter, Cow<str>>, _> =
+                parse_parameters(input, Compat::Strict);
             assert_eq!(output, Err(expected_output));
         }
     }
@@ -74,7 +100,8 @@ mod tests {
         )];
 
         for (input, expected_output) in tests {
-            let output: Result<HashMap<DateParameter, String>, _> = parse_parameters(input);
+            let output: Result<HashMap<DateParameter, Cow<str>>, _> =
+                parse_parameters(input, Compat::Strict);
             assert_eq!(output, Err(expected_output));
         }
     }
@@ -87,11 +114,24 @@ mod tests {
         )];
 
         for (input, expected_output) in tests {
-            let output: Result<HashMap<DateParameter, String>, _> = parse_parameters(input);
+            let output: Result<HashMap<DateParameter, Cow<str>>, _> =
+                parse_parameters(input, Compat::Strict);
             assert_eq!(output, Err(expected_output));
         }
     }
 
+    #[test]
+    fn keeps_last_duplicate_parameter_in_lenient_mode() {
+        let output: HashMap<DateParameter, Cow<str>> =
+            parse_parameters("TZID=Europe/London;TZID=Europe/Berlin", Compat::Lenient).unwrap();
+        assert_eq!(
+            output,
+            [(DateParameter::Timezone, "Europe/Berlin".into())]
+                .into_iter()
+                .collect()
+        );
+    }
+
     #[test]
     fn does_not_attempt_to_parse_empty_parameters() {
         let tests = [
@@ -100,7 +140,8 @@ mod tests {
         ];
 
         for (input, expected_output) in tests {
-            let output: Result<HashMap<DateParameter, String>, _> = parse_parameters(input);
+            let output: Result<HashMap<DateParameter, Cow<str>>, _> =
+                parse_parameters(input, Compat::Strict);
             assert_eq!(output, Ok(expected_output));
         }
     }
@@ -1,19 +1,42 @@
-use std::{collections::HashMap, marker::PhantomData, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, marker::PhantomData, str::FromStr};
 
 use chrono::Weekday;
 
 use crate::{
     parser::{
         content_line::parameters::parse_parameters,
-        datetime::{datestring_to_date, parse_weekdays},
+        datetime::{datestring_to_date, parse_timezone, parse_weekdays},
         str_to_weekday,
         utils::parse_str_to_vec,
-        ParseError,
+        ChronoTzResolver, Compat, ParseError, ParserConfig, TzResolver,
     },
-    Frequency, RRule, Unvalidated,
+    Frequency, RRule, RScale, Skip, Unvalidated,
 };
 
 use super::content_line_parts::ContentLineCaptures;
+use crate::core::{ByRule, DateTime, OccurrenceCache};
+
+/// The extension parameters of an `RRULE` or `EXRULE` with the start datetime of the
+/// rule, when it isn't the start datetime of the set.
+#[derive(Debug, Hash, PartialEq, Eq)]
+enum RuleParameter {
+    DtStart,
+    DtStartTimezone,
+}
+
+impl FromStr for RuleParameter {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("X-DTSTART") {
+            Ok(Self::DtStart)
+        } else if s.eq_ignore_ascii_case("X-DTSTART-TZID") {
+            Ok(Self::DtStartTimezone)
+        } else {
+            Err(ParseError::PropertyParametersNotSupported(s.into()))
+        }
+    }
+}
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub enum RRuleProperty {
@@ -31,6 +54,8 @@ pub enum RRuleProperty {
     ByMonth,
     BySetPos,
     Wkst,
+    RScale,
+    Skip,
     #[cfg(feature = "by-easter")]
     ByEaster,
 }
@@ -39,26 +64,32 @@ impl FromStr for RRuleProperty {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let prop = match &s.to_uppercase()[..] {
-            "FREQ" => Self::Freq,
-            "UNTIL" => Self::Until,
-            "COUNT" => Self::Count,
-            "INTERVAL" => Self::Interval,
-            "BYSECOND" => Self::BySecond,
-            "BYMINUTE" => Self::ByMinute,
-            "BYHOUR" => Self::ByHour,
-            "BYWEEKDAY" | "BYDAY" => Self::ByDay,
-            "BYMONTHDAY" => Self::ByMonthDay,
-            "BYYEARDAY" => Self::ByYearDay,
-            "BYWEEKNO" => Self::ByWeekNo,
-            "BYMONTH" => Self::ByMonth,
-            "BYSETPOS" => Self::BySetPos,
-            "WKST" => Self::Wkst,
+        const NAMES: &[(&str, RRuleProperty)] = &[
+            ("FREQ", RRuleProperty::Freq),
+            ("UNTIL", RRuleProperty::Until),
+            ("COUNT", RRuleProperty::Count),
+            ("INTERVAL", RRuleProperty::Interval),
+            ("BYSECOND", RRuleProperty::BySecond),
+            ("BYMINUTE", RRuleProperty::ByMinute),
+            ("BYHOUR", RRuleProperty::ByHour),
+            ("BYWEEKDAY", RRuleProperty::ByDay),
+            ("BYDAY", RRuleProperty::ByDay),
+            ("BYMONTHDAY", RRuleProperty::ByMonthDay),
+            ("BYYEARDAY", RRuleProperty::ByYearDay),
+            ("BYWEEKNO", RRuleProperty::ByWeekNo),
+            ("BYMONTH", RRuleProperty::ByMonth),
+            ("BYSETPOS", RRuleProperty::BySetPos),
+            ("WKST", RRuleProperty::Wkst),
+            ("RSCALE", RRuleProperty::RScale),
+            ("SKIP", RRuleProperty::Skip),
             #[cfg(feature = "by-easter")]
-            "BYEASTER" => Self::ByEaster,
-            _ => return Err(ParseError::UnrecognizedParameter(s.into())),
-        };
-        Ok(prop)
+            ("BYEASTER", RRuleProperty::ByEaster),
+        ];
+        NAMES
+            .iter()
+            .find(|(name, _)| s.eq_ignore_ascii_case(name))
+            .map(|(_, prop)| *prop)
+            .ok_or_else(|| ParseError::UnrecognizedParameter(s.into()))
     }
 }
 
@@ -66,32 +97,162 @@ impl<'a> TryFrom<ContentLineCaptures<'a>> for RRule<Unvalidated> {
     type Error = ParseError;
 
     fn try_from(value: ContentLineCaptures) -> Result<Self, Self::Error> {
-        if let Some(parameters) = value.parameters {
-            if !parameters.is_empty() {
-                return Err(ParseError::PropertyParametersNotSupported(
-                    parameters.into(),
-                ));
-            }
+        parse_rule_content_line(value, &ParserConfig::default(), &ChronoTzResolver)
+    }
+}
+
+/// Parses an `RRULE` or `EXRULE` content line.
+///
+/// The only parameters are the `X-DTSTART` and `X-DTSTART-TZID` extensions, with the
+/// start datetime of the rule when it isn't the start datetime of the set. It is kept in
+/// the `dt_start` of the unvalidated rule, and the `TZID` is resolved with `resolver`.
+pub(crate) fn parse_rule_content_line(
+    value: ContentLineCaptures,
+    config: &ParserConfig,
+    resolver: &dyn TzResolver,
+) -> Result<RRule<Unvalidated>, ParseError> {
+    let dt_start = value
+        .parameters
+        .filter(|parameters| !parameters.is_empty())
+        .map(|parameters| parse_rule_start(parameters, config.compat, resolver))
+        .transpose()?;
+
+    let mut properties: HashMap<RRuleProperty, Cow<str>> =
+        parse_parameters(value.value, config.compat)?;
+    if config.compat == Compat::Lenient {
+        properties.retain(|_, value| !value.is_empty());
+        replace_names(&mut properties);
+        replace_frequencies(&mut properties);
+    }
+
+    let mut rrule = props_to_rrule(&properties)?;
+    if config.clamp_month_days && !properties.contains_key(&RRuleProperty::Skip) {
+        rrule.skip = Skip::Backward;
+    }
+    rrule.dt_start = dt_start;
+    Ok(rrule)
+}
+
+/// Parses the `X-DTSTART` and `X-DTSTART-TZID` parameters of a rule.
+///
+/// Any other parameters, or a `X-DTSTART-TZID` without a `X-DTSTART`, aren't supported.
+fn parse_rule_start(
+    raw_parameters: &str,
+    compat: Compat,
+    resolver: &dyn TzResolver,
+) -> Result<DateTime, ParseError> {
+    let not_supported = || ParseError::PropertyParametersNotSupported(raw_parameters.into());
+    let parameters: HashMap<RuleParameter, Cow<str>> =
+        parse_parameters(raw_parameters, compat).map_err(|_| not_supported())?;
+    let dt_start = parameters
+        .get(&RuleParameter::DtStart)
+        .ok_or_else(not_supported)?;
+    let timezone = parameters
+        .get(&RuleParameter::DtStartTimezone)
+        .map(|tz| parse_timezone(tz, resolver))
+        .transpose()?;
+    datestring_to_date(dt_start, timezone, "X-DTSTART")
+}
+
+/// The full and three letter weekday names, with their two letter form.
+const WEEKDAY_NAMES: [(&str, &str, &str); 7] = [
+    ("MONDAY", "MON", "MO"),
+    ("TUESDAY", "TUE", "TU"),
+    ("WEDNESDAY", "WED", "WE"),
+    ("THURSDAY", "THU", "TH"),
+    ("FRIDAY", "FRI", "FR"),
+    ("SATURDAY", "SAT", "SA"),
+    ("SUNDAY", "SUN", "SU"),
+];
+
+/// The full and three letter month names, with their number.
+const MONTH_NAMES: [(&str, &str, &str); 12] = [
+    ("JANUARY", "JAN", "1"),
+    ("FEBRUARY", "FEB", "2"),
+    ("MARCH", "MAR", "3"),
+    ("APRIL", "APR", "4"),
+    ("MAY", "MAY", "5"),
+    ("JUNE", "JUN", "6"),
+    ("JULY", "JUL", "7"),
+    ("AUGUST", "AUG", "8"),
+    ("SEPTEMBER", "SEP", "9"),
+    ("OCTOBER", "OCT", "10"),
+    ("NOVEMBER", "NOV", "11"),
+    ("DECEMBER", "DEC", "12"),
+];
+
+/// Replaces the weekday names in `BYDAY` and `WKST`, e.g. `MONDAY` or `-1FRI`, and the
+/// month names in `BYMONTH`, e.g. `JAN` or `February`, with their RFC 5545 form.
+fn replace_names(properties: &mut HashMap<RRuleProperty, Cow<str>>) {
+    fn replace(value: &str, names: &[(&str, &str, &str)]) -> String {
+        value
+            .split(',')
+            .map(|part| {
+                let part = part.trim();
+                // Weekdays can be prefixed with a number, e.g. `-1FRIDAY`.
+                let name_start = part
+                    .find(|c: char| c.is_ascii_alphabetic())
+                    .unwrap_or(part.len());
+                let (prefix, name) = part.split_at(name_start);
+                names
+                    .iter()
+                    .find(|(full, short, _)| {
+                        name.eq_ignore_ascii_case(full) || name.eq_ignore_ascii_case(short)
+                    })
+                    .map_or_else(|| part.into(), |(_, _, rfc)| format!("{}{}", prefix, rfc))
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    for (property, names) in [
+        (RRuleProperty::ByDay, &WEEKDAY_NAMES[..]),
+        (RRuleProperty::Wkst, &WEEKDAY_NAMES[..]),
+        (RRuleProperty::ByMonth, &MONTH_NAMES[..]),
+    ] {
+        if let Some(value) = properties.get_mut(&property) {
+            *value = replace(value, names).into();
         }
+    }
+}
 
-        let properties: HashMap<RRuleProperty, String> = parse_parameters(value.value)?;
+/// The frequencies that aren't part of RFC 5545, with their number of months.
+const MONTH_FREQUENCIES: [(&str, u16); 2] = [("QUARTERLY", 3), ("SEMIANNUALLY", 6)];
 
-        props_to_rrule(&properties)
+/// Replaces the frequencies in [`MONTH_FREQUENCIES`], which finance software uses, with a
+/// monthly frequency and a multiplied interval, e.g. `FREQ=QUARTERLY;INTERVAL=2` with
+/// `FREQ=MONTHLY;INTERVAL=6`.
+fn replace_frequencies(properties: &mut HashMap<RRuleProperty, Cow<str>>) {
+    let Some(months) = properties.get(&RRuleProperty::Freq).and_then(|freq| {
+        MONTH_FREQUENCIES
+            .iter()
+            .find(|(name, _)| freq.eq_ignore_ascii_case(name))
+            .map(|(_, months)| *months)
+    }) else {
+        return;
+    };
+    let interval = match properties.get(&RRuleProperty::Interval) {
+        Some(interval) => interval.parse::<u16>().ok(),
+        None => Some(1),
+    };
+    // Invalid intervals are left to the error of the frequency.
+    if let Some(interval) = interval.and_then(|interval| interval.checked_mul(months)) {
+        properties.insert(RRuleProperty::Freq, "MONTHLY".into());
+        properties.insert(RRuleProperty::Interval, interval.to_string().into());
     }
 }
 
 /// Takes a map of [`RRuleProperty`] and returns an [`RRule`].
 #[allow(clippy::too_many_lines)]
 fn props_to_rrule(
-    props: &HashMap<RRuleProperty, String>,
+    props: &HashMap<RRuleProperty, Cow<str>>,
 ) -> Result<RRule<Unvalidated>, ParseError> {
-    let freq = props
-        .get(&RRuleProperty::Freq)
-        .map(|freq| Frequency::from_str(freq))
+    let get = |property| props.get(&property).map(Cow::as_ref);
+    let freq = get(RRuleProperty::Freq)
+        .map(Frequency::from_str)
         .transpose()?
         .ok_or_else(|| ParseError::MissingProperty("FREQ".into()))?;
-    let interval = props
-        .get(&RRuleProperty::Interval)
+    let interval = get(RRuleProperty::Interval)
         .map(|interval| {
             interval
                 .parse()
@@ -99,92 +260,80 @@ fn props_to_rrule(
         })
         .transpose()?
         .unwrap_or(1);
-    let count = props
-        .get(&RRuleProperty::Count)
+    let count = get(RRuleProperty::Count)
         .map(|count| {
             count
                 .parse()
                 .map_err(|_| ParseError::InvalidCount(count.into()))
         })
         .transpose()?;
-    let until = props
-        .get(&RRuleProperty::Until)
+    let until = get(RRuleProperty::Until)
         .map(|until| datestring_to_date(until, None, "UNTIL"))
         .transpose()?;
-    let week_start = props
-        .get(&RRuleProperty::Wkst)
+    let week_start = get(RRuleProperty::Wkst)
         .map(|week_start| {
             str_to_weekday(week_start)
                 .map_err(|_| ParseError::InvalidWeekdayStart(week_start.into()))
         })
         .transpose()?
         .unwrap_or(Weekday::Mon);
-    let by_set_pos = props
-        .get(&RRuleProperty::BySetPos)
+    let rscale = get(RRuleProperty::RScale)
+        .map(RScale::from_str)
+        .transpose()?;
+    let skip = get(RRuleProperty::Skip)
+        .map(Skip::from_str)
+        .transpose()?
+        .unwrap_or_default();
+    let by_set_pos = get(RRuleProperty::BySetPos)
         .map(|by_set_pos| {
-            parse_str_to_vec(by_set_pos, |_| true)
-                .map_err(|_| ParseError::InvalidBySetPos(by_set_pos.into()))
+            parse_str_to_vec(by_set_pos, |_| true).map_err(ParseError::InvalidBySetPos)
         })
         .transpose()?
         .unwrap_or_default();
-    let by_month = props
-        .get(&RRuleProperty::ByMonth)
-        .map(|by_month| {
-            parse_str_to_vec(by_month, |month| (1..=12).contains(&month))
-                .map_err(|_| ParseError::InvalidByMonth(by_month.into()))
-        })
+    let (by_month, by_leap_month) = get(RRuleProperty::ByMonth)
+        .map(parse_by_month)
         .transpose()?
         .unwrap_or_default();
-    let by_month_day = props
-        .get(&RRuleProperty::ByMonthDay)
+    let by_month_day = get(RRuleProperty::ByMonthDay)
         .map(|by_month_day| {
             parse_str_to_vec(by_month_day, |monthday| (-31..=31).contains(&monthday))
-                .map_err(|_| ParseError::InvalidByMonthDay(by_month_day.into()))
+                .map_err(ParseError::InvalidByMonthDay)
         })
         .transpose()?
         .unwrap_or_default();
-    let by_year_day = props
-        .get(&RRuleProperty::ByYearDay)
+    let by_year_day = get(RRuleProperty::ByYearDay)
         .map(|by_year_day| {
             parse_str_to_vec(by_year_day, |yearday| (-366..=366).contains(&yearday))
-                .map_err(|_| ParseError::InvalidByYearDay(by_year_day.into()))
+                .map_err(ParseError::InvalidByYearDay)
         })
         .transpose()?
         .unwrap_or_default();
-    let by_week_no = props
-        .get(&RRuleProperty::ByWeekNo)
+    let by_week_no = get(RRuleProperty::ByWeekNo)
         .map(|by_week_no| {
             parse_str_to_vec(by_week_no, |weekno| (-53..=53).contains(&weekno))
-                .map_err(|_| ParseError::InvalidByWeekNo(by_week_no.into()))
+                .map_err(ParseError::InvalidByWeekNo)
         })
         .transpose()?
         .unwrap_or_default();
-    let by_weekday = props
-        .get(&RRuleProperty::ByDay)
-        .map(|by_weekday| parse_weekdays(by_weekday))
+    let by_weekday = get(RRuleProperty::ByDay)
+        .map(parse_weekdays)
         .transpose()?
         .unwrap_or_default();
-    let by_hour = props
-        .get(&RRuleProperty::ByHour)
+    let by_hour = get(RRuleProperty::ByHour)
         .map(|by_hour| {
-            parse_str_to_vec(by_hour, |hour| hour < 24)
-                .map_err(|_| ParseError::InvalidByHour(by_hour.into()))
+            parse_str_to_vec(by_hour, |hour| hour < 24).map_err(ParseError::InvalidByHour)
         })
         .transpose()?
         .unwrap_or_default();
-    let by_minute = props
-        .get(&RRuleProperty::ByMinute)
+    let by_minute = get(RRuleProperty::ByMinute)
         .map(|by_minute| {
-            parse_str_to_vec(by_minute, |minute| minute < 60)
-                .map_err(|_| ParseError::InvalidByMinute(by_minute.into()))
+            parse_str_to_vec(by_minute, |minute| minute < 60).map_err(ParseError::InvalidByMinute)
         })
         .transpose()?
         .unwrap_or_default();
-    let by_second = props
-        .get(&RRuleProperty::BySecond)
+    let by_second = get(RRuleProperty::BySecond)
         .map(|by_second| {
-            parse_str_to_vec(by_second, |second| second < 60)
-                .map_err(|_| ParseError::InvalidBySecond(by_second.into()))
+            parse_str_to_vec(by_second, |second| second < 60).map_err(ParseError::InvalidBySecond)
         })
         .transpose()?
         .unwrap_or_default();
@@ -192,9 +341,8 @@ fn props_to_rrule(
     #[cfg(not(feature = "by-easter"))]
     let by_easter = None;
     #[cfg(feature = "by-easter")]
-    let by_easter = props
-        .get(&RRuleProperty::ByEaster)
-        .map(|new_by_easter: &String| {
+    let by_easter = get(RRuleProperty::ByEaster)
+        .map(|new_by_easter| {
             i16::from_str(new_by_easter)
                 .map_err(|_| ParseError::InvalidByEaster(new_by_easter.into()))
         })
@@ -207,27 +355,141 @@ fn props_to_rrule(
         count,
         until,
         week_start,
-        by_set_pos,
-        by_month,
-        by_month_day,
-        by_n_month_day: vec![],
-        by_year_day,
-        by_week_no,
-        by_weekday,
-        by_hour,
-        by_minute,
-        by_second,
+        by_set_pos: by_set_pos.into(),
+        by_month: by_month.into(),
+        by_leap_month: by_leap_month.into(),
+        by_month_day: by_month_day.into(),
+        by_n_month_day: ByRule::new(),
+        by_year_day: by_year_day.into(),
+        by_week_no: by_week_no.into(),
+        by_weekday: by_weekday.into(),
+        by_hour: by_hour.into(),
+        by_minute: by_minute.into(),
+        by_second: by_second.into(),
         by_easter,
+        rscale,
+        skip,
+        leap_day: Skip::Omit,
+        dt_start: None,
+        cache: OccurrenceCache::default(),
         stage: PhantomData,
     })
 }
 
+/// Parses the months and the leap months, like `5L` of RFC 7529, of a `BYMONTH` value.
+fn parse_by_month(value: &str) -> Result<(Vec<u8>, Vec<u8>), ParseError> {
+    let (leap_months, months): (Vec<&str>, Vec<&str>) = value
+        .split(',')
+        .map(str::trim)
+        .partition(|month| month.ends_with(['L', 'l']));
+    let is_month = |month: u8| (1..=12).contains(&month);
+    let months =
+        parse_str_to_vec(&months.join(","), is_month).map_err(ParseError::InvalidByMonth)?;
+    let leap_months = leap_months
+        .into_iter()
+        .map(|month| {
+            month[..month.len() - 1]
+                .parse()
+                .ok()
+                .filter(|month| is_month(*month))
+                .ok_or_else(|| ParseError::InvalidByMonth(month.into()))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((months, leap_months))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parser::content_line::{ContentLineCaptures, PropertyName};
+    use crate::NWeekday;
+    use smallvec::smallvec;
 
     use super::*;
 
+    #[test]
+    fn replaces_weekday_and_month_names_in_lenient_mode() {
+        let content_line = ContentLineCaptures {
+            property_name: PropertyName::RRule,
+            parameters: None,
+            value: "FREQ=YEARLY;BYDAY=MONDAY, -1fri,TU;BYMONTH=JAN,february,3;WKST=Sunday",
+        };
+        let rrule = parse_rule_content_line(
+            content_line.clone(),
+            &ParserConfig::lenient(),
+            &ChronoTzResolver,
+        )
+        .unwrap();
+        assert_eq!(
+            rrule.get_by_weekday(),
+            [
+                NWeekday::Every(Weekday::Mon),
+                NWeekday::Nth(-1, Weekday::Fri),
+                NWeekday::Every(Weekday::Tue),
+            ]
+        );
+        assert_eq!(rrule.get_by_month(), [1, 2, 3]);
+        assert_eq!(rrule.week_start, Weekday::Sun);
+
+        assert!(
+            parse_rule_content_line(content_line, &ParserConfig::default(), &ChronoTzResolver)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn replaces_month_frequencies_in_lenient_mode() {
+        let tests = [
+            ("FREQ=QUARTERLY", 3),
+            ("FREQ=quarterly;INTERVAL=2", 6),
+            ("FREQ=SEMIANNUALLY", 6),
+        ];
+        for (value, interval) in tests {
+            let content_line = ContentLineCaptures {
+                property_name: PropertyName::RRule,
+                parameters: None,
+                value,
+            };
+            let rrule = parse_rule_content_line(
+                content_line.clone(),
+                &ParserConfig::lenient(),
+                &ChronoTzResolver,
+            )
+            .unwrap();
+            assert_eq!(rrule.freq, Frequency::Monthly, "{value}");
+            assert_eq!(rrule.interval, interval, "{value}");
+
+            let res =
+                parse_rule_content_line(content_line, &ParserConfig::default(), &ChronoTzResolver);
+            assert!(
+                matches!(res, Err(ParseError::InvalidFrequency(_))),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn clamps_month_days_without_skip_rule_part() {
+        let config = ParserConfig {
+            clamp_month_days: true,
+            ..Default::default()
+        };
+        let content_line = ContentLineCaptures {
+            property_name: PropertyName::RRule,
+            parameters: None,
+            value: "FREQ=MONTHLY;BYMONTHDAY=31",
+        };
+        let rrule = parse_rule_content_line(content_line, &config, &ChronoTzResolver).unwrap();
+        assert_eq!(rrule.skip, Skip::Backward);
+
+        let content_line = ContentLineCaptures {
+            property_name: PropertyName::RRule,
+            parameters: None,
+            value: "FREQ=MONTHLY;BYMONTHDAY=31;RSCALE=GREGORIAN;SKIP=OMIT",
+        };
+        let rrule = parse_rule_content_line(content_line, &config, &ChronoTzResolver).unwrap();
+        assert_eq!(rrule.skip, Skip::Omit);
+    }
+
     #[test]
     fn parses_rrule_content_line() {
         let tests = [
@@ -249,7 +511,7 @@ mod tests {
                     value: "BYHOUR=4;FREQ=DAILY",
                 },
                 RRule {
-                    by_hour: vec![4],
+                    by_hour: smallvec![4],
                     freq: Frequency::Daily,
                     ..Default::default()
                 },
@@ -262,7 +524,7 @@ mod tests {
                     value: "byhour=4;freQ=DAILY",
                 },
                 RRule {
-                    by_hour: vec![4],
+                    by_hour: smallvec![4],
                     freq: Frequency::Daily,
                     ..Default::default()
                 },
@@ -277,14 +539,24 @@ mod tests {
 
     #[test]
     fn rejects_property_parameters_in_rrule_line() {
-        let tests = [(
-            ContentLineCaptures {
-                property_name: PropertyName::RRule,
-                parameters: Some("TZID=Europe/London"),
-                value: "BYHOUR=4",
-            },
-            ParseError::PropertyParametersNotSupported("TZID=Europe/London".into()),
-        )];
+        let tests = [
+            (
+                ContentLineCaptures {
+                    property_name: PropertyName::RRule,
+                    parameters: Some("TZID=Europe/London"),
+                    value: "BYHOUR=4",
+                },
+                ParseError::PropertyParametersNotSupported("TZID=Europe/London".into()),
+            ),
+            (
+                ContentLineCaptures {
+                    property_name: PropertyName::RRule,
+                    parameters: Some("X-DTSTART-TZID=Europe/London"),
+                    value: "BYHOUR=4",
+                },
+                ParseError::PropertyParametersNotSupported("X-DTSTART-TZID=Europe/London".into()),
+            ),
+        ];
 
         for (input, expected_output) in tests {
             let output = RRule::try_from(input);
@@ -292,6 +564,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_start_parameters_in_rrule_line() {
+        let tests = [
+            ("X-DTSTART=20210104T130000Z", "2021-01-04 13:00:00 UTC"),
+            #[cfg(feature = "chrono-tz")]
+            (
+                "X-DTSTART-TZID=Europe/Berlin;X-DTSTART=20210104T140000",
+                "2021-01-04 14:00:00 CET",
+            ),
+        ];
+
+        for (parameters, expected_start) in tests {
+            let input = ContentLineCaptures {
+                property_name: PropertyName::RRule,
+                parameters: Some(parameters),
+                value: "FREQ=WEEKLY",
+            };
+            let rrule = RRule::try_from(input).unwrap();
+            assert_eq!(rrule.dt_start.unwrap().to_string(), expected_start);
+        }
+    }
+
     #[test]
     fn rejects_invalid_freq() {
         let mut props = HashMap::new();
@@ -313,7 +607,7 @@ mod tests {
 
         props.insert(RRuleProperty::ByHour, "5,6,25".into());
         let res = props_to_rrule(&props);
-        assert_eq!(res.unwrap_err(), ParseError::InvalidByHour("5,6,25".into()));
+        assert_eq!(res.unwrap_err(), ParseError::InvalidByHour("25".into()));
     }
 
     #[test]
@@ -326,9 +620,6 @@ mod tests {
 
         props.insert(RRuleProperty::ByMinute, "4,5,64".into());
         let res = props_to_rrule(&props);
-        assert_eq!(
-            res.unwrap_err(),
-            ParseError::InvalidByMinute("4,5,64".into())
-        );
+        assert_eq!(res.unwrap_err(), ParseError::InvalidByMinute("64".into()));
     }
 }
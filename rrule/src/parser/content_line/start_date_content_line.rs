@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
 use super::{
     content_line_parts::ContentLineCaptures, date_content_line::DateParameter,
@@ -8,7 +8,7 @@ use crate::{
     core::{DateTime, Tz},
     parser::{
         datetime::{datestring_to_date, parse_timezone},
-        ParseError,
+        ChronoTzResolver, Compat, ParseError, TzResolver,
     },
 };
 
@@ -25,18 +25,32 @@ impl<'a> TryFrom<&ContentLineCaptures<'a>> for StartDateContentLine {
     type Error = ParseError;
 
     fn try_from(content_line: &ContentLineCaptures) -> Result<Self, Self::Error> {
-        let parameters: HashMap<DateParameter, String> = content_line
+        Self::parse(content_line, Compat::Strict, &ChronoTzResolver)
+    }
+}
+
+impl StartDateContentLine {
+    /// Parses a `DTSTART` content line.
+    ///
+    /// In lenient mode, a `VALUE` parameter that doesn't match the value is ignored.
+    /// The `TZID` parameter is resolved with the `resolver`.
+    pub(crate) fn parse(
+        content_line: &ContentLineCaptures,
+        compat: Compat,
+        resolver: &dyn TzResolver,
+    ) -> Result<Self, ParseError> {
+        let parameters: HashMap<DateParameter, Cow<str>> = content_line
             .parameters
             .as_ref()
-            .map(|p| parse_parameters(p))
+            .map(|p| parse_parameters(p, compat))
             .transpose()?
             .unwrap_or_default();
 
         let mut timezone = parameters
             .get(&DateParameter::Timezone)
-            .map(|tz| parse_timezone(tz))
+            .map(|tz| parse_timezone(tz, resolver))
             .transpose()?;
-        if timezone.is_none() && content_line.value.to_uppercase().ends_with('Z') {
+        if timezone.is_none() && content_line.value.ends_with(['Z', 'z']) {
             timezone = Some(UTC);
         }
 
@@ -47,10 +61,10 @@ impl<'a> TryFrom<&ContentLineCaptures<'a>> for StartDateContentLine {
             "DATE"
         };
         if let Some(value_in_parameter) = value_in_parameter {
-            if value_in_parameter != value {
+            if value_in_parameter != value && compat == Compat::Strict {
                 return Err(ParseError::ParameterValueMismatch {
                     parameter: "VALUE".into(),
-                    parameter_value: value_in_parameter.into(),
+                    parameter_value: value_in_parameter.to_string(),
                     found_value: value.into(),
                 });
             }
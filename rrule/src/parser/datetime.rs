@@ -1,17 +1,15 @@
-use std::str::FromStr;
-
-use super::{regex::ParsedDateString, ParseError};
+use super::{lexer::ParsedDateString, ParseError, TzResolver};
 use crate::{
     core::{DateTime, Tz},
     NWeekday,
 };
 use chrono::{NaiveDate, TimeZone, Weekday};
 
-/// Attempts to convert a `str` to a `chrono_tz::Tz`.
-pub(crate) fn parse_timezone(tz: &str) -> Result<Tz, ParseError> {
-    chrono_tz::Tz::from_str(tz)
-        .map_err(|_| ParseError::InvalidTimezone(tz.into()))
-        .map(Tz::Tz)
+/// Attempts to convert a `str` to a `Tz` with the `resolver`.
+pub(crate) fn parse_timezone(tz: &str, resolver: &dyn TzResolver) -> Result<Tz, ParseError> {
+    resolver
+        .resolve(tz)
+        .ok_or_else(|| ParseError::InvalidTimezone(tz.into()))
 }
 
 /// Convert a datetime string and a timezone to a `chrono::DateTime<Tz>`.
@@ -59,7 +57,7 @@ pub(crate) fn datestring_to_date(
     // For more info https://icalendar.org/iCalendar-RFC-5545/3-3-5-date-time.html
     let datetime: chrono::DateTime<Tz> = if flags.zulu_timezone_set {
         // If a `Z` is present, UTC should be used.
-        chrono::DateTime::<chrono::Utc>::from_utc(datetime, chrono::Utc).with_timezone(&Tz::UTC)
+        Tz::UTC.from_utc_datetime(&datetime)
     } else {
         // If no `Z` is present, local time should be used.
         use chrono::offset::LocalResult;
@@ -111,17 +109,20 @@ pub(crate) fn datestring_to_date(
 
 /// Attempts to convert a `str` to a `Weekday`.
 pub(crate) fn str_to_weekday(d: &str) -> Result<Weekday, ParseError> {
-    let day = match &d.to_uppercase()[..] {
-        "MO" => Weekday::Mon,
-        "TU" => Weekday::Tue,
-        "WE" => Weekday::Wed,
-        "TH" => Weekday::Thu,
-        "FR" => Weekday::Fri,
-        "SA" => Weekday::Sat,
-        "SU" => Weekday::Sun,
-        _ => return Err(ParseError::InvalidWeekday(d.to_string())),
-    };
-    Ok(day)
+    const WEEKDAYS: [(&str, Weekday); 7] = [
+        ("MO", Weekday::Mon),
+        ("TU", Weekday::Tue),
+        ("WE", Weekday::Wed),
+        ("TH", Weekday::Thu),
+        ("FR", Weekday::Fri),
+        ("SA", Weekday::Sat),
+        ("SU", Weekday::Sun),
+    ];
+    WEEKDAYS
+        .iter()
+        .find(|(name, _)| d.eq_ignore_ascii_case(name))
+        .map(|(_, weekday)| *weekday)
+        .ok_or_else(|| ParseError::InvalidWeekday(d.to_string()))
 }
 
 /// Parse the "BYWEEKDAY" and "BYDAY" values
@@ -132,13 +133,13 @@ pub(crate) fn parse_weekdays(val: &str) -> Result<Vec<NWeekday>, ParseError> {
     let mut wdays = vec![];
     // Separate all days
     for day in val.split(',') {
-        let wday = day.parse::<NWeekday>()?;
+        let wday = day.trim().parse::<NWeekday>()?;
         wdays.push(wday);
     }
     Ok(wdays)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono-tz"))]
 mod tests {
     use super::*;
 
@@ -1,83 +1,372 @@
 #![allow(clippy::module_name_repetitions)]
+use super::utils::{closest_match, find_token, folded_offset};
+use std::ops::Range;
 use thiserror::Error;
 
+/// Errors that can occur while parsing an iCalendar string.
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
+    /// The given timezone name is not known.
     #[error("`{0}` is not a valid timezone.")]
     InvalidTimezone(String),
+    /// The datetime value of a property could not be parsed.
     #[error("`{value}` is not a valid datetime format for `{property}`.")]
-    InvalidDateTime { value: String, property: String },
+    InvalidDateTime {
+        /// The offending value.
+        value: String,
+        /// The property the value belongs to.
+        property: String,
+    },
+    /// The datetime string is not in the `YYYYMMDD[THHMMSS[Z]]` format.
     #[error("`{0}` is not a valid datetime format.")]
     InvalidDateTimeFormat(String),
+    /// The datetime does not exist in the local timezone, e.g. because of a DST gap.
     #[error("{property}:{value} is not a valid datetime in local timezone.")]
-    InvalidDateTimeInLocalTimezone { value: String, property: String },
+    InvalidDateTimeInLocalTimezone {
+        /// The offending value.
+        value: String,
+        /// The property the value belongs to.
+        property: String,
+    },
+    /// The datetime occurs twice in the local timezone, e.g. because of a DST fall-back.
     #[error("{property}:{value} is not a valid datetime in local timezone. This value is ambiguous and can be `{date1}` or `{date2}`")]
     DateTimeInLocalTimezoneIsAmbiguous {
+        /// The offending value.
         value: String,
+        /// The property the value belongs to.
         property: String,
+        /// The first possible interpretation of the value.
         date1: String,
+        /// The second possible interpretation of the value.
         date2: String,
     },
-    #[error("`{0}` is not a valid frequency.")]
+    /// The `FREQ` value is not a known frequency.
+    #[error("`{0}` is not a valid frequency.{}", did_you_mean(.0, &FREQUENCIES))]
     InvalidFrequency(String),
+    /// A `BYDAY` value is not a valid weekday.
     #[error("`{0}` is not a valid weekday. Valid values are `MO`, `TU`, `WE`, `TH`, `FR`, `SA` and `SU`.")]
     InvalidWeekday(String),
+    /// The `WKST` value is not a valid weekday.
     #[error("`{0}` is not a valid weekday start. Valid values are `MO`, `TU`, `WE`, `TH`, `FR`, `SA` and `SU`.")]
     InvalidWeekdayStart(String),
+    /// The `BYEASTER` value is not a valid integer.
     #[error("`{0}` is not a valid BYEASTER value.")]
     InvalidByEaster(String),
+    /// The `SKIP` value is not `OMIT`, `BACKWARD` or `FORWARD`.
+    #[error("`{0}` is not a valid SKIP value. Valid values are `OMIT`, `BACKWARD` and `FORWARD`.")]
+    InvalidSkip(String),
+    /// The `RSCALE` value is a calendar scale other than `GREGORIAN` and `CHINESE`, like
+    /// `HEBREW`.
+    #[error("The `{0}` calendar scale is not supported, only `GREGORIAN` and `CHINESE` are.")]
+    UnsupportedRScale(String),
+    /// The `INTERVAL` value is not a valid integer.
     #[error("`{0}` is not a valid INTERVAL value.")]
     InvalidInterval(String),
+    /// The `COUNT` value is not a valid integer.
     #[error("`{0}` is not a valid COUNT value.")]
     InvalidCount(String),
+    /// The `BYHOUR` value is invalid.
     #[error("`{0}` is not a valid BYHOUR value. Expected a comma-separated list of values in range 0..=23, e.g. `1,3,4`")]
     InvalidByHour(String),
+    /// The `BYWEEKNO` value is invalid.
     #[error("`{0}` is not a valid BYWEEKNO value. Expected a comma-separated list of values in range -53..=53, e.g. `-1,30,53`")]
     InvalidByWeekNo(String),
+    /// The `BYYEARDAY` value is invalid.
     #[error("`{0}` is not a valid BYYEARDAY value. Expected a comma-separated list of values in range -366..=366, e.g. `-100,`")]
     InvalidByYearDay(String),
+    /// The `BYMONTHDAY` value is invalid.
     #[error("`{0}` is not a valid BYMONTHDAY value. Expected a comma-separated list of values in range -31..=31, e.g. `-30,10`")]
     InvalidByMonthDay(String),
+    /// The `BYMONTH` value is invalid.
     #[error("`{0}` is not a valid BYMONTH value. Expected a comma-separated list of values in range 1..=12, e.g. `6,9,10`")]
     InvalidByMonth(String),
+    /// The `BYMINUTE` value is invalid.
     #[error("`{0}` is not a valid BYMINUTE value. Expected a comma-separated list of values in range 0..=59, e.g. `0,15,30,45`")]
     InvalidByMinute(String),
+    /// The `BYSECOND` value is invalid.
     #[error("`{0}` is not a valid BYSECOND value. Expected a comma-separated list of values in range 0..=59, e.g. `0,15,30,45`")]
     InvalidBySecond(String),
+    /// The `BYSETPOS` value is invalid.
     #[error("`{0}` is not a valid BYSETPOS value. Expected a comma-separated list of integers, e.g. `-3,1`")]
     InvalidBySetPos(String),
+    /// A required property is missing.
     #[error("The property `{0}` wasn't found, and it is required.")]
     MissingProperty(String),
+    /// A property parameter is not formatted as `key=value`.
     #[error(
         "`{0}` is a malformed property parameter. Parameter should be specified as `key=value`"
     )]
     InvalidParameterFormat(String),
-    #[error("`{0}` is not a valid property parameter.")]
+    /// A property parameter is not recognized.
+    #[error("`{0}` is not a valid property parameter.{}", did_you_mean(.0, &PARAMETERS))]
     UnrecognizedParameter(String),
+    /// A property or parameter was specified more than once.
     #[error("Found duplicate property for `{0}`, properties and parameters need to be unique.")]
     DuplicateProperty(String),
+    /// The input has no `DTSTART` property.
     #[error("Missing start date. There needs to be a unique start date which the iteration can start from.")]
     MissingStartDate,
+    /// The input has neither an `RRULE` nor an `RDATE` property.
     #[error("Missing date generation property. There needs to be at least one `RRULE` or `RDATE` to generate occurrences.")]
     MissingDateGenerationRules,
+    /// Property parameters were given for a property that doesn't support them.
     #[error("Property parameters aren't supported for RRULE / EXRULE, found parameters: `{0}`")]
     PropertyParametersNotSupported(String),
+    /// The property name is not recognized.
     #[error(
-        "`{0}` is not a valid property name, expected one of: `RRULE,EXRULE,DTSTART,RDATE,EXDATE`"
+        "`{0}` is not a valid property name, expected one of: `RRULE,EXRULE,DTSTART,RDATE,EXDATE,DTEND,DURATION`.{}",
+        did_you_mean(.0, &PROPERTY_NAMES)
     )]
     UnrecognizedPropertyName(String),
+    /// `DTSTART` and `UNTIL` don't have the same value type.
     #[error(
         "The value of `DTSTART` and `UNTIL` needs to match. Both need to be either a `DATE` or `DATE-TIME`"
     )]
     DtStartUntilMismatchValue,
+    /// `DTSTART` is in local time but `UNTIL` is not.
     #[error(
         "The value of `DTSTART` was specified in local timezone, but `UNTIL` was specified with a zulu time when it had to be specified in local time as well"
     )]
     DtStartUntilMismatchTimezone,
+    /// The `VALUE` parameter doesn't match the type of the property value.
     #[error("Property parameter `{parameter}` was set to have value `{parameter_value}`, but found `{found_value}` ")]
     ParameterValueMismatch {
+        /// The name of the parameter.
         parameter: String,
+        /// The value given for the parameter.
         parameter_value: String,
+        /// The value type found in the property value.
         found_value: String,
     },
+    /// The input of a single [`crate::RRule`] has other properties than `DTSTART` and one `RRULE`.
+    #[error("Expected exactly one `DTSTART` and one `RRULE` property, without `RDATE`, `EXDATE` or `EXRULE`.")]
+    ExpectedSingleRRule,
+    /// The iteration cursor token is not valid.
+    #[error("`{0}` is not a valid iteration cursor.")]
+    InvalidCursor(String),
+    /// The input is not a valid xCal document.
+    #[cfg(feature = "xcal")]
+    #[error("Invalid xCal input: {0}")]
+    InvalidXCal(String),
+    /// The input exceeds one of the [`crate::ParserLimits`].
+    #[error("The input exceeds the limit of {max} {limit}, found {found}.")]
+    LimitExceeded {
+        /// The limit that was exceeded.
+        limit: String,
+        /// The value of the limit.
+        max: usize,
+        /// The size found in the input.
+        found: usize,
+    },
+    /// The value of a `DURATION` property is not valid.
+    #[error("`{0}` is not a valid DURATION value. Expected a positive duration like `PT1H30M` or `P1D`.")]
+    InvalidDuration(String),
+    /// The value of an `RDATE;VALUE=PERIOD` property is not valid.
+    #[error("`{0}` is not a valid PERIOD value. Expected a start and an end or a positive duration, like `19970101T180000Z/PT5H30M`.")]
+    InvalidPeriod(String),
+    /// There is more than one `DTEND` or `DURATION` property.
+    #[error("Found more than one `DTEND` or `DURATION` property, there can be only one of them.")]
+    ConflictingDuration,
+    /// The `DTEND` is before the `DTSTART`.
+    #[error("The `DTEND` can't be before the `DTSTART`.")]
+    DtEndBeforeDtStart,
+    /// The input of [`crate::build_rruleset_from_vevent`] doesn't have exactly one `VEVENT`
+    /// without a `RECURRENCE-ID`.
+    #[error("Expected exactly one `VEVENT` component without a `RECURRENCE-ID`.")]
+    ExpectedSingleVEvent,
+    /// A calendar has more than one `VEVENT` component with the same `UID` and without a
+    /// `RECURRENCE-ID`.
+    #[error("Found more than one `VEVENT` component with the `UID` `{0}` and without a `RECURRENCE-ID`.")]
+    DuplicateUid(String),
+    /// A word of an English description of a rule isn't recognized, see
+    /// [`crate::RRule::from_text`].
+    #[error("`{0}` is not recognized in the description of a recurrence.")]
+    UnrecognizedText(String),
+    /// A `RECURRENCE-ID` is not in the `VEVENT` component of an overridden recurrence.
+    #[error(
+        "`RECURRENCE-ID` is only allowed in the `VEVENT` component of an overridden recurrence."
+    )]
+    MisplacedRecurrenceId,
+    /// A `VTIMEZONE` component is invalid.
+    #[error("Invalid `VTIMEZONE`: {0}.")]
+    InvalidVTimezone(String),
+    /// An error caused by a part of the input string.
+    #[error("{error} (`{token}` at offset {})", span.start)]
+    Spanned {
+        /// The byte offsets of the offending part in the input.
+        span: Range<usize>,
+        /// The offending part of the input.
+        token: String,
+        /// The error caused by that part.
+        error: Box<Self>,
+    },
+}
+
+/// The known frequencies, to suggest one for a misspelled frequency.
+const FREQUENCIES: [&str; 7] = [
+    "YEARLY", "MONTHLY", "WEEKLY", "DAILY", "HOURLY", "MINUTELY", "SECONDLY",
+];
+
+/// The known property names, to suggest one for a misspelled property name.
+const PROPERTY_NAMES: [&str; 7] = [
+    "DTSTART", "RRULE", "EXRULE", "RDATE", "EXDATE", "DTEND", "DURATION",
+];
+
+/// The known rule parts and property parameters, to suggest one for a misspelled parameter.
+const PARAMETERS: [&str; 18] = [
+    "FREQ",
+    "UNTIL",
+    "COUNT",
+    "INTERVAL",
+    "BYSECOND",
+    "BYMINUTE",
+    "BYHOUR",
+    "BYDAY",
+    "BYMONTHDAY",
+    "BYYEARDAY",
+    "BYWEEKNO",
+    "BYMONTH",
+    "BYSETPOS",
+    "WKST",
+    "SKIP",
+    "RSCALE",
+    "TZID",
+    "VALUE",
+];
+
+/// Returns a sentence suggesting the closest of the `keywords`, or an empty string
+/// if none of them is close to `value`.
+fn did_you_mean(value: &str, keywords: &[&'static str]) -> String {
+    closest_match(value, keywords)
+        .map(|keyword| format!(" Did you mean `{}`?", keyword))
+        .unwrap_or_default()
+}
+
+impl ParseError {
+    /// Returns the keyword that was probably meant, if the error is caused by a
+    /// misspelled frequency, property name, rule part or parameter.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{RRule, RRuleError, Unvalidated};
+    ///
+    /// let Err(RRuleError::ParserError(error)) = "FREQ=DAYLY".parse::<RRule<Unvalidated>>() else {
+    ///     panic!("expected a parse error");
+    /// };
+    /// assert_eq!(error.suggestion(), Some("DAILY"));
+    /// assert!(error.to_string().contains("Did you mean `DAILY`?"));
+    /// ```
+    #[must_use]
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self.inner() {
+            Self::InvalidFrequency(value) => closest_match(value, &FREQUENCIES),
+            Self::UnrecognizedPropertyName(value) => closest_match(value, &PROPERTY_NAMES),
+            Self::UnrecognizedParameter(value) => closest_match(value, &PARAMETERS),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte offsets of the part of the input that caused the error,
+    /// if the error could be located in the input.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{RRuleError, RRuleSet};
+    ///
+    /// let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=MO,MX";
+    /// let Err(RRuleError::ParserError(error)) = input.parse::<RRuleSet>() else {
+    ///     panic!("expected a parse error");
+    /// };
+    /// assert_eq!(error.span(), Some(52..54));
+    /// assert_eq!(error.token(), Some("MX"));
+    /// ```
+    #[must_use]
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::Spanned { span, .. } => Some(span.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the offending value of the error, like the invalid weekday of
+    /// [`ParseError::InvalidWeekday`], if it has one.
+    #[must_use]
+    pub fn token(&self) -> Option<&str> {
+        match self {
+            Self::Spanned { token, .. } => Some(token),
+            Self::InvalidDateTime { value, .. }
+            | Self::InvalidDateTimeInLocalTimezone { value, .. }
+            | Self::DateTimeInLocalTimezoneIsAmbiguous { value, .. }
+            | Self::ParameterValueMismatch {
+                parameter_value: value,
+                ..
+            } => Some(value),
+            Self::InvalidTimezone(value)
+            | Self::InvalidDateTimeFormat(value)
+            | Self::InvalidFrequency(value)
+            | Self::InvalidWeekday(value)
+            | Self::InvalidWeekdayStart(value)
+            | Self::InvalidByEaster(value)
+            | Self::InvalidSkip(value)
+            | Self::UnsupportedRScale(value)
+            | Self::InvalidInterval(value)
+            | Self::InvalidCount(value)
+            | Self::InvalidByHour(value)
+            | Self::InvalidByWeekNo(value)
+            | Self::InvalidByYearDay(value)
+            | Self::InvalidByMonthDay(value)
+            | Self::InvalidByMonth(value)
+            | Self::InvalidByMinute(value)
+            | Self::InvalidBySecond(value)
+            | Self::InvalidBySetPos(value)
+            | Self::InvalidDuration(value)
+            | Self::InvalidPeriod(value)
+            | Self::InvalidParameterFormat(value)
+            | Self::UnrecognizedParameter(value)
+            | Self::DuplicateProperty(value)
+            | Self::DuplicateUid(value)
+            | Self::UnrecognizedText(value)
+            | Self::PropertyParametersNotSupported(value)
+            | Self::UnrecognizedPropertyName(value)
+            | Self::InvalidCursor(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its position in the input.
+    #[must_use]
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::Spanned { error, .. } => error,
+            _ => self,
+        }
+    }
+
+    /// Adds the position of the offending value in `line` to the error, or the
+    /// position of the whole line if the value can't be found in it.
+    ///
+    /// The `line` starts at `line_offset` in the unfolded `input`, and the span is
+    /// converted to byte offsets in the folded `input`.
+    pub(crate) fn locate(self, input: &str, line_offset: usize, line: &str) -> Self {
+        if matches!(self, Self::Spanned { .. }) {
+            return self;
+        }
+        let range = self
+            .token()
+            .and_then(|token| find_token(line, token).map(|start| start..start + token.len()))
+            .unwrap_or(0..line.len());
+        let start = folded_offset(input, line_offset + range.start);
+        // The end is mapped from the last byte, so a fold after the token isn't part of the span.
+        let end = match range.end.checked_sub(1) {
+            Some(last) if !range.is_empty() => folded_offset(input, line_offset + last) + 1,
+            _ => start,
+        };
+        Self::Spanned {
+            span: start..end,
+            token: line[range].into(),
+            error: Box::new(self),
+        }
+    }
 }
@@ -1,17 +1,9 @@
-//! Utility functions around the regexes we use for parsing rrule strings.
+//! Hand-written scanners for the date strings and the property names of the content
+//! lines, which work on the slices of the input without allocating.
 use std::str::FromStr;
 
-use lazy_static::lazy_static;
-use regex::{Captures, Regex};
-
 use super::{content_line::PropertyName, ParseError};
 
-lazy_static! {
-    static ref DATESTR_RE: Regex =
-        Regex::new(r"(?m)^([0-9]{4})([0-9]{2})([0-9]{2})(T([0-9]{2})([0-9]{2})([0-9]{2})(Z?))?$")
-            .expect("DATESTR_RE regex failed");
-}
-
 #[derive(Debug, PartialEq)]
 pub(crate) struct ParsedDateString {
     pub year: i32,
@@ -33,74 +25,89 @@ pub(crate) struct ParsedDateStringTime {
     pub sec: u32,
 }
 
-fn get_datetime_captures<T: FromStr>(
-    captures: &Captures,
-    idx: usize,
-    val: &str,
-) -> Result<T, ParseError> {
-    captures
-        .get(idx)
-        .ok_or_else(|| ParseError::InvalidDateTimeFormat(val.into()))?
-        .as_str()
-        .parse()
-        .map_err(|_| ParseError::InvalidDateTimeFormat(val.into()))
+/// Parses the ASCII digits of `digits`, or returns `None` if it has any other character.
+fn parse_digits(digits: &[u8]) -> Option<u32> {
+    digits.iter().try_fold(0_u32, |number, digit| {
+        digit
+            .is_ascii_digit()
+            .then(|| number * 10 + u32::from(digit - b'0'))
+    })
 }
 
 impl ParsedDateString {
     /// Parses a date string with format `YYYYMMDD(THHMMSSZ)` where the part in parentheses
     /// is optional. It returns [`ParsedDateString`].
+    ///
+    /// The date string is the first line of `val` with this format.
     pub(crate) fn from_ical_datetime(val: &str) -> Result<Self, ParseError> {
-        let captures = DATESTR_RE
-            .captures(val)
-            .ok_or_else(|| ParseError::InvalidDateTimeFormat(val.into()))?;
-
-        let year = get_datetime_captures(&captures, 1, val)?;
-        let month = get_datetime_captures(&captures, 2, val)?;
-        let day = get_datetime_captures(&captures, 3, val)?;
-
-        // Check if time part is captured
-        let time = if captures.get(4).is_some() {
-            let hour = get_datetime_captures(&captures, 5, val)?;
-            let min = get_datetime_captures(&captures, 6, val)?;
-            let sec = get_datetime_captures(&captures, 7, val)?;
-            Some(ParsedDateStringTime { hour, min, sec })
-        } else {
-            None
-        };
+        val.split('\n')
+            .find_map(Self::from_line)
+            .ok_or_else(|| ParseError::InvalidDateTimeFormat(val.into()))
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let bytes = line.as_bytes();
+        let (date, rest) = bytes.split_at(bytes.len().min(8));
+        if date.len() < 8 {
+            return None;
+        }
+        let year = i32::try_from(parse_digits(&date[..4])?).ok()?;
+        let month = parse_digits(&date[4..6])?;
+        let day = parse_digits(&date[6..])?;
 
-        let zulu_timezone_set = match captures.get(8) {
-            Some(part) => part.as_str() == "Z",
-            None => false,
+        let (time, zulu) = match rest {
+            [] => (None, rest),
+            [b'T' | b't', time @ ..] => {
+                let (time, zulu) = time.split_at(time.len().min(6));
+                if time.len() < 6 {
+                    return None;
+                }
+                let time = ParsedDateStringTime {
+                    hour: parse_digits(&time[..2])?,
+                    min: parse_digits(&time[2..4])?,
+                    sec: parse_digits(&time[4..])?,
+                };
+                (Some(time), zulu)
+            }
+            _ => return None,
+        };
+        let zulu_timezone_set = match zulu {
+            [] => false,
+            [b'Z' | b'z'] => true,
+            _ => return None,
         };
-        let flags = ParsedDateStringFlags { zulu_timezone_set };
 
-        Ok(Self {
+        Some(Self {
             year,
             month,
             day,
             time,
-            flags,
+            flags: ParsedDateStringFlags { zulu_timezone_set },
         })
     }
 }
 
-lazy_static! {
-    static ref PARSE_PROPERTY_NAME_RE: Regex =
-        Regex::new(r"(?m)^([A-Z]+?)[:;]").expect("PARSE_PROPERTY_NAME_RE regex failed");
-}
-
 /// Get the line property name, the `RRULE:`, `EXRULE:` etc part.
+///
+/// The property name is the name before the first `:` or `;` of the first line of `val`
+/// that starts with one.
 pub(crate) fn get_property_name(val: &str) -> Result<Option<PropertyName>, ParseError> {
-    PARSE_PROPERTY_NAME_RE
-        .captures(val)
-        .and_then(|captures| captures.get(1))
-        .map(|name| PropertyName::from_str(name.as_str()))
+    val.split('\n')
+        .find_map(property_name_of_line)
+        .map(PropertyName::from_str)
         .transpose()
 }
 
+/// Returns the name of a line that starts with letters and dashes followed by `:` or `;`.
+fn property_name_of_line(line: &str) -> Option<&str> {
+    let name_len = line.find(|c: char| !(c.is_ascii_alphabetic() || c == '-'))?;
+    let is_name_end = line[name_len..].starts_with([':', ';']);
+    (name_len > 0 && is_name_end).then(|| &line[..name_len])
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::parser::{content_line::PropertyName, regex::get_property_name, ParseError};
+    use crate::parser::{content_line::PropertyName, lexer::get_property_name, ParseError};
 
     use super::{ParsedDateString, ParsedDateStringFlags, ParsedDateStringTime};
 
@@ -182,6 +189,9 @@ mod tests {
                 "201010177",
                 "20101017T1200",
                 "210101017T1200",
+                "20101017Z",
+                "20101017T120000ZZ",
+                "2010+017T120000Z",
             ]
             .to_vec(),
         ]
@@ -207,6 +217,10 @@ mod tests {
                 "DTSTART;TZID=America/Everywhere:20120251T023000Z\nFREQ=DAILY;INTERVAL=1;",
                 PropertyName::DtStart,
             ),
+            (
+                "RECURRENCE-ID;TZID=Europe/Paris:20120201T093000",
+                PropertyName::RecurrenceId,
+            ),
         ];
         for (input, expected_output) in tests {
             let output = get_property_name(input);
@@ -1,20 +1,41 @@
 //! Module for parsing text inputs to a [`Grammar`] which can further be used
 //! to construct an [`crate::RRuleSet`].
+mod config;
 mod content_line;
 mod datetime;
 mod error;
-mod regex;
+mod lexer;
+mod resolver;
 mod utils;
+mod warning;
+#[cfg(feature = "windows-zones")]
+mod windows_zones;
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
-pub(crate) use content_line::{ContentLine, ContentLineCaptures};
+pub use config::{Compat, ParserConfig, ParserLimits};
+pub(crate) use content_line::{
+    parse_date_content_line, parse_exdate_content_line, parse_rdate_content_line,
+    parse_rule_content_line, ContentLine, ContentLineCaptures, StartDateContentLine,
+};
+#[cfg(feature = "serde")]
+pub(crate) use datetime::datestring_to_date;
 pub(crate) use datetime::str_to_weekday;
 pub use error::ParseError;
+pub(crate) use resolver::CachedTzResolver;
+pub use resolver::{ChronoTzResolver, TzResolver};
+#[cfg(feature = "icalendar")]
+pub(crate) use utils::split_unquoted;
+pub(crate) use utils::{find_unquoted, unfold_lines};
+pub use warning::ParseWarning;
 
-use crate::RRule;
-
-use self::content_line::{PropertyName, StartDateContentLine};
+use self::content_line::PropertyName;
+use self::lexer::get_property_name;
+use self::resolver::InputTzResolver;
+use self::utils::{lines_with_offsets, unescape_text};
+use crate::core::DateTime;
+use crate::{RecurrenceOverride, Tz, VTimezone};
 
 /// Grammar represents a well-formatted rrule input.
 #[derive(Debug, PartialEq)]
@@ -27,44 +48,193 @@ impl FromStr for Grammar {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let content_lines_parts = s
-            .lines()
-            .map(ContentLineCaptures::new)
-            .collect::<Result<Vec<_>, _>>()?;
+        Self::parse(s, &ParserConfig::default())
+    }
+}
+
+impl Grammar {
+    pub(crate) fn parse(s: &str, config: &ParserConfig) -> Result<Self, ParseError> {
+        Self::parse_lines(s, config, false)
+    }
+
+    /// Parses a `VEVENT` component, with the `VTIMEZONE` components and the `VEVENT`
+    /// components of the overridden recurrences around it, like in an iCalendar file.
+    /// The properties of the event other than `DTSTART`, `DTEND`, `DURATION`, `RRULE`,
+    /// `EXRULE`, `RDATE` and `EXDATE` are ignored, as well as the lines outside of the
+    /// components.
+    pub(crate) fn parse_vevent(s: &str, config: &ParserConfig) -> Result<Self, ParseError> {
+        Self::parse_lines(s, config, true)
+    }
+
+    fn parse_lines(s: &str, config: &ParserConfig, vevent: bool) -> Result<Self, ParseError> {
+        let input = unfold_lines(s);
+        let components = Components::split(s, &input, config, vevent)?;
+        let resolver = InputTzResolver {
+            resolver: config.resolver.as_ref(),
+            timezones: components.timezones,
+        };
+        let mut content_lines_parts = components.content_lines_parts;
 
+        let (event_components, override_components): (Vec<_>, Vec<_>) = components
+            .vevents
+            .into_iter()
+            .partition(|lines| vevent && !has_recurrence_id(lines));
+        if vevent {
+            let [event_lines] = <[_; 1]>::try_from(event_components)
+                .map_err(|_| ParseError::ExpectedSingleVEvent)?;
+            content_lines_parts.extend(Self::event_content_lines(s, &event_lines, config)?);
+        }
+        Self::from_content_lines(
+            s,
+            content_lines_parts,
+            &override_components,
+            config,
+            &resolver,
+        )
+    }
+
+    /// Parses the recurring `VEVENT` components of a `VCALENDAR`, with the `VEVENT`
+    /// components of their overridden recurrences, which have the same `UID`.
+    ///
+    /// The events without an `RRULE` or `RDATE`, and the overrides without the event they
+    /// override, are left out.
+    pub(crate) fn parse_calendar(
+        s: &str,
+        config: &ParserConfig,
+    ) -> Result<Vec<CalendarEvent>, ParseError> {
+        let input = unfold_lines(s);
+        let components = Components::split(s, &input, config, true)?;
+        let resolver = InputTzResolver {
+            resolver: config.resolver.as_ref(),
+            timezones: components.timezones,
+        };
+
+        // The components of each `UID`, in the order of the input. The components without
+        // a `UID` can't be related to each other, so each of them is on its own.
+        let mut uids: HashMap<&str, usize> = HashMap::new();
+        let mut groups: Vec<Vec<Vec<(usize, &str)>>> = vec![];
+        for lines in components.vevents {
+            let group = property_value(&lines, "UID").map(|(_, _, uid)| uid);
+            match group.and_then(|uid| uids.get(uid)) {
+                Some(&idx) => groups[idx].push(lines),
+                None => {
+                    if let Some(uid) = group {
+                        uids.insert(uid, groups.len());
+                    }
+                    groups.push(vec![lines]);
+                }
+            }
+        }
+
+        let mut events = vec![];
+        for group in groups {
+            let (mut event_components, override_components): (Vec<_>, Vec<_>) = group
+                .into_iter()
+                .partition(|lines| !has_recurrence_id(lines));
+            if let Some(duplicate) = event_components.get(1) {
+                let (offset, line, uid) = property_value(duplicate, "UID").unwrap_or((0, "", ""));
+                let err = ParseError::DuplicateUid(uid.into());
+                return Err(err.locate(s, offset, line));
+            }
+            let Some(event_lines) = event_components.pop() else {
+                continue;
+            };
+            let is_recurring = event_properties(&event_lines).any(|(_, line)| {
+                matches!(
+                    get_property_name(line),
+                    Ok(Some(PropertyName::RRule | PropertyName::RDate))
+                )
+            });
+            if !is_recurring {
+                continue;
+            }
+
+            let content_lines_parts = Self::event_content_lines(s, &event_lines, config)?;
+            let grammar = Self::from_content_lines(
+                s,
+                content_lines_parts,
+                &override_components,
+                config,
+                &resolver,
+            )?;
+            let text = |name| property_value(&event_lines, name).map(|(_, _, value)| value);
+            events.push(CalendarEvent {
+                uid: text("UID").map(Into::into),
+                summary: text("SUMMARY").map(unescape_text),
+                grammar,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Returns the `DTSTART`, `DTEND`, `DURATION`, `RRULE`, `RDATE`, `EXRULE` and `EXDATE`
+    /// content lines of a `VEVENT` component.
+    fn event_content_lines<'a>(
+        s: &str,
+        lines: &[(usize, &'a str)],
+        config: &ParserConfig,
+    ) -> Result<Vec<(usize, &'a str, ContentLineCaptures<'a>)>, ParseError> {
+        let mut content_lines_parts = vec![];
+        for (offset, line) in event_properties(lines) {
+            let Ok(Some(property_name)) = get_property_name(line) else {
+                continue;
+            };
+            if property_name == PropertyName::RecurrenceId {
+                continue;
+            }
+            let parts =
+                ContentLineCaptures::new(line).map_err(|err| err.locate(s, offset, line))?;
+            let is_rule = matches!(
+                parts.property_name,
+                PropertyName::RRule | PropertyName::ExRule
+            );
+            config.limits.check_list_values(parts.value, is_rule)?;
+            content_lines_parts.push((offset, line, parts));
+        }
+        Ok(content_lines_parts)
+    }
+
+    /// Parses the content lines and the `VEVENT` components of the overridden recurrences
+    /// of a set.
+    fn from_content_lines(
+        s: &str,
+        content_lines_parts: Vec<(usize, &str, ContentLineCaptures)>,
+        override_components: &[Vec<(usize, &str)>],
+        config: &ParserConfig,
+        resolver: &dyn TzResolver,
+    ) -> Result<Self, ParseError> {
         let start = content_lines_parts
             .iter()
-            .find(|parts| matches!(parts.property_name, PropertyName::DtStart))
-            .map(StartDateContentLine::try_from)
+            .find(|(_, _, parts)| matches!(parts.property_name, PropertyName::DtStart))
+            .map(|(offset, line, parts)| {
+                StartDateContentLine::parse(parts, config.compat, resolver)
+                    .map_err(|err| err.locate(s, *offset, line))
+            })
             .ok_or(ParseError::MissingStartDate)??;
 
         let mut content_lines = vec![];
 
-        for parts in content_lines_parts {
-            let line = match parts.property_name {
-                PropertyName::RRule => {
-                    let rrule = RRule::try_from(parts)?;
-                    ContentLine::RRule(rrule)
-                }
-                PropertyName::ExRule => {
-                    let rrule = RRule::try_from(parts)?;
-                    ContentLine::ExRule(rrule)
-                }
-                PropertyName::RDate => ContentLine::RDate(TryFrom::try_from(parts)?),
-                PropertyName::ExDate => ContentLine::ExDate(TryFrom::try_from(parts)?),
-                PropertyName::DtStart => {
-                    // Nothing to do
-                    continue;
-                }
-            };
-            content_lines.push(line);
+        for (offset, line, parts) in content_lines_parts {
+            let content_line = Self::parse_content_line(parts, config, resolver)
+                .map_err(|err| err.locate(s, offset, line))?;
+            content_lines.extend(content_line);
+        }
+        // The timezones of all the `VTIMEZONE` components are known now.
+        for lines in override_components {
+            let recurrence_override = Self::parse_override_component(s, lines, config, resolver)?;
+            content_lines.push(ContentLine::Override(recurrence_override));
         }
 
         // Need to be at least one `RDATE` or `RRULE`
-        if !content_lines
-            .iter()
-            .any(|line| matches!(line, ContentLine::RRule(_) | ContentLine::RDate(_)))
-        {
+        if !content_lines.iter().any(|line| {
+            matches!(
+                line,
+                ContentLine::RRule(_)
+                    | ContentLine::RDate(_)
+                    | ContentLine::RDatePeriods(_)
+                    | ContentLine::RDateDays(_)
+            )
+        }) {
             return Err(ParseError::MissingDateGenerationRules);
         }
 
@@ -73,11 +243,224 @@ impl FromStr for Grammar {
             content_lines,
         })
     }
+
+    /// Parses a content line other than `DTSTART`, or returns `None` for `DTSTART`.
+    fn parse_content_line(
+        parts: ContentLineCaptures,
+        config: &ParserConfig,
+        resolver: &dyn TzResolver,
+    ) -> Result<Option<ContentLine>, ParseError> {
+        let line = match parts.property_name {
+            PropertyName::RRule => {
+                let rrule = parse_rule_content_line(parts, config, resolver)?;
+                ContentLine::RRule(rrule)
+            }
+            PropertyName::ExRule => {
+                let rrule = parse_rule_content_line(parts, config, resolver)?;
+                ContentLine::ExRule(rrule)
+            }
+            PropertyName::RDate => parse_rdate_content_line(parts, config.compat, resolver)?,
+            PropertyName::ExDate => parse_exdate_content_line(parts, config.compat, resolver)?,
+            PropertyName::DtStart => {
+                // Nothing to do
+                return Ok(None);
+            }
+            PropertyName::DtEnd => {
+                ContentLine::DtEnd(Self::parse_single_date(parts, config, resolver)?)
+            }
+            PropertyName::Duration => ContentLine::Duration(parts.value.parse()?),
+            PropertyName::RecurrenceId => return Err(ParseError::MisplacedRecurrenceId),
+        };
+        Ok(Some(line))
+    }
+
+    /// Parses the `VEVENT` component of an overridden recurrence from its lines and their
+    /// offsets. Only the `RECURRENCE-ID`, `DTSTART` and `STATUS` properties are used.
+    fn parse_override_component(
+        s: &str,
+        lines: &[(usize, &str)],
+        config: &ParserConfig,
+        resolver: &dyn TzResolver,
+    ) -> Result<RecurrenceOverride, ParseError> {
+        let mut recurrence_id = None;
+        let mut start = None;
+        let mut cancelled = false;
+        for &(offset, line) in lines {
+            let name = line.split([':', ';']).next().unwrap_or_default();
+            if name.eq_ignore_ascii_case("STATUS") {
+                let status = line.split_once(':').map(|(_, value)| value.trim());
+                cancelled = status.is_some_and(|status| status.eq_ignore_ascii_case("CANCELLED"));
+                continue;
+            }
+            let date = if name.eq_ignore_ascii_case("RECURRENCE-ID") {
+                &mut recurrence_id
+            } else if name.eq_ignore_ascii_case("DTSTART") {
+                &mut start
+            } else {
+                continue;
+            };
+            let value = ContentLineCaptures::new(line)
+                .and_then(|parts| Self::parse_single_date(parts, config, resolver))
+                .map_err(|err| err.locate(s, offset, line))?;
+            if date.replace(value).is_some() {
+                let err = ParseError::DuplicateProperty(name.to_uppercase());
+                return Err(err.locate(s, offset, line));
+            }
+        }
+
+        let recurrence_id = recurrence_id.ok_or_else(|| {
+            let (offset, line) = lines[0];
+            ParseError::MissingProperty("RECURRENCE-ID".into()).locate(s, offset, line)
+        })?;
+        Ok(if cancelled {
+            RecurrenceOverride::cancelled(recurrence_id)
+        } else {
+            // An override without a `DTSTART` only changes other properties.
+            RecurrenceOverride::moved(recurrence_id, start.unwrap_or(recurrence_id))
+        })
+    }
+
+    /// Parses a date property with a single value, like `RECURRENCE-ID` or `DTEND`.
+    fn parse_single_date(
+        parts: ContentLineCaptures,
+        config: &ParserConfig,
+        resolver: &dyn TzResolver,
+    ) -> Result<DateTime, ParseError> {
+        let value = parts.value;
+        match parse_date_content_line(parts, config.compat, resolver)?[..] {
+            [date] => Ok(date),
+            _ => Err(ParseError::InvalidDateTimeFormat(value.into())),
+        }
+    }
+}
+
+/// A recurring `VEVENT` component of a `VCALENDAR`, see [`Grammar::parse_calendar`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct CalendarEvent {
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    pub grammar: Grammar,
+}
+
+/// The components of an input and the content lines outside of them, with the offsets
+/// of their lines in the unfolded input.
+struct Components<'a> {
+    /// The timezones of the `VTIMEZONE` components.
+    timezones: Vec<Tz>,
+    /// The lines of the `VEVENT` components.
+    vevents: Vec<Vec<(usize, &'a str)>>,
+    /// The content lines outside of the components.
+    content_lines_parts: Vec<(usize, &'a str, ContentLineCaptures<'a>)>,
+}
+
+impl<'a> Components<'a> {
+    /// Splits the unfolded `input` of `s` into its components. The lines outside of the
+    /// components are ignored if `components_only` is `true`.
+    fn split(
+        s: &str,
+        input: &'a str,
+        config: &ParserConfig,
+        components_only: bool,
+    ) -> Result<Self, ParseError> {
+        let limits = &config.limits;
+        let mut components = Self {
+            timezones: vec![],
+            vevents: vec![],
+            content_lines_parts: vec![],
+        };
+        // The lines of the current `VTIMEZONE` or `VEVENT` component.
+        let mut vtimezone_lines: Option<Vec<&str>> = None;
+        let mut vevent_lines: Option<Vec<(usize, &str)>> = None;
+        for (idx, (offset, line)) in lines_with_offsets(input).enumerate() {
+            // The limits are checked before parsing, so the errors aren't located.
+            limits.check_content_lines(idx + 1)?;
+            limits.check_line_length(line)?;
+            if let Some(lines) = vtimezone_lines.as_mut() {
+                lines.push(line);
+                if line.eq_ignore_ascii_case("END:VTIMEZONE") {
+                    let vtimezone: VTimezone = lines.join("\n").parse()?;
+                    components.timezones.push(Tz::from(vtimezone));
+                    vtimezone_lines = None;
+                }
+                continue;
+            }
+            if line.eq_ignore_ascii_case("BEGIN:VTIMEZONE") {
+                vtimezone_lines = Some(vec![line]);
+                continue;
+            }
+            if let Some(lines) = vevent_lines.as_mut() {
+                lines.push((offset, line));
+                if line.eq_ignore_ascii_case("END:VEVENT") {
+                    components.vevents.extend(vevent_lines.take());
+                }
+                continue;
+            }
+            if line.eq_ignore_ascii_case("BEGIN:VEVENT") {
+                vevent_lines = Some(vec![(offset, line)]);
+                continue;
+            }
+            if components_only {
+                continue;
+            }
+
+            let parts =
+                ContentLineCaptures::new(line).map_err(|err| err.locate(s, offset, line))?;
+            let is_rule = matches!(
+                parts.property_name,
+                PropertyName::RRule | PropertyName::ExRule
+            );
+            limits.check_list_values(parts.value, is_rule)?;
+            components.content_lines_parts.push((offset, line, parts));
+        }
+        Ok(components)
+    }
+}
+
+/// Returns `true` if the lines of a `VEVENT` component have a `RECURRENCE-ID`.
+fn has_recurrence_id(lines: &[(usize, &str)]) -> bool {
+    event_properties(lines).any(|(_, line)| {
+        matches!(
+            get_property_name(line),
+            Ok(Some(PropertyName::RecurrenceId))
+        )
+    })
+}
+
+/// Returns the property lines of a `VEVENT` component, without the lines of the
+/// components in it, like `VALARM`.
+fn event_properties<'a: 'b, 'b>(
+    lines: &'b [(usize, &'a str)],
+) -> impl Iterator<Item = (usize, &'a str)> + 'b {
+    let mut depth = 0_usize;
+    lines.iter().copied().filter(move |(_, line)| {
+        let is_begin = line.len() >= 6 && line[..6].eq_ignore_ascii_case("BEGIN:");
+        let is_end = line.len() >= 4 && line[..4].eq_ignore_ascii_case("END:");
+        if is_begin {
+            depth += 1;
+        } else if is_end {
+            depth = depth.saturating_sub(1);
+        }
+        depth == 1 && !is_begin
+    })
+}
+
+/// Returns the offset, the line and the value of the first property named `name` in the
+/// lines of a `VEVENT` component.
+fn property_value<'a>(lines: &[(usize, &'a str)], name: &str) -> Option<(usize, &'a str, &'a str)> {
+    event_properties(lines).find_map(|(offset, line)| {
+        let property_name = line.split([':', ';']).next().unwrap_or_default();
+        if !property_name.eq_ignore_ascii_case(name) {
+            return None;
+        }
+        let colon = find_unquoted(line, ':')?;
+        Some((offset, line, line[colon + 1..].trim()))
+    })
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono-tz"))]
 mod test {
     use chrono::{TimeZone, Weekday};
+    use smallvec::smallvec;
 
     use super::*;
     use crate::{core::Tz, parser::content_line::ContentLine, Frequency, NWeekday, RRule};
@@ -107,7 +490,7 @@ mod test {
             freq: Frequency::Weekly,
             interval: 5,
             until: Some(UTC.with_ymd_and_hms(2013, 1, 30,23, 0, 0).unwrap()),
-            by_weekday: vec![NWeekday::Every(Weekday::Mon), NWeekday::Every(Weekday::Fri)],
+            by_weekday: smallvec![NWeekday::Every(Weekday::Mon), NWeekday::Every(Weekday::Fri)],
             ..Default::default()
         })
     ]
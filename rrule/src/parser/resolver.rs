@@ -0,0 +1,252 @@
+//! Resolution of `TZID` parameters to timezones.
+use std::collections::HashMap;
+use std::fmt::Debug;
+#[cfg(feature = "chrono-tz")]
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::FixedOffset;
+
+use crate::{Tz, VTimezone};
+
+/// Maps the `TZID` parameters of the input to timezones.
+///
+/// The parser consults the [`crate::ParserConfig::resolver`] for every `TZID`. It can
+/// be replaced to support timezone names that aren't IANA timezone names, like the
+/// Windows timezone names of Outlook, or to use fixed timezones in tests.
+///
+/// # Usage
+///
+#[cfg_attr(feature = "chrono-tz", doc = "```")]
+#[cfg_attr(not(feature = "chrono-tz"), doc = "```ignore")]
+/// use std::sync::Arc;
+///
+/// use rrule::{ChronoTzResolver, ParserConfig, RRuleSet, Tz, TzResolver};
+///
+/// #[derive(Debug)]
+/// struct OutlookResolver;
+///
+/// impl TzResolver for OutlookResolver {
+///     fn resolve(&self, tzid: &str) -> Option<Tz> {
+///         match tzid {
+///             "W. Europe Standard Time" => Some(Tz::Europe__Berlin),
+///             _ => ChronoTzResolver.resolve(tzid),
+///         }
+///     }
+/// }
+///
+/// let config = ParserConfig {
+///     resolver: Arc::new(OutlookResolver),
+///     ..Default::default()
+/// };
+/// let input = "DTSTART;TZID=W. Europe Standard Time:20210101T090000\nRRULE:FREQ=DAILY;COUNT=1";
+/// let rrule_set = RRuleSet::parse_with_config(input, &config).unwrap();
+/// assert_eq!(rrule_set.get_dt_start().timezone(), Tz::Europe__Berlin);
+/// ```
+pub trait TzResolver: Debug + Send + Sync {
+    /// Returns the timezone of the given `TZID`, or `None` if it's unknown.
+    fn resolve(&self, tzid: &str) -> Option<Tz>;
+}
+
+/// The default [`TzResolver`], which resolves IANA timezone names with `chrono-tz`.
+///
+/// Without the `chrono-tz` feature, it only resolves `UTC` and the UTC offsets below.
+///
+/// UTC offsets like `UTC+05:30` or `GMT-0800`, which Zoom and Lotus Notes use, are
+/// resolved to timezones with a fixed offset, see [`VTimezone::fixed`].
+///
+/// With the `windows-zones` feature, it also resolves the Windows timezone names that
+/// Outlook and Exchange use, like `Eastern Standard Time`, with the CLDR mapping.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChronoTzResolver;
+
+impl TzResolver for ChronoTzResolver {
+    fn resolve(&self, tzid: &str) -> Option<Tz> {
+        #[cfg(feature = "chrono-tz")]
+        let tz = {
+            let tz = chrono_tz::Tz::from_str(tzid).ok();
+            #[cfg(feature = "windows-zones")]
+            let tz = tz.or_else(|| super::windows_zones::windows_to_iana(tzid));
+            tz.map(Tz::Tz)
+        };
+        #[cfg(not(feature = "chrono-tz"))]
+        let tz = (tzid == "UTC").then_some(Tz::UTC);
+        tz.or_else(|| {
+            let offset = parse_offset_tzid(tzid)?;
+            Some(Tz::from(VTimezone::fixed(tzid, offset)))
+        })
+    }
+}
+
+/// Parses a `TZID` like `UTC+05:30`, `UTC+5` or `GMT-0800` to its offset.
+fn parse_offset_tzid(tzid: &str) -> Option<FixedOffset> {
+    let prefix = tzid.get(..3)?;
+    if !prefix.eq_ignore_ascii_case("UTC") && !prefix.eq_ignore_ascii_case("GMT") {
+        return None;
+    }
+    let offset = tzid[3..].trim_start();
+    let sign = match offset.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let offset = &offset[1..];
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if offset.len() > 2 => offset.split_at(offset.len() - 2),
+        None => (offset, "0"),
+    };
+    if !(1..=2).contains(&hours.len())
+        || !(1..=2).contains(&minutes.len())
+        || !hours
+            .bytes()
+            .chain(minutes.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Resolves the timezones defined by the `VTIMEZONE` components of the input,
+/// after the timezones of the configured resolver.
+#[derive(Debug)]
+pub(crate) struct InputTzResolver<'a> {
+    pub resolver: &'a dyn TzResolver,
+    pub timezones: Vec<Tz>,
+}
+
+impl<'a> TzResolver for InputTzResolver<'a> {
+    fn resolve(&self, tzid: &str) -> Option<Tz> {
+        self.resolver.resolve(tzid).or_else(|| {
+            self.timezones
+                .iter()
+                .find(|timezone| timezone.name() == tzid)
+                .copied()
+        })
+    }
+}
+
+/// Remembers the timezones that another resolver returned, so that parsing many inputs
+/// with the same `TZID`s resolves each of them once, see [`crate::build_rrulesets`].
+#[derive(Debug)]
+pub(crate) struct CachedTzResolver {
+    resolver: Arc<dyn TzResolver>,
+    timezones: Mutex<HashMap<String, Option<Tz>>>,
+}
+
+impl CachedTzResolver {
+    pub(crate) fn new(resolver: Arc<dyn TzResolver>) -> Self {
+        Self {
+            resolver,
+            timezones: Mutex::default(),
+        }
+    }
+}
+
+impl TzResolver for CachedTzResolver {
+    fn resolve(&self, tzid: &str) -> Option<Tz> {
+        let mut timezones = self
+            .timezones
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(tz) = timezones.get(tzid) {
+            return *tz;
+        }
+        let tz = self.resolver.resolve(tzid);
+        timezones.insert(tzid.into(), tz);
+        tz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn resolves_iana_timezone_names() {
+        assert_eq!(
+            ChronoTzResolver.resolve("Europe/Berlin"),
+            Some(Tz::Europe__Berlin)
+        );
+        assert_eq!(ChronoTzResolver.resolve("UTC"), Some(Tz::UTC));
+        assert_eq!(ChronoTzResolver.resolve("Europe/Atlantis"), None);
+    }
+
+    #[test]
+    #[cfg(not(feature = "chrono-tz"))]
+    fn resolves_only_utc_without_chrono_tz() {
+        assert_eq!(ChronoTzResolver.resolve("UTC"), Some(Tz::UTC));
+        assert_eq!(ChronoTzResolver.resolve("Europe/Berlin"), None);
+    }
+
+    #[test]
+    fn parses_offset_tzids() {
+        let tests = [
+            ("UTC+05:30", 5 * 3600 + 30 * 60),
+            ("UTC+5", 5 * 3600),
+            ("utc-3:30", -(3 * 3600 + 30 * 60)),
+            ("GMT-0800", -8 * 3600),
+            ("GMT +0100", 3600),
+            ("GMT+14", 14 * 3600),
+        ];
+        for (input, seconds) in tests {
+            assert_eq!(
+                parse_offset_tzid(input),
+                FixedOffset::east_opt(seconds),
+                "{input}"
+            );
+        }
+
+        let invalid = [
+            "UTC",
+            "UTC5",
+            "UTC+",
+            "UTC+15",
+            "UTC+05:60",
+            "UTC+123:00",
+            "GMT+0a",
+            "CET+01",
+        ];
+        for input in invalid {
+            assert_eq!(parse_offset_tzid(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn resolves_offset_tzids_to_fixed_offsets() {
+        let tz = ChronoTzResolver.resolve("UTC+05:30").unwrap();
+        assert_eq!(tz.name(), "UTC+05:30");
+        assert_eq!(
+            ChronoTzResolver.resolve("UTC+05:30"),
+            Some(tz),
+            "the same timezone is resolved again"
+        );
+    }
+
+    #[test]
+    fn resolves_each_tzid_once_with_the_cache() {
+        #[derive(Debug, Default)]
+        struct CountingResolver(std::sync::atomic::AtomicUsize);
+
+        impl TzResolver for CountingResolver {
+            fn resolve(&self, tzid: &str) -> Option<Tz> {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                (tzid == "UTC").then_some(Tz::UTC)
+            }
+        }
+
+        let counting = Arc::new(CountingResolver::default());
+        let resolver = CachedTzResolver::new(Arc::clone(&counting) as Arc<dyn TzResolver>);
+        for _ in 0..3 {
+            assert_eq!(resolver.resolve("UTC"), Some(Tz::UTC));
+            assert_eq!(resolver.resolve("Europe/Atlantis"), None);
+        }
+        assert_eq!(counting.0.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+}
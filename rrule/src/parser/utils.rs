@@ -3,16 +3,17 @@ use std::str::FromStr;
 
 /// Attempts to convert a comma separated `&str` to a `Vec<T>` of unique and sorted values.
 /// The function accepts a closure which can be used to validate the values which are parsed.
+/// On failure, the value that is invalid is returned.
 pub(crate) fn parse_str_to_vec<T: FromStr + Ord + PartialEq + Copy, F: Fn(T) -> bool>(
     val: &str,
     accept: F,
 ) -> Result<Vec<T>, String> {
-    if val.is_empty() {
+    if val.trim().is_empty() {
         return Ok(vec![]);
     }
 
     let mut parsed_vals = vec![];
-    for val in val.split(',') {
+    for val in val.split(',').map(str::trim) {
         let parsed_val = val.parse().map_err(|_| val.to_string())?;
         if accept(parsed_val) {
             parsed_vals.push(parsed_val);
@@ -27,9 +28,238 @@ pub(crate) fn parse_str_to_vec<T: FromStr + Ord + PartialEq + Copy, F: Fn(T) ->
     Ok(parsed_vals)
 }
 
+/// Unfolds content lines that are continued on the next line after a line break
+/// and a space or tab.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc5545#section-3.1>.
+pub(crate) fn unfold_lines(input: &str) -> String {
+    input
+        .replace("\r\n ", "")
+        .replace("\r\n\t", "")
+        .replace("\n ", "")
+        .replace("\n\t", "")
+}
+
+/// Unescapes the backslashes, commas, semicolons and line breaks of a `TEXT` value, like
+/// the `SUMMARY` of an event.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.11>.
+pub(crate) fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// The line breaks and whitespace that continue a folded content line.
+const FOLDS: [&[u8]; 4] = [b"\r\n ", b"\r\n\t", b"\n ", b"\n\t"];
+
+/// Returns the byte offset in the folded `input` of the byte at `offset` in the
+/// unfolded input.
+pub(crate) fn folded_offset(input: &str, offset: usize) -> usize {
+    let bytes = input.as_bytes();
+    let mut folded = 0;
+    let mut unfolded = 0;
+    loop {
+        if let Some(fold) = FOLDS.iter().find(|fold| bytes[folded..].starts_with(fold)) {
+            folded += fold.len();
+            continue;
+        }
+        if unfolded == offset || folded >= bytes.len() {
+            return folded;
+        }
+        folded += 1;
+        unfolded += 1;
+    }
+}
+
+/// Returns the non-empty lines of `input` without surrounding whitespace,
+/// together with the byte offset at which each line starts.
+pub(crate) fn lines_with_offsets(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            let trimmed = line.trim_start();
+            Some((start + line.len() - trimmed.len(), trimmed.trim_end()))
+        })
+        .filter(|(_, line)| !line.is_empty())
+}
+
+/// Returns the byte offset of the first `separator` in `s` that isn't within double quotes,
+/// as parameter values that contain `:`, `;` or `,` are quoted, e.g. `TZID="UTC+05:30"`.
+pub(crate) fn find_unquoted(s: &str, separator: char) -> Option<usize> {
+    let mut quoted = false;
+    s.char_indices().find_map(|(idx, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        (c == separator && !quoted).then_some(idx)
+    })
+}
+
+/// Returns the byte offset of the last `separator` in `s` that isn't within double quotes.
+pub(crate) fn rfind_unquoted(s: &str, separator: char) -> Option<usize> {
+    let mut quoted = false;
+    let mut found = None;
+    for (idx, c) in s.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            found = Some(idx);
+        }
+    }
+    found
+}
+
+/// Splits `s` at every `separator` that isn't within double quotes.
+pub(crate) fn split_unquoted(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut rest = s;
+    while let Some(idx) = find_unquoted(rest, separator) {
+        parts.push(&rest[..idx]);
+        rest = &rest[idx + separator.len_utf8()..];
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Returns the byte offset of `token` in `line`, preferring an occurrence that
+/// is a whole value, i.e. surrounded by `:`, `;`, `=` or `,`.
+pub(crate) fn find_token(line: &str, token: &str) -> Option<usize> {
+    if token.is_empty() {
+        return None;
+    }
+    let is_separator = |c: Option<char>| c.map_or(true, |c| matches!(c, ':' | ';' | '=' | ','));
+    line.match_indices(token)
+        .map(|(idx, _)| idx)
+        .find(|idx| {
+            is_separator(line[..*idx].chars().next_back())
+                && is_separator(line[idx + token.len()..].chars().next())
+        })
+        .or_else(|| line.find(token))
+}
+
+/// Returns the number of single character edits needed to change `a` into `b`
+/// (Levenshtein distance).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Returns the keyword closest to `value`, ignoring case, if it is close enough
+/// to be a misspelling of it.
+pub(crate) fn closest_match(value: &str, keywords: &[&'static str]) -> Option<&'static str> {
+    let value = value.trim().to_uppercase();
+    keywords
+        .iter()
+        .map(|keyword| (edit_distance(&value, keyword), *keyword))
+        .filter(|(distance, keyword)| *distance <= (keyword.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, keyword)| keyword)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_str_to_vec;
+    use super::{
+        closest_match, edit_distance, find_token, find_unquoted, folded_offset, lines_with_offsets,
+        parse_str_to_vec, rfind_unquoted, split_unquoted, unescape_text, unfold_lines,
+    };
+
+    #[test]
+    fn computes_edit_distance() {
+        let tests = [
+            ("DAILY", "DAILY", 0),
+            ("DAYLY", "DAILY", 1),
+            ("RRULLE", "RRULE", 1),
+            ("", "FREQ", 4),
+            ("BYMONTDAY", "BYMONTHDAY", 1),
+            ("FREQ", "COUNT", 5),
+        ];
+        for (a, b, expected_output) in tests {
+            assert_eq!(edit_distance(a, b), expected_output);
+        }
+    }
+
+    #[test]
+    fn finds_closest_match() {
+        let keywords = ["DAILY", "WEEKLY", "MONTHLY"];
+        let tests = [
+            ("DAYLY", Some("DAILY")),
+            ("weekly ", Some("WEEKLY")),
+            ("MONTLY", Some("MONTHLY")),
+            ("YEARLY", None),
+            ("", None),
+        ];
+        for (value, expected_output) in tests {
+            assert_eq!(closest_match(value, &keywords), expected_output);
+        }
+    }
+
+    #[test]
+    fn maps_unfolded_offsets_to_folded_offsets() {
+        let input = "RRULE:FREQ=DA\r\n ILY;BYDAY=MX";
+        let tests = [(0, 0), (12, 12), (13, 16), (16, 19), (23, 26), (25, 28)];
+        for (offset, expected_output) in tests {
+            assert_eq!(folded_offset(input, offset), expected_output);
+        }
+    }
+
+    #[test]
+    fn finds_lines_with_offsets() {
+        let lines: Vec<_> = lines_with_offsets("A:1\r\n\n  B:2 \nC:3").collect();
+        assert_eq!(lines, [(0, "A:1"), (8, "B:2"), (13, "C:3")]);
+    }
+
+    #[test]
+    fn finds_tokens() {
+        let tests = [
+            ("RRULE:BYHOUR=1,10", "1", Some(13)),
+            ("RRULE:BYHOUR=10,1", "1", Some(16)),
+            ("RRULE:BYDAY=MO, MX", " MX", Some(15)),
+            ("RRULE:BYDAY=MO", "TU", None),
+            ("RRULE:BYDAY=MO", "", None),
+        ];
+        for (line, token, expected_output) in tests {
+            assert_eq!(find_token(line, token), expected_output);
+        }
+    }
+
+    #[test]
+    fn unfolds_lines() {
+        let tests = [
+            ("RRULE:FREQ=DAILY", "RRULE:FREQ=DAILY"),
+            ("RRULE:FREQ=DA\r\n ILY", "RRULE:FREQ=DAILY"),
+            ("RRULE:FREQ=DA\n\tILY", "RRULE:FREQ=DAILY"),
+            (
+                "DTSTART:20120201T093000Z\r\nRRULE:FREQ=DA\r\n ILY",
+                "DTSTART:20120201T093000Z\r\nRRULE:FREQ=DAILY",
+            ),
+        ];
+        for (input, expected_output) in tests {
+            assert_eq!(unfold_lines(input), expected_output);
+        }
+    }
 
     #[test]
     fn parses_str_to_vec() {
@@ -51,4 +281,34 @@ mod tests {
             assert_eq!(output, expected_output);
         }
     }
+
+    #[test]
+    fn finds_and_splits_at_unquoted_separators() {
+        assert_eq!(
+            find_unquoted("DTSTART;TZID=\"UTC+05:30\":2021", ':'),
+            Some(24)
+        );
+        assert_eq!(find_unquoted("TZID=\"UTC+05:30\"", ':'), None);
+        assert_eq!(rfind_unquoted("DTSTART;TZID=UTC+05:30:2021", ':'), Some(22));
+        assert_eq!(rfind_unquoted("DTSTART;TZID=\"A:B\"", ':'), None);
+        assert_eq!(
+            split_unquoted("TZID=\"A;B\";VALUE=DATE", ';'),
+            vec!["TZID=\"A;B\"", "VALUE=DATE"]
+        );
+        assert_eq!(split_unquoted("", ';'), vec![""]);
+    }
+
+    #[test]
+    fn unescapes_text_values() {
+        let tests = [
+            ("Stand-up", "Stand-up"),
+            ("Lunch\\, Dinner\\; Drinks", "Lunch, Dinner; Drinks"),
+            ("First line\\nSecond line\\N", "First line\nSecond line\n"),
+            ("C:\\\\Users", "C:\\Users"),
+            ("Trailing\\", "Trailing\\"),
+        ];
+        for (input, expected_output) in tests {
+            assert_eq!(unescape_text(input), expected_output);
+        }
+    }
 }
@@ -0,0 +1,24 @@
+#![allow(clippy::module_name_repetitions)]
+use thiserror::Error;
+
+/// A recoverable issue found while parsing an iCalendar string,
+/// see [`crate::build_rruleset_with_warnings`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// `UNTIL` was given in local time, while `DTSTART` is in UTC or has a timezone,
+    /// in which case `UNTIL` has to be in UTC. It was interpreted in the timezone of `DTSTART`.
+    #[error("`UNTIL` value `{until}` is not in UTC, it was interpreted in the timezone `{tz}` of `DTSTART`.")]
+    UntilNotInUtc {
+        /// The `UNTIL` value in UTC.
+        until: String,
+        /// The timezone of `DTSTART`.
+        tz: String,
+    },
+    /// An `EXRULE` was found, which is deprecated in RFC 5545.
+    /// It is ignored unless the `exrule` feature is enabled.
+    #[error("`EXRULE` is deprecated in RFC 5545.")]
+    ExRuleDeprecated,
+    /// The same `RDATE` was given more than once.
+    #[error("Found duplicate `RDATE` `{0}`.")]
+    DuplicateRDate(String),
+}
@@ -1,4 +1,4 @@
-#![cfg(test)]
+#![cfg(all(test, feature = "chrono-tz"))]
 
 mod common;
 mod datetime;
@@ -8,3 +8,5 @@ mod rfc_tests;
 mod rrule;
 mod rruleset;
 mod serde;
+mod stream;
+mod thread_safety;
@@ -1,18 +1,23 @@
 use crate::core::Tz;
 use crate::tests::common::{test_recurring_rrule, ymd_hms};
-use crate::{Frequency, NWeekday, RRule, RRuleSet, Weekday};
-use chrono::{Datelike, TimeZone};
+use crate::{
+    Frequency, LintWarning, NWeekday, Occurrence, ParseError, RRule, RRuleError, RRuleSet, RScale,
+    RulePart, Skip, Unvalidated, ValidationError, Weekday,
+};
+use chrono::{Datelike, Duration, TimeZone};
+use smallvec::smallvec;
+use std::ops::ControlFlow;
 
 #[test]
 fn yearly() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -32,11 +37,11 @@ fn yearly_interval() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         interval: 2,
         ..Default::default()
     };
@@ -57,11 +62,11 @@ fn yearly_interval_large() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         interval: 40,
         ..Default::default()
     };
@@ -83,9 +88,9 @@ fn yearly_by_easter() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         by_easter: Some(0),
         ..Default::default()
     };
@@ -107,9 +112,9 @@ fn yearly_by_easterpos() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         by_easter: Some(1),
         ..Default::default()
     };
@@ -131,9 +136,9 @@ fn yearly_by_easterpos_neg() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         by_easter: Some(-2),
         ..Default::default()
     };
@@ -149,16 +154,32 @@ fn yearly_by_easterpos_neg() {
     );
 }
 
+#[test]
+#[cfg(feature = "by-easter")]
+fn parses_by_easter_with_the_time_of_dt_start() {
+    let rrule_set: RRuleSet = "DTSTART:19970902T090000Z\nRRULE:FREQ=YEARLY;BYEASTER=-2;COUNT=3"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        rrule_set.all(10).dates,
+        [
+            ymd_hms(1998, 4, 10, 9, 0, 0),
+            ymd_hms(1999, 4, 2, 9, 0, 0),
+            ymd_hms(2000, 4, 21, 9, 0, 0),
+        ]
+    );
+}
+
 #[test]
 fn yearly_by_month() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -178,10 +199,10 @@ fn yearly_by_monthday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -201,11 +222,11 @@ fn yearly_by_month_and_monthday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![5, 7],
+        by_month: smallvec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![5, 7],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -225,10 +246,10 @@ fn yearly_by_weekday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -248,13 +269,13 @@ fn yearly_by_nweekday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![
+        by_weekday: smallvec![
             NWeekday::Nth(1, Weekday::Tue),
             NWeekday::Nth(-1, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -274,13 +295,13 @@ fn yearly_by_nweekday_large() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![
+        by_weekday: smallvec![
             NWeekday::Nth(13, Weekday::Tue),
             NWeekday::Nth(-13, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -300,11 +321,11 @@ fn yearly_by_month_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -324,14 +345,14 @@ fn yearly_by_month_and_nweekday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![
             NWeekday::Nth(1, Weekday::Tue),
             NWeekday::Nth(-1, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -351,14 +372,14 @@ fn yearly_by_month_and_nweekday_large() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![
             NWeekday::Nth(3, Weekday::Tue),
             NWeekday::Nth(-3, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -378,11 +399,11 @@ fn yearly_by_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -402,12 +423,12 @@ fn yearly_by_month_and_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -427,10 +448,10 @@ fn yearly_by_yearday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(4),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![1, 100, 200, 365],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1, 100, 200, 365],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -451,10 +472,10 @@ fn yearly_by_yeardayneg() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(4),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![-365, -266, -166, -1],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![-365, -266, -166, -1],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -475,11 +496,11 @@ fn yearly_by_month_and_yearday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(4),
-        by_month: vec![4, 7],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![1, 100, 200, 365],
+        by_month: smallvec![4, 7],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1, 100, 200, 365],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -500,10 +521,10 @@ fn yearly_by_weekno() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_hour: vec![9],
-        by_week_no: vec![20],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_week_no: smallvec![20],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -523,11 +544,11 @@ fn yearly_by_weekno_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Mon)],
-        by_hour: vec![9],
-        by_week_no: vec![1],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Mon)],
+        by_hour: smallvec![9],
+        by_week_no: smallvec![1],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -547,11 +568,11 @@ fn yearly_by_weekno_and_weekday_large() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Sun)],
-        by_hour: vec![9],
-        by_week_no: vec![52],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Sun)],
+        by_hour: smallvec![9],
+        by_week_no: smallvec![52],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -571,11 +592,11 @@ fn yearly_by_weekno_and_weekday_last() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Sun)],
-        by_hour: vec![9],
-        by_week_no: vec![-1],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Sun)],
+        by_hour: smallvec![9],
+        by_week_no: smallvec![-1],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -595,11 +616,11 @@ fn yearly_by_weekno_and_weekday53_last() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Mon)],
-        by_hour: vec![9],
-        by_week_no: vec![53],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Mon)],
+        by_hour: smallvec![9],
+        by_week_no: smallvec![53],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -619,11 +640,11 @@ fn yearly_by_hour() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -643,11 +664,11 @@ fn yearly_by_minute() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -667,11 +688,11 @@ fn yearly_by_second() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -691,11 +712,11 @@ fn yearly_by_hour_and_minute() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -715,11 +736,11 @@ fn yearly_by_hour_and_second() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -739,11 +760,11 @@ fn yearly_by_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -763,11 +784,11 @@ fn yearly_by_hour_and_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_month: vec![9],
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -787,11 +808,11 @@ fn yearly_by_setpos() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_set_pos: vec![3, -3],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![15],
+        by_hour: smallvec![6, 18],
+        by_set_pos: smallvec![3, -3],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![15],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -811,10 +832,10 @@ fn monthly() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -834,10 +855,10 @@ fn monthly_interval() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         interval: 2,
         ..Default::default()
     };
@@ -858,10 +879,10 @@ fn monthly_interval_large() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         interval: 18,
         ..Default::default()
     };
@@ -877,15 +898,40 @@ fn monthly_interval_large() {
     );
 }
 
+#[test]
+fn monthly_interval_from_the_end_of_a_month_into_a_shorter_month_of_the_next_year() {
+    let rrule = RRule {
+        freq: Frequency::Monthly,
+        count: Some(4),
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_n_month_day: smallvec![-1],
+        interval: 3,
+        ..Default::default()
+    };
+    test_recurring_rrule(
+        rrule,
+        true,
+        ymd_hms(1997, 11, 30, 9, 0, 0),
+        &[
+            ymd_hms(1997, 11, 30, 9, 0, 0),
+            ymd_hms(1998, 2, 28, 9, 0, 0),
+            ymd_hms(1998, 5, 31, 9, 0, 0),
+            ymd_hms(1998, 8, 31, 9, 0, 0),
+        ],
+    );
+}
+
 #[test]
 #[cfg(feature = "by-easter")]
 fn monthly_by_easter() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         by_easter: Some(0),
         ..Default::default()
     };
@@ -907,9 +953,9 @@ fn monthly_by_easterpos() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         by_easter: Some(1),
         ..Default::default()
     };
@@ -931,9 +977,9 @@ fn monthly_by_easterpos_neg() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         by_easter: Some(-2),
         ..Default::default()
     };
@@ -954,10 +1000,10 @@ fn monthly_neg_by_monthday_janfeb_for_nonleapyear() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(4),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_n_month_day: vec![-1],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_n_month_day: smallvec![-1],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -978,10 +1024,10 @@ fn monthly_neg_by_monthday_janfeb_for_leapyear() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(4),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_n_month_day: vec![-1],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_n_month_day: smallvec![-1],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1002,10 +1048,10 @@ fn monthly_neg_monthday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(6),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_n_month_day: vec![-1, -3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_n_month_day: smallvec![-1, -3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1028,11 +1074,11 @@ fn monthly_by_month() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1052,10 +1098,10 @@ fn monthly_by_monthday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1075,11 +1121,11 @@ fn monthly_by_month_and_monthday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![5, 7],
+        by_month: smallvec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![5, 7],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1099,10 +1145,10 @@ fn monthly_by_weekday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1122,13 +1168,13 @@ fn monthly_by_nweekday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_weekday: vec![
+        by_weekday: smallvec![
             NWeekday::Nth(1, Weekday::Tue),
             NWeekday::Nth(-1, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1148,13 +1194,13 @@ fn monthly_by_nweekday_large() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_weekday: vec![
+        by_weekday: smallvec![
             NWeekday::Nth(3, Weekday::Tue),
             NWeekday::Nth(-3, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1174,11 +1220,11 @@ fn monthly_by_month_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1198,14 +1244,14 @@ fn monthly_by_month_and_nweekday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![
             NWeekday::Nth(1, Weekday::Tue),
             NWeekday::Nth(-1, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1225,14 +1271,14 @@ fn monthly_by_month_and_nweekday_large() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![
             NWeekday::Nth(3, Weekday::Tue),
             NWeekday::Nth(-3, Weekday::Thu),
         ],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1252,11 +1298,11 @@ fn monthly_by_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1276,12 +1322,12 @@ fn monthly_by_month_and_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1301,10 +1347,10 @@ fn monthly_by_hour() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1324,10 +1370,10 @@ fn monthly_by_minute() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1347,10 +1393,10 @@ fn monthly_by_second() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1370,10 +1416,10 @@ fn monthly_by_hour_and_minute() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1393,10 +1439,10 @@ fn monthly_by_hour_and_second() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1416,10 +1462,10 @@ fn monthly_by_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1439,10 +1485,10 @@ fn monthly_by_hour_and_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
-        by_month_day: vec![2],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1462,11 +1508,11 @@ fn monthly_by_setpos() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_set_pos: vec![3, -3],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![13, 17],
+        by_hour: smallvec![6, 18],
+        by_set_pos: smallvec![3, -3],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![13, 17],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1486,10 +1532,10 @@ fn weekly() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1509,10 +1555,10 @@ fn weekly_interval() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -1533,10 +1579,10 @@ fn weekly_interval_large() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 20,
         ..Default::default()
     };
@@ -1557,11 +1603,11 @@ fn weekly_by_month() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(6),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1584,10 +1630,10 @@ fn weekly_by_weekday() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1608,10 +1654,10 @@ fn weekly_by_nweekday() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1631,11 +1677,11 @@ fn weekly_by_month_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1655,10 +1701,10 @@ fn weekly_by_hour() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1678,10 +1724,10 @@ fn weekly_by_minute() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1701,10 +1747,10 @@ fn weekly_by_second() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1724,10 +1770,10 @@ fn weekly_by_hour_and_minute() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1747,10 +1793,10 @@ fn weekly_by_hour_and_second() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1770,10 +1816,10 @@ fn weekly_by_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1793,10 +1839,10 @@ fn weekly_by_hour_and_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(5),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue)],
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue)],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1818,11 +1864,11 @@ fn weekly_by_setpos() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![6, 18],
-        by_set_pos: vec![3, -3],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![6, 18],
+        by_set_pos: smallvec![3, -3],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1842,9 +1888,9 @@ fn daily() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1864,9 +1910,9 @@ fn daily_interval() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -1887,9 +1933,9 @@ fn daily_interval_large() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 92,
         ..Default::default()
     };
@@ -1910,10 +1956,10 @@ fn daily_by_month() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1933,10 +1979,10 @@ fn daily_by_monthday() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1956,11 +2002,11 @@ fn daily_by_month_and_monthday() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![5, 7],
+        by_month: smallvec![1, 3],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![5, 7],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -1980,10 +2026,10 @@ fn daily_by_weekday() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2003,11 +2049,11 @@ fn daily_by_month_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2027,11 +2073,11 @@ fn daily_by_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2051,12 +2097,12 @@ fn daily_by_month_and_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2076,9 +2122,9 @@ fn daily_by_hour() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2098,9 +2144,9 @@ fn daily_by_minute() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2120,9 +2166,9 @@ fn daily_by_second() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2142,9 +2188,9 @@ fn daily_by_hour_and_minute() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2164,9 +2210,9 @@ fn daily_by_hour_and_second() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2186,9 +2232,9 @@ fn daily_by_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![9],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_hour: smallvec![9],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2208,9 +2254,9 @@ fn daily_by_hour_and_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2230,10 +2276,10 @@ fn daily_by_setpos() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_set_pos: vec![3, -3],
-        by_minute: vec![15, 45],
-        by_second: vec![0],
+        by_hour: smallvec![6, 18],
+        by_set_pos: smallvec![3, -3],
+        by_minute: smallvec![15, 45],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2253,8 +2299,8 @@ fn hourly() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2274,8 +2320,8 @@ fn hourly_interval() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -2296,8 +2342,8 @@ fn hourly_interval_large() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 769,
         ..Default::default()
     };
@@ -2318,9 +2364,9 @@ fn hourly_by_month() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2340,9 +2386,9 @@ fn hourly_by_monthday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2362,10 +2408,10 @@ fn hourly_by_month_and_monthday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![5, 7],
+        by_month: smallvec![1, 3],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![5, 7],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2385,9 +2431,9 @@ fn hourly_by_weekday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(5),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 5,
         ..Default::default()
     };
@@ -2410,10 +2456,10 @@ fn hourly_by_month_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2433,10 +2479,10 @@ fn hourly_by_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2456,11 +2502,11 @@ fn hourly_by_month_and_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2480,9 +2526,9 @@ fn hourly_by_yearday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(8),
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![1, 100, 200, 365],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1, 100, 200, 365],
         interval: 12,
         ..Default::default()
     };
@@ -2508,9 +2554,9 @@ fn hourly_by_yeardayneg() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(8),
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![-365, -266, -166, -1],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![-365, -266, -166, -1],
         interval: 12,
         ..Default::default()
     };
@@ -2536,10 +2582,10 @@ fn hourly_by_month_and_yearday() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(4),
-        by_month: vec![4, 7],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![1, 100, 200, 365],
+        by_month: smallvec![4, 7],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1, 100, 200, 365],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2560,9 +2606,9 @@ fn hourly_by_hour() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2582,8 +2628,8 @@ fn hourly_by_minute() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2603,8 +2649,8 @@ fn hourly_by_second() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_minute: vec![0],
-        by_second: vec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2624,9 +2670,9 @@ fn hourly_by_hour_and_minute() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2646,9 +2692,9 @@ fn hourly_by_hour_and_second() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![0],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![0],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2668,8 +2714,8 @@ fn hourly_by_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2689,9 +2735,9 @@ fn hourly_by_hour_and_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(5),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2713,9 +2759,9 @@ fn hourly_by_setpos() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(3),
-        by_set_pos: vec![3, -3],
-        by_minute: vec![15, 45],
-        by_second: vec![15, 45],
+        by_set_pos: smallvec![3, -3],
+        by_minute: smallvec![15, 45],
+        by_second: smallvec![15, 45],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2735,7 +2781,7 @@ fn minutely() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_second: vec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2755,7 +2801,7 @@ fn minutely_interval() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_second: vec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -2776,7 +2822,7 @@ fn minutely_interval_large() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_second: vec![0],
+        by_second: smallvec![0],
         interval: 1501,
         ..Default::default()
     };
@@ -2797,8 +2843,8 @@ fn minutely_by_month() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2818,8 +2864,8 @@ fn minutely_by_monthday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2839,9 +2885,9 @@ fn minutely_by_month_and_monthday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_second: vec![0],
-        by_month_day: vec![5, 7],
+        by_month: smallvec![1, 3],
+        by_second: smallvec![0],
+        by_month_day: smallvec![5, 7],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2861,8 +2907,8 @@ fn minutely_by_weekday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Thu), NWeekday::Every(Weekday::Sat)],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Thu), NWeekday::Every(Weekday::Sat)],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2882,9 +2928,9 @@ fn minutely_by_month_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_second: vec![0],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2904,9 +2950,9 @@ fn minutely_by_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2926,10 +2972,10 @@ fn minutely_by_month_and_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_second: vec![0],
-        by_month_day: vec![1, 3],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2949,8 +2995,8 @@ fn minutely_by_yearday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(4),
-        by_second: vec![0],
-        by_year_day: vec![1, 100, 200, 365],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1, 100, 200, 365],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2971,8 +3017,8 @@ fn minutely_by_yeardayneg() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(4),
-        by_second: vec![0],
-        by_year_day: vec![-365, -266, -166, -1],
+        by_second: smallvec![0],
+        by_year_day: smallvec![-365, -266, -166, -1],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -2993,9 +3039,9 @@ fn minutely_by_month_and_yearday() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(4),
-        by_month: vec![4, 7],
-        by_second: vec![0],
-        by_year_day: vec![1, 100, 200, 365],
+        by_month: smallvec![4, 7],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1, 100, 200, 365],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3016,8 +3062,8 @@ fn minutely_by_hour() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_second: vec![0],
+        by_hour: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3037,8 +3083,8 @@ fn minutely_by_minute() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3058,7 +3104,7 @@ fn minutely_by_second() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_second: vec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3078,9 +3124,9 @@ fn minutely_by_hour_and_minute() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![0],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3100,8 +3146,8 @@ fn minutely_by_hour_and_second() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3121,8 +3167,8 @@ fn minutely_by_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3142,9 +3188,9 @@ fn minutely_by_hour_and_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(5),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3166,8 +3212,8 @@ fn minutely_by_setpos() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(3),
-        by_set_pos: vec![3, -3],
-        by_second: vec![15, 30, 45],
+        by_set_pos: smallvec![3, -3],
+        by_second: smallvec![15, 30, 45],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3266,7 +3312,7 @@ fn secondly_by_month() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_month: vec![1, 3],
+        by_month: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3286,7 +3332,7 @@ fn secondly_by_monthday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_month_day: vec![1, 3],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3306,8 +3352,8 @@ fn secondly_by_month_and_monthday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_month_day: vec![5, 7],
+        by_month: smallvec![1, 3],
+        by_month_day: smallvec![5, 7],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3327,7 +3373,7 @@ fn secondly_by_weekday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Thu), NWeekday::Every(Weekday::Sat)],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Thu), NWeekday::Every(Weekday::Sat)],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3347,8 +3393,8 @@ fn secondly_by_month_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3368,8 +3414,8 @@ fn secondly_by_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_month_day: vec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3389,9 +3435,9 @@ fn secondly_by_month_and_monthday_and_weekday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_month: vec![1, 3],
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_month_day: vec![1, 3],
+        by_month: smallvec![1, 3],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_month_day: smallvec![1, 3],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3411,7 +3457,7 @@ fn secondly_by_yearday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(4),
-        by_year_day: vec![1, 100, 200, 365],
+        by_year_day: smallvec![1, 100, 200, 365],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3432,7 +3478,7 @@ fn secondly_by_yeardayneg() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(4),
-        by_year_day: vec![-365, -266, -166, -1],
+        by_year_day: smallvec![-365, -266, -166, -1],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3453,8 +3499,8 @@ fn secondly_by_month_and_yearday() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(4),
-        by_month: vec![4, 7],
-        by_year_day: vec![1, 100, 200, 365],
+        by_month: smallvec![4, 7],
+        by_year_day: smallvec![1, 100, 200, 365],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3475,7 +3521,7 @@ fn secondly_by_hour() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_hour: vec![6, 18],
+        by_hour: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3495,7 +3541,7 @@ fn secondly_by_minute() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_minute: vec![6, 18],
+        by_minute: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3515,7 +3561,7 @@ fn secondly_by_second() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_second: vec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3535,8 +3581,8 @@ fn secondly_by_hour_and_minute() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3556,8 +3602,8 @@ fn secondly_by_hour_and_second() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_hour: vec![6, 18],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3577,8 +3623,8 @@ fn secondly_by_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(3),
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3598,9 +3644,9 @@ fn secondly_by_hour_and_minute_and_second() {
     let rrule = RRule {
         freq: Frequency::Secondly,
         count: Some(5),
-        by_hour: vec![6, 18],
-        by_minute: vec![6, 18],
-        by_second: vec![6, 18],
+        by_hour: smallvec![6, 18],
+        by_minute: smallvec![6, 18],
+        by_second: smallvec![6, 18],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3623,9 +3669,9 @@ fn until_not_matching() {
         freq: Frequency::Daily,
         count: Some(3),
         until: Some(ymd_hms(1997, 9, 5, 8, 0, 0)),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3646,9 +3692,9 @@ fn until_matching() {
         freq: Frequency::Daily,
         count: Some(4),
         until: Some(ymd_hms(1997, 9, 4, 9, 0, 0)),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3669,9 +3715,9 @@ fn until_single() {
         freq: Frequency::Daily,
         count: Some(1),
         until: Some(ymd_hms(1997, 9, 2, 9, 0, 0)),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3688,9 +3734,9 @@ fn until_with_date() {
         freq: Frequency::Daily,
         count: Some(4),
         until: Some(ymd_hms(1997, 9, 5, 0, 0, 0)),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     test_recurring_rrule(
@@ -3710,10 +3756,10 @@ fn week_start_interval_mo() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Sun)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Sun)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -3735,10 +3781,10 @@ fn week_start_interval_su() {
         freq: Frequency::Weekly,
         count: Some(3),
         week_start: Weekday::Sun,
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Sun)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Sun)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -3815,7 +3861,7 @@ fn test_before_inclusive_hit() {
         .unwrap();
 
     let before = ymd_hms(2012, 2, 2, 9, 30, 0);
-    let rrule = rrule.before(before);
+    let rrule = rrule.only_before(before);
 
     assert_eq!(Some(&before), rrule.all_unchecked().last());
 }
@@ -3827,7 +3873,7 @@ fn test_before_inclusive_miss() {
         .unwrap();
 
     let before = ymd_hms(2012, 2, 3, 9, 0, 0);
-    let rrule = rrule.before(before);
+    let rrule = rrule.only_before(before);
     let oracle = ymd_hms(2012, 2, 2, 9, 30, 0);
 
     assert_eq!(Some(&oracle), rrule.all_unchecked().last());
@@ -3840,7 +3886,7 @@ fn test_after_inclusive_hit() {
         .unwrap();
 
     let after = ymd_hms(2012, 2, 2, 9, 30, 0);
-    let rrule = rrule.after(after);
+    let rrule = rrule.only_after(after);
 
     assert_eq!(after, rrule.all(1).dates[0]);
 }
@@ -3852,7 +3898,7 @@ fn test_after_inclusive_miss() {
         .unwrap();
 
     let after = ymd_hms(2012, 2, 2, 10, 0, 0);
-    let rrule = rrule.after(after);
+    let rrule = rrule.only_after(after);
     let oracle = ymd_hms(2012, 2, 3, 9, 30, 0);
 
     assert_eq!(oracle, rrule.all(1).dates[0]);
@@ -3868,7 +3914,7 @@ fn test_between_inclusive_both_miss() {
     let middle = ymd_hms(2012, 2, 3, 9, 30, 0);
     let before = ymd_hms(2012, 2, 4, 9, 0, 0);
 
-    let rrule = rrule.before(before).after(after);
+    let rrule = rrule.only_before(before).only_after(after);
 
     assert_eq!(vec![middle], rrule.all_unchecked());
 }
@@ -3883,7 +3929,7 @@ fn test_between_inclusive_lower_miss() {
     let middle = ymd_hms(2012, 2, 3, 9, 30, 0);
     let before = ymd_hms(2012, 2, 4, 9, 30, 0);
 
-    let rrule = rrule.before(before).after(after);
+    let rrule = rrule.only_before(before).only_after(after);
 
     assert_eq!(vec![middle, before], rrule.all_unchecked());
 }
@@ -3898,7 +3944,7 @@ fn test_between_inclusive_upper_miss() {
     let middle = ymd_hms(2012, 2, 3, 9, 30, 0);
     let before = ymd_hms(2012, 2, 4, 9, 0, 0);
 
-    let rrule = rrule.before(before).after(after);
+    let rrule = rrule.only_before(before).only_after(after);
 
     assert_eq!(vec![after, middle], rrule.all_unchecked());
 }
@@ -3913,7 +3959,1370 @@ fn test_between_inclusive_both_hit() {
     let middle = ymd_hms(2012, 2, 3, 9, 30, 0);
     let before = ymd_hms(2012, 2, 4, 9, 30, 0);
 
-    let rrule = rrule.before(before).after(after);
+    let rrule = rrule.only_before(before).only_after(after);
 
     assert_eq!(vec![after, middle, before], rrule.all_unchecked());
 }
+
+#[test]
+fn rrule_between_without_end() {
+    let dt_start = ymd_hms(2012, 2, 1, 9, 30, 0);
+    let rrule = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+
+    let after = ymd_hms(2012, 2, 2, 9, 30, 0);
+    let middle = ymd_hms(2012, 2, 3, 9, 30, 0);
+    let before = ymd_hms(2012, 2, 4, 9, 30, 0);
+
+    let result = rrule.between(after, before, true);
+    assert_eq!(vec![after, middle, before], result.dates);
+    assert!(!result.limited);
+
+    let result = rrule.between(after, before, false);
+    assert_eq!(vec![middle], result.dates);
+}
+
+#[test]
+fn rrule_builder_builds_the_same_rules() {
+    let dt_start = ymd_hms(2012, 2, 1, 9, 30, 0);
+    let until = ymd_hms(2012, 6, 1, 9, 30, 0);
+    let built = RRule::builder()
+        .interval(2)
+        .by_weekday(vec![NWeekday::Nth(-1, Weekday::Fri)])
+        .until(until)
+        .freq(Frequency::Monthly)
+        .week_start(Weekday::Sun)
+        .build();
+    let rrule = RRule::new(Frequency::Monthly)
+        .interval(2)
+        .by_weekday(vec![NWeekday::Nth(-1, Weekday::Fri)])
+        .until(until)
+        .week_start(Weekday::Sun);
+    assert_eq!(built, rrule);
+
+    let validated = RRule::builder()
+        .freq(Frequency::Daily)
+        .count(3)
+        .validate(dt_start)
+        .unwrap();
+    assert_eq!(
+        validated,
+        RRule::new(Frequency::Daily)
+            .count(3)
+            .validate(dt_start)
+            .unwrap()
+    );
+    assert_eq!(
+        RRule::builder()
+            .freq(Frequency::Daily)
+            .count(3)
+            .build_set(dt_start)
+            .unwrap()
+            .all(10)
+            .dates
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn rrule_between_with_cache() {
+    let dt_start = ymd_hms(2012, 2, 1, 9, 30, 0);
+    let rrule = RRule::new(Frequency::Weekly)
+        .by_weekday(vec![
+            NWeekday::Every(Weekday::Mon),
+            NWeekday::Every(Weekday::Fri),
+        ])
+        .validate(dt_start)
+        .unwrap();
+    let cached = std::sync::Arc::new(rrule.clone().with_cache());
+    assert_eq!(*cached, rrule);
+
+    let after = ymd_hms(2012, 2, 2, 9, 30, 0);
+    let before = ymd_hms(2012, 3, 2, 9, 30, 0);
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let cached = std::sync::Arc::clone(&cached);
+            std::thread::spawn(move || cached.between(after, before, true))
+        })
+        .collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), rrule.between(after, before, true));
+    }
+    assert_eq!(
+        cached.between(after, before, false),
+        rrule.between(after, before, false)
+    );
+
+    cached.clear_cache();
+    assert_eq!(
+        cached.between(after, before, true),
+        rrule.between(after, before, true)
+    );
+}
+
+#[test]
+fn rrule_after_and_before() {
+    let dt_start = ymd_hms(2012, 2, 1, 9, 30, 0);
+    let rrule = RRule::new(Frequency::Daily)
+        .count(5)
+        .validate(dt_start)
+        .unwrap();
+
+    let dt = ymd_hms(2012, 2, 3, 9, 30, 0);
+    assert_eq!(rrule.after(dt, true), Some(dt));
+    assert_eq!(rrule.after(dt, false), Some(ymd_hms(2012, 2, 4, 9, 30, 0)));
+    assert_eq!(rrule.before(dt, true), Some(dt));
+    assert_eq!(rrule.before(dt, false), Some(ymd_hms(2012, 2, 2, 9, 30, 0)));
+
+    // Outside of the recurrences
+    assert_eq!(rrule.after(ymd_hms(2012, 2, 5, 9, 30, 0), false), None);
+    assert_eq!(rrule.before(dt_start, false), None);
+    assert_eq!(
+        rrule.before(ymd_hms(2020, 1, 1, 0, 0, 0), false),
+        Some(ymd_hms(2012, 2, 5, 9, 30, 0))
+    );
+}
+
+#[test]
+fn rrule_after_computed_directly_matches_the_iteration() {
+    const BERLIN: Tz = Tz::Europe__Berlin;
+    let rrules = [
+        RRule::new(Frequency::Daily).interval(3),
+        RRule::new(Frequency::Weekly).interval(2).count(20),
+        RRule::new(Frequency::Hourly).interval(5),
+        RRule::new(Frequency::Hourly).until(ymd_hms(2021, 10, 31, 3, 0, 0)),
+    ];
+
+    // Starting before the daylight saving time transitions.
+    let dt_starts = [
+        BERLIN.with_ymd_and_hms(2021, 3, 26, 2, 30, 0).unwrap(),
+        BERLIN.with_ymd_and_hms(2021, 10, 29, 2, 30, 0).unwrap(),
+    ];
+    for rrule in rrules {
+        for dt_start in dt_starts {
+            let rrule = rrule.clone().validate(dt_start).unwrap();
+            for step in 0..150 {
+                let dt = dt_start - Duration::days(1) + Duration::minutes(50) * step;
+                for inclusive in [true, false] {
+                    let expected =
+                        rrule
+                            .iter()
+                            .find(|date| if inclusive { *date >= dt } else { *date > dt });
+                    assert_eq!(rrule.after(dt, inclusive), expected, "{} {}", rrule, dt);
+                }
+            }
+        }
+    }
+
+    let rrule = RRule::new(Frequency::Hourly)
+        .validate(ymd_hms(1970, 1, 1, 0, 30, 0))
+        .unwrap();
+    assert_eq!(
+        rrule.after(ymd_hms(2500, 6, 15, 12, 0, 0), false),
+        Some(ymd_hms(2500, 6, 15, 12, 30, 0))
+    );
+}
+
+#[test]
+fn rrule_iter_is_lazy() {
+    let dt_start = ymd_hms(2012, 2, 1, 9, 30, 0);
+    let rrule = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+
+    let mondays = rrule
+        .iter()
+        .filter(|dt| dt.weekday() == Weekday::Mon)
+        .take(2)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mondays,
+        vec![
+            ymd_hms(2012, 2, 6, 9, 30, 0),
+            ymd_hms(2012, 2, 13, 9, 30, 0)
+        ]
+    );
+
+    let mut count = 0;
+    for dt in &rrule {
+        if dt > ymd_hms(2012, 2, 10, 0, 0, 0) {
+            break;
+        }
+        count += 1;
+    }
+    assert_eq!(count, 9);
+}
+
+#[test]
+fn rrule_contains() {
+    let dt_start = ymd_hms(2012, 2, 1, 9, 30, 0);
+    let rrule = RRule::new(Frequency::Monthly)
+        .by_month_day(vec![-1])
+        .validate(dt_start)
+        .unwrap();
+
+    assert!(rrule.contains(ymd_hms(2012, 2, 29, 9, 30, 0)));
+    assert!(rrule.contains(ymd_hms(2030, 4, 30, 9, 30, 0)));
+    assert!(!rrule.contains(ymd_hms(2012, 2, 29, 9, 31, 0)));
+    assert!(!rrule.contains(ymd_hms(2012, 3, 30, 9, 30, 0)));
+    assert!(!rrule.contains(ymd_hms(2012, 1, 31, 9, 30, 0)));
+    // Same instant in another timezone
+    assert!(rrule.contains(ymd_hms(2012, 2, 29, 9, 30, 0).with_timezone(&Tz::Europe__Berlin)));
+}
+
+#[test]
+fn rrule_get_matches_iteration() {
+    let dt_start = Tz::America__New_York
+        .with_ymd_and_hms(2021, 3, 1, 2, 30, 0)
+        .unwrap();
+    let rrules = [
+        RRule::new(Frequency::Daily),
+        RRule::new(Frequency::Daily).interval(3).count(20),
+        RRule::new(Frequency::Weekly).interval(2),
+        RRule::new(Frequency::Weekly).until(ymd_hms(2021, 5, 1, 0, 0, 0)),
+        RRule::new(Frequency::Monthly)
+            .by_month_day(vec![-1])
+            .count(5),
+    ];
+
+    for rrule in rrules {
+        let rrule = rrule.validate(dt_start).unwrap();
+        let dates = rrule.iter().take(40).collect::<Vec<_>>();
+        for n in 0..40 {
+            assert_eq!(rrule.get(n), dates.get(n).copied(), "{} at {}", rrule, n);
+        }
+    }
+}
+
+#[test]
+fn rrule_last_occurrence() {
+    let dt_start = Tz::America__New_York
+        .with_ymd_and_hms(2021, 3, 1, 2, 30, 0)
+        .unwrap();
+    let rrules = [
+        RRule::new(Frequency::Daily).count(20),
+        RRule::new(Frequency::Daily).until(ymd_hms(2021, 3, 14, 7, 30, 0)),
+        RRule::new(Frequency::Daily).until(ymd_hms(2021, 3, 14, 7, 29, 0)),
+        RRule::new(Frequency::Weekly)
+            .interval(2)
+            .count(3)
+            .until(ymd_hms(2022, 1, 1, 0, 0, 0)),
+        RRule::new(Frequency::Weekly)
+            .count(30)
+            .until(ymd_hms(2021, 4, 1, 0, 0, 0)),
+        RRule::new(Frequency::Monthly)
+            .by_month_day(vec![-1])
+            .until(ymd_hms(2021, 12, 1, 0, 0, 0)),
+    ];
+
+    for rrule in rrules {
+        let rrule = rrule.validate(dt_start).unwrap();
+        assert_eq!(rrule.last_occurrence(), rrule.iter().last(), "{}", rrule);
+    }
+
+    let infinite = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+    assert_eq!(infinite.last_occurrence(), None);
+}
+
+#[test]
+fn rrule_count_remaining() {
+    let dt_start = Tz::America__New_York
+        .with_ymd_and_hms(2021, 3, 1, 2, 30, 0)
+        .unwrap();
+    let rrules = [
+        RRule::new(Frequency::Daily).count(20),
+        RRule::new(Frequency::Daily).until(ymd_hms(2021, 3, 14, 7, 30, 0)),
+        RRule::new(Frequency::Weekly)
+            .count(30)
+            .until(ymd_hms(2021, 4, 1, 0, 0, 0)),
+        RRule::new(Frequency::Monthly)
+            .by_month_day(vec![-1])
+            .until(ymd_hms(2021, 12, 1, 0, 0, 0)),
+    ];
+    let afters = [
+        ymd_hms(2021, 2, 1, 0, 0, 0),
+        ymd_hms(2021, 3, 1, 7, 30, 0),
+        ymd_hms(2021, 3, 8, 12, 0, 0),
+        ymd_hms(2022, 1, 1, 0, 0, 0),
+    ];
+
+    for rrule in rrules {
+        let rrule = rrule.validate(dt_start).unwrap();
+        for after in afters {
+            let expected = rrule.iter().filter(|date| *date > after).count() as u64;
+            assert_eq!(rrule.count_remaining(after), Some(expected), "{}", rrule);
+        }
+    }
+
+    let infinite = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+    assert_eq!(infinite.count_remaining(dt_start), None);
+}
+
+#[test]
+fn rrule_iter_rev() {
+    let dt_start = Tz::America__New_York
+        .with_ymd_and_hms(2021, 3, 1, 2, 30, 0)
+        .unwrap();
+    let rrules = [
+        RRule::new(Frequency::Daily).count(20),
+        RRule::new(Frequency::Daily).until(ymd_hms(2021, 3, 20, 7, 0, 0)),
+        RRule::new(Frequency::Weekly)
+            .count(30)
+            .until(ymd_hms(2021, 4, 1, 0, 0, 0)),
+        RRule::new(Frequency::Monthly)
+            .by_month_day(vec![-1])
+            .until(ymd_hms(2021, 12, 1, 0, 0, 0)),
+    ];
+
+    for rrule in rrules {
+        let rrule = rrule.validate(dt_start).unwrap();
+        let mut expected: Vec<_> = rrule.iter().collect();
+        expected.reverse();
+        assert_eq!(
+            rrule.iter_rev().unwrap().collect::<Vec<_>>(),
+            expected,
+            "{}",
+            rrule
+        );
+    }
+
+    let infinite = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+    assert!(infinite.iter_rev().is_none());
+}
+
+#[test]
+fn rrule_iter_size_hint() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+
+    let rrule = RRule::new(Frequency::Daily)
+        .count(5)
+        .validate(dt_start)
+        .unwrap();
+    let mut iter = rrule.iter();
+    assert_eq!(iter.size_hint(), (5, Some(5)));
+    iter.next();
+    assert_eq!(iter.size_hint(), (4, Some(4)));
+    assert_eq!(iter.collect::<Vec<_>>().len(), 4);
+
+    let rrule = RRule::new(Frequency::Monthly)
+        .by_month_day(vec![31])
+        .count(5)
+        .validate(dt_start)
+        .unwrap();
+    let mut iter = rrule.iter();
+    assert_eq!(iter.size_hint(), (0, Some(5)));
+    iter.next();
+    assert_eq!(iter.size_hint(), (0, Some(4)));
+
+    let rrule = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+    assert_eq!(rrule.iter().size_hint(), (0, None));
+}
+
+#[test]
+fn rrule_for_each_until() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrule = RRule::new(Frequency::Weekly)
+        .count(10)
+        .validate(dt_start)
+        .unwrap();
+
+    let mut visited = vec![];
+    let found = rrule.for_each_until(|date| {
+        if date > ymd_hms(2021, 1, 20, 0, 0, 0) {
+            return ControlFlow::Break(date);
+        }
+        visited.push(date);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(found, Some(ymd_hms(2021, 1, 22, 9, 0, 0)));
+    assert_eq!(visited, rrule.iter().take(3).collect::<Vec<_>>());
+
+    let mut count = 0;
+    let found: Option<()> = rrule.for_each_until(|_| {
+        count += 1;
+        ControlFlow::Continue(())
+    });
+    assert_eq!(found, None);
+    assert_eq!(count, 10);
+}
+
+#[test]
+fn rrule_occurrences_between_keeps_index() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrule = RRule::new(Frequency::Monthly)
+        .by_month_day(vec![1, 15])
+        .validate(dt_start)
+        .unwrap();
+
+    let occurrences = rrule.occurrences_between(
+        ymd_hms(2021, 3, 1, 9, 0, 0),
+        ymd_hms(2021, 4, 1, 9, 0, 0),
+        false,
+    );
+    assert_eq!(
+        occurrences,
+        vec![Occurrence {
+            index: 5,
+            date: ymd_hms(2021, 3, 15, 9, 0, 0)
+        }]
+    );
+
+    let occurrences = rrule.occurrences_between(
+        ymd_hms(2021, 3, 1, 9, 0, 0),
+        ymd_hms(2021, 4, 1, 9, 0, 0),
+        true,
+    );
+    assert_eq!(
+        occurrences.iter().map(|o| o.index).collect::<Vec<_>>(),
+        vec![4, 5, 6]
+    );
+    for occurrence in occurrences {
+        assert_eq!(rrule.get(occurrence.index), Some(occurrence.date));
+    }
+}
+
+#[test]
+fn rrule_display_roundtrips() {
+    let rrules = [
+        (
+            "FREQ=MONTHLY;BYMONTHDAY=1,-1;COUNT=5",
+            ymd_hms(2021, 1, 1, 9, 0, 0),
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=MONTHLY;COUNT=5;BYMONTHDAY=1,-1;BYHOUR=9;BYMINUTE=0;BYSECOND=0",
+        ),
+        (
+            "FREQ=WEEKLY;INTERVAL=2;WKST=SU;UNTIL=20210601T000000Z",
+            Tz::Europe__Berlin
+                .with_ymd_and_hms(2021, 1, 1, 9, 0, 0)
+                .unwrap(),
+            "DTSTART;TZID=Europe/Berlin:20210101T090000\nRRULE:FREQ=WEEKLY;UNTIL=20210601T000000Z;INTERVAL=2;WKST=SU;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=FR",
+        ),
+        (
+            "FREQ=YEARLY;BYDAY=1MO,-1FR;COUNT=4",
+            ymd_hms(2021, 1, 1, 9, 0, 0),
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY;COUNT=4;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=-1FR,1MO",
+        ),
+    ];
+
+    for (rrule, dt_start, expected) in rrules {
+        let rrule = rrule
+            .parse::<RRule<Unvalidated>>()
+            .unwrap()
+            .validate(dt_start)
+            .unwrap();
+        assert_eq!(rrule.to_string(), expected);
+
+        assert_eq!(rrule.to_string().parse::<RRule>().unwrap(), rrule);
+
+        let rrule_set: RRuleSet = rrule.to_string().parse().unwrap();
+        assert_eq!(rrule_set.get_rrule(), &[rrule]);
+        assert_eq!(*rrule_set.get_dt_start(), dt_start);
+    }
+}
+
+#[test]
+fn rrule_from_str_requires_single_rrule() {
+    let rrule: RRule = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3"
+        .parse()
+        .unwrap();
+    assert_eq!(rrule.iter().count(), 3);
+
+    let invalid_inputs = [
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nRRULE:FREQ=WEEKLY",
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEXDATE:20210102T090000Z",
+        "DTSTART:20210101T090000Z\nRDATE:20210102T090000Z",
+    ];
+    for input in invalid_inputs {
+        assert_eq!(
+            input.parse::<RRule>(),
+            Err(ParseError::ExpectedSingleRRule.into()),
+            "{}",
+            input
+        );
+    }
+    assert_eq!(
+        "RRULE:FREQ=DAILY".parse::<RRule>(),
+        Err(ParseError::MissingStartDate.into())
+    );
+}
+
+#[test]
+fn validated_rrules_are_deduplicated_by_content() {
+    use std::collections::HashSet;
+
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrules: HashSet<RRule> = [
+        "FREQ=WEEKLY;BYDAY=FR,MO;BYHOUR=9",
+        "FREQ=WEEKLY;BYHOUR=9;BYDAY=MO,FR,MO",
+        "FREQ=WEEKLY;BYDAY=MO,FR",
+        "FREQ=WEEKLY;BYDAY=MO,FR;INTERVAL=2",
+    ]
+    .into_iter()
+    .map(|rrule| {
+        rrule
+            .parse::<RRule<Unvalidated>>()
+            .unwrap()
+            .validate(dt_start)
+            .unwrap()
+    })
+    .collect();
+    assert_eq!(rrules.len(), 2);
+}
+
+#[test]
+fn with_timezone_keeps_the_local_wall_clock_time() {
+    let rrule: RRule = "DTSTART;TZID=Europe/Berlin:20210322T090000\n\
+        RRULE:FREQ=WEEKLY;UNTIL=20210405T070000Z;BYDAY=MO"
+        .parse()
+        .unwrap();
+    let dates = |rrule: &RRule| {
+        rrule
+            .iter()
+            .map(|date| date.to_rfc3339())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        dates(&rrule),
+        [
+            "2021-03-22T09:00:00+01:00",
+            "2021-03-29T09:00:00+02:00",
+            "2021-04-05T09:00:00+02:00"
+        ]
+    );
+
+    // The recurrences move along with the daylight saving time of the new timezone.
+    let moved = rrule.with_timezone(Tz::America__New_York).unwrap();
+    assert_eq!(
+        dates(&moved),
+        [
+            "2021-03-22T09:00:00-04:00",
+            "2021-03-29T09:00:00-04:00",
+            "2021-04-05T09:00:00-04:00"
+        ]
+    );
+    assert_eq!(moved.with_timezone(Tz::Europe__Berlin), Ok(rrule));
+
+    let rrule: RRule = "DTSTART;TZID=Europe/Berlin:20210314T023000\nRRULE:FREQ=DAILY"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        rrule.with_timezone(Tz::America__New_York),
+        Err(ParseError::InvalidDateTimeInLocalTimezone {
+            value: "20210314T023000".into(),
+            property: "DTSTART".into(),
+        }
+        .into())
+    );
+}
+
+#[test]
+fn skip_moves_missing_month_days() {
+    let dates = |rrule: &str| {
+        rrule
+            .parse::<RRule>()
+            .unwrap()
+            .iter()
+            .map(|date| date.to_rfc3339())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        dates("DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;COUNT=3"),
+        [
+            "2021-01-31T09:00:00+00:00",
+            "2021-03-31T09:00:00+00:00",
+            "2021-05-31T09:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        dates("DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;COUNT=3;SKIP=BACKWARD"),
+        [
+            "2021-01-31T09:00:00+00:00",
+            "2021-02-28T09:00:00+00:00",
+            "2021-03-31T09:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        dates("DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;COUNT=4;SKIP=FORWARD"),
+        [
+            "2021-01-31T09:00:00+00:00",
+            "2021-03-01T09:00:00+00:00",
+            "2021-03-31T09:00:00+00:00",
+            "2021-05-01T09:00:00+00:00"
+        ]
+    );
+    // Only the months of the interval are moved.
+    assert_eq!(
+        dates("DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;INTERVAL=2;COUNT=3;SKIP=FORWARD"),
+        [
+            "2021-01-31T09:00:00+00:00",
+            "2021-03-31T09:00:00+00:00",
+            "2021-05-31T09:00:00+00:00"
+        ]
+    );
+    // A month day that exists is not repeated.
+    assert_eq!(
+        dates(
+            "DTSTART:20210201T090000Z\nRRULE:FREQ=MONTHLY;COUNT=4;BYMONTHDAY=28,-30;SKIP=BACKWARD"
+        ),
+        [
+            "2021-02-28T09:00:00+00:00",
+            "2021-03-02T09:00:00+00:00",
+            "2021-03-28T09:00:00+00:00",
+            "2021-04-01T09:00:00+00:00"
+        ]
+    );
+}
+
+#[test]
+fn skip_moves_leap_days_of_yearly_rules() {
+    let dates = |skip: &str| {
+        format!(
+            "DTSTART:20200229T090000Z\nRRULE:FREQ=YEARLY;COUNT=3;SKIP={}",
+            skip
+        )
+        .parse::<RRule>()
+        .unwrap()
+        .iter()
+        .map(|date| date.to_rfc3339())
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        dates("OMIT"),
+        [
+            "2020-02-29T09:00:00+00:00",
+            "2024-02-29T09:00:00+00:00",
+            "2028-02-29T09:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        dates("BACKWARD"),
+        [
+            "2020-02-29T09:00:00+00:00",
+            "2021-02-28T09:00:00+00:00",
+            "2022-02-28T09:00:00+00:00"
+        ]
+    );
+    assert_eq!(
+        dates("FORWARD"),
+        [
+            "2020-02-29T09:00:00+00:00",
+            "2021-03-01T09:00:00+00:00",
+            "2022-03-01T09:00:00+00:00"
+        ]
+    );
+}
+
+#[test]
+fn parses_and_writes_skip() {
+    let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYMONTHDAY=31;SKIP=backward".parse().unwrap();
+    assert_eq!(rrule.get_skip(), Skip::Backward);
+    assert_eq!(
+        rrule.to_string(),
+        "FREQ=MONTHLY;BYMONTHDAY=31;SKIP=BACKWARD"
+    );
+    assert_eq!(
+        RRule::new(Frequency::Monthly).skip(Skip::Forward),
+        "FREQ=MONTHLY;SKIP=FORWARD".parse().unwrap()
+    );
+    // Omitting the dates is the default.
+    assert_eq!(
+        "FREQ=MONTHLY;SKIP=OMIT"
+            .parse::<RRule<Unvalidated>>()
+            .unwrap(),
+        RRule::new(Frequency::Monthly)
+    );
+    let Err(RRuleError::ParserError(error)) =
+        "FREQ=MONTHLY;SKIP=AHEAD".parse::<RRule<Unvalidated>>()
+    else {
+        unreachable!("expected a parse error");
+    };
+    assert_eq!(error.inner(), &ParseError::InvalidSkip("AHEAD".into()));
+}
+
+#[test]
+fn accepts_the_gregorian_and_chinese_rscales() {
+    let rrule: RRule<Unvalidated> = "RSCALE=GREGORIAN;FREQ=YEARLY;BYMONTHDAY=29;SKIP=FORWARD"
+        .parse()
+        .unwrap();
+    assert_eq!(rrule.get_rscale(), Some(RScale::Gregorian));
+    assert_eq!(
+        rrule.to_string(),
+        "FREQ=YEARLY;BYMONTHDAY=29;RSCALE=GREGORIAN;SKIP=FORWARD"
+    );
+    assert_eq!(
+        rrule.to_string().parse::<RRule<Unvalidated>>().unwrap(),
+        rrule
+    );
+
+    let rrule: RRule<Unvalidated> = "RSCALE=CHINESE;FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1"
+        .parse()
+        .unwrap();
+    assert_eq!(rrule.get_rscale(), Some(RScale::Chinese));
+    assert_eq!(
+        rrule.to_string(),
+        "FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1;RSCALE=CHINESE"
+    );
+
+    let Err(RRuleError::ParserError(error)) =
+        "RSCALE=HEBREW;FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=1".parse::<RRule<Unvalidated>>()
+    else {
+        unreachable!("expected a parse error");
+    };
+    assert_eq!(
+        error.inner(),
+        &ParseError::UnsupportedRScale("HEBREW".into())
+    );
+}
+
+#[test]
+fn chinese_yearly() {
+    let dt_start = ymd_hms(2024, 2, 10, 9, 0, 0);
+    let rrule: RRule<Unvalidated> = "RSCALE=CHINESE;FREQ=YEARLY;COUNT=3".parse().unwrap();
+    test_recurring_rrule(
+        rrule,
+        true,
+        dt_start,
+        &[
+            ymd_hms(2024, 2, 10, 9, 0, 0),
+            ymd_hms(2025, 1, 29, 9, 0, 0),
+            ymd_hms(2026, 2, 17, 9, 0, 0),
+        ],
+    );
+
+    // The Dragon Boat Festival, on the 5th day of the 5th month.
+    let rrule: RRule<Unvalidated> = "RSCALE=CHINESE;FREQ=YEARLY;COUNT=3;BYMONTH=5;BYMONTHDAY=5"
+        .parse()
+        .unwrap();
+    test_recurring_rrule(
+        rrule,
+        true,
+        dt_start,
+        &[
+            ymd_hms(2024, 6, 10, 9, 0, 0),
+            ymd_hms(2025, 5, 31, 9, 0, 0),
+            ymd_hms(2026, 6, 19, 9, 0, 0),
+        ],
+    );
+}
+
+#[test]
+fn chinese_leap_months_with_skip() {
+    // The leap 6th month of 2017 and 2025.
+    let dt_start = ymd_hms(2017, 1, 28, 9, 0, 0);
+    let dates = |skip: &str| -> Vec<crate::core::DateTime> {
+        format!("RSCALE=CHINESE;FREQ=YEARLY;BYMONTH=6L;BYMONTHDAY=1;SKIP={skip}")
+            .parse::<RRule<Unvalidated>>()
+            .unwrap()
+            .validate(dt_start)
+            .unwrap()
+            .into_iter()
+            .take(3)
+            .collect()
+    };
+    assert_eq!(
+        dates("OMIT"),
+        [
+            ymd_hms(2017, 7, 23, 9, 0, 0),
+            ymd_hms(2025, 7, 25, 9, 0, 0),
+            ymd_hms(2036, 7, 23, 9, 0, 0),
+        ]
+    );
+    // The 6th month in the years without a leap 6th month.
+    assert_eq!(
+        dates("BACKWARD"),
+        [
+            ymd_hms(2017, 7, 23, 9, 0, 0),
+            ymd_hms(2018, 7, 13, 9, 0, 0),
+            ymd_hms(2019, 7, 3, 9, 0, 0),
+        ]
+    );
+    // The 7th month in the years without a leap 6th month.
+    assert_eq!(
+        dates("FORWARD"),
+        [
+            ymd_hms(2017, 7, 23, 9, 0, 0),
+            ymd_hms(2018, 8, 11, 9, 0, 0),
+            ymd_hms(2019, 8, 1, 9, 0, 0),
+        ]
+    );
+}
+
+#[test]
+fn chinese_monthly_with_skip() {
+    let dt_start = ymd_hms(2025, 1, 29, 9, 0, 0);
+    let dates = |skip: &str| -> Vec<crate::core::DateTime> {
+        format!("RSCALE=CHINESE;FREQ=MONTHLY;BYMONTHDAY=30;SKIP={skip}")
+            .parse::<RRule<Unvalidated>>()
+            .unwrap()
+            .validate(dt_start)
+            .unwrap()
+            .into_iter()
+            .take(4)
+            .collect()
+    };
+    // The months of 2025 from the 1st month have 30, 29, 30, 29, 29, 30, 29 (the leap 6th
+    // month) and 30 days.
+    assert_eq!(
+        dates("OMIT"),
+        [
+            ymd_hms(2025, 2, 27, 9, 0, 0),
+            ymd_hms(2025, 4, 27, 9, 0, 0),
+            ymd_hms(2025, 7, 24, 9, 0, 0),
+            ymd_hms(2025, 9, 21, 9, 0, 0),
+        ]
+    );
+    assert_eq!(
+        dates("BACKWARD"),
+        [
+            ymd_hms(2025, 2, 27, 9, 0, 0),
+            ymd_hms(2025, 3, 28, 9, 0, 0),
+            ymd_hms(2025, 4, 27, 9, 0, 0),
+            ymd_hms(2025, 5, 26, 9, 0, 0),
+        ]
+    );
+    assert_eq!(
+        dates("FORWARD"),
+        [
+            ymd_hms(2025, 2, 27, 9, 0, 0),
+            ymd_hms(2025, 3, 29, 9, 0, 0),
+            ymd_hms(2025, 4, 27, 9, 0, 0),
+            ymd_hms(2025, 5, 27, 9, 0, 0),
+        ]
+    );
+}
+
+#[test]
+fn rejects_unsupported_rule_parts_with_the_chinese_rscale() {
+    let dt_start = ymd_hms(2025, 1, 29, 9, 0, 0);
+    for (input, rule_part) in [
+        ("RSCALE=CHINESE;FREQ=WEEKLY", "FREQ=WEEKLY"),
+        ("RSCALE=CHINESE;FREQ=MONTHLY;BYDAY=MO", "BYDAY"),
+    ] {
+        let rrule: RRule<Unvalidated> = input.parse().unwrap();
+        let error = rrule.validate(dt_start).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "RRule validation error: `{rule_part}` is not supported with `RSCALE=CHINESE`."
+            ),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn explains_the_recurrences_of_the_chinese_rscale() {
+    let dt_start = ymd_hms(2024, 2, 10, 9, 0, 0);
+    let rrule: RRule<Unvalidated> = "RSCALE=CHINESE;FREQ=YEARLY".parse().unwrap();
+    let rrule = rrule.validate(dt_start).unwrap();
+    let explanations = rrule.explain(dt_start..ymd_hms(2026, 1, 1, 0, 0, 0));
+    let dates: Vec<_> = explanations
+        .iter()
+        .map(|explanation| *explanation.get_dt())
+        .collect();
+    assert_eq!(
+        dates,
+        [ymd_hms(2024, 2, 10, 9, 0, 0), ymd_hms(2025, 1, 29, 9, 0, 0)]
+    );
+    assert!(explanations
+        .iter()
+        .all(|explanation| explanation.is_accepted() && explanation.get_rule_part().is_none()));
+}
+
+#[test]
+fn keeps_leap_months() {
+    let rrule: RRule<Unvalidated> = "FREQ=YEARLY;BYMONTH=6,5L,5;BYMONTHDAY=1".parse().unwrap();
+    assert_eq!(rrule.get_by_month(), [5, 6]);
+    assert_eq!(rrule.get_by_leap_month(), [5]);
+    assert_eq!(rrule.to_string(), "FREQ=YEARLY;BYMONTH=5,5L,6;BYMONTHDAY=1");
+    assert_eq!(
+        RRule::new(Frequency::Yearly)
+            .by_month(&[chrono::Month::May, chrono::Month::June])
+            .by_leap_month(&[chrono::Month::May])
+            .by_month_day(vec![1]),
+        rrule
+    );
+
+    // The Gregorian calendar has no leap months.
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let dates: Vec<_> = rrule
+        .validate(dt_start)
+        .unwrap()
+        .into_iter()
+        .take(3)
+        .collect();
+    assert_eq!(
+        dates,
+        [
+            ymd_hms(2021, 5, 1, 9, 0, 0),
+            ymd_hms(2021, 6, 1, 9, 0, 0),
+            ymd_hms(2022, 5, 1, 9, 0, 0),
+        ]
+    );
+    let rrule = "FREQ=YEARLY;COUNT=3;BYMONTH=5L"
+        .parse::<RRule<Unvalidated>>()
+        .unwrap()
+        .validate(dt_start)
+        .unwrap();
+    assert_eq!(
+        rrule.to_string().lines().last(),
+        Some("RRULE:FREQ=YEARLY;COUNT=3;BYMONTH=5L;BYMONTHDAY=1;BYHOUR=9;BYMINUTE=0;BYSECOND=0")
+    );
+    assert_eq!(rrule.iter().next(), None);
+
+    for value in ["13L", "L", "5X"] {
+        assert!(
+            format!("FREQ=YEARLY;BYMONTH={}", value)
+                .parse::<RRule<Unvalidated>>()
+                .is_err(),
+            "{value}"
+        );
+    }
+}
+
+#[test]
+fn leap_day_moves_only_the_29th_of_february_of_yearly_rules() {
+    let dates = |rrule: RRule<Unvalidated>, dt_start| {
+        rrule
+            .validate(dt_start)
+            .unwrap()
+            .into_iter()
+            .take(3)
+            .map(|date| date.date_naive().to_string())
+            .collect::<Vec<_>>()
+    };
+    let birthday = ymd_hms(2020, 2, 29, 9, 0, 0);
+
+    let yearly = RRule::new(Frequency::Yearly);
+    assert_eq!(
+        dates(yearly.clone(), birthday),
+        ["2020-02-29", "2024-02-29", "2028-02-29"]
+    );
+    assert_eq!(
+        dates(yearly.clone().leap_day(Skip::Backward), birthday),
+        ["2020-02-29", "2021-02-28", "2022-02-28"]
+    );
+    assert_eq!(
+        dates(yearly.clone().leap_day(Skip::Forward), birthday),
+        ["2020-02-29", "2021-03-01", "2022-03-01"]
+    );
+    // The SKIP rule part takes precedence.
+    assert_eq!(
+        dates(
+            yearly.clone().skip(Skip::Forward).leap_day(Skip::Backward),
+            birthday
+        ),
+        ["2020-02-29", "2021-03-01", "2022-03-01"]
+    );
+
+    // Other missing dates are still left out.
+    assert_eq!(
+        dates(
+            yearly.leap_day(Skip::Backward),
+            ymd_hms(2021, 4, 30, 9, 0, 0)
+        )[..1],
+        ["2021-04-30"]
+    );
+    assert_eq!(
+        dates(
+            RRule::new(Frequency::Monthly).leap_day(Skip::Backward),
+            ymd_hms(2021, 1, 31, 9, 0, 0)
+        ),
+        ["2021-01-31", "2021-03-31", "2021-05-31"]
+    );
+
+    // It is not part of the RRULE.
+    let rrule = RRule::new(Frequency::Yearly)
+        .leap_day(Skip::Backward)
+        .validate(birthday)
+        .unwrap();
+    assert_eq!(rrule.get_leap_day(), Skip::Backward);
+    assert!(!rrule.to_string().contains("SKIP"));
+}
+
+#[test]
+fn reads_the_parts_of_a_parsed_rule() {
+    let rrule_set: RRuleSet =
+        "DTSTART:20240101T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20250131T000000Z"
+            .parse()
+            .unwrap();
+    let rrule = &rrule_set.get_rrule()[0];
+    assert_eq!(rrule.get_freq(), Frequency::Weekly);
+    assert_eq!(rrule.get_interval(), 1);
+    assert_eq!(rrule.get_count(), None);
+    assert_eq!(rrule.get_until(), Some(&ymd_hms(2025, 1, 31, 0, 0, 0)));
+    assert_eq!(rrule.get_week_start(), Weekday::Mon);
+    assert_eq!(
+        rrule.get_by_weekday(),
+        &[NWeekday::Every(Weekday::Mon), NWeekday::Every(Weekday::Fri)]
+    );
+    assert_eq!(rrule.get_dt_start(), rrule_set.get_dt_start());
+
+    let rrule_set: RRuleSet = "DTSTART:20240101T090000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=1,-1"
+        .parse()
+        .unwrap();
+    let rrule = &rrule_set.get_rrule()[0];
+    assert_eq!(rrule.get_by_month_day(), &[1]);
+    assert_eq!(rrule.get_by_n_month_day(), &[-1]);
+}
+
+#[test]
+fn explains_the_candidates_of_a_rule() {
+    let dt_start = ymd_hms(2021, 1, 6, 9, 0, 0);
+    let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;BYDAY=MO,FR;COUNT=3".parse().unwrap();
+    let rrule = rrule.validate(dt_start).unwrap();
+
+    let explanations = rrule.explain(ymd_hms(2021, 1, 1, 0, 0, 0)..ymd_hms(2021, 2, 1, 0, 0, 0));
+    let explanations: Vec<_> = explanations.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        explanations,
+        [
+            "2021-01-06 09:00:00 UTC rejected by BYDAY",
+            "2021-01-07 09:00:00 UTC rejected by BYDAY",
+            "2021-01-08 09:00:00 UTC accepted",
+            "2021-01-09 09:00:00 UTC rejected by BYDAY",
+            "2021-01-10 09:00:00 UTC rejected by BYDAY",
+            "2021-01-11 09:00:00 UTC accepted",
+            "2021-01-12 09:00:00 UTC rejected by BYDAY",
+            "2021-01-13 09:00:00 UTC rejected by BYDAY",
+            "2021-01-14 09:00:00 UTC rejected by BYDAY",
+            "2021-01-15 09:00:00 UTC accepted",
+            "2021-01-16 09:00:00 UTC rejected by BYDAY",
+            "2021-01-17 09:00:00 UTC rejected by BYDAY",
+            "2021-01-18 09:00:00 UTC rejected by COUNT",
+        ]
+    );
+}
+
+#[test]
+fn explains_the_candidates_before_the_start_and_after_the_until() {
+    let dt_start = ymd_hms(2021, 1, 20, 9, 0, 0);
+    let rrule: RRule<Unvalidated> =
+        "FREQ=MONTHLY;BYMONTHDAY=10,31;SKIP=BACKWARD;UNTIL=20210310T000000Z"
+            .parse()
+            .unwrap();
+    let rrule = rrule.validate(dt_start).unwrap();
+
+    let explanations = rrule.explain(ymd_hms(2021, 1, 1, 0, 0, 0)..ymd_hms(2022, 1, 1, 0, 0, 0));
+    let decisions: Vec<_> = explanations
+        .iter()
+        .filter(|explanation| explanation.get_rule_part() != Some(RulePart::ByMonthDay))
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        decisions,
+        [
+            "2021-01-10 09:00:00 UTC rejected by DTSTART",
+            "2021-01-31 09:00:00 UTC accepted",
+            "2021-02-10 09:00:00 UTC accepted",
+            "2021-02-28 09:00:00 UTC accepted by SKIP",
+            "2021-03-10 09:00:00 UTC rejected by UNTIL",
+        ]
+    );
+    let accepted: Vec<_> = explanations
+        .iter()
+        .filter(|explanation| explanation.is_accepted())
+        .map(|explanation| *explanation.get_dt())
+        .collect();
+    assert_eq!(accepted, rrule.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn explains_the_candidates_rejected_by_by_set_pos() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYMONTH=2;BYDAY=MO;BYSETPOS=2"
+        .parse()
+        .unwrap();
+    let rrule = rrule.validate(dt_start).unwrap();
+
+    let explanations = rrule.explain(ymd_hms(2021, 1, 30, 0, 0, 0)..ymd_hms(2021, 2, 16, 0, 0, 0));
+    let explanations: Vec<_> = explanations
+        .iter()
+        .map(|explanation| {
+            (
+                explanation.get_dt().day(),
+                explanation.is_accepted(),
+                explanation.get_rule_part(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        explanations,
+        [
+            (30, false, Some(RulePart::ByMonth)),
+            (31, false, Some(RulePart::ByMonth)),
+            (1, false, Some(RulePart::BySetPos)),
+            (2, false, Some(RulePart::ByDay)),
+            (3, false, Some(RulePart::ByDay)),
+            (4, false, Some(RulePart::ByDay)),
+            (5, false, Some(RulePart::ByDay)),
+            (6, false, Some(RulePart::ByDay)),
+            (7, false, Some(RulePart::ByDay)),
+            (8, true, Some(RulePart::BySetPos)),
+            (9, false, Some(RulePart::ByDay)),
+            (10, false, Some(RulePart::ByDay)),
+            (11, false, Some(RulePart::ByDay)),
+            (12, false, Some(RulePart::ByDay)),
+            (13, false, Some(RulePart::ByDay)),
+            (14, false, Some(RulePart::ByDay)),
+            (15, false, Some(RulePart::BySetPos)),
+        ]
+    );
+}
+
+#[test]
+fn lints_suspicious_rules() {
+    let tests = [
+        (
+            "FREQ=YEARLY;BYMONTH=2,4;BYMONTHDAY=30,-31",
+            vec![
+                LintWarning::MonthDayNeverExists { day: -31 },
+                LintWarning::MonthDayMissingInMonths {
+                    day: 30,
+                    months: vec![2],
+                },
+            ],
+        ),
+        ("FREQ=MONTHLY;BYMONTHDAY=31;SKIP=BACKWARD", vec![]),
+        (
+            "FREQ=DAILY;COUNT=3;UNTIL=20301231T000000Z",
+            vec![LintWarning::CountAndUntil],
+        ),
+        (
+            "FREQ=WEEKLY;BYDAY=2MO,FR",
+            vec![LintWarning::NumberedWeekdayWithFrequency {
+                weekday: NWeekday::Nth(2, Weekday::Mon),
+                freq: Frequency::Weekly,
+            }],
+        ),
+        ("FREQ=MONTHLY;BYDAY=2MO,FR", vec![]),
+        ("FREQ=DAILY;INTERVAL=0", vec![LintWarning::ZeroInterval]),
+        (
+            "FREQ=DAILY;BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12;BYDAY=MO,TU,WE,TH,FR,SA,SU",
+            vec![
+                LintWarning::RedundantByRule {
+                    by_rule: "BYMONTH".into(),
+                },
+                LintWarning::RedundantByRule {
+                    by_rule: "BYDAY".into(),
+                },
+            ],
+        ),
+        (
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR,SA,SU;BYSETPOS=-1",
+            vec![],
+        ),
+        // With these frequencies, the rule parts expand the recurrences.
+        ("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU", vec![]),
+        (
+            "FREQ=YEARLY;BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12;BYMONTHDAY=1",
+            vec![],
+        ),
+        ("FREQ=DAILY;BYMINUTE=0,15,30,45", vec![]),
+    ];
+    for (input, expected_warnings) in tests {
+        let rrule: RRule<Unvalidated> = input.parse().unwrap();
+        assert_eq!(rrule.lint(), expected_warnings, "{}", input);
+    }
+
+    // The month day of a validated rule is taken from the start date.
+    let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYMONTH=1,2,3".parse().unwrap();
+    let rrule = rrule.validate(ymd_hms(2021, 1, 31, 9, 0, 0)).unwrap();
+    assert_eq!(
+        rrule.lint(),
+        vec![LintWarning::MonthDayMissingInMonths {
+            day: 31,
+            months: vec![2],
+        }]
+    );
+    assert_eq!(
+        rrule.lint()[0].to_string(),
+        "`BYMONTHDAY` `31` doesn't exist in the months [2], which are left out."
+    );
+}
+
+#[test]
+fn normalizes_rules() {
+    let dt_start = ymd_hms(2021, 1, 15, 9, 30, 0);
+    let tests = [
+        ("FREQ=DAILY;BYHOUR=9;BYMINUTE=30;BYSECOND=0", "FREQ=DAILY"),
+        ("FREQ=DAILY;BYHOUR=17,9,9;WKST=SU", "FREQ=DAILY;BYHOUR=9,17"),
+        ("FREQ=WEEKLY;BYDAY=FR", "FREQ=WEEKLY"),
+        (
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=FR,MO;WKST=SU",
+            "FREQ=WEEKLY;INTERVAL=2;WKST=SU;BYDAY=MO,FR",
+        ),
+        (
+            "FREQ=MONTHLY;BYMONTHDAY=15,-1",
+            "FREQ=MONTHLY;BYMONTHDAY=-1,15",
+        ),
+        ("FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=15", "FREQ=YEARLY"),
+        (
+            "FREQ=YEARLY;BYMONTH=3;BYMONTHDAY=15",
+            "FREQ=YEARLY;BYMONTH=3",
+        ),
+        ("FREQ=YEARLY;BYMONTHDAY=15", "FREQ=YEARLY;BYMONTHDAY=15"),
+        ("FREQ=HOURLY;BYMINUTE=30;BYSECOND=0", "FREQ=HOURLY"),
+    ];
+    for (input, expected_output) in tests {
+        let rrule: RRule<Unvalidated> = input.parse().unwrap();
+        let rrule = rrule.validate(dt_start).unwrap();
+        let normalized = rrule.normalized();
+        assert_eq!(normalized.to_string(), expected_output, "{}", input);
+        let revalidated = normalized.clone().validate(dt_start).unwrap();
+        assert_eq!(revalidated.normalized(), normalized);
+        assert_eq!(
+            revalidated.into_iter().take(20).collect::<Vec<_>>(),
+            rrule.into_iter().take(20).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn compares_the_recurrences_of_rules() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let horizon = ymd_hms(2021, 12, 31, 0, 0, 0);
+    let rrule = |input: &str| {
+        let rrule: RRule<Unvalidated> = input.parse().unwrap();
+        rrule.validate(dt_start).unwrap()
+    };
+    let tests = [
+        ("FREQ=MONTHLY;BYMONTHDAY=1", "FREQ=MONTHLY", true),
+        ("FREQ=DAILY;INTERVAL=7", "FREQ=WEEKLY", true),
+        (
+            "FREQ=YEARLY;BYMONTH=1,2,3;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYMONTH=1,2,3",
+            true,
+        ),
+        (
+            "FREQ=WEEKLY;COUNT=3",
+            "FREQ=DAILY;INTERVAL=7;UNTIL=20210115T090000Z",
+            true,
+        ),
+        ("FREQ=WEEKLY;COUNT=3", "FREQ=WEEKLY;COUNT=4", false),
+        // Only the recurrences up to the horizon are compared for infinite rules.
+        ("FREQ=MONTHLY;COUNT=12", "FREQ=MONTHLY", true),
+        ("FREQ=MONTHLY;COUNT=11", "FREQ=MONTHLY", false),
+        ("FREQ=DAILY", "FREQ=DAILY;BYHOUR=9,10", false),
+    ];
+    for (input, other_input, expected_output) in tests {
+        let equivalent = rrule(input).equivalent_to(&rrule(other_input), horizon);
+        assert_eq!(equivalent, expected_output, "{} {}", input, other_input);
+    }
+}
+
+#[test]
+fn computes_the_stats_of_the_recurrences_within_a_window() {
+    let dt_start = ymd_hms(2021, 1, 31, 9, 0, 0);
+    let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYMONTHDAY=-1;BYHOUR=9,17".parse().unwrap();
+    let rrule = rrule.validate(dt_start).unwrap();
+
+    let stats = rrule.stats(ymd_hms(2021, 2, 1, 0, 0, 0)..ymd_hms(2021, 5, 1, 0, 0, 0));
+    assert_eq!(stats.get_count(), 6);
+    assert_eq!(stats.get_min_gap(), Some(Duration::hours(8)));
+    assert_eq!(
+        stats.get_max_gap(),
+        Some(Duration::days(31) - Duration::hours(8))
+    );
+    // Feb 28 was a Sunday, Mar 31 a Wednesday and Apr 30 a Friday.
+    assert_eq!(stats.get_per_weekday(), &[0, 0, 2, 0, 2, 0, 2]);
+    assert_eq!(stats.get_per_month(), &[0, 2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let stats = rrule.stats(ymd_hms(2021, 2, 1, 0, 0, 0)..ymd_hms(2021, 2, 28, 12, 0, 0));
+    assert_eq!(stats.get_count(), 1);
+    assert_eq!(stats.get_min_gap(), None);
+    assert_eq!(stats.get_max_gap(), None);
+}
+
+#[test]
+fn simplifies_rules() {
+    let dt_start = ymd_hms(2021, 1, 31, 9, 0, 0);
+    let horizon = ymd_hms(2030, 1, 1, 0, 0, 0);
+    let tests = [
+        ("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR,SA,SU", "FREQ=DAILY"),
+        (
+            "FREQ=DAILY;BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12;BYHOUR=9",
+            "FREQ=DAILY",
+        ),
+        (
+            "FREQ=HOURLY;BYHOUR=0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23",
+            "FREQ=HOURLY",
+        ),
+        (
+            "FREQ=DAILY;INTERVAL=7;BYMONTH=1,7",
+            "FREQ=WEEKLY;BYMONTH=1,7",
+        ),
+        ("FREQ=DAILY;INTERVAL=7;BYDAY=SU", "FREQ=WEEKLY"),
+        (
+            "FREQ=DAILY;INTERVAL=7;BYDAY=MO",
+            "FREQ=DAILY;INTERVAL=7;BYDAY=MO",
+        ),
+        (
+            "FREQ=MONTHLY;INTERVAL=24;BYMONTHDAY=31",
+            "FREQ=YEARLY;INTERVAL=2",
+        ),
+        (
+            "FREQ=MONTHLY;INTERVAL=12;BYMONTHDAY=1",
+            "FREQ=MONTHLY;INTERVAL=12;BYMONTHDAY=1",
+        ),
+        (
+            "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU",
+            "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR,SA,SU",
+        ),
+    ];
+    for (input, expected_output) in tests {
+        let rrule: RRule<Unvalidated> = input.parse().unwrap();
+        let rrule = rrule.validate(dt_start).unwrap();
+        let simplified = rrule.simplified();
+        assert_eq!(simplified.to_string(), expected_output, "{}", input);
+        let simplified = simplified.validate(dt_start).unwrap();
+        assert!(simplified.equivalent_to(&rrule, horizon), "{}", input);
+    }
+}
+
+#[test]
+fn n_weekday_constructors_match_the_parsed_weekdays() {
+    let rrule: RRule<Unvalidated> = "FREQ=MONTHLY;BYDAY=2MO,-1FR,-2SU".parse().unwrap();
+    let built = RRule::builder()
+        .freq(Frequency::Monthly)
+        .by_weekday(vec![
+            NWeekday::Nth(2, Weekday::Mon),
+            NWeekday::last(Weekday::Fri),
+            NWeekday::nth_last(2, Weekday::Sun),
+        ])
+        .build();
+    assert_eq!(built, rrule);
+}
+
+#[test]
+fn n_weekday_nth_last_accepts_1_to_53() {
+    assert_eq!(
+        NWeekday::nth_last(53, Weekday::Mon),
+        NWeekday::Nth(-53, Weekday::Mon)
+    );
+    assert_eq!(
+        NWeekday::try_nth_last(1, Weekday::Mon),
+        Some(NWeekday::last(Weekday::Mon))
+    );
+    for n in [0, -1, 54, i16::MIN] {
+        assert_eq!(NWeekday::try_nth_last(n, Weekday::Mon), None, "{n}");
+        let result = std::panic::catch_unwind(|| NWeekday::nth_last(n, Weekday::Mon));
+        assert!(result.is_err(), "{n}");
+    }
+}
+
+#[test]
+fn rejects_negative_month_days_of_weekly_rules() {
+    // The negative month days are moved to `by_n_month_day` when the rule is parsed.
+    let Err(RRuleError::ValidationError(error)) =
+        "DTSTART:20210101T000000Z\nRRULE:FREQ=WEEKLY;BYMONTHDAY=-1".parse::<RRuleSet>()
+    else {
+        unreachable!("expected a validation error");
+    };
+    assert_eq!(
+        error,
+        ValidationError::InvalidByRuleAndFrequency {
+            by_rule: "BYMONTHDAY".into(),
+            freq: Frequency::Weekly,
+        }
+    );
+}
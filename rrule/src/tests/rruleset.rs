@@ -1,5 +1,16 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, TimeZone};
+use smallvec::smallvec;
+
 use crate::tests::common::{check_occurrences, test_recurring_rrule_set, ymd_hms};
-use crate::{Frequency, NWeekday, RRule, RRuleSet, Weekday};
+use crate::{
+    build_rruleset_from_vevent, build_rruleset_with_warnings, build_rrulesets, parse_calendar,
+    BusinessDayPolicy, DstFoldPolicy, DstGapPolicy, DuplicatePolicy, EventDate, EventDuration,
+    Exclusion, Frequency, NWeekday, OccurrenceSource, ParseError, ParseWarning, ParserConfig,
+    ParserLimits, RRule, RRuleError, RRuleSet, RecurrenceOverride, Tz, Unvalidated, Weekday,
+};
 
 #[test]
 #[cfg(feature = "exrule")]
@@ -9,10 +20,10 @@ fn rrule_and_exrule() {
     let rrule1 = RRule {
         freq: Frequency::Yearly,
         count: Some(6),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     let rrule = rrule1.validate(dt_start).unwrap();
@@ -20,10 +31,10 @@ fn rrule_and_exrule() {
     let rrule2 = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     let exrule = rrule2.validate(dt_start).unwrap();
@@ -75,10 +86,10 @@ fn setdate_and_exrule() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(3),
-        by_weekday: vec![NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     let exrule = rrule.validate(dt_start).unwrap();
@@ -110,10 +121,10 @@ fn rrule_and_exdate_1() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(6),
-        by_weekday: vec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Tue), NWeekday::Every(Weekday::Thu)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -172,11 +183,11 @@ fn rrule_and_exyearly_yearly_big() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(13),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -184,11 +195,11 @@ fn rrule_and_exyearly_yearly_big() {
     let exrule = RRule {
         freq: Frequency::Yearly,
         count: Some(10),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let exrule = exrule.validate(dt_start).unwrap();
@@ -212,11 +223,11 @@ fn before() {
 
     let rrule = RRule {
         freq: Frequency::Yearly,
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -224,11 +235,11 @@ fn before() {
     let exrule = RRule {
         freq: Frequency::Yearly,
         count: Some(10),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let exrule = exrule.validate(dt_start).unwrap();
@@ -236,7 +247,7 @@ fn before() {
     let set = RRuleSet::new(dt_start)
         .rrule(rrule)
         .exrule(exrule)
-        .before(ymd_hms(2015, 9, 2, 9, 0, 0));
+        .only_before(ymd_hms(2015, 9, 2, 9, 0, 0));
 
     assert_eq!(
         set.all_unchecked().last().unwrap().clone(),
@@ -251,11 +262,11 @@ fn after() {
 
     let rrule = RRule {
         freq: Frequency::Yearly,
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -263,11 +274,11 @@ fn after() {
     let exrule = RRule {
         freq: Frequency::Yearly,
         count: Some(10),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let exrule = exrule.validate(dt_start).unwrap();
@@ -275,7 +286,7 @@ fn after() {
     let set = RRuleSet::new(dt_start)
         .rrule(rrule)
         .exrule(exrule)
-        .after(ymd_hms(2000, 9, 2, 9, 0, 0));
+        .only_after(ymd_hms(2000, 9, 2, 9, 0, 0));
 
     assert_eq!(set.all(1).dates[0], ymd_hms(2007, 9, 2, 9, 0, 0),);
 }
@@ -287,11 +298,11 @@ fn between() {
 
     let rrule = RRule {
         freq: Frequency::Yearly,
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -299,11 +310,11 @@ fn between() {
     let exrule = RRule {
         freq: Frequency::Yearly,
         count: Some(10),
-        by_month: vec![9],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![2],
+        by_month: smallvec![9],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![2],
         ..Default::default()
     };
     let exrule = exrule.validate(dt_start).unwrap();
@@ -311,8 +322,8 @@ fn between() {
     let set = RRuleSet::new(dt_start)
         .rrule(rrule)
         .exrule(exrule)
-        .after(ymd_hms(2000, 9, 2, 9, 0, 0))
-        .before(ymd_hms(2010, 9, 2, 9, 0, 0));
+        .only_after(ymd_hms(2000, 9, 2, 9, 0, 0))
+        .only_before(ymd_hms(2010, 9, 2, 9, 0, 0));
 
     check_occurrences(
         &set.all(u16::MAX).dates,
@@ -332,11 +343,11 @@ fn before_70s() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(2),
-        by_month: vec![1],
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1],
+        by_month: smallvec![1],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -395,7 +406,7 @@ fn minutely_with_interval_1() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(2),
-        by_second: vec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -415,7 +426,7 @@ fn minutely_with_interval_2() {
     let rrule = RRule {
         freq: Frequency::Minutely,
         count: Some(2),
-        by_second: vec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -436,8 +447,8 @@ fn hourly_with_interval_1() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(2),
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -457,8 +468,8 @@ fn hourly_with_interval_2() {
     let rrule = RRule {
         freq: Frequency::Hourly,
         count: Some(2),
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -479,8 +490,8 @@ fn daily_with_interval_1() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(2),
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -500,9 +511,9 @@ fn daily_with_interval_2() {
     let rrule = RRule {
         freq: Frequency::Daily,
         count: Some(2),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
         ..Default::default()
     };
@@ -523,10 +534,10 @@ fn weekly_with_interval_1() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(2),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_weekday: vec![NWeekday::Every(Weekday::Mon)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Mon)],
         ..Default::default()
     };
     // 4th is Monday
@@ -547,10 +558,10 @@ fn weekly_with_interval_2() {
     let rrule = RRule {
         freq: Frequency::Weekly,
         count: Some(2),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_weekday: vec![NWeekday::Every(Weekday::Mon)],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_weekday: smallvec![NWeekday::Every(Weekday::Mon)],
         interval: 2,
         ..Default::default()
     };
@@ -572,10 +583,10 @@ fn monthly_with_interval_1() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(2),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_month_day: vec![1],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_month_day: smallvec![1],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -595,11 +606,11 @@ fn monthly_with_interval_2() {
     let rrule = RRule {
         freq: Frequency::Monthly,
         count: Some(2),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
         interval: 2,
-        by_month_day: vec![1],
+        by_month_day: smallvec![1],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -619,10 +630,10 @@ fn yearly_with_interval_1() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(2),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![1],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1],
         ..Default::default()
     };
     let rrule = rrule.validate(dt_start).unwrap();
@@ -642,10 +653,10 @@ fn yearly_with_interval_2() {
     let rrule = RRule {
         freq: Frequency::Yearly,
         count: Some(2),
-        by_hour: vec![9],
-        by_minute: vec![0],
-        by_second: vec![0],
-        by_year_day: vec![1],
+        by_hour: smallvec![9],
+        by_minute: smallvec![0],
+        by_second: smallvec![0],
+        by_year_day: smallvec![1],
         interval: 2,
         ..Default::default()
     };
@@ -658,3 +669,2028 @@ fn yearly_with_interval_2() {
         &[ymd_hms(1960, 1, 1, 9, 0, 0), ymd_hms(1962, 1, 1, 9, 0, 0)],
     );
 }
+
+#[test]
+fn between_stops_at_window_end() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrule = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+    let set = RRuleSet::new(dt_start)
+        .rrule(rrule)
+        .rdate(ymd_hms(2021, 1, 3, 12, 0, 0))
+        .exdate(ymd_hms(2021, 1, 4, 9, 0, 0));
+
+    let result = set.between(
+        ymd_hms(2021, 1, 3, 9, 0, 0),
+        ymd_hms(2021, 1, 5, 9, 0, 0),
+        true,
+    );
+    assert!(!result.limited);
+    check_occurrences(
+        &result.dates,
+        &[
+            "2021-01-03T09:00:00+00:00",
+            "2021-01-03T12:00:00+00:00",
+            "2021-01-05T09:00:00+00:00",
+        ],
+    );
+
+    let result = set.between(
+        ymd_hms(2021, 1, 3, 9, 0, 0),
+        ymd_hms(2021, 1, 5, 9, 0, 0),
+        false,
+    );
+    check_occurrences(&result.dates, &["2021-01-03T12:00:00+00:00"]);
+}
+
+#[test]
+fn after_and_before_of_a_set() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrule = RRule::new(Frequency::Weekly).validate(dt_start).unwrap();
+    let set = RRuleSet::new(dt_start)
+        .rrule(rrule)
+        .rdate(ymd_hms(2021, 1, 3, 12, 0, 0))
+        .exdate(ymd_hms(2021, 1, 8, 9, 0, 0));
+
+    let dt = ymd_hms(2021, 1, 3, 12, 0, 0);
+    assert_eq!(set.after(dt, true), Some(dt));
+    assert_eq!(set.after(dt, false), Some(ymd_hms(2021, 1, 15, 9, 0, 0)));
+    assert_eq!(set.before(dt, true), Some(dt));
+    assert_eq!(set.before(dt, false), Some(dt_start));
+    assert_eq!(set.before(dt_start, false), None);
+}
+
+#[test]
+fn iter_merges_infinite_rules() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let every_2_days = RRule::new(Frequency::Daily)
+        .interval(2)
+        .validate(dt_start)
+        .unwrap();
+    let every_3_days = RRule::new(Frequency::Daily)
+        .interval(3)
+        .validate(dt_start)
+        .unwrap();
+    let set = RRuleSet::new(dt_start)
+        .rrule(every_2_days)
+        .rrule(every_3_days)
+        .rdate(ymd_hms(2021, 1, 2, 12, 0, 0))
+        .exdate(ymd_hms(2021, 1, 5, 9, 0, 0));
+
+    let dates = set.iter().take(6).collect::<Vec<_>>();
+    check_occurrences(
+        &dates,
+        &[
+            "2021-01-01T09:00:00+00:00",
+            "2021-01-02T12:00:00+00:00",
+            "2021-01-03T09:00:00+00:00",
+            "2021-01-04T09:00:00+00:00",
+            "2021-01-07T09:00:00+00:00",
+            "2021-01-09T09:00:00+00:00",
+        ],
+    );
+
+    let set = set.duplicates(DuplicatePolicy::Keep);
+    let dates = set.iter().take(6).collect::<Vec<_>>();
+    check_occurrences(
+        &dates,
+        &[
+            "2021-01-01T09:00:00+00:00",
+            "2021-01-01T09:00:00+00:00",
+            "2021-01-02T12:00:00+00:00",
+            "2021-01-03T09:00:00+00:00",
+            "2021-01-04T09:00:00+00:00",
+            "2021-01-07T09:00:00+00:00",
+        ],
+    );
+}
+
+#[test]
+fn contains_applies_rdates_and_exdates() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrule = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+    let set = RRuleSet::new(dt_start)
+        .rrule(rrule)
+        .rdate(ymd_hms(2021, 1, 3, 12, 0, 0))
+        .exdate(ymd_hms(2021, 1, 4, 9, 0, 0));
+
+    assert!(set.contains(dt_start));
+    assert!(set.contains(ymd_hms(2021, 1, 3, 12, 0, 0)));
+    assert!(set.contains(ymd_hms(2021, 1, 5, 9, 0, 0)));
+    assert!(!set.contains(ymd_hms(2021, 1, 4, 9, 0, 0)));
+    assert!(!set.contains(ymd_hms(2021, 1, 5, 10, 0, 0)));
+}
+
+#[test]
+fn is_finite_and_is_infinite() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let finite = RRule::new(Frequency::Daily)
+        .count(3)
+        .validate(dt_start)
+        .unwrap();
+    let infinite = RRule::new(Frequency::Daily).validate(dt_start).unwrap();
+    assert!(finite.is_finite());
+    assert!(infinite.is_infinite());
+
+    let rdate_only = RRuleSet::new(dt_start).rdate(ymd_hms(2021, 1, 2, 9, 0, 0));
+    assert!(rdate_only.is_finite());
+
+    let set = rdate_only.rrule(finite);
+    assert!(set.is_finite());
+    assert!(!set.is_infinite());
+
+    let set = set.rrule(infinite);
+    assert!(!set.is_finite());
+    assert!(set.is_infinite());
+}
+
+#[test]
+fn all_with_error_fails_when_limit_exceeded() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let finite = RRuleSet::new(dt_start).rrule(
+        RRule::new(Frequency::Daily)
+            .count(5)
+            .validate(dt_start)
+            .unwrap(),
+    );
+    assert_eq!(finite.clone().all_with_error(5).unwrap().len(), 5);
+    assert_eq!(
+        finite.clone().all_with_error(4),
+        Err(RRuleError::IterationLimitExceeded(4))
+    );
+    assert_eq!(
+        finite
+            .only_before(ymd_hms(2021, 1, 2, 9, 0, 0))
+            .all_with_error(2)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let infinite =
+        RRuleSet::new(dt_start).rrule(RRule::new(Frequency::Daily).validate(dt_start).unwrap());
+    assert_eq!(
+        infinite.all_with_error(u16::MAX),
+        Err(RRuleError::IterationLimitExceeded(u16::MAX))
+    );
+}
+
+#[test]
+fn iter_from_cursor_continues_after_last_recurrence() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=10\n\
+        RRULE:FREQ=WEEKLY;BYHOUR=12\n\
+        RDATE:20210104T100000Z,20210301T090000Z\n\
+        EXDATE:20210105T090000Z"
+        .parse()
+        .unwrap();
+    let expected: Vec<_> = rrule_set.iter().take(30).collect();
+
+    let mut iter = rrule_set.iter();
+    assert_eq!(iter.cursor(), None);
+    let mut dates: Vec<_> = iter.by_ref().take(7).collect();
+    while dates.len() < 30 {
+        let cursor = iter.cursor().unwrap();
+        iter = rrule_set.iter_from_cursor(&cursor.to_string().parse().unwrap());
+        dates.extend(iter.by_ref().take(7));
+    }
+    dates.truncate(30);
+
+    assert_eq!(dates, expected);
+}
+
+#[test]
+fn iter_from_cursor_matches_the_iteration_from_the_start() {
+    let sets = [
+        "DTSTART:20160229T090000Z\nRRULE:FREQ=YEARLY",
+        "DTSTART:20150131T090000Z\nRRULE:FREQ=MONTHLY;SKIP=FORWARD;RSCALE=GREGORIAN",
+        "DTSTART:20150101T090000Z\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=1,-1",
+        "DTSTART:20150101T090000Z\nRRULE:FREQ=YEARLY;BYWEEKNO=1,52;BYDAY=MO,SU",
+        "DTSTART;TZID=Europe/Berlin:20150101T023000\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=SU,WE;WKST=TH",
+        "DTSTART;TZID=America/New_York:20150101T010000\nRRULE:FREQ=HOURLY;INTERVAL=5;BYHOUR=1,6,11,16,21",
+        "DTSTART:20150101T090000Z\nRRULE:FREQ=MINUTELY;INTERVAL=20;BYHOUR=9;BYDAY=MO",
+        "DTSTART:20150101T090000Z\nRRULE:FREQ=MINUTELY;INTERVAL=7;BYDAY=MO",
+        "DTSTART:20150101T090000Z\nRRULE:FREQ=SECONDLY;INTERVAL=50;BYSECOND=10,40;BYDAY=TU",
+        "DTSTART:20150101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=3\nEXRULE:FREQ=WEEKLY;BYDAY=SA,SU",
+    ];
+    for set in sets {
+        let rrule_set: RRuleSet = set.parse().unwrap();
+        let expected: Vec<_> = rrule_set.iter().take(200).collect();
+        for position in [1, 2, 50, 120, 199] {
+            let mut iter = rrule_set.iter();
+            iter.by_ref().take(position).for_each(drop);
+            let cursor = iter.cursor().unwrap();
+            let resumed: Vec<_> = rrule_set
+                .iter_from_cursor(&cursor)
+                .take(200 - position)
+                .collect();
+            assert_eq!(resumed, expected[position..], "{set} from {position}");
+        }
+    }
+}
+
+#[test]
+fn iter_from_cursor_keeps_the_duplicates_at_the_cursor() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY\n\
+        RRULE:FREQ=WEEKLY\n\
+        RDATE:20210108T090000Z"
+        .parse()
+        .unwrap();
+    let rrule_set = rrule_set.duplicates(DuplicatePolicy::Keep);
+    let expected: Vec<_> = rrule_set.iter().take(20).collect();
+    // The recurrences of the 8th of January are returned three times.
+    assert_eq!(expected[8..11], [ymd_hms(2021, 1, 8, 9, 0, 0); 3]);
+
+    for position in 8..=11 {
+        let mut iter = rrule_set.iter();
+        iter.by_ref().take(position).for_each(drop);
+        let cursor = iter.cursor().unwrap();
+        let cursor = cursor.to_string().parse().unwrap();
+        let resumed: Vec<_> = rrule_set
+            .iter_from_cursor(&cursor)
+            .take(20 - position)
+            .collect();
+        assert_eq!(resumed, expected[position..], "from {position}");
+    }
+}
+
+#[test]
+fn iter_from_state_keeps_the_duplicates_at_the_last_recurrence() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;UNTIL=20210120T090000Z\n\
+        RRULE:FREQ=WEEKLY;UNTIL=20210120T090000Z\n\
+        RDATE:20210108T090000Z"
+        .parse()
+        .unwrap();
+    let rrule_set = rrule_set.duplicates(DuplicatePolicy::Keep);
+    let expected: Vec<_> = rrule_set.iter().collect();
+
+    for position in 8..=11 {
+        let mut iter = rrule_set.iter();
+        iter.by_ref().take(position).for_each(drop);
+        let state = iter.state().unwrap();
+        assert_eq!(state.emitted, position as u64);
+
+        let mut iter = rrule_set.iter_from_state(&state);
+        let resumed: Vec<_> = iter.by_ref().collect();
+        assert_eq!(resumed, expected[position..], "from {position}");
+        assert_eq!(iter.state().unwrap().emitted, expected.len() as u64);
+    }
+}
+
+#[test]
+fn peek_does_not_advance_iterator() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=5\n\
+        EXDATE:20210102T090000Z"
+        .parse()
+        .unwrap();
+    let expected: Vec<_> = rrule_set.iter().collect();
+
+    let mut iter = rrule_set.iter();
+    assert_eq!(iter.peek_nth(3), Some(&expected[3]));
+    assert_eq!(iter.peek_nth(4), None);
+    assert_eq!(iter.peek(), Some(&expected[0]));
+    assert_eq!(iter.next(), Some(expected[0]));
+    assert_eq!(
+        iter.cursor().unwrap().to_string(),
+        expected[0].timestamp().to_string()
+    );
+    assert_eq!(iter.peek(), Some(&expected[1]));
+    assert_eq!(iter.collect::<Vec<_>>(), expected[1..]);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_between_matches_between() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;BYHOUR=9,18\n\
+        RRULE:FREQ=WEEKLY;BYDAY=MO,WE\n\
+        RRULE:FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=12\n\
+        RDATE:20210215T120000Z,20220101T000000Z\n\
+        EXDATE:20210203T090000Z,20210204T180000Z"
+        .parse()
+        .unwrap();
+    let after = ymd_hms(2021, 2, 1, 9, 0, 0);
+    let before = ymd_hms(2021, 6, 30, 9, 0, 0);
+
+    for inclusive in [true, false] {
+        assert_eq!(
+            rrule_set.par_between(after, before, inclusive),
+            rrule_set.between(after, before, inclusive)
+        );
+    }
+}
+
+#[test]
+fn all_grouped_by_month_uses_local_calendar() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=America/New_York:20210101T220000\n\
+        RRULE:FREQ=WEEKLY;BYDAY=SU"
+        .parse()
+        .unwrap();
+    let start = ymd_hms(2021, 1, 1, 0, 0, 0);
+    let end = ymd_hms(2021, 6, 1, 0, 0, 0);
+
+    let months = rrule_set.all_grouped_by_month(start..end);
+    // 2021-01-31T22:00 in New York is already February in UTC.
+    assert_eq!(months[&(2021, 1)].len(), 5);
+    assert_eq!(months.values().map(Vec::len).sum::<usize>(), 22);
+    let dates: Vec<_> = months.into_values().flatten().collect();
+    assert_eq!(
+        dates,
+        rrule_set
+            .iter()
+            .take_while(|date| *date < end)
+            .collect::<Vec<_>>()
+    );
+
+    let years = rrule_set.all_grouped_by_year(start..end);
+    assert_eq!(years.keys().copied().collect::<Vec<_>>(), vec![2021]);
+}
+
+#[test]
+fn display_roundtrips_rdates_and_exdates() {
+    let rrule_set = RRuleSet::new(ymd_hms(2021, 1, 1, 9, 0, 0))
+        .rrule(
+            RRule::new(Frequency::Daily)
+                .count(10)
+                .validate(ymd_hms(2021, 1, 1, 9, 0, 0))
+                .unwrap(),
+        )
+        .rdate(ymd_hms(2021, 2, 1, 9, 0, 0))
+        .rdate(ymd_hms(2021, 2, 2, 9, 0, 0))
+        .rdate(ymd_hms(2021, 3, 1, 8, 0, 0).with_timezone(&Tz::Europe__Paris))
+        .exdate(ymd_hms(2021, 1, 3, 9, 0, 0));
+
+    let output = rrule_set.to_string();
+    assert_eq!(
+        output,
+        "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=10;BYHOUR=9;BYMINUTE=0;BYSECOND=0\n\
+        RDATE:20210201T090000Z,20210202T090000Z\n\
+        RDATE;TZID=Europe/Paris:20210301T090000\n\
+        EXDATE:20210103T090000Z"
+    );
+
+    let parsed: RRuleSet = output.parse().unwrap();
+    assert_eq!(parsed.to_string(), output);
+    assert_eq!(parsed.all(100).dates, rrule_set.all(100).dates);
+}
+
+#[test]
+fn display_roundtrips_nth_weekdays() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=YEARLY;COUNT=4;BYDAY=1MO,-1FR\n\
+        EXDATE:20210104T090000Z"
+        .parse()
+        .unwrap();
+
+    let output = rrule_set.to_string();
+    assert_eq!(
+        output,
+        "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=YEARLY;COUNT=4;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=-1FR,1MO\n\
+        EXDATE:20210104T090000Z"
+    );
+
+    let parsed: RRuleSet = output.parse().unwrap();
+    assert_eq!(parsed, rrule_set);
+    assert_eq!(parsed.all(100).dates, rrule_set.all(100).dates);
+}
+
+#[cfg(feature = "exrule")]
+#[test]
+fn display_roundtrips_exrules() {
+    let input = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=10;BYHOUR=9;BYMINUTE=0;BYSECOND=0\n\
+        EXRULE:FREQ=DAILY;COUNT=3;INTERVAL=2;BYHOUR=9;BYMINUTE=0;BYSECOND=0";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert_eq!(rrule_set.get_rrule().len(), 1);
+    assert_eq!(rrule_set.get_exrule().len(), 1);
+    assert_eq!(
+        rrule_set.to_string(),
+        input.replace("UTC+05:30", "\"UTC+05:30\"")
+    );
+}
+
+#[cfg(feature = "xcal")]
+#[test]
+fn xcal_roundtrips_rrule_set() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Paris:20210101T090000\n\
+        RRULE:FREQ=WEEKLY;UNTIL=20210301T000000Z;BYDAY=MO,-1FR\n\
+        RDATE:20210201T090000Z\n\
+        EXDATE;TZID=Europe/Paris:20210104T090000"
+        .parse()
+        .unwrap();
+
+    let xcal = rrule_set.to_xcal();
+    assert!(xcal.starts_with(
+        "<dtstart><parameters><tzid><text>Europe/Paris</text></tzid></parameters>\
+        <date-time>2021-01-01T09:00:00</date-time></dtstart>\
+        <rrule><recur><freq>WEEKLY</freq><until>2021-03-01T00:00:00Z</until>"
+    ));
+    assert!(xcal.contains("<byday>MO</byday><byday>-1FR</byday>"));
+    assert!(xcal.ends_with(
+        "<rdate><date-time>2021-02-01T09:00:00Z</date-time></rdate>\
+        <exdate><parameters><tzid><text>Europe/Paris</text></tzid></parameters>\
+        <date-time>2021-01-04T09:00:00</date-time></exdate>"
+    ));
+
+    let parsed = RRuleSet::from_xcal(&format!(
+        "<icalendar xmlns=\"urn:ietf:params:xml:ns:icalendar-2.0\"><vcalendar><components>\
+        <vevent><properties><summary><text>Meeting</text></summary>{}</properties></vevent>\
+        </components></vcalendar></icalendar>",
+        xcal
+    ))
+    .unwrap();
+    assert_eq!(parsed, rrule_set);
+}
+
+#[cfg(feature = "xcal")]
+#[test]
+fn xcal_roundtrips_nth_weekdays() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=MONTHLY;COUNT=4;BYDAY=1MO"
+        .parse()
+        .unwrap();
+
+    let xcal = rrule_set.to_xcal();
+    assert!(xcal.contains("<byday>1MO</byday>"));
+
+    let parsed = RRuleSet::from_xcal(&xcal).unwrap();
+    assert_eq!(parsed, rrule_set);
+    assert_eq!(parsed.all(10).dates, rrule_set.all(10).dates);
+}
+
+#[cfg(feature = "xcal")]
+#[test]
+fn xcal_rejects_invalid_input() {
+    assert!(RRuleSet::from_xcal("<dtstart><date-time>2021-01-01</dtstart>").is_err());
+    assert!(RRuleSet::from_xcal(
+        "<dtstart><date-time>2021-01-01T09:00:00Z</date-time></dtstart>\
+        <rrule><recur><freq>NEVER</freq></recur></rrule>"
+    )
+    .is_err());
+}
+
+#[test]
+fn recurrence_lines_roundtrip() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0).with_timezone(&Tz::Europe__Paris);
+    let lines = [
+        "RRULE:FREQ=WEEKLY;COUNT=4;BYDAY=FR",
+        "RDATE;TZID=Europe/Paris:20210105T100000",
+        "EXDATE;TZID=Europe/Paris:20210108T100000",
+    ];
+    let rrule_set = RRuleSet::from_recurrence_lines(dt_start, &lines).unwrap();
+    check_occurrences(
+        &rrule_set.clone().all(10).dates,
+        &[
+            "2021-01-01T10:00:00+01:00",
+            "2021-01-05T10:00:00+01:00",
+            "2021-01-15T10:00:00+01:00",
+            "2021-01-22T10:00:00+01:00",
+        ],
+    );
+
+    let output = rrule_set.to_recurrence_lines();
+    assert_eq!(
+        output,
+        vec![
+            "RRULE:FREQ=WEEKLY;COUNT=4;BYHOUR=10;BYMINUTE=0;BYSECOND=0;BYDAY=FR",
+            "RDATE;TZID=Europe/Paris:20210105T100000",
+            "EXDATE;TZID=Europe/Paris:20210108T100000",
+        ]
+    );
+    assert_eq!(
+        RRuleSet::from_recurrence_lines(dt_start, &output).unwrap(),
+        rrule_set
+    );
+
+    assert!(RRuleSet::from_recurrence_lines(dt_start, &["RRULE:FREQ=NEVER"]).is_err());
+}
+
+#[test]
+fn recurrence_lines_roundtrip_nth_weekdays() {
+    let dt_start = ymd_hms(2021, 1, 1, 9, 0, 0);
+    let rrule_set =
+        RRuleSet::from_recurrence_lines(dt_start, &["RRULE:FREQ=MONTHLY;COUNT=4;BYDAY=1MO"])
+            .unwrap();
+
+    let output = rrule_set.to_recurrence_lines();
+    assert_eq!(
+        output,
+        ["RRULE:FREQ=MONTHLY;COUNT=4;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=1MO"]
+    );
+    let parsed = RRuleSet::from_recurrence_lines(dt_start, &output).unwrap();
+    assert_eq!(parsed, rrule_set);
+    check_occurrences(
+        &parsed.all(10).dates,
+        &[
+            "2021-01-04T09:00:00+00:00",
+            "2021-02-01T09:00:00+00:00",
+            "2021-03-01T09:00:00+00:00",
+            "2021-04-05T09:00:00+00:00",
+        ],
+    );
+}
+
+#[test]
+fn equality_ignores_order_of_rules_and_dates() {
+    use std::collections::HashSet;
+
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=3\n\
+        RRULE:FREQ=WEEKLY;BYDAY=FR,MO;COUNT=3\n\
+        RDATE:20210201T090000Z,20210202T090000Z"
+        .parse()
+        .unwrap();
+    let reordered: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=WEEKLY;COUNT=3;BYDAY=MO,FR\n\
+        RDATE:20210202T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=3\n\
+        RDATE:20210201T090000Z"
+        .parse()
+        .unwrap();
+    let changed: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=4\n\
+        RRULE:FREQ=WEEKLY;BYDAY=FR,MO;COUNT=3\n\
+        RDATE:20210201T090000Z,20210202T090000Z"
+        .parse()
+        .unwrap();
+
+    assert_eq!(rrule_set, reordered);
+    assert_ne!(rrule_set, changed);
+
+    let sets: HashSet<_> = [rrule_set, reordered, changed].into_iter().collect();
+    assert_eq!(sets.len(), 2);
+}
+
+#[test]
+fn parses_folded_lines() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Paris:2021\r\n 0101T090000\r\n\
+        RRULE:FREQ=WEEKLY;COUNT=3;BY\r\n\tDAY=MO,FR\r\n"
+        .parse()
+        .unwrap();
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &[
+            "2021-01-01T09:00:00+01:00",
+            "2021-01-04T09:00:00+01:00",
+            "2021-01-08T09:00:00+01:00",
+        ],
+    );
+
+    let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;\r\n BYDAY=MO".parse().unwrap();
+    assert_eq!(rrule.to_string(), "FREQ=WEEKLY;BYDAY=MO");
+}
+
+#[test]
+fn parses_input_with_extra_whitespace() {
+    let rrule_set: RRuleSet = "\r\n  DTSTART;TZID = Europe/Paris: 20210101T090000  \r\n\
+        \r\n\
+        RRULE: FREQ = WEEKLY ; COUNT=3; BYDAY=MO, FR ;\r\n\
+        EXDATE;TZID=Europe/Paris:20210104T090000 , 20210105T090000\t\r\n\
+        \r\n"
+        .parse()
+        .unwrap();
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &["2021-01-01T09:00:00+01:00", "2021-01-08T09:00:00+01:00"],
+    );
+
+    let rrule: RRule<Unvalidated> = " FREQ=DAILY ;INTERVAL = 2 \r\n".parse().unwrap();
+    assert_eq!(rrule.to_string(), "FREQ=DAILY;INTERVAL=2");
+}
+
+#[test]
+fn parses_lowercase_input() {
+    let rrule_set: RRuleSet = "dtstart;tzid=Europe/Paris:20210101t090000\n\
+        Rrule:freq=weekly;count=3;byday=mo,fr;wkst=su\n\
+        exdate:20210104t080000z"
+        .parse()
+        .unwrap();
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &["2021-01-01T09:00:00+01:00", "2021-01-08T09:00:00+01:00"],
+    );
+
+    // Timezone names keep their case.
+    assert!(
+        "DTSTART;TZID=europe/paris:20210101T090000\nRRULE:FREQ=DAILY"
+            .parse::<RRuleSet>()
+            .is_err()
+    );
+}
+
+#[test]
+fn lenient_mode_accepts_vendor_quirks() {
+    let input = "DTSTART;VALUE=DATE:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;BYDAY=MO ,TU;FREQ=WEEKLY;WKST=;COUNT=3;\n\
+        EXDATE:20210105";
+    let rrule_set = RRuleSet::parse_with_config(input, &ParserConfig::lenient()).unwrap();
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &[
+            "2021-01-04T09:00:00+00:00",
+            "2021-01-05T09:00:00+00:00",
+            "2021-01-11T09:00:00+00:00",
+        ],
+    );
+
+    let strict_config = ParserConfig::default();
+    for input in [
+        input,
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;FREQ=WEEKLY",
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;WKST=",
+        "DTSTART;VALUE=DATE:20210101T090000Z\nRRULE:FREQ=DAILY",
+    ] {
+        assert!(RRuleSet::parse_with_config(input, &strict_config).is_err());
+        assert!(input.parse::<RRuleSet>().is_err());
+    }
+}
+
+#[test]
+fn parse_errors_point_to_the_offending_token() {
+    let tests = [
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=MO,MX",
+            52..54,
+            "MX",
+        ),
+        (
+            "DTSTART:20210101T090000Z\r\nRRULE:FREQ=WEEKLY;BYHOUR=1,\r\n 25",
+            56..58,
+            "25",
+        ),
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEXDATE:20210102T090000Z,2021013",
+            66..73,
+            "2021013",
+        ),
+        ("DTSTART:2021\nRRULE:FREQ=DAILY", 8..12, "2021"),
+        ("DTSTART:20210101T090000Z\nFOO:BAR", 25..28, "FOO"),
+    ];
+    for (input, span, token) in tests {
+        let Err(RRuleError::ParserError(error)) = input.parse::<RRuleSet>() else {
+            unreachable!("expected a parse error for {input:?}");
+        };
+        assert_eq!(error.span(), Some(span), "{input:?}");
+        assert_eq!(error.token(), Some(token), "{input:?}");
+    }
+
+    let error = "FREQ=WEEKLY;BYDAY=MX"
+        .parse::<RRule<Unvalidated>>()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "RRule parsing error: `MX` is not a valid weekday. \
+         Valid values are `MO`, `TU`, `WE`, `TH`, `FR`, `SA` and `SU`. (`MX` at offset 18)"
+    );
+}
+
+#[test]
+fn build_with_warnings_reports_recoverable_issues() {
+    let (rrule_set, warnings) = build_rruleset_with_warnings(
+        "DTSTART;TZID=Europe/Paris:20210101T090000\n\
+         RRULE:FREQ=DAILY;UNTIL=20210102T090000\n\
+         RDATE;TZID=Europe/Paris:20210110T090000,20210110T090000\n\
+         EXRULE:FREQ=DAILY;COUNT=1",
+        &ParserConfig::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        warnings,
+        [
+            ParseWarning::UntilNotInUtc {
+                until: "2021-01-02T08:00:00+00:00".into(),
+                tz: "Europe/Paris".into(),
+            },
+            ParseWarning::DuplicateRDate("2021-01-10T09:00:00+01:00".into()),
+            ParseWarning::ExRuleDeprecated,
+        ]
+    );
+    assert_eq!(
+        rrule_set.get_rrule()[0].get_until(),
+        Some(&ymd_hms(2021, 1, 2, 8, 0, 0))
+    );
+
+    // Input without issues has no warnings, and the issues are still errors when parsing.
+    let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;UNTIL=20210102T090000";
+    assert!(input.parse::<RRuleSet>().is_err());
+    let (_, warnings) = build_rruleset_with_warnings(
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY",
+        &ParserConfig::default(),
+    )
+    .unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn build_rrulesets_parses_each_input() {
+    let inputs = [
+        "DTSTART;TZID=Europe/Paris:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3",
+        "DTSTART;TZID=Europe/Atlantis:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3",
+        "DTSTART;TZID=Europe/Paris:20210104T090000\nRRULE:FREQ=WEEKLY;BYDAY=MO,FR;COUNT=4",
+        "RRULE:FREQ=DAILY;COUNT=3",
+    ];
+    let rrule_sets = build_rrulesets(&inputs, &ParserConfig::default());
+    assert_eq!(rrule_sets.len(), inputs.len());
+    for (input, rrule_set) in inputs.iter().zip(rrule_sets) {
+        assert_eq!(rrule_set, input.parse::<RRuleSet>(), "{}", input);
+    }
+}
+
+#[test]
+fn parse_errors_suggest_misspelled_keywords() {
+    let tests = [
+        ("DTSTART:20210101T090000Z\nRRULLE:FREQ=DAILY", "RRULE"),
+        ("DTSTART:20210101T090000Z\nRRULE:FREQ=DAYLY", "DAILY"),
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYMONTDAY=1",
+            "BYMONTHDAY",
+        ),
+        (
+            "DTSTART;TZIDD=Europe/Paris:20210101T090000\nRRULE:FREQ=DAILY",
+            "TZID",
+        ),
+    ];
+    for (input, suggestion) in tests {
+        let Err(RRuleError::ParserError(error)) = input.parse::<RRuleSet>() else {
+            unreachable!("expected a parse error for {input:?}");
+        };
+        assert_eq!(error.suggestion(), Some(suggestion), "{input:?}");
+        assert!(error
+            .to_string()
+            .contains(&format!("Did you mean `{}`?", suggestion)));
+    }
+
+    let Err(RRuleError::ParserError(error)) =
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=SOMETIMES".parse::<RRuleSet>()
+    else {
+        unreachable!("expected a parse error");
+    };
+    assert_eq!(error.suggestion(), None);
+    assert!(!error.to_string().contains("Did you mean"));
+}
+
+#[test]
+fn parser_limits_reject_large_input() {
+    let config = ParserConfig {
+        limits: ParserLimits {
+            max_line_length: Some(60),
+            max_content_lines: Some(3),
+            max_list_values: Some(3),
+        },
+        ..Default::default()
+    };
+    let tests = [
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=1,2,3,4",
+            "values per list",
+            3,
+            4,
+        ),
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEXDATE:20210102T090000Z,20210103T090000Z,\
+             20210104T090000Z,20210105T090000Z",
+            "bytes per line",
+            60,
+            74,
+        ),
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nRDATE:20210102T090000Z\n\
+             RDATE:20210103T090000Z",
+            "content lines",
+            3,
+            4,
+        ),
+    ];
+    for (input, limit, max, found) in tests {
+        assert!(input.parse::<RRuleSet>().is_ok());
+        assert_eq!(
+            RRuleSet::parse_with_config(input, &config),
+            Err(ParseError::LimitExceeded {
+                limit: limit.into(),
+                max,
+                found,
+            }
+            .into())
+        );
+    }
+}
+
+#[test]
+fn parses_multiple_rules_and_date_lines_with_shared_parameters() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Paris:20210104T090000\n\
+        RRULE:FREQ=WEEKLY;COUNT=2;BYDAY=MO\n\
+        RRULE:FREQ=WEEKLY;COUNT=2;BYDAY=WE\n\
+        RDATE;TZID=America/New_York:20210105T090000,20210107T090000\n\
+        RDATE;VALUE=DATE-TIME:20210108T120000Z\n\
+        EXDATE;TZID=Europe/Paris:20210111T090000,20210113T090000\n\
+        EXDATE:20210106T080000Z"
+        .parse()
+        .unwrap();
+
+    assert_eq!(rrule_set.get_rrule().len(), 2);
+    assert_eq!(
+        rrule_set.get_rdate(),
+        &[
+            Tz::America__New_York
+                .with_ymd_and_hms(2021, 1, 5, 9, 0, 0)
+                .unwrap(),
+            Tz::America__New_York
+                .with_ymd_and_hms(2021, 1, 7, 9, 0, 0)
+                .unwrap(),
+            ymd_hms(2021, 1, 8, 12, 0, 0),
+        ]
+    );
+    assert_eq!(
+        rrule_set.get_exdate(),
+        &[
+            Tz::Europe__Paris
+                .with_ymd_and_hms(2021, 1, 11, 9, 0, 0)
+                .unwrap(),
+            Tz::Europe__Paris
+                .with_ymd_and_hms(2021, 1, 13, 9, 0, 0)
+                .unwrap(),
+            ymd_hms(2021, 1, 6, 8, 0, 0),
+        ]
+    );
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &[
+            "2021-01-04T09:00:00+01:00",
+            "2021-01-05T09:00:00-05:00",
+            "2021-01-07T09:00:00-05:00",
+            "2021-01-08T12:00:00+00:00",
+        ],
+    );
+}
+
+#[test]
+fn uses_vtimezone_components_for_unknown_tzids() {
+    let rrule_set: RRuleSet = "BEGIN:VTIMEZONE\n\
+        TZID:Contoso Europe Time\n\
+        BEGIN:STANDARD\n\
+        DTSTART:16010101T030000\n\
+        TZOFFSETFROM:+0200\n\
+        TZOFFSETTO:+0100\n\
+        RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10\n\
+        END:STANDARD\n\
+        BEGIN:DAYLIGHT\n\
+        DTSTART:16010101T020000\n\
+        TZOFFSETFROM:+0100\n\
+        TZOFFSETTO:+0200\n\
+        RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3\n\
+        END:DAYLIGHT\n\
+        END:VTIMEZONE\n\
+        DTSTART;TZID=Contoso Europe Time:20210321T090000\n\
+        RRULE:FREQ=WEEKLY;COUNT=3\n\
+        EXDATE;TZID=Contoso Europe Time:20210404T090000"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        rrule_set.get_dt_start().timezone().name(),
+        "Contoso Europe Time"
+    );
+    assert!(rrule_set
+        .to_string()
+        .starts_with("DTSTART;TZID=Contoso Europe Time:20210321T090000\n"));
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &["2021-03-21T09:00:00+01:00", "2021-03-28T09:00:00+02:00"],
+    );
+
+    // Without the `VTIMEZONE`, the timezone is unknown.
+    assert!(
+        "DTSTART;TZID=Contoso Europe Time:20210321T090000\nRRULE:FREQ=DAILY"
+            .parse::<RRuleSet>()
+            .is_err()
+    );
+}
+
+#[test]
+#[cfg(feature = "windows-zones")]
+fn resolves_windows_timezone_names() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=Eastern Standard Time:20210313T090000\n\
+        RRULE:FREQ=DAILY;COUNT=2"
+        .parse()
+        .unwrap();
+
+    assert_eq!(rrule_set.get_dt_start().timezone(), Tz::America__New_York);
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &["2021-03-13T09:00:00-05:00", "2021-03-14T09:00:00-04:00"],
+    );
+}
+
+#[test]
+fn resolves_offset_tzids_to_fixed_offsets() {
+    let input = "DTSTART;TZID=GMT-0800:20210313T090000\n\
+        RRULE:FREQ=DAILY;COUNT=2\n\
+        RDATE;TZID=UTC+05:30:20210320T090000";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+
+    // A `TZID` with a colon is quoted, which is also accepted.
+    let output = rrule_set.to_string();
+    assert!(output.starts_with("DTSTART;TZID=GMT-0800:20210313T090000\n"));
+    assert!(output.ends_with("\nRDATE;TZID=\"UTC+05:30\":20210320T090000"));
+    assert_eq!(output.parse::<RRuleSet>().unwrap(), rrule_set);
+    // The offset doesn't change with daylight saving time.
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &[
+            "2021-03-13T09:00:00-08:00",
+            "2021-03-14T09:00:00-08:00",
+            "2021-03-20T09:00:00+05:30",
+        ],
+    );
+}
+
+#[test]
+fn handles_dst_gaps_with_the_configured_policy() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Berlin:20210327T020000\n\
+        RRULE:FREQ=DAILY;COUNT=4;BYMINUTE=0,30"
+        .parse()
+        .unwrap();
+
+    check_occurrences(
+        &rrule_set.clone().all(10).dates,
+        &[
+            "2021-03-27T02:00:00+01:00",
+            "2021-03-27T02:30:00+01:00",
+            "2021-03-28T03:00:00+02:00",
+            "2021-03-28T03:30:00+02:00",
+        ],
+    );
+    // Both times in the gap are shifted to the transition, which is only returned once.
+    check_occurrences(
+        &rrule_set
+            .clone()
+            .dst_gap(DstGapPolicy::ShiftForward)
+            .all(10)
+            .dates,
+        &[
+            "2021-03-27T02:00:00+01:00",
+            "2021-03-27T02:30:00+01:00",
+            "2021-03-28T03:00:00+02:00",
+            "2021-03-29T02:00:00+02:00",
+        ],
+    );
+    check_occurrences(
+        &rrule_set.dst_gap(DstGapPolicy::Skip).all(10).dates,
+        &[
+            "2021-03-27T02:00:00+01:00",
+            "2021-03-27T02:30:00+01:00",
+            "2021-03-29T02:00:00+02:00",
+            "2021-03-29T02:30:00+02:00",
+        ],
+    );
+}
+
+#[test]
+fn shifts_times_in_dst_gaps_to_the_transition() {
+    // Lord Howe Island moves its clocks forward by 30 minutes, from 02:00 to 02:30.
+    let rrule_set: RRuleSet = "DTSTART;TZID=Australia/Lord_Howe:20211001T021500\n\
+        RRULE:FREQ=MONTHLY;COUNT=2;BYDAY=SU;BYSETPOS=1"
+        .parse()
+        .unwrap();
+
+    check_occurrences(
+        &rrule_set.clone().all(10).dates,
+        &["2021-10-03T02:45:00+11:00", "2021-11-07T02:15:00+11:00"],
+    );
+    check_occurrences(
+        &rrule_set
+            .clone()
+            .dst_gap(DstGapPolicy::ShiftForward)
+            .all(10)
+            .dates,
+        &["2021-10-03T02:30:00+11:00", "2021-11-07T02:15:00+11:00"],
+    );
+    check_occurrences(
+        &rrule_set.dst_gap(DstGapPolicy::Skip).all(10).dates,
+        &["2021-11-07T02:15:00+11:00", "2021-12-05T02:15:00+11:00"],
+    );
+}
+
+#[test]
+fn handles_ambiguous_times_with_the_configured_policy() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=America/New_York:20211106T010000\n\
+        RRULE:FREQ=DAILY;UNTIL=20211107T120000Z;BYMINUTE=0,30"
+        .parse()
+        .unwrap();
+
+    check_occurrences(
+        &rrule_set.clone().all(10).dates,
+        &[
+            "2021-11-06T01:00:00-04:00",
+            "2021-11-06T01:30:00-04:00",
+            "2021-11-07T01:00:00-04:00",
+            "2021-11-07T01:30:00-04:00",
+        ],
+    );
+    check_occurrences(
+        &rrule_set
+            .clone()
+            .dst_fold(DstFoldPolicy::Later)
+            .all(10)
+            .dates,
+        &[
+            "2021-11-06T01:00:00-04:00",
+            "2021-11-06T01:30:00-04:00",
+            "2021-11-07T01:00:00-05:00",
+            "2021-11-07T01:30:00-05:00",
+        ],
+    );
+    // The dates of both offsets are returned in order.
+    check_occurrences(
+        &rrule_set.dst_fold(DstFoldPolicy::Both).all(10).dates,
+        &[
+            "2021-11-06T01:00:00-04:00",
+            "2021-11-06T01:30:00-04:00",
+            "2021-11-07T01:00:00-04:00",
+            "2021-11-07T01:30:00-04:00",
+            "2021-11-07T01:00:00-05:00",
+            "2021-11-07T01:30:00-05:00",
+        ],
+    );
+}
+
+#[test]
+fn returns_floating_recurrences_as_wall_clock_times() {
+    let rrule_set: RRuleSet = "DTSTART:20210327T023000\n\
+        RRULE:FREQ=DAILY;UNTIL=20210330T023000\n\
+        RDATE:20210401T090000\n\
+        EXDATE:20210329T023000"
+        .parse()
+        .unwrap();
+    let floating = rrule_set.floating().unwrap();
+    let date = |m: u32, d: u32, h: u32, min: u32| {
+        NaiveDate::from_ymd_opt(2021, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    };
+
+    assert_eq!(
+        floating.all(10),
+        vec![
+            date(3, 27, 2, 30),
+            date(3, 28, 2, 30),
+            date(3, 30, 2, 30),
+            date(4, 1, 9, 0)
+        ]
+    );
+    assert_eq!(floating.iter().count(), 4);
+    assert_eq!(
+        floating.between(date(3, 28, 0, 0), date(3, 30, 2, 30), true),
+        vec![date(3, 28, 2, 30), date(3, 30, 2, 30)]
+    );
+
+    let rrule_set: RRuleSet = "DTSTART:20210327T023000Z\nRRULE:FREQ=DAILY"
+        .parse()
+        .unwrap();
+    assert!(rrule_set.floating().is_none());
+}
+
+#[test]
+fn lenient_mode_interprets_local_until_in_the_start_date_timezone() {
+    let input = "DTSTART;TZID=America/New_York:20210101T090000\n\
+        RRULE:FREQ=DAILY;UNTIL=20210103T090000";
+    assert!(input.parse::<RRuleSet>().is_err());
+
+    let rrule_set = RRuleSet::parse_with_config(input, &ParserConfig::lenient()).unwrap();
+    assert_eq!(
+        rrule_set.get_rrule()[0].get_until(),
+        Some(&Tz::UTC.with_ymd_and_hms(2021, 1, 3, 14, 0, 0).unwrap())
+    );
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &[
+            "2021-01-01T09:00:00-05:00",
+            "2021-01-02T09:00:00-05:00",
+            "2021-01-03T09:00:00-05:00",
+        ],
+    );
+}
+
+#[test]
+fn excludes_holidays_in_the_local_date_of_the_occurrences() {
+    let holidays: BTreeSet<NaiveDate> = [(2020, 12, 25), (2021, 1, 1)]
+        .into_iter()
+        .filter_map(|(year, month, day)| NaiveDate::from_ymd_opt(year, month, day))
+        .collect();
+    let rrule_set: RRuleSet = "DTSTART;TZID=America/New_York:20201224T200000\n\
+        RRULE:FREQ=WEEKLY;COUNT=3\n\
+        RDATE;TZID=America/New_York:20201225T090000"
+        .parse()
+        .unwrap();
+    let rrule_set = rrule_set.exclude_holidays(Arc::new(holidays));
+
+    // 2020-12-31T20:00 in New York is already the 1st of January in UTC.
+    check_occurrences(
+        &rrule_set.iter().collect::<Vec<_>>(),
+        &[
+            "2020-12-24T20:00:00-05:00",
+            "2020-12-31T20:00:00-05:00",
+            "2021-01-07T20:00:00-05:00",
+        ],
+    );
+    assert_eq!(rrule_set.clone(), rrule_set);
+    assert_ne!(rrule_set.clone().exclude_holidays(vec![]), rrule_set);
+}
+
+#[test]
+fn shifts_occurrences_to_the_nearest_business_day() {
+    let holidays = vec![NaiveDate::from_ymd_opt(2021, 5, 10).unwrap()];
+    let rrule_set: RRuleSet = "DTSTART:20210501T090000Z\n\
+        RRULE:FREQ=WEEKLY;BYDAY=SA,SU;COUNT=4\n\
+        RDATE:20210501T080000Z"
+        .parse()
+        .unwrap();
+    let rrule_set = rrule_set.shift_to_business_days(BusinessDayPolicy::Nearest, holidays);
+
+    // The dates are sorted after they are moved, and the Sunday before the holiday
+    // is moved to the Tuesday after it.
+    let expected = [
+        "2021-04-30T08:00:00+00:00",
+        "2021-04-30T09:00:00+00:00",
+        "2021-05-03T09:00:00+00:00",
+        "2021-05-07T09:00:00+00:00",
+        "2021-05-11T09:00:00+00:00",
+    ];
+    check_occurrences(&rrule_set.clone().all(10).dates, &expected);
+
+    let mut iter = rrule_set.iter();
+    assert_eq!(iter.by_ref().take(2).count(), 2);
+    let cursor = iter.cursor().unwrap();
+    check_occurrences(
+        &rrule_set.iter_from_cursor(&cursor).collect::<Vec<_>>(),
+        &expected[2..],
+    );
+}
+
+#[test]
+fn rrules_recur_from_their_own_start_date() {
+    let daily: RRule<Unvalidated> = "FREQ=DAILY;COUNT=2".parse().unwrap();
+    let weekly: RRule<Unvalidated> = "FREQ=WEEKLY;COUNT=2".parse().unwrap();
+    let berlin = Tz::Europe__Berlin;
+    let rrule_set = RRuleSet::new(ymd_hms(2021, 1, 1, 9, 0, 0))
+        .rrule(daily.validate(ymd_hms(2021, 1, 1, 9, 0, 0)).unwrap())
+        .rrule(
+            weekly
+                .validate(berlin.with_ymd_and_hms(2021, 1, 1, 14, 0, 0).unwrap())
+                .unwrap(),
+        );
+
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &[
+            "2021-01-01T09:00:00+00:00",
+            "2021-01-01T14:00:00+01:00",
+            "2021-01-02T09:00:00+00:00",
+            "2021-01-08T14:00:00+01:00",
+        ],
+    );
+}
+
+#[test]
+fn edits_the_rules_and_dates_of_a_set() {
+    let mut rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=3\n\
+        RDATE:20210110T090000Z,20210111T090000Z\n\
+        EXDATE:20210102T090000Z"
+        .parse()
+        .unwrap();
+
+    assert!(rrule_set.remove_rdate(&ymd_hms(2021, 1, 10, 9, 0, 0)));
+    assert!(!rrule_set.remove_rdate(&ymd_hms(2021, 1, 10, 9, 0, 0)));
+    rrule_set.clear_exdates();
+    let weekly: RRule<Unvalidated> = "FREQ=WEEKLY;COUNT=2".parse().unwrap();
+    let daily = rrule_set
+        .replace_rrule(0, weekly.validate(ymd_hms(2021, 1, 1, 9, 0, 0)).unwrap())
+        .unwrap();
+    assert_eq!(daily.get_freq(), Frequency::Daily);
+
+    assert!(rrule_set.get_exdate().is_empty());
+    check_occurrences(
+        &rrule_set.clone().all(10).dates,
+        &[
+            "2021-01-01T09:00:00+00:00",
+            "2021-01-08T09:00:00+00:00",
+            "2021-01-11T09:00:00+00:00",
+        ],
+    );
+
+    assert!(rrule_set.remove_rrule(0).is_some());
+    rrule_set.clear_rdates();
+    assert!(rrule_set.all(10).dates.is_empty());
+}
+
+#[test]
+fn combines_sets_by_the_instant_of_their_occurrences() {
+    let hourly: RRuleSet = "DTSTART:20210101T080000Z\n\
+        RRULE:FREQ=HOURLY;COUNT=4\n\
+        RDATE:20210101T090000Z"
+        .parse()
+        .unwrap();
+    let booked: RRuleSet = "DTSTART;TZID=Europe/Berlin:20210101T100000\n\
+        RRULE:FREQ=HOURLY;INTERVAL=2;COUNT=3"
+        .parse()
+        .unwrap();
+
+    check_occurrences(
+        &hourly.difference(&booked).collect::<Vec<_>>(),
+        &["2021-01-01T08:00:00+00:00", "2021-01-01T10:00:00+00:00"],
+    );
+    check_occurrences(
+        &hourly.intersection(&booked).collect::<Vec<_>>(),
+        &["2021-01-01T09:00:00+00:00", "2021-01-01T11:00:00+00:00"],
+    );
+    check_occurrences(
+        &hourly.union(&booked).collect::<Vec<_>>(),
+        &[
+            "2021-01-01T08:00:00+00:00",
+            "2021-01-01T09:00:00+00:00",
+            "2021-01-01T10:00:00+00:00",
+            "2021-01-01T11:00:00+00:00",
+            "2021-01-01T14:00:00+01:00",
+        ],
+    );
+}
+
+#[test]
+fn diffs_the_occurrences_of_two_versions_of_a_set() {
+    let old: RRuleSet = "DTSTART;TZID=Europe/Berlin:20210104T100000\n\
+        RRULE:FREQ=WEEKLY;BYDAY=MO,WE"
+        .parse()
+        .unwrap();
+    let after = ymd_hms(2021, 1, 1, 0, 0, 0);
+    let before = ymd_hms(2021, 2, 1, 0, 0, 0);
+
+    // The same instants in another timezone.
+    let moved: RRuleSet = "DTSTART:20210104T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE"
+        .parse()
+        .unwrap();
+    let diff = old.diff(&moved, after, before, true);
+    assert!(diff.added.is_empty() && diff.removed.is_empty() && !diff.limited);
+
+    let new: RRuleSet = "DTSTART;TZID=Europe/Berlin:20210104T100000\n\
+        RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20210112T000000Z\n\
+        EXDATE;TZID=Europe/Berlin:20210106T100000"
+        .parse()
+        .unwrap();
+    let diff = old.diff(&new, after, before, true);
+    assert!(diff.added.is_empty());
+    check_occurrences(
+        &diff.removed,
+        &[
+            "2021-01-06T10:00:00+01:00",
+            "2021-01-13T10:00:00+01:00",
+            "2021-01-18T10:00:00+01:00",
+            "2021-01-20T10:00:00+01:00",
+            "2021-01-25T10:00:00+01:00",
+            "2021-01-27T10:00:00+01:00",
+        ],
+    );
+}
+
+#[test]
+fn attributes_occurrences_to_their_source() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=3\n\
+        RRULE:FREQ=DAILY;COUNT=2;BYHOUR=12\n\
+        RDATE:20210104T090000Z,20210102T080000Z\n\
+        EXDATE:20210102T090000Z"
+        .parse()
+        .unwrap();
+    let holidays = vec![NaiveDate::from_ymd_opt(2021, 1, 3).unwrap()];
+    let rrule_set = rrule_set.exclude_holidays(holidays);
+
+    let occurrences: Vec<_> = rrule_set
+        .iter_with_sources()
+        .with_exclusions()
+        .map(|occurrence| {
+            (
+                occurrence.date.to_rfc3339(),
+                occurrence.source,
+                occurrence.excluded_by,
+            )
+        })
+        .collect();
+    let expected = [
+        (
+            "2021-01-01T09:00:00+00:00",
+            OccurrenceSource::RRule(0),
+            None,
+        ),
+        (
+            "2021-01-01T12:00:00+00:00",
+            OccurrenceSource::RRule(1),
+            None,
+        ),
+        (
+            "2021-01-02T08:00:00+00:00",
+            OccurrenceSource::RDate(1),
+            None,
+        ),
+        (
+            "2021-01-02T09:00:00+00:00",
+            OccurrenceSource::RRule(0),
+            Some(Exclusion::ExDate),
+        ),
+        (
+            "2021-01-02T12:00:00+00:00",
+            OccurrenceSource::RRule(1),
+            None,
+        ),
+        (
+            "2021-01-03T09:00:00+00:00",
+            OccurrenceSource::RRule(0),
+            Some(Exclusion::Holiday),
+        ),
+        (
+            "2021-01-04T09:00:00+00:00",
+            OccurrenceSource::RDate(0),
+            None,
+        ),
+    ]
+    .map(|(date, source, excluded_by)| (date.to_string(), source, excluded_by));
+    assert_eq!(occurrences, expected);
+
+    assert_eq!(
+        rrule_set
+            .iter_with_sources()
+            .map(|occurrence| occurrence.date)
+            .collect::<Vec<_>>(),
+        rrule_set.iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn merges_occurrences_moved_to_the_same_business_day() {
+    let rrule_set: RRuleSet = "DTSTART:20210501T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=SA,SU;COUNT=4"
+        .parse()
+        .unwrap();
+    let no_holidays: Vec<NaiveDate> = vec![];
+    let rrule_set = rrule_set.shift_to_business_days(BusinessDayPolicy::Next, no_holidays);
+
+    let mondays = ["2021-05-03T09:00:00+00:00", "2021-05-10T09:00:00+00:00"];
+    check_occurrences(&rrule_set.clone().all(10).dates, &mondays);
+    let excluded: Vec<_> = rrule_set
+        .iter_with_sources()
+        .with_exclusions()
+        .map(|occurrence| occurrence.excluded_by)
+        .collect();
+    assert_eq!(
+        excluded,
+        [
+            None,
+            Some(Exclusion::Duplicate),
+            None,
+            Some(Exclusion::Duplicate)
+        ]
+    );
+
+    let rrule_set = rrule_set.duplicates(DuplicatePolicy::Keep);
+    check_occurrences(
+        &rrule_set.all(10).dates,
+        &[mondays[0], mondays[0], mondays[1], mondays[1]],
+    );
+}
+
+#[test]
+fn expand_many_returns_the_recurrences_of_each_set() {
+    let sets: Vec<RRuleSet> = [
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY",
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nRDATE:20210202T120000Z,20210301T090000Z",
+        "DTSTART;TZID=Europe/Paris:20210101T090000\nRRULE:FREQ=DAILY",
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=WEEKLY;BYDAY=SA,SU\nEXDATE:20210206T090000Z",
+    ]
+    .iter()
+    .map(|set| set.parse().unwrap())
+    .collect();
+    let no_holidays: Vec<NaiveDate> = vec![];
+    let shifted = sets[3]
+        .clone()
+        .shift_to_business_days(BusinessDayPolicy::Previous, no_holidays);
+    let sets = [sets, vec![shifted]].concat();
+
+    let start = ymd_hms(2021, 2, 1, 9, 0, 0);
+    let end = ymd_hms(2021, 2, 8, 9, 0, 0);
+    let results = RRuleSet::expand_many(&sets, start..end);
+
+    assert_eq!(results.len(), sets.len());
+    for (set, result) in sets.iter().zip(&results) {
+        let mut expected = set.between(start, end, true);
+        expected.dates.retain(|date| *date < end);
+        assert_eq!(*result, expected);
+    }
+    check_occurrences(
+        &results[1].dates[1..4],
+        &[
+            "2021-02-02T09:00:00+00:00",
+            "2021-02-02T12:00:00+00:00",
+            "2021-02-03T09:00:00+00:00",
+        ][..],
+    );
+    // The same rule in another timezone isn't shared.
+    assert_eq!(
+        results[2].dates[0].to_rfc3339(),
+        "2021-02-02T09:00:00+01:00"
+    );
+}
+
+#[test]
+fn replaces_overridden_recurrences() {
+    let input = "DTSTART;TZID=Europe/Paris:20210104T090000\n\
+        RRULE:FREQ=DAILY;COUNT=4\n\
+        EXDATE;TZID=Europe/Paris:20210110T090000\n\
+        BEGIN:VEVENT\n\
+        UID:standup\n\
+        RECURRENCE-ID;TZID=Europe/Paris:20210105T090000\n\
+        DTSTART;TZID=Europe/Paris:20210106T090000\n\
+        SUMMARY:Moved to Wednesday\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        RECURRENCE-ID;TZID=Europe/Paris:20210106T090000\n\
+        DTSTART;TZID=Europe/Paris:20210110T090000\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        RECURRENCE-ID;TZID=Europe/Paris:20210107T090000\n\
+        STATUS:CANCELLED\n\
+        END:VEVENT";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+
+    // The moved recurrences aren't excluded by the overrides or the exdates.
+    let expected = [
+        "2021-01-04T09:00:00+01:00",
+        "2021-01-06T09:00:00+01:00",
+        "2021-01-10T09:00:00+01:00",
+    ];
+    check_occurrences(&rrule_set.clone().all(10).dates, &expected);
+    let after = ymd_hms(2021, 1, 1, 0, 0, 0);
+    let before = ymd_hms(2021, 2, 1, 0, 0, 0);
+    check_occurrences(&rrule_set.between(after, before, true).dates, &expected);
+    assert_eq!(
+        RRuleSet::expand_many(std::slice::from_ref(&rrule_set), after..before)[0].dates,
+        rrule_set.between(after, before, true).dates
+    );
+
+    let occurrences: Vec<_> = rrule_set
+        .iter_with_sources()
+        .with_exclusions()
+        .map(|occurrence| (occurrence.source, occurrence.excluded_by))
+        .collect();
+    assert_eq!(
+        occurrences,
+        [
+            (OccurrenceSource::RRule(0), None),
+            (OccurrenceSource::RRule(0), Some(Exclusion::Overridden)),
+            (OccurrenceSource::Override(0), None),
+            (OccurrenceSource::RRule(0), Some(Exclusion::Overridden)),
+            (OccurrenceSource::RRule(0), Some(Exclusion::Overridden)),
+            (OccurrenceSource::Override(1), None),
+        ]
+    );
+
+    let overrides = rrule_set.get_overrides();
+    assert_eq!(overrides.len(), 3);
+    assert!(overrides[2].is_cancelled());
+    assert_eq!(
+        rrule_set.to_string().parse::<RRuleSet>().unwrap(),
+        rrule_set
+    );
+}
+
+#[test]
+fn replaces_an_earlier_override_of_the_same_recurrence() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3"
+        .parse()
+        .unwrap();
+    let second = ymd_hms(2021, 1, 2, 9, 0, 0);
+    let mut rrule_set = rrule_set
+        .override_recurrence(RecurrenceOverride::moved(
+            second,
+            ymd_hms(2021, 1, 5, 9, 0, 0),
+        ))
+        .override_recurrence(RecurrenceOverride::cancelled(second));
+    assert_eq!(
+        rrule_set.get_overrides(),
+        [RecurrenceOverride::cancelled(second)]
+    );
+    assert_eq!(rrule_set.clone().all(10).dates.len(), 2);
+
+    assert_eq!(
+        rrule_set.remove_override(&second),
+        Some(RecurrenceOverride::cancelled(second))
+    );
+    assert_eq!(rrule_set.remove_override(&second), None);
+    assert_eq!(rrule_set.all(10).dates.len(), 3);
+}
+
+#[test]
+fn rejects_invalid_overrides() {
+    let tests = [
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nRECURRENCE-ID:20210102T090000Z",
+            ParseError::MisplacedRecurrenceId,
+        ),
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\n\
+             BEGIN:VEVENT\nDTSTART:20210102T100000Z\nEND:VEVENT",
+            ParseError::MissingProperty("RECURRENCE-ID".into()),
+        ),
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nBEGIN:VEVENT\n\
+             RECURRENCE-ID:20210102T090000Z\nRECURRENCE-ID:20210103T090000Z\nEND:VEVENT",
+            ParseError::DuplicateProperty("RECURRENCE-ID".into()),
+        ),
+    ];
+    for (input, expected) in tests {
+        let Err(RRuleError::ParserError(error)) = input.parse::<RRuleSet>() else {
+            unreachable!("expected a parse error");
+        };
+        assert_eq!(error.inner(), &expected);
+    }
+}
+
+#[test]
+fn date_valued_exdates_and_rdates_are_whole_days() {
+    let input = "DTSTART;TZID=America/New_York:20211224T090000\n\
+        RRULE:FREQ=DAILY;BYHOUR=9,22;COUNT=6\n\
+        RDATE;VALUE=DATE:20211231\n\
+        EXDATE;VALUE=DATE:20211225";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert_eq!(
+        rrule_set.get_exdate_days(),
+        [NaiveDate::from_ymd_opt(2021, 12, 25).unwrap()]
+    );
+    // 2021-12-25T22:00 in New York is on the 26th in UTC, but on the excluded day in the
+    // timezone of the recurrence.
+    let expected = [
+        "2021-12-24T09:00:00-05:00",
+        "2021-12-24T22:00:00-05:00",
+        "2021-12-26T09:00:00-05:00",
+        "2021-12-26T22:00:00-05:00",
+        "2021-12-31T09:00:00-05:00",
+    ];
+    check_occurrences(&rrule_set.clone().all(10).dates, &expected);
+
+    let window = ymd_hms(2021, 12, 1, 0, 0, 0)..ymd_hms(2022, 1, 1, 0, 0, 0);
+    let expanded = RRuleSet::expand_many(std::slice::from_ref(&rrule_set), window);
+    check_occurrences(&expanded[0].dates, &expected);
+
+    let excluded = rrule_set
+        .iter_with_sources()
+        .with_exclusions()
+        .filter(|occurrence| occurrence.excluded_by == Some(Exclusion::ExDate))
+        .count();
+    assert_eq!(excluded, 2);
+
+    let lines = rrule_set.to_string();
+    assert!(lines.ends_with("EXDATE;VALUE=DATE:20211225"));
+    assert_eq!(lines.parse::<RRuleSet>().unwrap(), rrule_set);
+}
+
+#[test]
+fn rdate_periods_have_their_own_duration() {
+    let input = "DTSTART:19970101T090000Z\n\
+        DURATION:PT1H\n\
+        RRULE:FREQ=WEEKLY;COUNT=2\n\
+        RDATE;VALUE=PERIOD:19970101T180000Z/19970102T070000Z,19970104T180000Z/PT5H30M";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert_eq!(
+        rrule_set.get_rdate_periods(),
+        [
+            (
+                ymd_hms(1997, 1, 1, 18, 0, 0),
+                EventDuration::new(0, 13 * 3600)
+            ),
+            (
+                ymd_hms(1997, 1, 4, 18, 0, 0),
+                EventDuration::new(0, 5 * 3600 + 1800)
+            ),
+        ]
+    );
+    assert_eq!(
+        rrule_set.iter_intervals().collect::<Vec<_>>(),
+        [
+            (ymd_hms(1997, 1, 1, 9, 0, 0), ymd_hms(1997, 1, 1, 10, 0, 0)),
+            (ymd_hms(1997, 1, 1, 18, 0, 0), ymd_hms(1997, 1, 2, 7, 0, 0)),
+            (
+                ymd_hms(1997, 1, 4, 18, 0, 0),
+                ymd_hms(1997, 1, 4, 23, 30, 0)
+            ),
+            (ymd_hms(1997, 1, 8, 9, 0, 0), ymd_hms(1997, 1, 8, 10, 0, 0)),
+        ]
+    );
+
+    let lines = rrule_set.to_string();
+    assert!(lines.contains("RDATE;VALUE=PERIOD:19970101T180000Z/PT13H\n"));
+    assert_eq!(lines.parse::<RRuleSet>().unwrap(), rrule_set);
+
+    let mut rrule_set = rrule_set;
+    assert!(rrule_set.remove_rdate(&ymd_hms(1997, 1, 1, 18, 0, 0)));
+    assert_eq!(rrule_set.get_rdate_periods().len(), 1);
+}
+
+#[test]
+fn rejects_invalid_rdate_periods() {
+    let tests = [
+        "19970101T180000Z",
+        "19970101T180000Z/",
+        "19970101T180000Z/19961231T180000Z",
+        "19970101T180000Z/-PT1H",
+        "19970101T180000Z/PT1X",
+    ];
+    for period in tests {
+        let input = format!("DTSTART:19970101T090000Z\nRDATE;VALUE=PERIOD:{}", period);
+        let Err(RRuleError::ParserError(error)) = input.parse::<RRuleSet>() else {
+            unreachable!("expected a parse error");
+        };
+        assert_eq!(error.inner(), &ParseError::InvalidPeriod(period.into()));
+    }
+}
+
+#[test]
+fn builds_rruleset_from_vevent() {
+    let input = "BEGIN:VCALENDAR\n\
+        VERSION:2.0\n\
+        PRODID:-//Example Corp.//CalDAV Client//EN\n\
+        BEGIN:VEVENT\n\
+        UID:20210104T090000-standup@example.com\n\
+        DTSTAMP:20201220T120000Z\n\
+        SUMMARY:Standup\n\
+        DTSTART;TZID=Europe/Paris:20210104T090000\n\
+        DTEND;TZID=Europe/Paris:20210104T091500\n\
+        RRULE:FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;COUNT=5\n\
+        EXDATE;TZID=Europe/Paris:20210106T090000\n\
+        BEGIN:VALARM\n\
+        ACTION:DISPLAY\n\
+        TRIGGER:-PT5M\n\
+        DURATION:PT1M\n\
+        END:VALARM\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        UID:20210104T090000-standup@example.com\n\
+        RECURRENCE-ID;TZID=Europe/Paris:20210107T090000\n\
+        DTSTART;TZID=Europe/Paris:20210107T100000\n\
+        END:VEVENT\n\
+        END:VCALENDAR";
+    let rrule_set = build_rruleset_from_vevent(input).unwrap();
+
+    assert_eq!(rrule_set.get_duration(), Some(EventDuration::new(0, 900)));
+    check_occurrences(
+        &rrule_set.clone().all(10).dates,
+        &[
+            "2021-01-04T09:00:00+01:00",
+            "2021-01-05T09:00:00+01:00",
+            "2021-01-07T10:00:00+01:00",
+            "2021-01-08T09:00:00+01:00",
+        ],
+    );
+    assert_eq!(
+        rrule_set.to_string(),
+        "DTSTART;TZID=Europe/Paris:20210104T090000\n\
+         DURATION:PT15M\n\
+         RRULE:FREQ=DAILY;COUNT=5;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=MO,TU,WE,TH,FR\n\
+         EXDATE;TZID=Europe/Paris:20210106T090000\n\
+         BEGIN:VEVENT\n\
+         RECURRENCE-ID;TZID=Europe/Paris:20210107T090000\n\
+         DTSTART;TZID=Europe/Paris:20210107T100000\n\
+         END:VEVENT"
+    );
+    assert_eq!(
+        rrule_set.to_string().parse::<RRuleSet>().unwrap(),
+        rrule_set
+    );
+}
+
+#[test]
+fn all_day_events_last_whole_days() {
+    let rrule_set = build_rruleset_from_vevent(
+        "BEGIN:VEVENT\n\
+         DTSTART;VALUE=DATE:20210301\n\
+         DTEND;VALUE=DATE:20210303\n\
+         RRULE:FREQ=YEARLY\n\
+         END:VEVENT",
+    )
+    .unwrap();
+    assert_eq!(rrule_set.get_duration(), Some(EventDuration::new(2, 0)));
+
+    let rrule_set: RRuleSet = "DTSTART:20210301T090000Z\nDURATION:P1W\nRRULE:FREQ=YEARLY"
+        .parse()
+        .unwrap();
+    assert_eq!(rrule_set.get_duration(), Some(EventDuration::new(7, 0)));
+}
+
+#[test]
+fn all_day_recurrences_are_dates() {
+    let input = "DTSTART;TZID=Europe/Paris;VALUE=DATE:20240130\n\
+        RRULE:FREQ=MONTHLY;COUNT=3;BYMONTHDAY=-1\n\
+        RDATE;TZID=Europe/Paris;VALUE=DATE:20240315\n\
+        EXDATE;TZID=Europe/Paris;VALUE=DATE:20240229";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert!(rrule_set.is_all_day());
+    // The dates are written as floating dates, without a `TZID`.
+    let lines = rrule_set.to_string();
+    assert_eq!(
+        lines,
+        "DTSTART;VALUE=DATE:20240130\n\
+        RRULE:FREQ=MONTHLY;COUNT=3;BYMONTHDAY=-1;BYHOUR=0;BYMINUTE=0;BYSECOND=0\n\
+        RDATE;VALUE=DATE:20240315\n\
+        EXDATE;VALUE=DATE:20240229"
+    );
+
+    let date = |y, m, d| EventDate::Date(NaiveDate::from_ymd_opt(y, m, d).unwrap());
+    let dates = rrule_set.iter_event_dates().collect::<Vec<_>>();
+    assert_eq!(
+        dates,
+        [date(2024, 1, 31), date(2024, 3, 15), date(2024, 3, 31)]
+    );
+    let parsed: RRuleSet = lines.parse().unwrap();
+    assert!(parsed.is_all_day());
+    assert_eq!(parsed.iter_event_dates().collect::<Vec<_>>(), dates);
+    assert_eq!(rrule_set.clone().all_event_dates(2), dates[..2]);
+
+    // Without a duration, an all-day recurrence lasts a day, which is 23 hours long on
+    // the daylight saving time transition of 2024-03-31 in Paris.
+    let lengths = rrule_set
+        .iter_intervals()
+        .map(|(start, end)| (end - start).num_hours())
+        .collect::<Vec<_>>();
+    assert_eq!(lengths, [24, 24, 23]);
+
+    let rrule_set: RRuleSet = "DTSTART:20240101T090000Z\nRRULE:FREQ=DAILY;COUNT=1"
+        .parse()
+        .unwrap();
+    assert!(!rrule_set.is_all_day());
+    assert_eq!(
+        rrule_set.all_event_dates(10),
+        [EventDate::DateTime(ymd_hms(2024, 1, 1, 9, 0, 0))]
+    );
+}
+
+#[test]
+fn all_day_sets_keep_the_instants_of_exdates_and_rdates() {
+    let dt_start = ymd_hms(2021, 1, 1, 0, 0, 0);
+    let rrule_set = RRuleSet::new(dt_start)
+        .all_day(true)
+        .rrule(
+            RRule::new(Frequency::Daily)
+                .count(5)
+                .validate(dt_start)
+                .unwrap(),
+        )
+        .rdate(ymd_hms(2021, 2, 1, 0, 0, 0))
+        .rdate(ymd_hms(2021, 2, 2, 12, 0, 0))
+        .exdate(ymd_hms(2021, 1, 2, 0, 0, 0));
+
+    let output = rrule_set.to_string();
+    assert_eq!(
+        output,
+        "DTSTART;VALUE=DATE:20210101\n\
+        RRULE:FREQ=DAILY;COUNT=5;BYHOUR=0;BYMINUTE=0;BYSECOND=0\n\
+        RDATE;VALUE=DATE:20210201\n\
+        RDATE:20210202T120000Z\n\
+        EXDATE:20210102T000000Z"
+    );
+    assert!(!output.contains("TZID"));
+
+    // The exdate is still an instant, and not a whole day.
+    let parsed: RRuleSet = output.parse().unwrap();
+    assert_eq!(parsed.get_exdate(), [ymd_hms(2021, 1, 2, 0, 0, 0)]);
+    assert!(parsed.get_exdate_days().is_empty());
+}
+
+#[test]
+fn all_day_overrides_and_exdate_days_are_written_without_tzid() {
+    let input = "DTSTART;TZID=Europe/Paris;VALUE=DATE:20240101\n\
+        RRULE:FREQ=DAILY;COUNT=5\n\
+        EXDATE;TZID=Europe/Paris;VALUE=DATE:20240102\n\
+        BEGIN:VEVENT\n\
+        RECURRENCE-ID;TZID=Europe/Paris;VALUE=DATE:20240103\n\
+        DTSTART;TZID=Europe/Paris;VALUE=DATE:20240110\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        RECURRENCE-ID;TZID=Europe/Paris;VALUE=DATE:20240104\n\
+        STATUS:CANCELLED\n\
+        END:VEVENT";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+
+    let output = rrule_set.to_string();
+    assert_eq!(
+        output,
+        "DTSTART;VALUE=DATE:20240101\n\
+        RRULE:FREQ=DAILY;COUNT=5;BYHOUR=0;BYMINUTE=0;BYSECOND=0\n\
+        EXDATE;VALUE=DATE:20240102\n\
+        BEGIN:VEVENT\n\
+        RECURRENCE-ID;VALUE=DATE:20240103\n\
+        DTSTART;VALUE=DATE:20240110\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        RECURRENCE-ID;VALUE=DATE:20240104\n\
+        STATUS:CANCELLED\n\
+        END:VEVENT"
+    );
+
+    let date = |d| EventDate::Date(NaiveDate::from_ymd_opt(2024, 1, d).unwrap());
+    let dates = rrule_set.iter_event_dates().collect::<Vec<_>>();
+    assert_eq!(dates, [date(1), date(5), date(10)]);
+    let parsed: RRuleSet = output.parse().unwrap();
+    assert_eq!(parsed.get_exdate_days(), rrule_set.get_exdate_days());
+    assert_eq!(parsed.iter_event_dates().collect::<Vec<_>>(), dates);
+}
+
+#[test]
+fn intervals_keep_the_local_time_across_dst_transitions() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Paris:20210326T023000\nRRULE:FREQ=DAILY;COUNT=3"
+        .parse()
+        .unwrap();
+    let ends = |duration: Option<EventDuration>| {
+        let rrule_set = match duration {
+            Some(duration) => rrule_set.clone().duration(duration),
+            None => rrule_set.clone(),
+        };
+        let intervals = rrule_set.iter_intervals().collect::<Vec<_>>();
+        assert_eq!(intervals, rrule_set.all_intervals(10));
+        intervals
+            .into_iter()
+            .map(|(_, end)| end.with_timezone(&Tz::UTC))
+            .collect::<Vec<_>>()
+    };
+
+    // The clocks go forward from 02:00 to 03:00 on 2021-03-28, so 02:30 doesn't exist
+    // on that day and is interpreted with the offset before the gap, which is 03:30 in
+    // summer time.
+    assert_eq!(
+        ends(Some(EventDuration::new(1, 0))),
+        [
+            ymd_hms(2021, 3, 27, 1, 30, 0),
+            ymd_hms(2021, 3, 28, 1, 30, 0),
+            ymd_hms(2021, 3, 29, 1, 30, 0),
+        ]
+    );
+    assert_eq!(
+        ends(Some(EventDuration::new(1, 3600))),
+        [
+            ymd_hms(2021, 3, 27, 2, 30, 0),
+            ymd_hms(2021, 3, 28, 2, 30, 0),
+            ymd_hms(2021, 3, 29, 2, 30, 0),
+        ]
+    );
+    assert_eq!(
+        ends(None),
+        [
+            ymd_hms(2021, 3, 26, 1, 30, 0),
+            ymd_hms(2021, 3, 27, 1, 30, 0),
+            ymd_hms(2021, 3, 28, 1, 30, 0),
+        ]
+    );
+}
+
+#[test]
+fn intervals_of_days_are_shorter_on_dst_transitions() {
+    let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Paris:20210327T090000\n\
+        DURATION:P1D\n\
+        RRULE:FREQ=DAILY;COUNT=2\n\
+        BEGIN:VEVENT\n\
+        RECURRENCE-ID;TZID=Europe/Paris:20210328T090000\n\
+        DTSTART;TZID=Europe/Paris:20210328T140000\n\
+        END:VEVENT"
+        .parse()
+        .unwrap();
+
+    let lengths = rrule_set
+        .iter_intervals()
+        .map(|(start, end)| (end - start).num_hours())
+        .collect::<Vec<_>>();
+    // The day of 2021-03-28 is 23 hours long in Paris, and the moved recurrence lasts as
+    // long as the others.
+    assert_eq!(lengths, [23, 24]);
+}
+
+#[test]
+fn rejects_invalid_vevents() {
+    let tests = [
+        (
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY",
+            ParseError::ExpectedSingleVEvent,
+        ),
+        (
+            "BEGIN:VEVENT\nDTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nEND:VEVENT\n\
+             BEGIN:VEVENT\nDTSTART:20210101T090000Z\nRRULE:FREQ=WEEKLY\nEND:VEVENT",
+            ParseError::ExpectedSingleVEvent,
+        ),
+        (
+            "BEGIN:VEVENT\nDTSTART:20210101T090000Z\nDTEND:20210101T100000Z\n\
+             DURATION:PT1H\nRRULE:FREQ=DAILY\nEND:VEVENT",
+            ParseError::ConflictingDuration,
+        ),
+        (
+            "BEGIN:VEVENT\nDTSTART:20210101T090000Z\nDTEND:20210101T080000Z\n\
+             RRULE:FREQ=DAILY\nEND:VEVENT",
+            ParseError::DtEndBeforeDtStart,
+        ),
+        (
+            "BEGIN:VEVENT\nDTSTART:20210101T090000Z\nDURATION:-PT1H\n\
+             RRULE:FREQ=DAILY\nEND:VEVENT",
+            ParseError::InvalidDuration("-PT1H".into()),
+        ),
+    ];
+    for (input, expected) in tests {
+        let Err(RRuleError::ParserError(error)) = build_rruleset_from_vevent(input) else {
+            unreachable!("expected a parse error");
+        };
+        assert_eq!(error.inner(), &expected);
+    }
+}
+
+#[test]
+fn parses_the_recurring_events_of_a_calendar() {
+    let input = "BEGIN:VCALENDAR\n\
+        VERSION:2.0\n\
+        BEGIN:VEVENT\n\
+        UID:standup@example.com\n\
+        SUMMARY:Stand-up\n\
+        DTSTART;TZID=Europe/Paris:20210104T090000\n\
+        DURATION:PT15M\n\
+        RRULE:FREQ=DAILY;COUNT=3\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        UID:review@example.com\n\
+        SUMMARY:Review\\, planning\\; retro\n\
+        DTSTART:20210105T140000Z\n\
+        RRULE:FREQ=WEEKLY;COUNT=2\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        UID:standup@example.com\n\
+        RECURRENCE-ID;TZID=Europe/Paris:20210105T090000\n\
+        STATUS:CANCELLED\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        UID:lunch@example.com\n\
+        DTSTART:20210104T120000Z\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        UID:missing@example.com\n\
+        RECURRENCE-ID:20210104T120000Z\n\
+        DTSTART:20210104T130000Z\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        DTSTART:20210106T080000Z\n\
+        RDATE:20210107T080000Z\n\
+        END:VEVENT\n\
+        END:VCALENDAR";
+    let events = parse_calendar(input).unwrap();
+
+    let metadata: Vec<_> = events
+        .iter()
+        .map(|event| (event.get_uid(), event.get_summary()))
+        .collect();
+    assert_eq!(
+        metadata,
+        [
+            (Some("standup@example.com"), Some("Stand-up")),
+            (Some("review@example.com"), Some("Review, planning; retro")),
+            (None, None),
+        ]
+    );
+    let standup = events[0].get_rruleset();
+    assert_eq!(standup.get_duration(), Some(EventDuration::new(0, 900)));
+    check_occurrences(
+        &standup.clone().all(10).dates,
+        &["2021-01-04T09:00:00+01:00", "2021-01-06T09:00:00+01:00"],
+    );
+    check_occurrences(
+        &events[2].clone().into_rruleset().all(10).dates,
+        &["2021-01-07T08:00:00+00:00"],
+    );
+}
+
+#[test]
+fn rejects_calendars_with_duplicate_uids() {
+    let input = "BEGIN:VCALENDAR\n\
+        BEGIN:VEVENT\n\
+        UID:standup@example.com\n\
+        DTSTART:20210104T090000Z\n\
+        RRULE:FREQ=DAILY\n\
+        END:VEVENT\n\
+        BEGIN:VEVENT\n\
+        UID:standup@example.com\n\
+        DTSTART:20210104T100000Z\n\
+        RRULE:FREQ=DAILY\n\
+        END:VEVENT\n\
+        END:VCALENDAR";
+    let Err(RRuleError::ParserError(error)) = parse_calendar(input) else {
+        unreachable!("expected a parse error");
+    };
+    assert_eq!(
+        error.inner(),
+        &ParseError::DuplicateUid("standup@example.com".into())
+    );
+    assert_eq!(&input[error.span().unwrap()], "standup@example.com");
+}
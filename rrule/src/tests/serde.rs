@@ -1,4 +1,8 @@
 use crate::RRuleSet;
+#[cfg(feature = "serde")]
+use crate::{IterState, RRule, Tz, Unvalidated};
+#[cfg(feature = "serde")]
+use chrono::TimeZone;
 use std::str::FromStr;
 
 #[test]
@@ -43,3 +47,261 @@ fn serialize_deserialize_json_to_and_from_rrule_set() {
         assert_eq!(src_obj, final_obj);
     }
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_deserialize_json_iter_state() {
+    let rrule_set =
+        RRuleSet::from_str("DTSTART:20120201T093000Z\nRRULE:FREQ=DAILY;COUNT=5").unwrap();
+    let mut iter = rrule_set.iter();
+    iter.next();
+    iter.next();
+    let state = iter.state().unwrap();
+
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(
+        json,
+        r#"{"last":"2012-02-02T09:30:00Z","emitted":2,"emitted_at_last":1}"#
+    );
+
+    let state = serde_json::from_str::<IterState>(&json).unwrap();
+    let mut iter = rrule_set.iter_from_state(&state);
+    assert_eq!(iter.by_ref().count(), 3);
+    assert_eq!(iter.state().unwrap().emitted, 5);
+
+    // States serialized without the number of recurrences at the last instant.
+    let json = r#"{"last":"2012-02-02T09:30:00Z","emitted":2}"#;
+    let state = serde_json::from_str::<IterState>(json).unwrap();
+    assert_eq!(state.emitted_at_last, 1);
+    assert_eq!(rrule_set.iter_from_state(&state).count(), 3);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_deserialize_binary_rrule_set() {
+    let test_cases = [
+        "DTSTART:20120201T093000Z\nRRULE:FREQ=DAILY;COUNT=5\nRDATE:20120301T093000Z\nEXDATE:20120203T093000Z",
+        "DTSTART:20120201T093000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=1,-1;BYDAY=-2MO,FR",
+        "DTSTART;TZID=America/New_York:19000201T093000\nRRULE:UNTIL=19990404T110000Z;FREQ=WEEKLY;BYDAY=TU,WE\nRDATE;TZID=Europe/Paris:19000301T093000",
+        "DTSTART:20120201T093000Z\nDURATION:P1DT1H\nRRULE:FREQ=DAILY;COUNT=5\nBEGIN:VEVENT\nRECURRENCE-ID:20120202T093000Z\nDTSTART:20120202T120000Z\nEND:VEVENT\nBEGIN:VEVENT\nRECURRENCE-ID:20120203T093000Z\nSTATUS:CANCELLED\nEND:VEVENT",
+        "DTSTART:20120201T093000Z\nRRULE:FREQ=DAILY;COUNT=5\nRDATE:20120210T093000Z\nRDATE;VALUE=PERIOD:20120301T093000Z/PT1H",
+        "DTSTART;TZID=UTC;VALUE=DATE:20120201\nRRULE:FREQ=WEEKLY;COUNT=5\nEXDATE;TZID=UTC;VALUE=DATE:20120208",
+    ];
+
+    for test_str in test_cases {
+        let rrule_set = RRuleSet::from_str(test_str).unwrap();
+
+        let bytes = bincode::serialize(&rrule_set).unwrap();
+        let decoded = bincode::deserialize::<RRuleSet>(&bytes).unwrap();
+
+        assert_eq!(rrule_set, decoded);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn binary_rrule_set_is_compact_and_versioned() {
+    use bincode::Options;
+
+    let rrule_set = RRuleSet::from_str(
+        "DTSTART;TZID=Europe/Paris:20120201T093000\n\
+        RRULE:FREQ=WEEKLY;COUNT=10;BYDAY=MO,-1FR\n\
+        EXDATE;TZID=Europe/Paris:20120203T093000",
+    )
+    .unwrap();
+
+    // Like postcard, these options write integers and lengths as varints.
+    let options = bincode::DefaultOptions::new();
+    let mut bytes = options.serialize(&rrule_set).unwrap();
+    let text_bytes = options.serialize(&rrule_set.to_string()).unwrap();
+    assert!(bytes.len() * 2 < text_bytes.len());
+    assert_eq!(bytes[0], 4);
+
+    // Data written in an unknown format version is rejected.
+    bytes[0] = 3;
+    assert!(options.deserialize::<RRuleSet>(&bytes).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_deserialize_rrule() {
+    let rrule: RRule<Unvalidated> = "FREQ=WEEKLY;INTERVAL=2;UNTIL=20120401T000000Z;BYDAY=MO,FR"
+        .parse()
+        .unwrap();
+
+    let json = serde_json::to_string(&rrule).unwrap();
+    assert_eq!(
+        json,
+        r#""FREQ=WEEKLY;UNTIL=20120401T000000Z;INTERVAL=2;BYDAY=MO,FR""#
+    );
+    assert_eq!(
+        serde_json::from_str::<RRule<Unvalidated>>(&json).unwrap(),
+        rrule
+    );
+
+    let bytes = bincode::serialize(&rrule).unwrap();
+    assert_eq!(
+        bincode::deserialize::<RRule<Unvalidated>>(&bytes).unwrap(),
+        rrule
+    );
+
+    let rrule = rrule
+        .validate(Tz::UTC.with_ymd_and_hms(2012, 2, 1, 9, 30, 0).unwrap())
+        .unwrap();
+    let json = serde_json::to_string(&rrule).unwrap();
+    assert_eq!(serde_json::from_str::<RRule>(&json).unwrap(), rrule);
+    let bytes = bincode::serialize(&rrule).unwrap();
+    assert_eq!(bincode::deserialize::<RRule>(&bytes).unwrap(), rrule);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_deserialize_json_nth_weekdays() {
+    let rrule: RRule<Unvalidated> = "FREQ=YEARLY;COUNT=4;BYDAY=1MO,-1FR".parse().unwrap();
+    let json = serde_json::to_string(&rrule).unwrap();
+    assert_eq!(json, r#""FREQ=YEARLY;COUNT=4;BYDAY=1MO,-1FR""#);
+    assert_eq!(
+        serde_json::from_str::<RRule<Unvalidated>>(&json).unwrap(),
+        rrule
+    );
+
+    let rrule_set =
+        RRuleSet::from_str("DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY;COUNT=4;BYDAY=1MO,-1FR")
+            .unwrap();
+    let json = serde_json::to_string(&rrule_set).unwrap();
+    let decoded = serde_json::from_str::<RRuleSet>(&json).unwrap();
+    assert_eq!(decoded, rrule_set);
+    assert_eq!(decoded.all(4).dates, rrule_set.all(4).dates);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_deserialize_rule_with_its_own_start() {
+    let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    let rule_start = Tz::Europe__Berlin
+        .with_ymd_and_hms(2021, 1, 4, 14, 0, 0)
+        .unwrap();
+    let rrule = "FREQ=WEEKLY;COUNT=3"
+        .parse::<RRule<Unvalidated>>()
+        .unwrap()
+        .validate(rule_start)
+        .unwrap();
+    let rrule_set = RRuleSet::new(dt_start).rrule(rrule);
+    let dates = rrule_set.clone().all(10).dates;
+    assert_eq!(dates.len(), 3);
+    assert_eq!(dates[0], rule_start);
+
+    let bytes = bincode::serialize(&rrule_set).unwrap();
+    let decoded = bincode::deserialize::<RRuleSet>(&bytes).unwrap();
+    assert_eq!(decoded, rrule_set);
+    assert_eq!(decoded.all(10).dates, dates);
+
+    let json = serde_json::to_string(&rrule_set).unwrap();
+    assert_eq!(
+        json,
+        r#""DTSTART:20210101T090000Z\nRRULE;X-DTSTART-TZID=Europe/Berlin;X-DTSTART=20210104T140000:FREQ=WEEKLY;COUNT=3;BYHOUR=14;BYMINUTE=0;BYSECOND=0;BYDAY=MO""#
+    );
+    let decoded = serde_json::from_str::<RRuleSet>(&json).unwrap();
+    assert_eq!(decoded, rrule_set);
+    let decoded_dates = decoded.all(10).dates;
+    assert_eq!(decoded_dates, dates);
+    assert_eq!(decoded_dates[0].timezone().name(), "Europe/Berlin");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn jcal_to_and_from_rrule() {
+    use crate::JCalRecur;
+
+    let rrule: RRule<Unvalidated> =
+        "FREQ=MONTHLY;UNTIL=20120401T000000Z;INTERVAL=2;BYMONTHDAY=1,-1;BYDAY=-1SU;WKST=SU"
+            .parse()
+            .unwrap();
+
+    let json = serde_json::to_string(&JCalRecur::from(&rrule)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"freq":"MONTHLY","until":"2012-04-01T00:00:00Z","interval":2,"byday":"-1SU","bymonthday":[-1,1],"wkst":"SU"}"#
+    );
+    let jcal: JCalRecur = serde_json::from_str(&json).unwrap();
+    assert_eq!(RRule::try_from(jcal).unwrap(), rrule);
+
+    // Single values and arrays are both accepted, and `until` can be a date.
+    let jcal: JCalRecur = serde_json::from_str(
+        r#"{"freq":"YEARLY","until":"2012-04-01","bymonth":[3],"byday":"SU"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        RRule::try_from(jcal).unwrap(),
+        "FREQ=YEARLY;UNTIL=20120401T000000Z;BYMONTH=3;BYDAY=SU"
+            .parse::<RRule<Unvalidated>>()
+            .unwrap()
+    );
+
+    // Leap months are strings.
+    let rrule: RRule<Unvalidated> = "FREQ=YEARLY;BYMONTH=5,5L".parse().unwrap();
+    let json = serde_json::to_string(&JCalRecur::from(&rrule)).unwrap();
+    assert_eq!(json, r#"{"freq":"YEARLY","bymonth":[5,"5L"]}"#);
+    let jcal: JCalRecur = serde_json::from_str(&json).unwrap();
+    assert_eq!(RRule::try_from(jcal).unwrap(), rrule);
+
+    let jcal: JCalRecur = serde_json::from_str(r#"{"freq":"DAILY","wkst":"XX"}"#).unwrap();
+    assert!(RRule::try_from(jcal).is_err());
+    let jcal: JCalRecur = serde_json::from_str(r#"{"freq":"DAILY","bymonth":"5X"}"#).unwrap();
+    assert!(RRule::try_from(jcal).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn rrule_options_to_and_from_rrule() {
+    use crate::RRuleOptions;
+
+    let rrule: RRule = "DTSTART;TZID=Europe/Paris:20210101T090000\n\
+        RRULE:FREQ=MONTHLY;UNTIL=20210601T000000Z;BYMONTHDAY=1,-1;WKST=SU"
+        .parse()
+        .unwrap();
+
+    let options = RRuleOptions::from(&rrule);
+    let json = serde_json::to_value(&options).unwrap();
+    assert_eq!(json["dt_start"], "2021-01-01T09:00:00+01:00");
+    assert_eq!(json["tzid"], "Europe/Paris");
+    assert_eq!(json["freq"], "MONTHLY");
+    assert_eq!(json["until"], "2021-06-01T00:00:00Z");
+    assert_eq!(json["week_start"], "SU");
+    assert_eq!(json["by_month_day"], serde_json::json!([1, -1]));
+
+    let options: RRuleOptions = serde_json::from_value(json).unwrap();
+    assert_eq!(RRule::try_from(options).unwrap(), rrule);
+
+    // Without a `tzid`, the start date is in UTC.
+    let options: RRuleOptions = serde_json::from_str(
+        r#"{"dt_start": "2021-01-01T09:00:00+01:00", "freq": "DAILY", "count": 2}"#,
+    )
+    .unwrap();
+    let rrule = RRule::try_from(options).unwrap();
+    assert_eq!(
+        rrule.dt_start(),
+        Tz::UTC.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap()
+    );
+    assert_eq!(rrule.iter().count(), 2);
+
+    let options: RRuleOptions = serde_json::from_str(
+        r#"{"dt_start": "2021-01-01T09:00:00Z", "tzid": "Nowhere", "freq": "DAILY"}"#,
+    )
+    .unwrap();
+    assert!(RRule::try_from(options).is_err());
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn rrule_options_json_schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(crate::RRuleOptions)).unwrap();
+    let properties = &schema["properties"];
+    assert_eq!(properties["dt_start"]["format"], "date-time");
+    assert_eq!(properties["by_month"]["type"], "array");
+    assert_eq!(schema["required"], serde_json::json!(["dt_start", "freq"]));
+    assert_eq!(
+        schema["definitions"]["Frequency"]["enum"][0],
+        serde_json::json!("YEARLY")
+    );
+}
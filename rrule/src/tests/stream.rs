@@ -0,0 +1,34 @@
+#![cfg(feature = "tokio")]
+
+use crate::core::Tz;
+use crate::{Frequency, RRule, RRuleSet};
+use chrono::{Duration, TimeZone, Timelike, Utc};
+use futures::StreamExt;
+
+#[tokio::test]
+async fn stream_yields_all_recurrences() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=5\n\
+        EXDATE:20210102T090000Z"
+        .parse()
+        .unwrap();
+
+    let dates: Vec<_> = rrule_set.stream().collect().await;
+    assert_eq!(dates, rrule_set.iter().collect::<Vec<_>>());
+}
+
+#[tokio::test(start_paused = true)]
+async fn timer_stream_skips_past_recurrences() {
+    let dt_start = Tz::UTC
+        .from_utc_datetime(&(Utc::now() - Duration::minutes(90)).naive_utc())
+        .with_nanosecond(0)
+        .unwrap();
+    let rrule = RRule::new(Frequency::Hourly)
+        .count(4)
+        .validate(dt_start)
+        .unwrap();
+    let rrule_set = RRuleSet::new(dt_start).rrule(rrule);
+
+    let dates: Vec<_> = rrule_set.timer_stream().collect().await;
+    assert_eq!(dates, rrule_set.iter().skip(2).collect::<Vec<_>>());
+}
@@ -0,0 +1,42 @@
+//! The rules, the sets and their iterators can be shared between threads, e.g. in the
+//! state of a web server.
+use crate::{
+    EventDateIter, IntervalIter, IterCursor, IterState, Occurrence, OccurrenceIter, ParseError,
+    ParserConfig, RRule, RRuleError, RRuleIter, RRuleResult, RRuleRevIter, RRuleSet, RRuleSetIter,
+    RecurringEvent, SetOperationIter, SourceIter, Unvalidated, ValidationError,
+};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn rules_and_sets_are_send_and_sync() {
+    assert_send_sync::<RRule>();
+    assert_send_sync::<RRule<Unvalidated>>();
+    assert_send_sync::<RRuleSet>();
+    assert_send_sync::<RecurringEvent>();
+    assert_send_sync::<ParserConfig>();
+    assert_send_sync::<RRuleResult>();
+    assert_send_sync::<Occurrence>();
+    assert_send_sync::<IterCursor>();
+    assert_send_sync::<IterState>();
+    assert_send_sync::<RRuleError>();
+    assert_send_sync::<ParseError>();
+    assert_send_sync::<ValidationError>();
+}
+
+#[test]
+fn iterators_are_send_and_sync() {
+    assert_send_sync::<RRuleIter<'_>>();
+    assert_send_sync::<RRuleRevIter>();
+    assert_send_sync::<RRuleSetIter<'_>>();
+    assert_send_sync::<OccurrenceIter<RRuleIter<'_>>>();
+    assert_send_sync::<IntervalIter<'_>>();
+    assert_send_sync::<EventDateIter<'_>>();
+    assert_send_sync::<SetOperationIter<'_>>();
+    assert_send_sync::<SourceIter<'_>>();
+    #[cfg(feature = "tokio")]
+    {
+        assert_send_sync::<crate::RRuleSetStream<'_>>();
+        assert_send_sync::<crate::RRuleSetTimerStream<'_>>();
+    }
+}
@@ -2,61 +2,164 @@
 
 use thiserror::Error;
 
-use crate::Frequency;
+use crate::{Frequency, RScale};
 
+/// Errors that can occur while validating an [`crate::RRule`].
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ValidationError {
+    /// `BYSETPOS` was used without any other BYxxx rule part.
     #[error("BYSETPOS should only be used in conjunction with another BYxxx rule part.")]
     BySetPosWithoutByRule,
+    /// A field has a value that is not allowed.
     #[error("`{field}` can't be `{value}`, must be larger or smaller then `{value}`.")]
-    InvalidFieldValue { field: String, value: String },
+    InvalidFieldValue {
+        /// The name of the field.
+        field: String,
+        /// The offending value.
+        value: String,
+    },
+    /// A field has a value outside its allowed range.
     #[error(
         "`{field}` is `{value}`, but is not allowed outside the range: `{start_idx}..={end_idx}`."
     )]
     InvalidFieldValueRange {
+        /// The name of the field.
         field: String,
+        /// The offending value.
         value: String,
+        /// The start of the allowed range.
         start_idx: String,
+        /// The end of the allowed range.
         end_idx: String,
     },
+    /// A field has a value outside the range allowed by the frequency.
     #[error(
         "`{field}` is `{value}`, but with the current frequency ({freq}) is not allowed \
             outside the range: `{start_idx}..={end_idx}`."
     )]
     InvalidFieldValueRangeWithFreq {
+        /// The name of the field.
         field: String,
+        /// The offending value.
         value: String,
+        /// The frequency of the rule.
         freq: Frequency,
+        /// The start of the allowed range.
         start_idx: String,
+        /// The end of the allowed range.
         end_idx: String,
     },
+    /// A BYxxx rule part can't be combined with the frequency.
     #[error("`{by_rule}` can not be used with the current frequency ({freq}).")]
-    InvalidByRuleAndFrequency { by_rule: String, freq: Frequency },
+    InvalidByRuleAndFrequency {
+        /// The name of the BYxxx rule part.
+        by_rule: String,
+        /// The frequency of the rule.
+        freq: Frequency,
+    },
+    /// A rule part isn't supported with the calendar system of the rule, like `BYDAY`
+    /// with `RSCALE=CHINESE`.
+    #[error("`{rule_part}` is not supported with `RSCALE={rscale}`.")]
+    UnsupportedWithRScale {
+        /// The rule part, like `BYDAY` or `FREQ=WEEKLY`.
+        rule_part: String,
+        /// The calendar system of the rule.
+        rscale: RScale,
+    },
+    /// `UNTIL` is before `DTSTART`.
     #[error("`UNTIL` is `{until}`, but `DTSTART` (`{dt_start}`) is later. That should not be happening.")]
-    UntilBeforeStart { until: String, dt_start: String },
+    UntilBeforeStart {
+        /// The `UNTIL` value.
+        until: String,
+        /// The `DTSTART` value.
+        dt_start: String,
+    },
+    /// `INTERVAL` is higher than the validator limits allow.
     #[error(
         "`INTERVAL` is `{0}`, is higher than expected, make sure this is correct. \
             See 'validator limits' in docs for more info."
     )]
     TooBigInterval(u16),
+    /// The year of `DTSTART` is outside the validator limits.
     #[error(
         "`DTSTART` year is `{0}`, is higher/lower than expected, make sure this is correct. \
             See 'validator limits' in docs for more info."
     )]
     StartYearOutOfRange(i32),
+    /// No timeset can be generated from the rule.
     #[error(
         "Unable to generate a timeset for the RRULE. Please specify a BYHOUR, BYMINUTE or BYSECOND"
     )]
     UnableToGenerateTimeset,
-    #[cfg(feature = "by-easter")]
-    #[error("`BYEASTER` can only be used when `BYHOUR`, `BYMINUTE` and `BYSECOND` are set.")]
-    InvalidByRuleWithByEaster,
+    /// The timezone of `UNTIL` doesn't match the timezone of `DTSTART`.
     #[error(
         "The value of `DTSTART` was specified in {dt_start_tz} timezone, but `UNTIL` was specified in timezone {until_tz}. Allowed timezones for `UNTIL` with the given start date timezone are: `{expected:?}`"
     )]
     DtStartUntilMismatchTimezone {
+        /// The timezone of `DTSTART`.
         dt_start_tz: String,
+        /// The timezone of `UNTIL`.
         until_tz: String,
+        /// The timezones allowed for `UNTIL`.
         expected: Vec<String>,
     },
+    /// The rule has more than one problem, they are all listed in the order they were
+    /// found.
+    #[error("The rule has {} problems: {}", .0.len(), join_errors(.0))]
+    Multiple(Vec<Self>),
+}
+
+impl ValidationError {
+    /// Returns every problem the error reports, so that they can be shown one by one.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{Frequency, RRule, RRuleError, Tz};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let err = RRule::default()
+    ///     .freq(Frequency::Daily)
+    ///     .by_hour(vec![24])
+    ///     .by_minute(vec![60])
+    ///     .validate(dt_start)
+    ///     .unwrap_err();
+    /// let RRuleError::ValidationError(err) = err else {
+    ///     panic!("expected a validation error");
+    /// };
+    /// assert_eq!(err.errors().len(), 2);
+    /// ```
+    #[must_use]
+    pub fn errors(&self) -> &[Self] {
+        match self {
+            Self::Multiple(errors) => errors,
+            error => std::slice::from_ref(error),
+        }
+    }
+
+    /// Returns every problem the error reports, see [`ValidationError::errors`].
+    pub(crate) fn into_errors(self) -> Vec<Self> {
+        match self {
+            Self::Multiple(errors) => errors,
+            error => vec![error],
+        }
+    }
+
+    /// Combines the problems found in a rule into a single error, if there is any.
+    pub(crate) fn from_errors(mut errors: Vec<Self>) -> Option<Self> {
+        match errors.len() {
+            0 => None,
+            1 => errors.pop(),
+            _ => Some(Self::Multiple(errors)),
+        }
+    }
+}
+
+fn join_errors(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
 }
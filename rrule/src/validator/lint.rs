@@ -0,0 +1,174 @@
+#![allow(clippy::module_name_repetitions)]
+//! Warnings about rules that are valid, but probably don't do what was meant, see
+//! [`crate::RRule::lint`].
+use thiserror::Error;
+
+use crate::{Frequency, NWeekday, RRule, RScale, Skip};
+
+/// The number of days of the months from January, with the 29th of February of leap
+/// years.
+const MONTH_LENGTHS: [u8; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// The number of days of the long months of the Chinese calendar, which any month can be.
+const CHINESE_MONTH_LENGTH: u8 = 30;
+
+/// A rule that is valid, but probably doesn't do what was meant, see [`crate::RRule::lint`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    /// A month day of `BYMONTHDAY` doesn't exist in any of the months of the rule, like
+    /// the 31st in `BYMONTH=4,6`, so the rule never produces it.
+    #[error("`BYMONTHDAY` `{day}` doesn't exist in any of the months of the rule.")]
+    MonthDayNeverExists {
+        /// The month day, which is negative when counted from the end of the month.
+        day: i8,
+    },
+    /// A month day of `BYMONTHDAY` doesn't exist in some of the months of the rule, like
+    /// the 31st in April, so these months are left out.
+    #[error("`BYMONTHDAY` `{day}` doesn't exist in the months {months:?}, which are left out.")]
+    MonthDayMissingInMonths {
+        /// The month day, which is negative when counted from the end of the month.
+        day: i8,
+        /// The months without the month day, from 1 to 12.
+        months: Vec<u8>,
+    },
+    /// Both `COUNT` and `UNTIL` are given, which RFC 5545 doesn't allow. The rule ends at
+    /// whichever comes first.
+    #[error(
+        "`COUNT` and `UNTIL` should not be used together, the rule ends at whichever comes first."
+    )]
+    CountAndUntil,
+    /// `BYDAY` has a numbered weekday, like `2MO`, with a frequency other than `MONTHLY`
+    /// and `YEARLY`, where RFC 5545 doesn't give it a meaning.
+    #[error(
+        "`BYDAY` has a numbered weekday, which should only be used with `MONTHLY` and `YEARLY` \
+            rules, not with the current frequency ({freq})."
+    )]
+    NumberedWeekdayWithFrequency {
+        /// The numbered weekday.
+        weekday: NWeekday,
+        /// The frequency of the rule.
+        freq: Frequency,
+    },
+    /// The interval is `0`, so the rule never produces any date.
+    #[error("`INTERVAL` is `0`, so the rule never produces any date.")]
+    ZeroInterval,
+    /// A BYxxx rule part that limits the recurrences of the frequency has all of its
+    /// possible values, like the 12 months in `BYMONTH` of a `MONTHLY` rule, so it can be
+    /// left out.
+    #[error("`{by_rule}` has all of its possible values, so it can be left out.")]
+    RedundantByRule {
+        /// The name of the BYxxx rule part.
+        by_rule: String,
+    },
+}
+
+/// Returns the warnings about the rule, in the order of the variants of [`LintWarning`].
+pub(crate) fn lint_rrule<S>(rrule: &RRule<S>) -> Vec<LintWarning> {
+    let mut warnings = lint_month_days(rrule);
+    if rrule.count.is_some() && rrule.until.is_some() {
+        warnings.push(LintWarning::CountAndUntil);
+    }
+    if !matches!(rrule.freq, Frequency::Monthly | Frequency::Yearly) {
+        warnings.extend(rrule.by_weekday.iter().filter_map(|weekday| match weekday {
+            NWeekday::Nth(..) => Some(LintWarning::NumberedWeekdayWithFrequency {
+                weekday: *weekday,
+                freq: rrule.freq,
+            }),
+            NWeekday::Every(_) => None,
+        }));
+    }
+    if rrule.interval == 0 {
+        warnings.push(LintWarning::ZeroInterval);
+    }
+    warnings.extend(redundant_by_rules(rrule).into_iter().map(|by_rule| {
+        LintWarning::RedundantByRule {
+            by_rule: by_rule.into(),
+        }
+    }));
+    warnings
+}
+
+/// Checks that the month days exist in the months of the rule, or in all months if the
+/// rule has no `BYMONTH`. A missing month day is fine when `SKIP` moves it.
+fn lint_month_days<S>(rrule: &RRule<S>) -> Vec<LintWarning> {
+    let months: Vec<u8> = if rrule.by_month.is_empty() {
+        (1..=12).collect()
+    } else {
+        rrule.by_month.to_vec()
+    };
+    let days = rrule.by_month_day.iter().chain(&rrule.by_n_month_day);
+    days.filter_map(|day| {
+        let missing_months: Vec<u8> = months
+            .iter()
+            .copied()
+            .filter(|month| {
+                let month_len = if rrule.rscale == Some(RScale::Chinese) {
+                    Some(CHINESE_MONTH_LENGTH)
+                } else {
+                    usize::from(*month)
+                        .checked_sub(1)
+                        .and_then(|idx| MONTH_LENGTHS.get(idx).copied())
+                };
+                month_len.is_some_and(|month_len| day.unsigned_abs() > month_len)
+            })
+            .collect();
+        if missing_months.len() == months.len() {
+            Some(LintWarning::MonthDayNeverExists { day: *day })
+        } else if !missing_months.is_empty() && rrule.skip == Skip::Omit {
+            Some(LintWarning::MonthDayMissingInMonths {
+                day: *day,
+                months: missing_months,
+            })
+        } else {
+            None
+        }
+    })
+    .collect()
+}
+
+/// Checks for BYxxx rule parts with all of their possible values, which only limit the
+/// recurrences of the frequency, like `BYDAY` in a `DAILY` rule. BYxxx rule parts that
+/// expand them, like `BYDAY` in a `WEEKLY` rule, are not redundant.
+pub(crate) fn redundant_by_rules<S>(rrule: &RRule<S>) -> Vec<&'static str> {
+    let freq = rrule.freq;
+    let has_all = |values: &[u8], count: u8| (0..count).all(|value| values.contains(&value));
+    let weekdays: Vec<_> = rrule
+        .by_weekday
+        .iter()
+        .filter_map(|weekday| match weekday {
+            NWeekday::Every(weekday) => Some(weekday.num_days_from_monday()),
+            NWeekday::Nth(..) => None,
+        })
+        .collect();
+    let has_all_weekdays = freq >= Frequency::Daily
+        && rrule.by_set_pos.is_empty()
+        && (0..7).all(|day| weekdays.contains(&day));
+    // The years of the Chinese calendar can have a leap month on top of the 12 months.
+    let has_all_months = freq != Frequency::Yearly
+        && rrule.rscale != Some(RScale::Chinese)
+        && (1..=12).all(|month| rrule.by_month.contains(&month));
+    let has_all_month_days =
+        freq >= Frequency::Daily && (1..=31).all(|day| rrule.by_month_day.contains(&day));
+    let by_rules = [
+        ("BYMONTH", has_all_months),
+        ("BYMONTHDAY", has_all_month_days),
+        ("BYDAY", has_all_weekdays),
+        (
+            "BYHOUR",
+            freq >= Frequency::Hourly && has_all(&rrule.by_hour, 24),
+        ),
+        (
+            "BYMINUTE",
+            freq >= Frequency::Minutely && has_all(&rrule.by_minute, 60),
+        ),
+        (
+            "BYSECOND",
+            freq >= Frequency::Secondly && has_all(&rrule.by_second, 60),
+        ),
+    ];
+    by_rules
+        .into_iter()
+        .filter(|(_, is_redundant)| *is_redundant)
+        .map(|(by_rule, _)| by_rule)
+        .collect()
+}
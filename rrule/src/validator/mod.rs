@@ -2,7 +2,10 @@
 //! And in turn create a [`crate::core::RRule<Validated>`].
 
 mod error;
+mod lint;
 pub(crate) mod validate_rrule;
 pub use error::ValidationError;
+pub use lint::LintWarning;
+pub(crate) use lint::{lint_rrule, redundant_by_rules};
 
 pub(crate) use validate_rrule::YEAR_RANGE;
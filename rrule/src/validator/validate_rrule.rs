@@ -1,7 +1,7 @@
 use std::ops::RangeInclusive;
 
 use crate::core::DateTime;
-use crate::{Frequency, NWeekday, RRule, Tz, Unvalidated};
+use crate::{Frequency, NWeekday, RRule, RScale, Tz, Unvalidated};
 
 use super::ValidationError;
 
@@ -15,33 +15,55 @@ pub(crate) static YEAR_RANGE: RangeInclusive<i32> = -10_000..=10_000;
 
 type Validator = &'static dyn Fn(&RRule<Unvalidated>, &DateTime) -> Result<(), ValidationError>;
 
-const VALIDATION_PIPELINE: [Validator; 11] = [
+const VALIDATION_PIPELINE: [Validator; 13] = [
     &validate_until,
     &validate_by_set_pos,
     &validate_by_month,
     &validate_by_month_day,
     &validate_by_year_day,
     &validate_by_week_number,
+    &validate_by_rules_with_freq,
     &validate_by_weekday,
     &validate_by_hour,
     &validate_by_minute,
     &validate_by_second,
     &validate_by_easter,
+    &validate_rscale,
 ];
 
 /// Check if rules are valid as defined by the RFC and crate limitations.
 /// It checks all values in the [`RRule`] and makes sure that they are in
-/// the accepted ranges. If the function returns `Ok`, no errors where found.
+/// the accepted ranges, and returns all the problems found. If it is empty,
+/// the rule is valid.
 ///
 /// This check should always be done and just enforces limits set by the standard.
 /// Validation will always be enforced and can not be disabled using feature flags.
-pub(crate) fn validate_rrule_forced(
+pub(crate) fn collect_validation_errors(
     rrule: &RRule<Unvalidated>,
     dt_start: &DateTime,
-) -> Result<(), ValidationError> {
+) -> Vec<ValidationError> {
     VALIDATION_PIPELINE
         .into_iter()
-        .try_for_each(|validator| validator(rrule, dt_start))
+        .filter_map(|validator| validator(rrule, dt_start).err())
+        .flat_map(ValidationError::into_errors)
+        .collect()
+}
+
+/// Checks that a timeset can be generated from the finalized rule.
+pub(crate) fn validate_timeset(rrule: &RRule<Unvalidated>) -> Result<(), ValidationError> {
+    let has_timeset = match rrule.freq {
+        Frequency::Hourly => !rrule.by_minute.is_empty() || !rrule.by_second.is_empty(),
+        Frequency::Minutely => !rrule.by_second.is_empty(),
+        Frequency::Secondly => true,
+        _ => {
+            !rrule.by_hour.is_empty() || !rrule.by_minute.is_empty() || !rrule.by_second.is_empty()
+        }
+    };
+    if has_timeset {
+        Ok(())
+    } else {
+        Err(ValidationError::UnableToGenerateTimeset)
+    }
 }
 
 // Until:
@@ -64,7 +86,7 @@ fn validate_until(rrule: &RRule<Unvalidated>, dt_start: &DateTime) -> Result<(),
                         });
                     }
                 }
-                Tz::Tz(_) => {
+                _ => {
                     if until.timezone() != Tz::UTC {
                         return Err(ValidationError::DtStartUntilMismatchTimezone {
                             dt_start_tz: dt_start.timezone().name().into(),
@@ -118,6 +140,7 @@ fn validate_by_set_pos(
         && rrule.by_second.is_empty()
         && rrule.by_month_day.is_empty()
         && rrule.by_month.is_empty()
+        && rrule.by_leap_month.is_empty()
         && rrule.by_year_day.is_empty()
         && rrule.by_week_no.is_empty()
         && rrule.by_weekday.is_empty()
@@ -133,7 +156,8 @@ fn validate_by_month(
     rrule: &RRule<Unvalidated>,
     _dt_start: &DateTime,
 ) -> Result<(), ValidationError> {
-    validate_range_for_vec(&MONTH_RANGE, &rrule.by_month, "BYMONTH")
+    validate_range_for_vec(&MONTH_RANGE, &rrule.by_month, "BYMONTH")?;
+    validate_range_for_vec(&MONTH_RANGE, &rrule.by_leap_month, "BYMONTH")
 }
 
 // By_month_day:
@@ -143,19 +167,9 @@ fn validate_by_month_day(
     _dt_start: &DateTime,
 ) -> Result<(), ValidationError> {
     validate_not_equal_for_vec(&0, &rrule.by_month_day, "BYMONTHDAY")?;
-    validate_range_for_vec(&(-31..=31), &rrule.by_month_day, "BYMONTHDAY")?;
+    validate_range_for_vec(&(-31..=31), &rrule.by_month_day, "BYMONTHDAY")
     // - MUST NOT be specified when the FREQ rule part is set to WEEKLY.
-    //   Validated below
-    if !rrule.by_month_day.is_empty() {
-        let valid = rrule.freq != Frequency::Weekly;
-        if !valid {
-            return Err(ValidationError::InvalidByRuleAndFrequency {
-                by_rule: "BYMONTHDAY".into(),
-                freq: rrule.freq,
-            });
-        }
-    }
-    Ok(())
+    //   Validated in `validate_by_rules_with_freq`
 }
 
 // By_year_day:
@@ -165,22 +179,9 @@ fn validate_by_year_day(
     _dt_start: &DateTime,
 ) -> Result<(), ValidationError> {
     validate_not_equal_for_vec(&0, &rrule.by_year_day, "BYYEARDAY")?;
-    validate_range_for_vec(&(-366..=366), &rrule.by_year_day, "BYYEARDAY")?;
+    validate_range_for_vec(&(-366..=366), &rrule.by_year_day, "BYYEARDAY")
     // - MUST NOT be specified when the FREQ rule part is set to DAILY, WEEKLY, or MONTHLY.
-    //   Validated below
-    if !rrule.by_year_day.is_empty() {
-        let valid = !matches!(
-            rrule.freq,
-            Frequency::Monthly | Frequency::Weekly | Frequency::Daily
-        );
-        if !valid {
-            return Err(ValidationError::InvalidByRuleAndFrequency {
-                by_rule: "BYYEARDAY".into(),
-                freq: rrule.freq,
-            });
-        }
-    }
-    Ok(())
+    //   Validated in `validate_by_rules_with_freq`
 }
 // By_week_no:
 // - Can be a value from -53 to -1 and 1 to 53.
@@ -189,19 +190,89 @@ fn validate_by_week_number(
     _dt_start: &DateTime,
 ) -> Result<(), ValidationError> {
     validate_not_equal_for_vec(&0, &rrule.by_week_no, "BYWEEKNO")?;
-    validate_range_for_vec(&(-53..=53), &rrule.by_week_no, "BYWEEKNO")?;
+    validate_range_for_vec(&(-53..=53), &rrule.by_week_no, "BYWEEKNO")
     // - MUST NOT be used when the FREQ rule part is set to anything other than YEARLY.
-    //   Validated below
-    if !rrule.by_week_no.is_empty() {
-        let valid = rrule.freq == Frequency::Yearly;
-        if !valid {
-            return Err(ValidationError::InvalidByRuleAndFrequency {
-                by_rule: "BYWEEKNO".into(),
+    //   Validated in `validate_by_rules_with_freq`
+}
+
+/// The BYxxx rule parts that RFC 5545 only allows with some frequencies, if they are set
+/// in the rule, and the frequencies they are allowed with.
+///
+/// `BYDAY` with numbered weekdays is only meaningful with `MONTHLY` and `YEARLY`, but is
+/// accepted with the other frequencies and reported by [`crate::RRule::lint`] instead.
+fn restricted_by_rules<S>(rrule: &RRule<S>) -> [(&'static str, bool, &'static [Frequency]); 3] {
+    use Frequency::{Daily, Hourly, Minutely, Monthly, Secondly, Yearly};
+
+    [
+        (
+            "BYMONTHDAY",
+            !(rrule.by_month_day.is_empty() && rrule.by_n_month_day.is_empty()),
+            &[Yearly, Monthly, Daily, Hourly, Minutely, Secondly],
+        ),
+        (
+            "BYYEARDAY",
+            !rrule.by_year_day.is_empty(),
+            &[Yearly, Hourly, Minutely, Secondly],
+        ),
+        ("BYWEEKNO", !rrule.by_week_no.is_empty(), &[Yearly]),
+    ]
+}
+
+/// Returns an error for each BYxxx rule part that is set in the rule, but can't be
+/// combined with its frequency, in the order of the rule parts in RFC 5545.
+pub(crate) fn by_rules_with_freq_errors<S>(rrule: &RRule<S>) -> Vec<ValidationError> {
+    restricted_by_rules(rrule)
+        .into_iter()
+        .filter(|(_, is_set, freqs)| *is_set && !freqs.contains(&rrule.freq))
+        .map(
+            |(by_rule, _, _)| ValidationError::InvalidByRuleAndFrequency {
+                by_rule: by_rule.into(),
                 freq: rrule.freq,
-            });
-        }
-    }
-    Ok(())
+            },
+        )
+        .collect()
+}
+
+// By_month_day, by_year_day and by_week_no:
+// - Can only be used with the frequencies listed in `restricted_by_rules`.
+fn validate_by_rules_with_freq(
+    rrule: &RRule<Unvalidated>,
+    _dt_start: &DateTime,
+) -> Result<(), ValidationError> {
+    ValidationError::from_errors(by_rules_with_freq_errors(rrule)).map_or(Ok(()), Err)
+}
+
+// Rscale:
+// - The Chinese calendar is only supported for `YEARLY` and `MONTHLY` rules, with the
+//   months, the month days and the times.
+fn validate_rscale(
+    rrule: &RRule<Unvalidated>,
+    _dt_start: &DateTime,
+) -> Result<(), ValidationError> {
+    let Some(rscale @ RScale::Chinese) = rrule.rscale else {
+        return Ok(());
+    };
+    let freq = format!("FREQ={}", rrule.freq);
+    let unsupported = [
+        (
+            freq.as_str(),
+            !matches!(rrule.freq, Frequency::Yearly | Frequency::Monthly),
+        ),
+        ("BYSETPOS", !rrule.by_set_pos.is_empty()),
+        ("BYYEARDAY", !rrule.by_year_day.is_empty()),
+        ("BYWEEKNO", !rrule.by_week_no.is_empty()),
+        ("BYDAY", !rrule.by_weekday.is_empty()),
+        ("BYEASTER", rrule.by_easter.is_some()),
+    ];
+    let errors = unsupported
+        .into_iter()
+        .filter(|(_, is_set)| *is_set)
+        .map(|(rule_part, _)| ValidationError::UnsupportedWithRScale {
+            rule_part: rule_part.into(),
+            rscale,
+        })
+        .collect();
+    ValidationError::from_errors(errors).map_or(Ok(()), Err)
 }
 
 // By_weekday:
@@ -288,15 +359,6 @@ fn validate_by_easter(
                 });
             }
         }
-        // - Can only be used when `by_hour`, `by_minute` and `by_second` are used.
-        //   TODO don't know why this is true, but seems to loop forever otherwise.
-        if rrule.by_easter.is_some()
-            && (rrule.by_hour.is_empty()
-                || rrule.by_minute.is_empty()
-                || rrule.by_second.is_empty())
-        {
-            return Err(ValidationError::InvalidByRuleWithByEaster);
-        }
     }
     #[cfg(not(feature = "by-easter"))]
     {
@@ -359,6 +421,7 @@ fn validate_not_equal_for_vec<T: PartialEq<T> + ToString>(
 #[cfg(test)]
 mod tests {
     use chrono::TimeZone;
+    use smallvec::smallvec;
 
     use crate::core::Tz;
 
@@ -366,10 +429,17 @@ mod tests {
 
     const UTC: Tz = Tz::UTC;
 
+    fn validate_rrule_forced(
+        rrule: &RRule<Unvalidated>,
+        dt_start: &DateTime,
+    ) -> Result<(), ValidationError> {
+        ValidationError::from_errors(collect_validation_errors(rrule, dt_start)).map_or(Ok(()), Err)
+    }
+
     #[test]
     fn rejects_by_set_pos_without_byxxx_rule() {
         let rrule = RRule {
-            by_set_pos: vec![-1],
+            by_set_pos: smallvec![-1],
             ..Default::default()
         };
         let dt_start = UTC.with_ymd_and_hms(1970, 1, 1, 0, 0, 0).unwrap();
@@ -392,28 +462,28 @@ mod tests {
             (
                 "BYSETPOS",
                 RRule {
-                    by_set_pos: vec![0],
+                    by_set_pos: smallvec![0],
                     ..Default::default()
                 },
             ),
             (
                 "BYSETPOS",
                 RRule {
-                    by_set_pos: vec![1, -2, 0],
+                    by_set_pos: smallvec![1, -2, 0],
                     ..Default::default()
                 },
             ),
             (
                 "BYMONTHDAY",
                 RRule {
-                    by_month_day: vec![0],
+                    by_month_day: smallvec![0],
                     ..Default::default()
                 },
             ),
             (
                 "BYYEARDAY",
                 RRule {
-                    by_year_day: vec![0],
+                    by_year_day: smallvec![0],
                     ..Default::default()
                 },
             ),
@@ -439,7 +509,7 @@ mod tests {
             (
                 "BYMONTHDAY",
                 RRule {
-                    by_month_day: vec![34],
+                    by_month_day: smallvec![34],
                     ..Default::default()
                 },
                 "34",
@@ -449,7 +519,7 @@ mod tests {
             (
                 "BYYEARDAY",
                 RRule {
-                    by_year_day: vec![17, 400],
+                    by_year_day: smallvec![17, 400],
                     ..Default::default()
                 },
                 "400",
@@ -481,7 +551,7 @@ mod tests {
                 "BYSETPOS",
                 RRule {
                     freq: Frequency::Hourly,
-                    by_set_pos: vec![30],
+                    by_set_pos: smallvec![30],
                     ..Default::default()
                 },
                 "30",
@@ -492,7 +562,7 @@ mod tests {
                 "BYSETPOS",
                 RRule {
                     freq: Frequency::Yearly,
-                    by_set_pos: vec![400],
+                    by_set_pos: smallvec![400],
                     ..Default::default()
                 },
                 "400",
@@ -525,7 +595,7 @@ mod tests {
                 "BYMONTHDAY",
                 RRule {
                     freq: Frequency::Weekly,
-                    by_month_day: vec![-1],
+                    by_month_day: smallvec![-1],
                     ..Default::default()
                 },
             ),
@@ -533,7 +603,7 @@ mod tests {
                 "BYYEARDAY",
                 RRule {
                     freq: Frequency::Monthly,
-                    by_year_day: vec![120],
+                    by_year_day: smallvec![120],
                     ..Default::default()
                 },
             ),
@@ -541,7 +611,7 @@ mod tests {
                 "BYYEARDAY",
                 RRule {
                     freq: Frequency::Weekly,
-                    by_year_day: vec![120],
+                    by_year_day: smallvec![120],
                     ..Default::default()
                 },
             ),
@@ -549,7 +619,7 @@ mod tests {
                 "BYYEARDAY",
                 RRule {
                     freq: Frequency::Daily,
-                    by_year_day: vec![120],
+                    by_year_day: smallvec![120],
                     ..Default::default()
                 },
             ),
@@ -569,6 +639,102 @@ mod tests {
         }
     }
 
+    #[test]
+    fn follows_the_by_rule_and_freq_table() {
+        use Frequency::{Daily, Hourly, Minutely, Monthly, Secondly, Weekly, Yearly};
+
+        // The frequencies each rule part is forbidden with, from RFC 5545.
+        let tests: [(&str, RRule<Unvalidated>, &[Frequency]); 4] = [
+            (
+                "BYMONTHDAY",
+                RRule {
+                    by_month_day: smallvec![1],
+                    ..Default::default()
+                },
+                &[Weekly],
+            ),
+            (
+                "BYMONTHDAY",
+                RRule {
+                    by_n_month_day: smallvec![-1],
+                    ..Default::default()
+                },
+                &[Weekly],
+            ),
+            (
+                "BYYEARDAY",
+                RRule {
+                    by_year_day: smallvec![1],
+                    ..Default::default()
+                },
+                &[Monthly, Weekly, Daily],
+            ),
+            (
+                "BYWEEKNO",
+                RRule {
+                    by_week_no: smallvec![1],
+                    ..Default::default()
+                },
+                &[Monthly, Weekly, Daily, Hourly, Minutely, Secondly],
+            ),
+        ];
+        for (by_rule, rrule, forbidden) in tests {
+            for freq in [Yearly, Monthly, Weekly, Daily, Hourly, Minutely, Secondly] {
+                let rrule = rrule.clone().freq(freq);
+                let expected =
+                    forbidden
+                        .contains(&freq)
+                        .then(|| ValidationError::InvalidByRuleAndFrequency {
+                            by_rule: by_rule.into(),
+                            freq,
+                        });
+                assert_eq!(rrule.validate_by_rules().err(), expected);
+            }
+        }
+
+        let rrule = RRule {
+            freq: Weekly,
+            by_month_day: smallvec![1],
+            by_year_day: smallvec![400],
+            by_week_no: smallvec![1],
+            ..Default::default()
+        };
+        assert_eq!(
+            rrule.validate_by_rules().unwrap_err().errors().len(),
+            3,
+            "every rule part is reported"
+        );
+        let dt_start = UTC.with_ymd_and_hms(1970, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            collect_validation_errors(&rrule, &dt_start).len(),
+            4,
+            "the out of range `BYYEARDAY` is reported as well"
+        );
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let rrule = RRule {
+            until: Some(UTC.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()),
+            by_month_day: smallvec![0],
+            by_hour: smallvec![24],
+            ..Default::default()
+        };
+        let dt_start = UTC.with_ymd_and_hms(2020, 1, 2, 0, 0, 0).unwrap();
+        let err = validate_rrule_forced(&rrule, &dt_start).unwrap_err();
+        assert!(matches!(err, ValidationError::Multiple(_)));
+        assert_eq!(err.errors(), collect_validation_errors(&rrule, &dt_start));
+        assert!(matches!(
+            err.errors(),
+            [
+                ValidationError::UntilBeforeStart { .. },
+                ValidationError::InvalidFieldValue { .. },
+                ValidationError::InvalidFieldValueRange { .. },
+            ]
+        ));
+        assert!(err.to_string().starts_with("The rule has 3 problems: "));
+    }
+
     #[test]
     fn rejects_start_date_after_until() {
         let rrule = RRule {
@@ -610,6 +776,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "chrono-tz")]
     fn rejects_until_with_incompatible_timezone() {
         fn t(start_tz: Tz, until_tz: Tz) -> (DateTime, DateTime) {
             (